@@ -7,3 +7,16 @@ pub fn backup_start() -> gio::SimpleAction {
 pub fn backup_show() -> gio::SimpleAction {
     gio::SimpleAction::new("backup.show", Some(&String::static_variant_type()))
 }
+
+pub fn backup_window() -> gio::SimpleAction {
+    gio::SimpleAction::new("backup.window", Some(&String::static_variant_type()))
+}
+
+/// Stop the operation of a backup after asking for confirmation
+pub fn backup_stop() -> gio::SimpleAction {
+    gio::SimpleAction::new("backup.stop", Some(&String::static_variant_type()))
+}
+
+pub fn backup_abort() -> gio::SimpleAction {
+    gio::SimpleAction::new("backup.abort", Some(&String::static_variant_type()))
+}
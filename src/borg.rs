@@ -1,9 +1,12 @@
 pub mod communication;
 pub mod error;
+pub mod explain;
+pub mod fault_injection;
 pub mod functions;
 pub mod invert_command;
 pub mod json;
 pub mod log_json;
+pub mod operation_lock;
 pub mod prelude;
 mod process;
 pub mod scripts;
@@ -11,13 +14,16 @@ pub mod size_estimate;
 pub mod status;
 pub mod task;
 mod utils;
+pub mod version;
 
 pub use communication::*;
 pub use error::{Abort, Error, Failure, Outcome, Result};
 pub use functions::*;
 pub use json::*;
+pub use process::CommandPreview;
 pub use status::*;
 pub use task::Task;
+pub use utils::escape_control_chars;
 
 pub static DELAY_RECONNECT: std::time::Duration = std::time::Duration::from_secs(60);
 pub static MAX_RECONNECT: u16 = 30;
@@ -2,8 +2,8 @@ use crate::borg::prelude::*;
 
 use super::error;
 use super::Result;
-use arc_swap::ArcSwap;
-use async_std::channel::{self, unbounded};
+use arc_swap::{ArcSwap, ArcSwapOption};
+use async_std::channel::{self, bounded, unbounded};
 use std::sync::Arc;
 
 use super::log_json;
@@ -16,6 +16,9 @@ pub enum Update {
     Status(Status),
 }
 
+/// Number of streamed items that are buffered until the receiver catches up
+const ITEMS_BUFFER: usize = 1000;
+
 #[derive(Default, Debug, Clone)]
 pub struct Communication<T: Task> {
     pub general_info: Arc<ArcSwap<super::status::GeneralStatus>>,
@@ -23,6 +26,7 @@ pub struct Communication<T: Task> {
     pub status: Arc<ArcSwap<Status>>,
     pub(in crate::borg) instruction: Arc<ArcSwap<Instruction>>,
     sender: Arc<ArcSwap<Vec<channel::Sender<Update>>>>,
+    items: Arc<ArcSwapOption<channel::Sender<Vec<u8>>>>,
 }
 
 impl<T: Task> Communication<T> {
@@ -33,6 +37,23 @@ impl<T: Task> Communication<T> {
         receiver
     }
 
+    /// Receive the items of commands returning `JsonLines` or `JsonArray`
+    ///
+    /// There is only one receiver for items. The process waits for the receiver
+    /// if it falls behind, such that the items never pile up in memory.
+    pub(in crate::borg) fn item_receiver(&self) -> channel::Receiver<Vec<u8>> {
+        let (sender, receiver) = bounded(ITEMS_BUFFER);
+        self.items.store(Some(Arc::new(sender)));
+        receiver
+    }
+
+    /// Signal the item receiver that no more items follow
+    pub(in crate::borg) fn close_items(&self) {
+        if let Some(sender) = self.items.swap(None) {
+            sender.close();
+        }
+    }
+
     pub(in crate::borg) fn new_sender(&self) -> Sender<T> {
         Sender(self.clone())
     }
@@ -84,6 +105,14 @@ pub enum Instruction {
     Nothing,
     Abort(error::Abort),
     Response(Response),
+    /// Stop and exclude the currently processed path when restarting
+    SkipPath(std::path::PathBuf),
+    /// Stop and exclude the include root containing the current path when restarting
+    SkipIncludeRoot(std::path::PathBuf),
+    /// Suspend the borg process
+    Pause,
+    /// Continue a suspended borg process
+    Resume,
 }
 
 impl Default for Instruction {
@@ -103,6 +132,17 @@ impl<T: Task> Sender<T> {
         }
         Ok(())
     }
+
+    pub async fn send_item(
+        &self,
+        item: Vec<u8>,
+    ) -> std::result::Result<(), channel::SendError<Vec<u8>>> {
+        let sender = self.0.items.load_full();
+        if let Some(sender) = sender {
+            sender.send(item).await?;
+        }
+        Ok(())
+    }
 }
 
 impl<T: Task> Drop for Sender<T> {
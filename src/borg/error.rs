@@ -23,6 +23,10 @@ quick_error! {
         Unix(err: nix::Error) { from() }
         BorgReturnCode(err: ReturnCodeError) { from() }
         PasswordMissing { keyring_error: Option<String> }
+        PasswordKeyringLocked { display("{}", gettext("The keyring containing the encryption password is locked.")) }
+        PasswordCommand(err: std::io::Error) {
+            display("{}\n{}", gettext("Obtaining the encryption password from the configured command failed."), err)
+        }
         PasswordStorage(err: oo7::Error) {
             from()
             display("{}", gettext("Retrieving encryption password from the keyring failed. Pika Backup requires a keyring daemon (“secret service”) to store passwords. For installation instructions see the operating system documentation."))
@@ -51,6 +55,12 @@ impl std::convert::TryFrom<LogCollection> for Error {
         let first_with_id = errors.clone().find(|e| e.id().is_some());
 
         if let Some(failure) = first_with_id.and_then(|e| e.id()) {
+            if failure.is_connection_error() {
+                if let Some(host_key_failure) = host_key_failure(&value) {
+                    return Ok(host_key_failure.into());
+                }
+            }
+
             match failure {
                 // Find hint for ClosedWithHint
                 Failure::ConnectionClosedWithHint => {
@@ -87,7 +97,9 @@ impl std::convert::TryFrom<LogCollection> for Error {
                         Ok(format!(
                             "{}: {}",
                             failure,
-                            first_with_id.map(|x| x.message()).unwrap_or_default()
+                            first_with_id
+                                .map(|x| x.explained_message())
+                                .unwrap_or_default()
                         )
                         .into())
                     }
@@ -95,17 +107,44 @@ impl std::convert::TryFrom<LogCollection> for Error {
                 Failure::Other(msgid) => Ok(format!(
                     "{}: {}",
                     msgid,
-                    first_with_id.map(|x| x.message()).unwrap_or_default()
+                    first_with_id
+                        .map(|x| x.explained_message())
+                        .unwrap_or_default()
                 )
                 .into()),
+                // Unknown message IDs are better described by the message itself
+                Failure::Undefined => match first_with_id.map(|x| x.explained_message()) {
+                    Some(message) if !message.is_empty() => Ok(message.into()),
+                    _ => Ok(failure.into()),
+                },
                 _ => Ok(failure.into()),
             }
         } else {
-            errors.next().map(|x| x.message().into()).ok_or(())
+            errors
+                .next()
+                .map(|x| x.explained_message().into())
+                .ok_or(())
         }
     }
 }
 
+/// Failed verification of the server identity as reported by `ssh`
+fn host_key_failure(value: &LogCollection) -> Option<Failure> {
+    if value.iter().any(|x| {
+        x.message()
+            .contains("REMOTE HOST IDENTIFICATION HAS CHANGED")
+    }) {
+        Some(Failure::HostKeyChanged)
+    } else if value
+        .iter()
+        .any(|x| x.message().contains("Host key verification failed"))
+    {
+        Some(Failure::HostKeyUnknown)
+    } else {
+        None
+    }
+}
+
 /// The outcome of the backup operation
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Outcome {
@@ -143,6 +182,8 @@ pub enum Abort {
     OnBattery,
     /// program was shutdown via signal
     Shutdown,
+    /// the user session ended, for example by logging out
+    SessionEnd,
     /// program probably crashed while running
     LeftRunning,
     /// shell script configured by the user failed to run
@@ -150,6 +191,12 @@ pub enum Abort {
     /// Unable to mount / access the repository during setup.
     /// Detailed error message in parameter.
     RepositoryNotAvailable(String),
+    /// The password is stored in a keyring that is locked
+    KeyringLocked,
+    /// Stopped to restart without the path in the parameter
+    Skip(std::path::PathBuf),
+    /// Archives would be deleted while the repository is mounted for browsing
+    RepositoryMounted,
 }
 
 impl std::fmt::Display for Abort {
@@ -168,6 +215,11 @@ impl std::fmt::Display for Abort {
                 gettext("Aborted because too long not connected to power.")
             ),
             Self::Shutdown => write!(f, "{}", gettext("Aborted by system.")),
+            Self::SessionEnd => write!(
+                f,
+                "{}",
+                gettext("Interrupted by logging out or shutting down.")
+            ),
             Self::LeftRunning => write!(
                 f,
                 "{}",
@@ -183,6 +235,23 @@ impl std::fmt::Display for Abort {
                     gettextf("Unable to access backup repository: {}", &[msg])
                 )
             }
+            Self::KeyringLocked => write!(
+                f,
+                "{}",
+                gettext(
+                    "Postponed until the keyring containing the encryption password is unlocked."
+                )
+            ),
+            Self::RepositoryMounted => write!(
+                f,
+                "{}",
+                gettext("Postponed because archives are currently being browsed.")
+            ),
+            Self::Skip(path) => write!(
+                f,
+                "{}",
+                gettextf("Stopped to skip “{}”.", &[&path.display().to_string()])
+            ),
         }
     }
 }
@@ -200,6 +269,13 @@ pub enum Failure {
     PassphraseWrong,
     #[serde(rename = "Cache.RepositoryAccessAborted")]
     CacheRepositoryAccessAborted,
+    /// The cache or the security information has seen a newer repository state
+    #[serde(rename = "Cache.RepositoryReplay")]
+    CacheRepositoryReplay,
+    #[serde(rename = "Cache.EncryptionMethodMismatch")]
+    CacheEncryptionMethodMismatch,
+    /// A cache file failed its integrity check
+    FileIntegrityError,
     #[serde(rename = "Repository.AlreadyExists")]
     RepositoryAlreadyExists,
     #[serde(rename = "Repository.DoesNotExist")]
@@ -208,12 +284,19 @@ pub enum Failure {
     RepositoryInsufficientFreeSpaceError,
     /// Connection closed with mnually added hint
     ConnectionClosedWithHint_(String),
+    /// The server presented a different host key than the stored one
+    HostKeyChanged,
+    /// The server is not known and the policy requires a known host key
+    HostKeyUnknown,
 
     // # General
     /// Unknown borg exception
     Exception,
     /// Other (one-off) exception
     Other(String),
+    /// The location contains a different repository than during setup.
+    /// The ID of the found repository is in the parameter.
+    RepositoryMismatch(json::RepoId),
 
     /// Fallback
     #[serde(other)]
@@ -229,6 +312,16 @@ impl Failure {
                 | Self::ConnectionClosedWithHint_(_)
         )
     }
+
+    /// Errors that are resolved by rebuilding the local cache
+    pub const fn is_cache_error(&self) -> bool {
+        matches!(
+            self,
+            Self::CacheRepositoryReplay
+                | Self::CacheEncryptionMethodMismatch
+                | Self::FileIntegrityError
+        )
+    }
 }
 
 impl std::fmt::Display for Failure {
@@ -241,6 +334,15 @@ impl std::fmt::Display for Failure {
             Self::LockFailed => gettext("Failed to lock repository."),
             Self::PassphraseWrong => gettext("Invalid encryption password."),
             Self::CacheRepositoryAccessAborted => gettext("Repository access was aborted"),
+            Self::CacheRepositoryReplay => gettext(
+                "The local cache is newer than the backup repository. The cache has to be rebuilt.",
+            ),
+            Self::CacheEncryptionMethodMismatch => gettext(
+                "The encryption of the backup repository does not match the local cache. The cache has to be rebuilt.",
+            ),
+            Self::FileIntegrityError => {
+                gettext("The local cache is damaged. The cache has to be rebuilt.")
+            }
             Self::RepositoryAlreadyExists => {
                 gettext("A repository already exists at this location.")
             }
@@ -251,11 +353,78 @@ impl std::fmt::Display for Failure {
             Self::ConnectionClosedWithHint_(hint) => {
                 gettextf("Connection closed by remote host: “{}”", &[hint])
             }
+            Self::HostKeyChanged => gettext(
+                "The identity of the server has changed. This happens if the server was reinstalled, but could also mean that someone is intercepting the connection.",
+            ),
+            Self::HostKeyUnknown => gettext(
+                "The identity of the server is unknown. Connections are only allowed to servers with a stored host key.",
+            ),
             Self::Exception => gettext("Exception"),
             Self::Other(string) => string.to_string(),
+            Self::RepositoryMismatch(_) => gettext(
+                "Repository mismatch: The backup location contains a different repository than the one this backup was set up with.",
+            ),
             Self::Undefined => gettext("Unspecified error."),
         };
 
         write!(f, "{}", &text)
     }
 }
+
+#[test]
+fn test_host_key_failure() {
+    let entry = |levelname, message: &str, msgid| {
+        LogEntry::ParsedErr(LogMessage {
+            levelname,
+            name: String::from("borg.remote"),
+            message: message.to_string(),
+            msgid,
+        })
+    };
+
+    let mut messages: LogCollection = vec![
+        entry(
+            LogLevel::Warning,
+            "Remote: Host key verification failed.",
+            MsgId::Undefined,
+        ),
+        entry(
+            LogLevel::Error,
+            "Connection closed by remote host",
+            MsgId::ConnectionClosed,
+        ),
+    ];
+
+    assert!(matches!(
+        Error::try_from(messages.clone()),
+        Ok(Error::Failed(Failure::HostKeyUnknown))
+    ));
+
+    messages.insert(
+        0,
+        entry(
+            LogLevel::Warning,
+            "Remote: @    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @",
+            MsgId::Undefined,
+        ),
+    );
+
+    assert!(matches!(
+        Error::try_from(messages),
+        Ok(Error::Failed(Failure::HostKeyChanged))
+    ));
+}
+
+#[test]
+fn test_cache_failure() {
+    for msgid in [
+        "Cache.RepositoryReplay",
+        "Cache.EncryptionMethodMismatch",
+        "FileIntegrityError",
+    ] {
+        let failure: Failure = serde_json::from_str(&format!("\"{msgid}\"")).unwrap();
+        assert!(failure.is_cache_error(), "{msgid}");
+    }
+
+    assert!(!Failure::LockTimeout.is_cache_error());
+}
@@ -0,0 +1,185 @@
+/*!
+# Plain-language explanations of borg messages
+
+Borg messages are in English and often refer to internals. Known message
+formats are replaced by a localized explanation. Placeholders like `{path}` in
+the explanation are filled with the corresponding part of the original message.
+Unknown messages are shown unchanged.
+*/
+
+use once_cell::sync::Lazy;
+
+use super::log_json::MsgId;
+use crate::prelude::*;
+
+struct Rule {
+    /// Only applies to messages with this ID
+    msgid: Option<MsgId>,
+    /// Named groups are available as placeholders in the explanation
+    pattern: regex::Regex,
+    explanation: fn() -> String,
+}
+
+impl Rule {
+    fn new(msgid: Option<MsgId>, pattern: &str, explanation: fn() -> String) -> Self {
+        Self {
+            msgid,
+            pattern: regex::Regex::new(pattern).unwrap(),
+            explanation,
+        }
+    }
+
+    fn apply(&self, msgid: &MsgId, message: &str) -> Option<String> {
+        if self.msgid.as_ref().is_some_and(|x| x != msgid) {
+            return None;
+        }
+
+        let captures = self.pattern.captures(message).ok()??;
+        let mut explanation = (self.explanation)();
+
+        for name in self.pattern.capture_names().flatten() {
+            if let Some(value) = captures.name(name) {
+                explanation = explanation.replace(&format!("{{{name}}}"), value.as_str());
+            }
+        }
+
+        Some(explanation)
+    }
+}
+
+static RULES: Lazy<Vec<Rule>> = Lazy::new(|| {
+    vec![
+        Rule::new(
+            None,
+            r"^(?P<path>.+): \[Errno 13\] Permission denied",
+            || {
+                // Translators: Keep “{path}” unchanged, it is replaced by a file name
+                gettext("No permission to read “{path}”. The file was not saved.")
+            },
+        ),
+        Rule::new(
+            None,
+            r"^(?P<path>.+): \[Errno 2\] No such file or directory",
+            || {
+                // Translators: Keep “{path}” unchanged, it is replaced by a file name
+                gettext("“{path}” was removed while the backup was running.")
+            },
+        ),
+        Rule::new(
+            None,
+            r"^(?P<path>.+): \[Errno 5\] Input/output error",
+            || {
+                // Translators: Keep “{path}” unchanged, it is replaced by a file name
+                gettext("Reading “{path}” failed. This can be caused by a damaged drive.")
+            },
+        ),
+        Rule::new(
+            None,
+            r"^(?P<path>.+): file changed while we backed it up",
+            || {
+                // Translators: Keep “{path}” unchanged, it is replaced by a file name
+                gettext(
+                    "“{path}” changed while it was saved. The saved copy might be inconsistent.",
+                )
+            },
+        ),
+        Rule::new(None, r"\[Errno 28\] No space left on device", || {
+            gettext("There is no free space left at the backup location.")
+        }),
+        Rule::new(None, r"^Archive (?P<archive>.+) already exists", || {
+            // Translators: Keep “{archive}” unchanged, it is replaced by an archive name
+            gettext("An archive with the name “{archive}” already exists.")
+        }),
+        Rule::new(None, r"^Archive (?P<archive>.+) does not exist", || {
+            // Translators: Keep “{archive}” unchanged, it is replaced by an archive name
+            gettext("The archive “{archive}” does not exist anymore.")
+        }),
+        Rule::new(
+            None,
+            r#"^Inconsistency detected\. Please run "borg check"#,
+            || gettext("The repository is damaged. Check the backup integrity to repair it."),
+        ),
+        Rule::new(
+            None,
+            r"Could not resolve hostname (?P<host>[^:\s]+)",
+            || {
+                // Translators: Keep “{host}” unchanged, it is replaced by a server name
+                gettext("The server “{host}” could not be found. Check the network connection and the server address.")
+            },
+        ),
+        Rule::new(None, r"Permission denied \(publickey", || {
+            gettext("The server did not accept the login. Check that the SSH key of this computer is allowed on the server.")
+        }),
+        Rule::new(
+            None,
+            r"connect to host (?P<host>\S+) port \d+: Connection refused",
+            || {
+                // Translators: Keep “{host}” unchanged, it is replaced by a server name
+                gettext("The server “{host}” refused the connection. Check that the SSH service on the server is running.")
+            },
+        ),
+        Rule::new(
+            None,
+            r"connect to host (?P<host>\S+) port \d+: Connection timed out",
+            || {
+                // Translators: Keep “{host}” unchanged, it is replaced by a server name
+                gettext("The server “{host}” did not respond.")
+            },
+        ),
+        Rule::new(None, r"borg: (command )?not found", || {
+            gettext("BorgBackup is not installed on the server.")
+        }),
+        Rule::new(
+            Some(MsgId::RepositoryDoesNotExist),
+            r"^Repository (?P<location>.+) does not exist",
+            || {
+                // Translators: Keep “{location}” unchanged, it is replaced by a backup location
+                gettext("No backup repository exists at “{location}”.")
+            },
+        ),
+    ]
+});
+
+/// Localized explanation of a borg message, if the message is known
+pub fn explain(msgid: &MsgId, message: &str) -> Option<String> {
+    let message = message.trim();
+    let message = message.strip_prefix("Remote: ").unwrap_or(message);
+
+    RULES.iter().find_map(|rule| rule.apply(msgid, message))
+}
+
+#[test]
+fn test_explain() {
+    assert_eq!(
+        explain(
+            &MsgId::Undefined,
+            "/home/user/secret: [Errno 13] Permission denied: 'secret'"
+        ),
+        Some("No permission to read “/home/user/secret”. The file was not saved.".to_string())
+    );
+    assert_eq!(
+        explain(
+            &MsgId::Undefined,
+            "Remote: ssh: Could not resolve hostname nas.local: Name or service not known"
+        ),
+        Some("The server “nas.local” could not be found. Check the network connection and the server address.".to_string())
+    );
+    assert_eq!(
+        explain(
+            &MsgId::RepositoryDoesNotExist,
+            "Repository /media/backup does not exist."
+        ),
+        Some("No backup repository exists at “/media/backup”.".to_string())
+    );
+    assert_eq!(
+        explain(
+            &MsgId::Undefined,
+            "Repository /media/backup does not exist."
+        ),
+        None
+    );
+    assert_eq!(
+        explain(&MsgId::Undefined, "Something unknown happened"),
+        None
+    );
+}
@@ -0,0 +1,131 @@
+/*!
+Synthetic failures for testing the error handling
+
+Set the environment variable `PIKA_BACKUP_INJECT_FAILURE` to `<kind>[:<seconds>]`
+to make every borg process fail after the given number of seconds.
+
+- `connection-drop` – The connection to the repository is closed
+- `passphrase` – The encryption password is rejected
+- `stall` – borg stops sending any output
+
+The failures are injected into the output of the borg process, the repository
+is not modified. Connection drops and wrong passphrases terminate the process.
+*/
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const ENV_VAR: &str = "PIKA_BACKUP_INJECT_FAILURE";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    ConnectionDrop,
+    Passphrase,
+    Stall,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub kind: Kind,
+    /// Time after the start of the process
+    pub after: Duration,
+}
+
+impl Failure {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (kind, after) = match value.split_once(':') {
+            Some((kind, seconds)) => (kind, Duration::from_secs(seconds.trim().parse().ok()?)),
+            None => (value, Duration::ZERO),
+        };
+
+        let kind = match kind.trim() {
+            "connection-drop" => Kind::ConnectionDrop,
+            "passphrase" => Kind::Passphrase,
+            "stall" => Kind::Stall,
+            _ => return None,
+        };
+
+        Some(Self { kind, after })
+    }
+
+    /// Failure configured via the environment, read once
+    pub fn configured() -> Option<Self> {
+        static CONFIGURED: OnceLock<Option<Failure>> = OnceLock::new();
+
+        *CONFIGURED.get_or_init(|| {
+            let value = std::env::var(ENV_VAR).ok()?;
+            let failure = Self::parse(&value);
+
+            if failure.is_some() {
+                warn!("Injecting failures into borg processes: {:?}", failure);
+            } else {
+                error!("Invalid value for {}: {:?}", ENV_VAR, value);
+            }
+
+            failure
+        })
+    }
+
+    pub fn is_due(&self, elapsed: Duration) -> bool {
+        elapsed >= self.after
+    }
+
+    /// Log line borg would print for the failure
+    ///
+    /// Returns `None` if the failure is not represented by a message.
+    pub fn log_line(&self) -> Option<String> {
+        let (msgid, message) = match self.kind {
+            Kind::ConnectionDrop => ("ConnectionClosed", "Connection closed by remote host"),
+            Kind::Passphrase => (
+                "PassphraseWrong",
+                "passphrase supplied in BORG_PASSPHRASE, by BORG_PASSCOMMAND or via BORG_PASSPHRASE_FD is incorrect.",
+            ),
+            Kind::Stall => return None,
+        };
+
+        Some(
+            serde_json::json!({
+                "type": "log_message",
+                "time": chrono::Local::now().timestamp(),
+                "levelname": "ERROR",
+                "name": "borg.archiver",
+                "msgid": msgid,
+                "message": message,
+            })
+            .to_string(),
+        )
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        Failure::parse("connection-drop:30"),
+        Some(Failure {
+            kind: Kind::ConnectionDrop,
+            after: Duration::from_secs(30)
+        })
+    );
+    assert_eq!(
+        Failure::parse("passphrase"),
+        Some(Failure {
+            kind: Kind::Passphrase,
+            after: Duration::ZERO
+        })
+    );
+    assert_eq!(Failure::parse("stall:x"), None);
+    assert_eq!(Failure::parse("disk-full"), None);
+}
+
+#[test]
+fn test_log_line() {
+    let line = Failure::parse("connection-drop")
+        .unwrap()
+        .log_line()
+        .unwrap();
+    assert!(matches!(
+        super::utils::check_line(&line),
+        super::log_json::LogEntry::ParsedErr(_)
+    ));
+    assert_eq!(Failure::parse("stall").unwrap().log_line(), None);
+}
@@ -66,8 +66,94 @@ impl CommandRun<task::List> for Command<task::List> {
             task::NumArchives::All => (),
         }
 
-        let json: List = borg.output(&self.communication).await?;
-        Ok(json.archives)
+        let items = self.communication.item_receiver();
+        let process: Process<JsonArray> = borg.spawn_background(&self.communication)?;
+
+        let result = async {
+            let result = process.result.await;
+            self.communication.close_items();
+            result
+        };
+
+        let receive = async {
+            let mut archives = Vec::new();
+
+            while let Ok(item) = items.recv().await {
+                archives.push(serde_json::from_slice::<ListArchive>(&item)?);
+                self.communication
+                    .specific_info
+                    .update(|info| info.archives = archives.len());
+            }
+
+            Ok::<_, Error>(archives)
+        };
+
+        let (result, archives) = futures::join!(result, receive);
+        result?;
+
+        archives
+    }
+}
+
+#[async_trait]
+impl CommandRun<task::ListFiles> for Command<task::ListFiles> {
+    async fn run(self) -> Result<u64> {
+        let archive_name = self.task.archive_name();
+
+        let mut borg = BorgCall::new("list");
+        borg.add_options(["--json-lines"])
+            .add_existing_archive(
+                &self,
+                archive_name
+                    .as_ref()
+                    .map(ArchiveName::as_str)
+                    .unwrap_or_default(),
+            )
+            .add_basics(&self)
+            .await?;
+
+        let lines = self.communication.item_receiver();
+        let process: Process<JsonLines> = borg.spawn_background(&self.communication)?;
+
+        let result = async {
+            let result = process.result.await;
+            self.communication.close_items();
+            result
+        };
+
+        let receive = async {
+            let mut entries = 0;
+
+            while let Ok(line) = lines.recv().await {
+                match serde_json::from_slice(&line) {
+                    Ok(file) => {
+                        if let Some(sender) = &self.task.entries {
+                            // Receiver gone means nobody is interested anymore
+                            let _ = sender.send(file).await;
+                        }
+                        entries += 1;
+                    }
+                    // Paths that are not valid UTF-8 are not representable in JSON
+                    Err(err) => debug!("Skipping archive entry: {}", err),
+                }
+
+                self.communication
+                    .specific_info
+                    .update(|info| info.files = entries);
+            }
+
+            if let Some(sender) = &self.task.entries {
+                sender.close();
+            }
+
+            entries
+        };
+
+        let (result, entries) = futures::join!(result, receive);
+        let output = result?;
+        debug!("Listed {} of {} archive entries", entries, output.lines);
+
+        Ok(entries as u64)
     }
 }
 
@@ -82,14 +168,20 @@ impl CommandRun<task::Mount> for Command<task::Mount> {
             .mode(0o700)
             .create(&dir)?;
 
+        // Make all data readable for the current user
+        // <https://gitlab.gnome.org/World/pika-backup/-/issues/132>
+        let options = format!(
+            "umask={:04o},uid={}",
+            self.task.umask & 0o777,
+            nix::unistd::getuid()
+        );
+
         let mut borg = BorgCall::new("mount");
         borg.add_basics(&self)
             .await?
             // Also mount incomplete archives (checkpoints)
             .add_options(["--consider-checkpoints"])
-            // Make all data readable for the current user
-            // <https://gitlab.gnome.org/World/pika-backup/-/issues/132>
-            .add_options(["-o", &format!("umask=0000,uid={}", nix::unistd::getuid())])
+            .add_options(["-o", &options])
             .add_positional(&dir);
 
         borg.output(&self.communication).await
@@ -121,15 +213,34 @@ impl CommandRun<task::PruneInfo> for Command<task::PruneInfo> {
             })
             .filter(|x| x.name == "borg.output.list");
 
-        let prune = list_messages
+        let mut prune_archives: Vec<PruneArchive> = list_messages
+            .clone()
+            .filter_map(|x| PruneArchive::from_list_message(&x.message))
+            .collect();
+        let mut prune = list_messages
             .clone()
             .filter(|x| x.message.starts_with("Would prune"))
             .count();
-        let keep = list_messages
+        let mut keep = list_messages
+            .clone()
             .filter(|x| x.message.starts_with("Keeping"))
             .count();
 
-        Ok(PruneInfo { keep, prune })
+        // Checkpoints kept by prune are deleted in a separate step
+        if self.config.prune.delete_checkpoints {
+            let checkpoints: Vec<PruneArchive> = list_messages
+                .filter_map(|x| PruneArchive::from_checkpoint_message(&x.message))
+                .collect();
+            prune += checkpoints.len();
+            keep -= checkpoints.len();
+            prune_archives.extend(checkpoints);
+        }
+
+        Ok(PruneInfo {
+            keep,
+            prune,
+            prune_archives,
+        })
     }
 }
 
@@ -139,7 +250,19 @@ impl CommandRun<task::Prune> for Command<task::Prune> {
         let mut borg_call = prune_call(&self).await?;
         borg_call.add_options(["--progress"]);
 
-        borg_call.output(&self.communication).await
+        borg_call.output(&self.communication).await?;
+
+        if self.config.prune.delete_checkpoints {
+            let mut borg_call = BorgCall::new("delete");
+            borg_call.add_basics(&self).await?.add_options([&format!(
+                "--glob-archives={}*.checkpoint*",
+                self.config.archive_prefix
+            )]);
+
+            borg_call.output(&self.communication).await?;
+        }
+
+        Ok(())
     }
 }
 
@@ -167,6 +290,18 @@ impl CommandRun<task::Check> for Command<task::Check> {
             borg_call.add_options(["--repair"]);
         }
 
+        if self.task.repository_only() {
+            borg_call.add_options(["--repository-only"]);
+        }
+
+        if self.task.latest_archive_only() {
+            borg_call.add_options([
+                "--archives-only".to_string(),
+                format!("--glob-archives={}*", self.config.archive_prefix),
+                "--last=1".to_string(),
+            ]);
+        }
+
         borg_call.output(&self.communication).await
     }
 }
@@ -174,28 +309,56 @@ impl CommandRun<task::Check> for Command<task::Check> {
 #[async_trait]
 impl CommandRun<task::Delete> for Command<task::Delete> {
     async fn run(self) -> Result<()> {
-        let archive_name = self.task.archive_name().unwrap_or_default();
-
-        let mut borg_call = delete_call(&self, &archive_name).await?;
+        let archive_name = self.task.archive_name();
+
+        let mut borg_call = delete_call(
+            &self,
+            archive_name
+                .as_ref()
+                .map(ArchiveName::as_str)
+                .unwrap_or_default(),
+        )
+        .await?;
         borg_call.add_options(["--progress"]);
 
         borg_call.output(&self.communication).await
     }
 }
 
+impl Command<task::Create> {
+    /// Ensure that the location still contains the repository from the setup
+    ///
+    /// A swapped disk or a recreated directory would otherwise silently
+    /// receive the backups.
+    async fn verify_repo_id(&self) -> Result<()> {
+        if !self.config.is_repo_id_known() {
+            return Ok(());
+        }
+
+        let mut borg_call = BorgCall::new("info");
+        borg_call.add_options(["--json"]).add_basics(self).await?;
+
+        let info: Info = borg_call.output(&self.communication).await?;
+
+        if info.repository.id != self.config.repo_id {
+            return Err(Failure::RepositoryMismatch(info.repository.id).into());
+        }
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl CommandRun<task::Create> for Command<task::Create> {
     async fn run(self) -> Result<Stats> {
-        if self.config.include.is_empty() {
+        if self.config.include_dirs().is_empty() {
             return Err(Error::EmptyInclude);
         }
 
-        let mut borg_call = BorgCall::new("create");
+        self.verify_repo_id().await?;
+
+        let mut borg_call = create_call(&self).await;
         borg_call
-            .add_options(["--progress", "--json"])
-            // Good and fast compression
-            // <https://gitlab.gnome.org/World/pika-backup/-/issues/51>
-            .add_options(&["--compression=zstd"])
             .add_basics(&self)
             .await?
             .add_archive(&self)
@@ -238,6 +401,13 @@ impl CommandRun<task::Create> for Command<task::Create> {
 
                 last_skipped = skipped;
                 last_copied = copied;
+            } else if let Update::Msg(log_json::Output::Progress(log_json::Progress::FileStatus(
+                file,
+            ))) = msg
+            {
+                self.communication.specific_info.update(move |status| {
+                    status.add_recent_file(file.clone());
+                });
             }
         }
 
@@ -280,6 +450,95 @@ impl CommandRun<task::KeyChangePassphrase> for Command<task::KeyChangePassphrase
     }
 }
 
+#[async_trait]
+impl CommandRun<task::RepositoryFormat> for Command<task::RepositoryFormat> {
+    async fn run(self) -> Result<RepositoryFormat> {
+        let mut borg_call = BorgCall::new("info");
+        borg_call
+            .add_options(["--json", "--debug"])
+            .add_basics(&self)
+            .await?;
+
+        let process: Process<Info> = borg_call.spawn_background(&self.communication)?;
+
+        let mut manifest_authenticated = None;
+        let mut log = self.communication.new_receiver();
+
+        while let Some(msg) = log.next().await {
+            if let Update::Msg(log_json::Output::LogEntry(ref entry)) = msg {
+                if let Some(authenticated) = entry.manifest_tam() {
+                    manifest_authenticated = Some(authenticated);
+                }
+            }
+        }
+
+        process.result.await?;
+
+        let mut borg_call = BorgCall::new("list");
+        borg_call
+            .add_options(["--json", "--consider-checkpoints", "--format={name}{tam}"])
+            .add_basics(&self)
+            .await?;
+
+        // Borg versions before 1.2.5 do not know about archive authentication
+        let unauthenticated_archives =
+            match borg_call.output::<_, ListTam>(&self.communication).await {
+                Ok(list) => list
+                    .archives
+                    .into_iter()
+                    .filter(|archive| !archive.is_authenticated())
+                    .map(|archive| archive.name)
+                    .collect(),
+                Err(err) => {
+                    debug!("Failed to list archive authentication: {}", err);
+                    Vec::new()
+                }
+            };
+
+        Ok(RepositoryFormat {
+            manifest_authenticated,
+            unauthenticated_archives,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandRun<task::Upgrade> for Command<task::Upgrade> {
+    async fn run(self) -> Result<()> {
+        let format = self.task.format();
+
+        if format.manifest_needs_upgrade() {
+            let mut borg_call = BorgCall::new("upgrade");
+            borg_call.add_options(["--tam"]).add_basics(&self).await?;
+            borg_call.output::<_, ()>(&self.communication).await?;
+        }
+
+        if format.archives_need_upgrade() {
+            let mut borg_call = BorgCall::new("upgrade");
+            borg_call
+                .add_options(["--archives-tam"])
+                .add_basics(&self)
+                .await?;
+            borg_call.output::<_, ()>(&self.communication).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommandRun<task::RebuildCache> for Command<task::RebuildCache> {
+    async fn run(self) -> Result<()> {
+        // Repository info opens the cache which synchronizes it
+        let mut borg_call = BorgCall::new("info");
+        borg_call
+            .add_options(["--progress"])
+            .add_basics(&self)
+            .await?;
+        borg_call.output::<_, ()>(&self.communication).await
+    }
+}
+
 #[async_std::test]
 async fn create_non_existent_location() {
     let config = config::Backup::test_new_mock();
@@ -408,6 +667,82 @@ impl BorgRunConfig for CommandOnlyRepo {
 pub struct PruneInfo {
     pub keep: usize,
     pub prune: usize,
+    /// Archives that would be deleted
+    pub prune_archives: Vec<PruneArchive>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PruneArchive {
+    pub name: ArchiveName,
+    pub time: Option<chrono::NaiveDateTime>,
+}
+
+impl PruneArchive {
+    /// Parse lines like `Would prune: <name> Mon, 2023-01-02 10:00:00 [<id>]`
+    pub fn from_list_message(message: &str) -> Option<Self> {
+        Self::parse(message.strip_prefix("Would prune:")?)
+    }
+
+    /// Checkpoint that prune keeps, like `Keeping checkpoint archive: <name> …`
+    ///
+    /// These are deleted afterwards if [`config::Prune::delete_checkpoints`] is set.
+    pub fn from_checkpoint_message(message: &str) -> Option<Self> {
+        Self::parse(message.strip_prefix("Keeping checkpoint archive:")?)
+    }
+
+    fn parse(archive: &str) -> Option<Self> {
+        let archive = archive.trim();
+        let archive = archive
+            .rsplit_once(" [")
+            .map_or(archive, |(archive, _id)| archive);
+
+        let mut parts = archive.rsplitn(4, ' ');
+        let time = match (parts.next(), parts.next(), parts.next()) {
+            (Some(time), Some(date), Some(weekday)) => chrono::NaiveDateTime::parse_from_str(
+                &format!("{weekday} {date} {time}"),
+                "%a, %Y-%m-%d %H:%M:%S",
+            )
+            .ok(),
+            _ => None,
+        };
+
+        let name = match (time, parts.next()) {
+            (Some(_), Some(name)) => name.trim_end(),
+            // Unknown format, at least show the archive name
+            _ => archive.split_whitespace().next()?,
+        };
+
+        Some(Self {
+            name: ArchiveName::new(name.to_string()),
+            time,
+        })
+    }
+}
+
+/// Authentication of the repository metadata
+///
+/// Repositories and archives created with old borg versions lack the
+/// authentication of the manifest and archives (TAM). Newer borg versions
+/// refuse to use them until they have been upgraded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepositoryFormat {
+    /// `None` if borg did not report the state of the manifest
+    pub manifest_authenticated: Option<bool>,
+    pub unauthenticated_archives: Vec<ArchiveName>,
+}
+
+impl RepositoryFormat {
+    pub fn manifest_needs_upgrade(&self) -> bool {
+        self.manifest_authenticated == Some(false)
+    }
+
+    pub fn archives_need_upgrade(&self) -> bool {
+        !self.unauthenticated_archives.is_empty()
+    }
+
+    pub fn needs_upgrade(&self) -> bool {
+        self.manifest_needs_upgrade() || self.archives_need_upgrade()
+    }
 }
 
 pub async fn is_mounted(repo_id: &RepoId) -> bool {
@@ -449,12 +784,123 @@ pub async fn umount(repo_id: &RepoId) -> Result<()> {
     Ok(())
 }
 
+/// Close the SSH connections that have been kept open for reuse
+pub async fn close_ssh_connections() {
+    let control_dir = ssh_control_dir();
+
+    let Ok(sockets) = std::fs::read_dir(&control_dir) else {
+        return;
+    };
+
+    for socket in sockets.flatten() {
+        debug!("Closing shared SSH connection {:?}", socket.path());
+
+        // The host is not used since the control socket is given explicitly
+        let result = async_std::process::Command::new("ssh")
+            .arg("-o")
+            .arg(format!("ControlPath={}", socket.path().display()))
+            .args(["-O", "exit", "localhost"])
+            .output()
+            .await;
+
+        if let Err(err) = result {
+            warn!("Failed to close SSH connection: {}", err);
+        }
+
+        // Leftover from a connection that is already closed
+        let _ignore = std::fs::remove_file(socket.path());
+    }
+
+    if let Err(err) = async_std::fs::remove_dir(&control_dir).await {
+        debug!("Error when removing SSH control dir: {:?}", err);
+    }
+}
+
 pub fn mount_point(repo_id: &RepoId) -> std::path::PathBuf {
     let mut dir = mount_base_dir();
     dir.push(&format!("{:.8}", repo_id.as_str()));
     dir
 }
 
+/// Local cache borg keeps for a repository
+///
+/// Uses the same lookup as borg, including `BORG_CACHE_DIR` and `BORG_BASE_DIR`.
+pub fn cache_dir(repo_id: &RepoId) -> std::path::PathBuf {
+    let base = if let Some(dir) = std::env::var_os("BORG_CACHE_DIR") {
+        dir.into()
+    } else if let Some(dir) = std::env::var_os("BORG_BASE_DIR") {
+        std::path::Path::new(&dir).join(".cache").join("borg")
+    } else {
+        glib::user_cache_dir().join("borg")
+    };
+
+    base.join(repo_id.as_str())
+}
+
+/// Size of the local cache of a repository, if it exists
+pub fn cache_size(repo_id: &RepoId) -> Option<u64> {
+    let dir = cache_dir(repo_id);
+
+    if !dir.is_dir() {
+        return None;
+    }
+
+    Some(
+        walkdir::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum(),
+    )
+}
+
+/// Delete the local cache of a repository
+///
+/// Borg rebuilds the cache with the next operation that needs it.
+pub fn delete_cache(repo_id: &RepoId) -> std::io::Result<()> {
+    std::fs::remove_dir_all(cache_dir(repo_id))
+}
+
+/// Security information borg keeps about a repository
+///
+/// Uses the same lookup as borg, including `BORG_SECURITY_DIR`, `BORG_CONFIG_DIR`
+/// and `BORG_BASE_DIR`.
+pub fn security_dir(repo_id: &RepoId) -> std::path::PathBuf {
+    let base = if let Some(dir) = std::env::var_os("BORG_SECURITY_DIR") {
+        dir.into()
+    } else if let Some(dir) = std::env::var_os("BORG_CONFIG_DIR") {
+        std::path::Path::new(&dir).join("security")
+    } else if let Some(dir) = std::env::var_os("BORG_BASE_DIR") {
+        std::path::Path::new(&dir)
+            .join(".config")
+            .join("borg")
+            .join("security")
+    } else {
+        glib::user_config_dir().join("borg").join("security")
+    };
+
+    base.join(repo_id.as_str())
+}
+
+/// Delete the local cache and the last seen repository state
+///
+/// Afterwards, borg accepts the current state of the repository and rebuilds
+/// the cache from it. The other security information, like the used
+/// encryption nonces, is kept.
+pub fn reset_cache(repo_id: &RepoId) -> std::io::Result<()> {
+    let ignore_missing = |result: std::io::Result<()>| match result {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    };
+
+    ignore_missing(delete_cache(repo_id))?;
+    ignore_missing(std::fs::remove_file(
+        security_dir(repo_id).join("manifest-timestamp"),
+    ))
+}
+
 async fn prune_call<T: Task>(command: &Command<T>) -> Result<BorgCall> {
     if command.config.prune.keep.hourly < 1
         || command.config.prune.keep.daily < 1
@@ -475,6 +921,10 @@ async fn prune_call<T: Task>(command: &Command<T>) -> Result<BorgCall> {
         &format!("--keep-yearly={}", command.config.prune.keep.yearly),
     ]);
 
+    if command.config.prune.keep.last > 0 {
+        borg_call.add_options([&format!("--keep-last={}", command.config.prune.keep.last)]);
+    }
+
     Ok(borg_call)
 }
 
@@ -488,6 +938,47 @@ async fn delete_call<T: Task>(command: &Command<T>, archive_name: &str) -> Resul
     Ok(borg_call)
 }
 
+async fn create_call(command: &Command<task::Create>) -> BorgCall {
+    let mut borg_call = BorgCall::new("create");
+    borg_call
+        .add_options(["--progress", "--json"])
+        // Files for the live listing: added, modified and errors
+        // Unchanged files are left out since they are the bulk of most backups
+        .add_options(["--list", "--filter=AME"])
+        // Good and fast compression
+        // <https://gitlab.gnome.org/World/pika-backup/-/issues/51>
+        .add_options(&["--compression=zstd"])
+        .add_options(command.config.checkpoints.borg_arg())
+        .add_options(
+            command
+                .config
+                .skip_metadata
+                .borg_args(version::Version::detected().await),
+        );
+
+    // Reports the data transferred when the connection is closed
+    if matches!(command.config.repo, config::Repository::Remote(_)) {
+        borg_call.add_options(["--debug-topic=borg.remote"]);
+    }
+
+    borg_call
+}
+
+impl Command<task::Create> {
+    /// Command line of the next backup, without running it
+    ///
+    /// Does not access the password.
+    pub async fn preview(&self) -> CommandPreview {
+        let mut borg_call = create_call(self).await;
+        borg_call
+            .add_basics_without_password(self)
+            .add_archive(self)
+            .add_include_exclude(self);
+
+        borg_call.preview(self.config.encrypted)
+    }
+}
+
 async fn compact_call<T: Task>(command: &Command<T>) -> Result<BorgCall> {
     let mut borg_call = BorgCall::new("compact");
 
@@ -519,7 +1010,24 @@ impl CommandOnlyRepo {
             .await
     }
 
+    /// Briefly acquire the repository lock without waiting for other operations
+    ///
+    /// Does not require the encryption password.
+    pub async fn try_lock(self) -> Result<()> {
+        BorgCall::new("with-lock")
+            .add_options(["--lock-wait=1"])
+            .add_basics_without_password(&self)
+            .add_positional("true")
+            .output_generic()
+            .await
+    }
+
     pub async fn peek(self) -> Result<List> {
+        self.peek_abortable(Communication::default()).await
+    }
+
+    /// Can be aborted via the instructions of `communication`.
+    pub async fn peek_abortable(self, communication: Communication<task::Generic>) -> Result<List> {
         BorgCall::new("list")
             .add_options([
                 "--json",
@@ -532,7 +1040,7 @@ impl CommandOnlyRepo {
             ])
             .add_basics(&self)
             .await?
-            .output_generic()
+            .output_abortable(communication)
             .await
     }
 
@@ -550,6 +1058,20 @@ impl CommandOnlyRepo {
         Ok(())
     }
 
+    /// Only allow adding data to the repository
+    ///
+    /// Deleted archives stay recoverable until the mode is turned off again
+    /// and the repository is compacted.
+    pub async fn set_append_only(self, append_only: bool) -> Result<()> {
+        BorgCall::new("config")
+            .add_basics(&self)
+            .await?
+            .add_positional("append_only")
+            .add_positional(if append_only { "1" } else { "0" })
+            .output_generic::<()>()
+            .await
+    }
+
     pub async fn configure_free_space_if_required(self) -> Result<()> {
         if self.repo.is_filesystem() {
             let output = BorgCall::new("config")
@@ -568,17 +1090,30 @@ impl CommandOnlyRepo {
         Ok(())
     }
 
-    pub async fn init(self) -> Result<()> {
+    /// Create the repository
+    ///
+    /// Can be aborted via the instructions of `communication`.
+    pub async fn init(self, communication: Communication<task::Generic>) -> Result<()> {
         BorgCall::new("init")
             .add_options([format!("--encryption=repokey{}", fasted_hash_algorithm()).as_str()])
             .add_basics(&self)
             .await?
-            .output_generic::<()>()
+            .output_abortable::<_, ()>(communication)
             .await?;
 
         self.configure_free_space().await?;
         Ok(())
     }
+
+    /// Delete the whole repository including all archives
+    pub async fn delete_repository(self) -> Result<()> {
+        BorgCall::new("delete")
+            .add_envs([("BORG_DELETE_I_KNOW_WHAT_I_AM_DOING", "YES")])
+            .add_basics(&self)
+            .await?
+            .output_generic()
+            .await
+    }
 }
 
 pub async fn version() -> Result<String> {
@@ -589,3 +1124,42 @@ pub async fn version() -> Result<String> {
 
     Ok(String::from_utf8_lossy(&borg.output).trim().to_string())
 }
+
+#[test]
+fn test_prune_archive() {
+    assert_eq!(
+        PruneArchive::from_list_message(
+            "Would prune:                     xyz-ae4b2f46                         Mon, 2023-01-02 10:00:00 [0a8f3e]"
+        ),
+        Some(PruneArchive {
+            name: ArchiveName::new("xyz-ae4b2f46".into()),
+            time: chrono::NaiveDate::from_ymd_opt(2023, 1, 2)
+                .and_then(|date| date.and_hms_opt(10, 0, 0)),
+        })
+    );
+
+    assert_eq!(
+        PruneArchive::from_list_message("Would prune: xyz-ae4b2f46"),
+        Some(PruneArchive {
+            name: ArchiveName::new("xyz-ae4b2f46".into()),
+            time: None,
+        })
+    );
+
+    assert_eq!(
+        PruneArchive::from_list_message("Keeping archive (rule: daily #1): xyz-ae4b2f46"),
+        None
+    );
+
+    assert_eq!(
+        PruneArchive::from_checkpoint_message(
+            "Keeping checkpoint archive: xyz-ae4b2f46.checkpoint Mon, 2023-01-02 10:00:00 [0a8f3e]"
+        )
+        .map(|archive| archive.name),
+        Some(ArchiveName::new("xyz-ae4b2f46.checkpoint".into()))
+    );
+    assert_eq!(
+        PruneArchive::from_list_message("Keeping checkpoint archive: xyz-ae4b2f46.checkpoint"),
+        None
+    );
+}
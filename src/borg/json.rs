@@ -63,9 +63,20 @@ impl ArchiveName {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Stats {
     pub archive: NewArchive,
+    /// Unknown for runs from older versions
+    #[serde(default)]
+    pub cache: Option<Cache>,
 }
 
 impl Stats {
+    /// Estimated data sent to the repository
+    ///
+    /// The deduplicated size of this archive. It does not include metadata and
+    /// is therefore a lower bound of the data actually sent.
+    pub fn estimated_sent(&self) -> u64 {
+        self.archive.stats.deduplicated_size
+    }
+
     pub fn transfer_history_mock(archive: &ListArchive) -> Self {
         Stats {
             archive: NewArchive {
@@ -79,6 +90,7 @@ impl Stats {
                     original_size: 0,
                 },
             },
+            cache: None,
         }
     }
 
@@ -98,6 +110,7 @@ impl Stats {
                     original_size: 3124637266,
                 },
             },
+            cache: None,
         }
     }
 
@@ -115,6 +128,7 @@ impl Stats {
                     original_size: 0,
                 },
             },
+            cache: None,
         }
     }
 }
@@ -135,6 +149,19 @@ pub struct NewArchiveSize {
     pub original_size: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Cache {
+    pub stats: CacheStats,
+}
+
+/// Sizes of all archives in the repository
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CacheStats {
+    pub total_size: u64,
+    /// Size of the repository after deduplication and compression
+    pub unique_csize: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct List {
     pub archives: Vec<ListArchive>,
@@ -142,7 +169,7 @@ pub struct List {
     pub repository: Repository,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ListArchive {
     pub id: ArchiveId,
     pub name: ArchiveName,
@@ -154,6 +181,56 @@ pub struct ListArchive {
     pub command_line: Vec<String>,
 }
 
+impl ListArchive {
+    /// Whether the archive was created on this computer
+    pub fn is_from_this_computer(&self) -> bool {
+        self.hostname == glib::host_name()
+    }
+}
+
+/// Archive authentication from `borg list --format={name}{tam}`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListTam {
+    pub archives: Vec<ListArchiveTam>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListArchiveTam {
+    pub name: ArchiveName,
+    /// Only available since borg 1.2.5
+    #[serde(default)]
+    pub tam: Option<String>,
+}
+
+impl ListArchiveTam {
+    pub fn is_authenticated(&self) -> bool {
+        self.tam.as_deref() != Some("none")
+    }
+}
+
+/// Entry of the archive content from `borg list --json-lines`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveFile {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub size: u64,
+    /// Owner names, unknown for entries indexed by older versions
+    #[serde(default)]
+    pub user: String,
+    #[serde(default)]
+    pub group: String,
+    /// Modification time, unknown for entries indexed by older versions
+    #[serde(default)]
+    pub mtime: Option<chrono::naive::NaiveDateTime>,
+}
+
+impl ArchiveFile {
+    pub fn is_dir(&self) -> bool {
+        self.type_ == "d"
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Info {
     pub archives: Vec<InfoArchive>,
@@ -38,6 +38,8 @@ pub enum Progress {
     QuestionAcceptedFalse,
     #[serde(rename = "question_accepted_true")]
     QuestionAcceptedTrue,
+    #[serde(rename = "file_status")]
+    FileStatus(FileStatus),
 }
 
 impl fmt::Display for Progress {
@@ -53,11 +55,12 @@ impl fmt::Display for Progress {
             Self::QuestionAcceptedTrue => {
                 write!(f, "{}", gettext("Backup will continue."))
             }
+            Self::FileStatus(file) => write!(f, "{file}"),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ProgressArchive {
     #[serde(default)]
     pub original_size: u64,
@@ -86,6 +89,20 @@ impl fmt::Display for ProgressArchive {
     }
 }
 
+/// File processed by `borg create --list`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct FileStatus {
+    /// Letter like `A` for added, `M` for modified or `E` for errors
+    pub status: String,
+    pub path: String,
+}
+
+impl fmt::Display for FileStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.path)
+    }
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct ProgressPercent {
     #[serde(default)]
@@ -300,14 +317,26 @@ pub struct LogMessage {
     pub msgid: MsgId,
 }
 
+impl LogMessage {
+    /// Message as reported by borg, with the id if there is one
+    fn raw(&self) -> String {
+        if matches!(self.msgid, MsgId::Undefined) {
+            self.message.clone()
+        } else {
+            format!("{} – {}", self.msgid, self.message)
+        }
+    }
+}
+
 impl std::fmt::Display for LogMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         if self.message.is_empty() {
             write!(f, "")
-        } else if matches!(self.msgid, MsgId::Undefined) {
-            write!(f, "{}: {}", self.levelname, self.message)
+        } else if let Some(explanation) = super::explain::explain(&self.msgid, &self.message) {
+            // The original message is kept for error reports
+            write!(f, "{}: {} ({})", self.levelname, explanation, self.raw())
         } else {
-            write!(f, "{}: {} – {}", self.levelname, self.msgid, self.message)
+            write!(f, "{}: {}", self.levelname, self.raw())
         }
     }
 }
@@ -350,6 +379,15 @@ impl LogEntry {
         }
     }
 
+    /// Localized explanation of the message if available, otherwise the message
+    pub fn explained_message(&self) -> String {
+        match self {
+            Self::ParsedErr(message) => super::explain::explain(&message.msgid, &message.message)
+                .unwrap_or_else(|| message.message.clone()),
+            Self::UnparsableErr(message) => message.clone(),
+        }
+    }
+
     pub fn id(&self) -> Option<MsgId> {
         match self {
             Self::ParsedErr(message) => Some(message.msgid.clone()),
@@ -374,6 +412,48 @@ impl LogEntry {
         false
     }
 
+    /// Path of a file that borg reported to have changed while it was read
+    pub fn changed_file(&self) -> Option<std::path::PathBuf> {
+        match self {
+            Self::ParsedErr(message) if message.levelname == LogLevel::Warning => message
+                .message
+                .trim_end()
+                .strip_suffix(": file changed while we backed it up")
+                .map(std::path::PathBuf::from),
+            _ => None,
+        }
+    }
+
+    /// Whether the manifest is authenticated, from the debug output of borg
+    pub fn manifest_tam(&self) -> Option<bool> {
+        let message = self.message();
+
+        if message.contains("TAM-verified manifest") {
+            Some(true)
+        } else if message.contains("Manifest TAM not found and not required") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Data transferred over the connection to a remote repository
+    ///
+    /// Reported by borg when the connection is closed if the debug output of the
+    /// `borg.remote` topic is enabled.
+    pub fn network_usage(&self) -> Option<NetworkUsage> {
+        let message = self.message();
+        let (sent, rest) = message
+            .strip_prefix("RemoteRepository: ")?
+            .split_once(" bytes sent, ")?;
+        let (received, _) = rest.split_once(" bytes received")?;
+
+        Some(NetworkUsage {
+            sent: parse_file_size(sent)?,
+            received: parse_file_size(received)?,
+        })
+    }
+
     pub fn is_ignored(&self) -> bool {
         self.message()
             .contains("By default repositories initialized with this version will produce security")
@@ -394,6 +474,36 @@ impl LogEntry {
     }
 }
 
+/// Data in bytes transferred to and from a remote repository
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NetworkUsage {
+    pub sent: u64,
+    pub received: u64,
+}
+
+impl std::ops::Add for NetworkUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            sent: self.sent + other.sent,
+            received: self.received + other.received,
+        }
+    }
+}
+
+/// Parse sizes like `1.23 MB` as formatted by borg with decimal prefixes
+fn parse_file_size(size: &str) -> Option<u64> {
+    const PREFIXES: [&str; 9] = ["", "k", "M", "G", "T", "P", "E", "Z", "Y"];
+
+    let (number, unit) = size.trim().split_once(' ')?;
+    let prefix = unit.strip_suffix('B')?;
+    let exponent = PREFIXES.iter().position(|x| *x == prefix)?;
+    let number = number.parse::<f64>().ok()?;
+
+    Some((number * 1000_f64.powi(exponent as i32)).round() as u64)
+}
+
 pub type LogCollection = Vec<LogEntry>;
 
 pub trait LogExt {
@@ -401,6 +511,8 @@ pub trait LogExt {
     fn to_string(&self) -> String;
     fn filter_handled(self) -> Self;
     fn filter_hidden(self) -> Self;
+    fn changed_files(&self) -> std::collections::BTreeSet<std::path::PathBuf>;
+    fn network_usage(&self) -> Option<NetworkUsage>;
 }
 
 impl LogExt for LogCollection {
@@ -408,9 +520,10 @@ impl LogExt for LogCollection {
         self.iter().map(|e| e.level()).max()
     }
 
+    /// Control characters are escaped for display
     fn to_string(&self) -> String {
         self.iter()
-            .map(|m| format!("{}", &m))
+            .map(|m| super::escape_control_chars(&m.to_string()))
             .collect::<Vec<String>>()
             .join("\n")
     }
@@ -429,11 +542,26 @@ impl LogExt for LogCollection {
     }
 
     /// Connection errors are not filtered from output
+    ///
+    /// Debug output is only requested to collect information like the network usage.
     fn filter_hidden(self) -> Self {
         self.into_iter()
-            .filter(|x| !matches!(x.id(), Some(MsgId::PassphraseWrong)))
+            .filter(|x| {
+                !matches!(x.id(), Some(MsgId::PassphraseWrong)) && x.level() != LogLevel::Debug
+            })
             .collect()
     }
+
+    fn changed_files(&self) -> std::collections::BTreeSet<std::path::PathBuf> {
+        self.iter().filter_map(LogEntry::changed_file).collect()
+    }
+
+    /// Sum of all connections to remote repositories
+    fn network_usage(&self) -> Option<NetworkUsage> {
+        self.iter()
+            .filter_map(LogEntry::network_usage)
+            .reduce(|a, b| a + b)
+    }
 }
 
 impl std::fmt::Display for LogEntry {
@@ -480,3 +608,119 @@ impl std::fmt::Display for LogLevel {
         write!(f, "{text}")
     }
 }
+
+#[test]
+fn test_changed_files() {
+    let entry = |levelname, message: &str| {
+        LogEntry::ParsedErr(LogMessage {
+            levelname,
+            name: String::from("borg.archiver"),
+            message: message.to_string(),
+            msgid: MsgId::Undefined,
+        })
+    };
+
+    let messages: LogCollection = vec![
+        entry(
+            LogLevel::Warning,
+            "/home/user/log.txt: file changed while we backed it up",
+        ),
+        entry(
+            LogLevel::Warning,
+            "/home/user/log.txt: file changed while we backed it up\n",
+        ),
+        entry(
+            LogLevel::Warning,
+            "/home/user/secret: [Errno 13] Permission denied",
+        ),
+        entry(
+            LogLevel::Info,
+            "/home/user/x: file changed while we backed it up",
+        ),
+    ];
+
+    assert_eq!(
+        messages.changed_files(),
+        std::collections::BTreeSet::from(["/home/user/log.txt".into()])
+    );
+}
+
+#[test]
+fn test_manifest_tam() {
+    let entry = |message: &str| {
+        LogEntry::ParsedErr(LogMessage {
+            levelname: LogLevel::Debug,
+            name: String::from("borg.crypto.key"),
+            message: message.to_string(),
+            msgid: MsgId::Undefined,
+        })
+    };
+
+    assert_eq!(entry("TAM-verified manifest").manifest_tam(), Some(true));
+    assert_eq!(
+        entry("Manifest TAM not found and not required").manifest_tam(),
+        Some(false)
+    );
+    assert_eq!(
+        entry("Verified integrity of /repo/index.5").manifest_tam(),
+        None
+    );
+}
+
+#[test]
+fn test_network_usage() {
+    let entry = |message: &str| {
+        LogEntry::ParsedErr(LogMessage {
+            levelname: LogLevel::Debug,
+            name: String::from("borg.remote"),
+            message: message.to_string(),
+            msgid: MsgId::Undefined,
+        })
+    };
+
+    assert_eq!(
+        entry("RemoteRepository: 1.23 MB bytes sent, 456 B bytes received, 42 messages sent")
+            .network_usage(),
+        Some(NetworkUsage {
+            sent: 1_230_000,
+            received: 456
+        })
+    );
+    assert_eq!(
+        entry("SSH command line: ['ssh', 'host']").network_usage(),
+        None
+    );
+
+    let collection = vec![
+        entry("RemoteRepository: 2.00 kB bytes sent, 1.50 kB bytes received, 3 messages sent"),
+        entry("RemoteRepository: 1.00 kB bytes sent, 10 B bytes received, 2 messages sent"),
+    ];
+    assert_eq!(
+        LogExt::network_usage(&collection),
+        Some(NetworkUsage {
+            sent: 3000,
+            received: 1510
+        })
+    );
+    assert!(collection.filter_hidden().is_empty());
+}
+
+#[test]
+fn test_file_status() {
+    let progress = serde_json::from_str::<Progress>(
+        r#"{"type": "file_status", "status": "M", "path": "home/user/notes.txt"}"#,
+    )
+    .unwrap();
+
+    let Progress::FileStatus(file) = progress else {
+        panic!("Not parsed as file status: {progress:?}");
+    };
+    assert_eq!(
+        file,
+        FileStatus {
+            status: "M".to_string(),
+            path: "home/user/notes.txt".to_string(),
+        }
+    );
+    assert_eq!(file.to_string(), "M home/user/notes.txt");
+}
@@ -0,0 +1,164 @@
+//! Lock files that coordinate operations on a repository between processes
+//!
+//! Every operation holds an exclusive `flock` on a file named after the
+//! repository id. The lock is released by the kernel when the process exits,
+//! such that a crashed process never leaves a stale lock behind.
+
+use crate::prelude::*;
+
+use std::io::{Read, Seek, Write};
+use std::os::fd::AsRawFd;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use super::RepoId;
+
+/// Unique D-Bus name of this process, recorded in the locks it holds
+static BUS_NAME: OnceLock<String> = OnceLock::new();
+
+/// Describes the operation that currently holds the lock
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    pub pid: u32,
+    pub config_id: ConfigId,
+    pub operation: String,
+    /// Unique D-Bus name of the process to query the operation status from
+    #[serde(default)]
+    pub bus_name: Option<String>,
+}
+
+/// Exclusive lock for operations on a repository, released on drop
+#[derive(Debug)]
+pub struct OperationLock {
+    _file: std::fs::File,
+}
+
+/// Record the unique D-Bus name of this process in locks acquired afterwards
+pub fn set_bus_name(name: String) {
+    if BUS_NAME.set(name).is_err() {
+        warn!("D-Bus name for operation locks already set");
+    }
+}
+
+pub fn lock_dir() -> PathBuf {
+    crate::utils::host::user_runtime_dir()
+        .join(env!("CARGO_PKG_NAME"))
+        .join("operations")
+}
+
+fn lock_path(repo_id: &RepoId) -> PathBuf {
+    lock_dir().join(repo_id.as_str())
+}
+
+fn open(repo_id: &RepoId) -> std::io::Result<std::fs::File> {
+    std::fs::create_dir_all(lock_dir())?;
+
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path(repo_id))
+}
+
+fn try_flock(file: &std::fs::File) -> std::io::Result<bool> {
+    match nix::fcntl::flock(
+        file.as_raw_fd(),
+        nix::fcntl::FlockArg::LockExclusiveNonblock,
+    ) {
+        Ok(()) => Ok(true),
+        Err(nix::errno::Errno::EWOULDBLOCK) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn read_holder(mut file: &std::fs::File) -> Option<Holder> {
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+impl OperationLock {
+    /// Acquire the lock or return the operation that currently holds it
+    ///
+    /// Failing to access the lock file is not treated as an error since it
+    /// should never prevent operations from running.
+    pub fn try_acquire(
+        repo_id: &RepoId,
+        config_id: &ConfigId,
+        operation: &str,
+    ) -> std::result::Result<Option<Self>, Holder> {
+        let result = open(repo_id).and_then(|file| Ok((try_flock(&file)?, file)));
+
+        match result {
+            Ok((true, mut file)) => {
+                let holder = Holder {
+                    pid: std::process::id(),
+                    config_id: config_id.clone(),
+                    operation: operation.to_string(),
+                    bus_name: BUS_NAME.get().cloned(),
+                };
+
+                if let Err(err) = file
+                    .set_len(0)
+                    .and_then(|_| file.rewind())
+                    .and_then(|_| Ok(serde_json::to_writer(&file, &holder)?))
+                    .and_then(|_| file.flush())
+                {
+                    warn!("Failed to write operation lock file: {}", err);
+                }
+
+                Ok(Some(Self { _file: file }))
+            }
+            Ok((false, file)) => Err(read_holder(&file).unwrap_or_else(|| Holder {
+                pid: 0,
+                config_id: config_id.clone(),
+                operation: String::new(),
+                bus_name: None,
+            })),
+            Err(err) => {
+                warn!(
+                    "Failed to acquire operation lock for {:?}: {}",
+                    repo_id, err
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// The operation running on the repository in another process, if any
+///
+/// Operations of this process are tracked separately and not reported.
+pub fn holder(repo_id: &RepoId) -> Option<Holder> {
+    let file = std::fs::File::open(lock_path(repo_id)).ok()?;
+
+    match try_flock(&file) {
+        Ok(false) => read_holder(&file).filter(|holder| holder.pid != std::process::id()),
+        // The lock is released together with the file
+        _ => None,
+    }
+}
+
+#[test]
+fn test_operation_lock() {
+    let repo_id = RepoId::new(format!("test-lock-{}", std::process::id()));
+    let config_id = ConfigId::new("config".into());
+
+    let lock = OperationLock::try_acquire(&repo_id, &config_id, "Backup").unwrap();
+    assert!(lock.is_some());
+
+    // A second open file description conflicts even within the same process
+    let holder = OperationLock::try_acquire(&repo_id, &config_id, "Prune").unwrap_err();
+    assert_eq!(holder.operation, "Backup");
+    assert_eq!(holder.pid, std::process::id());
+    // The lock of this process is not an operation in the background
+    assert_eq!(self::holder(&repo_id), None);
+
+    drop(lock);
+    assert!(OperationLock::try_acquire(&repo_id, &config_id, "Prune")
+        .unwrap()
+        .is_some());
+
+    let _ = std::fs::remove_file(lock_path(&repo_id));
+}
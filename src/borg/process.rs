@@ -8,6 +8,7 @@ use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::io::Write;
+use std::os::unix::fs::DirBuilderExt;
 use std::os::unix::io::AsRawFd;
 use std::os::unix::net::UnixStream;
 use std::time::Duration;
@@ -22,12 +23,36 @@ use super::Task;
 use super::{BorgRunConfig, Command, Error, Result, USER_INTERACTION_TIME};
 use crate::config;
 
+/// Transient systemd scopes can't be created from within the Flatpak sandbox
+fn systemd_scope_available() -> bool {
+    !*crate::globals::APP_IS_SANDBOXED && glib::find_program_in_path("systemd-run").is_some()
+}
+
 /// Return raw stdout from `BorgCall` instead JSON decoding it
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RawOutput {
     pub output: Vec<u8>,
 }
 
+/// Pass stdout line by line to [`Communication::item_receiver`] while the process is running
+///
+/// Avoids holding the complete output of commands like `borg list --json-lines`
+/// in memory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonLines {
+    /// Number of non-empty lines
+    pub lines: u64,
+}
+
+/// Pass the elements of top level arrays to [`Communication::item_receiver`]
+///
+/// Used for commands like `borg list --json` that don't support JSON lines.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonArray {
+    /// Number of array elements
+    pub items: u64,
+}
+
 /// Manages calling borg
 ///
 /// Spawning one `BorgCall`` can involve multiple successive `BorgProcess`es to
@@ -40,6 +65,7 @@ pub struct BorgCall {
     envs: std::collections::BTreeMap<String, String>,
     pub positional: Vec<OsString>,
     password: config::Password,
+    performance: config::Performance,
 }
 
 impl std::fmt::Debug for BorgCall {
@@ -58,6 +84,64 @@ impl std::fmt::Debug for BorgCall {
     }
 }
 
+/// Command line and environment of a [`BorgCall`] with passwords redacted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandPreview {
+    pub args: Vec<String>,
+    pub envs: Vec<(String, String)>,
+}
+
+impl CommandPreview {
+    /// Command line that can be pasted into a shell
+    pub fn shell_line(&self) -> String {
+        self.envs
+            .iter()
+            .map(|(var, value)| format!("{var}={}", shell_quote(value)))
+            .chain(self.args.iter().map(|arg| shell_quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_=/.,:@%+".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Command used by borg to connect to remote repositories
+fn rsh(host_key_policy: config::remote::HostKeyPolicy) -> String {
+    // Avoid hangs from ssh asking for passwords via stdin
+    // https://borgbackup.readthedocs.io/en/stable/usage/notes.html#ssh-batch-mode
+    ["ssh", "-o", "BatchMode=yes"]
+        .into_iter()
+        .chain(host_key_policy.ssh_options())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Shared SSH connections are closed after being unused for this long
+const SSH_CONTROL_PERSIST: &str = "10m";
+
+/// Options for `ssh` to share one connection per server between borg calls
+fn ssh_control_options(control_dir: &std::path::Path) -> Vec<String> {
+    vec![
+        "-o".into(),
+        "ControlMaster=auto".into(),
+        "-o".into(),
+        format!("ControlPersist={SSH_CONTROL_PERSIST}"),
+        "-o".into(),
+        // '%C' is a hash of the connection parameters and keeps the path short
+        shell_quote(&format!("ControlPath={}/%C", control_dir.display())),
+    ]
+}
+
 pub struct Process<T> {
     pub result: async_std::task::JoinHandle<Result<T>>,
 }
@@ -66,16 +150,36 @@ impl BorgCall {
     pub fn new(command: impl Into<OsString>) -> Self {
         Self {
             command: Some(command.into()),
-            options: vec![
-                "--rsh".into(),
-                // Avoid hangs from ssh asking for passwords via stdin
-                // https://borgbackup.readthedocs.io/en/stable/usage/notes.html#ssh-batch-mode
-                "ssh -o BatchMode=yes -o StrictHostKeyChecking=accept-new".into(),
-            ],
+            options: vec!["--rsh".into(), rsh(Default::default()).into()],
             ..Self::default()
         }
     }
 
+    /// Replace the host key verification of the `--rsh` command
+    pub fn set_host_key_policy(&mut self, policy: config::remote::HostKeyPolicy) -> &mut Self {
+        if let Some(index) = self.options.iter().position(|x| x == "--rsh") {
+            if let Some(command) = self.options.get_mut(index + 1) {
+                *command = rsh(policy).into();
+            }
+        }
+
+        self
+    }
+
+    /// Share the SSH connection with other borg calls via a control socket
+    pub fn set_reuse_connection(&mut self, control_dir: &std::path::Path) -> &mut Self {
+        if let Some(index) = self.options.iter().position(|x| x == "--rsh") {
+            if let Some(command) = self.options.get_mut(index + 1) {
+                for option in ssh_control_options(control_dir) {
+                    command.push(" ");
+                    command.push(option);
+                }
+            }
+        }
+
+        self
+    }
+
     pub fn new_raw() -> Self {
         Self::default()
     }
@@ -130,6 +234,11 @@ impl BorgCall {
                 }
             }
         }
+        for file in borg.config.patterns_files.iter().filter(|file| file.linked) {
+            let mut arg = OsString::from("--patterns-from=");
+            arg.push(&file.path);
+            self.add_options(vec![arg]);
+        }
         self.positional.extend(
             borg.config
                 .include_dirs()
@@ -142,16 +251,16 @@ impl BorgCall {
 
     pub fn add_archive<T: Task>(&mut self, borg: &Command<T>) -> &mut Self {
         let random_str = glib::uuid_string_random();
-        let arg = format!(
-            "{repo}::{archive_prefix}{archive}",
-            repo = borg.config.repo,
+        let mut arg = borg.config.repo.borg_location();
+        arg.push(format!(
+            "::{archive_prefix}{archive}",
             archive_prefix = borg.config.archive_prefix,
             archive = random_str.get(..8).unwrap_or(&random_str)
-        );
+        ));
         if let Some(first) = self.positional.first_mut() {
-            *first = arg.into();
+            *first = arg;
         } else {
-            self.add_positional(&arg);
+            self.add_positional(arg);
         }
 
         self
@@ -161,6 +270,14 @@ impl BorgCall {
         if let Some(ref password) = borg.password() {
             debug!("Using password enforced by explicitly passed password");
             self.password = password.clone();
+        } else if let Some((config, command)) = borg.try_config().and_then(|config| {
+            let command = config.password_command.clone()?;
+            Some((config, command))
+        }) {
+            debug!("Using password from the configured command");
+            self.password = crate::utils::password::from_command(&config, &command)
+                .await
+                .map_err(Error::PasswordCommand)?;
         } else if borg.is_encrypted() {
             debug!("Config says the backup is encrypted");
             if let Some(config) = borg.try_config() {
@@ -173,6 +290,10 @@ impl BorgCall {
                             keyring_error: None,
                         },
                     ) => Err(err)?,
+                    // keyring is available but has to be unlocked first
+                    Err(Error::PasswordKeyringLocked) => crate::globals::MEMORY_PASSWORD_STORE
+                        .load_password(&config)
+                        .ok_or(Error::PasswordKeyringLocked)?,
                     // keyring unavailable
                     Err(err) => {
                         warn!("Error using keyring, using in-memory password store. Keyring error: '{err:?}'");
@@ -202,18 +323,20 @@ impl BorgCall {
     }
 
     async fn get_password_keyring(&self, repo_id: &super::RepoId) -> Result<config::Password> {
-        Ok(config::Password::from(
-            oo7::Keyring::new()
-                .await?
-                .search_items(HashMap::from([("repo-id", repo_id.as_str())]))
-                .await?
-                .first()
-                .ok_or(Error::PasswordMissing {
-                    keyring_error: None,
-                })?
-                .secret()
-                .await?,
-        ))
+        let items = oo7::Keyring::new()
+            .await?
+            .search_items(HashMap::from([("repo-id", repo_id.as_str())]))
+            .await?;
+
+        let item = items.first().ok_or(Error::PasswordMissing {
+            keyring_error: None,
+        })?;
+
+        if item.is_locked().await? {
+            return Err(Error::PasswordKeyringLocked);
+        }
+
+        Ok(config::Password::from(item.secret().await?))
     }
 
     fn stream_password(&self, command: &mut async_process::Command) -> Result<UnixStream> {
@@ -243,11 +366,53 @@ impl BorgCall {
         Ok(pipe_reader)
     }
 
+    /// Refer to an existing archive instead of the whole repository
+    pub fn add_existing_archive<T: BorgRunConfig>(
+        &mut self,
+        borg: &T,
+        archive_name: &str,
+    ) -> &mut Self {
+        let mut arg = borg.repo().borg_location();
+        arg.push("::");
+        arg.push(archive_name);
+        self.positional.insert(0, arg);
+
+        self
+    }
+
     pub fn add_basics_without_password<T: BorgRunConfig>(&mut self, borg: &T) -> &mut Self {
         self.add_options(&["--log-json"]);
 
+        if let Some(config) = borg.try_config() {
+            self.performance =
+                if config.low_priority_in_power_saver && crate::utils::power_saver_enabled() {
+                    config::Performance::Low
+                } else {
+                    config.performance
+                };
+        }
+
+        if let Some(policy) = borg.repo().host_key_policy() {
+            self.set_host_key_policy(policy);
+        }
+
+        if borg.repo().reuse_connection() {
+            let control_dir = utils::ssh_control_dir();
+            let created = std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(&control_dir);
+
+            match created {
+                Ok(()) => {
+                    self.set_reuse_connection(&control_dir);
+                }
+                Err(err) => warn!("Not reusing SSH connection: {}", err),
+            }
+        }
+
         if self.positional.is_empty() {
-            self.add_positional(&borg.repo().to_string());
+            self.add_positional(borg.repo().borg_location());
         }
 
         self.add_options(
@@ -277,8 +442,50 @@ impl BorgCall {
         args
     }
 
+    /// The command that would be run, for showing it to the user
+    ///
+    /// The password is replaced by a placeholder if `encrypted`.
+    pub fn preview(&self, encrypted: bool) -> CommandPreview {
+        let mut envs = self.envs.clone();
+
+        for (var, value) in &mut envs {
+            if var.contains("PASSPHRASE") {
+                *value = "***".to_string();
+            }
+        }
+
+        if encrypted {
+            envs.insert("BORG_PASSPHRASE".to_string(), "***".to_string());
+        }
+
+        let mut args: Vec<String> = self
+            .performance
+            .wrapper(systemd_scope_available())
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        args.push("borg".to_string());
+        args.extend(
+            self.args()
+                .iter()
+                .map(|arg| arg.to_string_lossy().to_string()),
+        );
+
+        CommandPreview {
+            args,
+            envs: envs.into_iter().collect(),
+        }
+    }
+
     pub(super) fn command(&self) -> Result<(async_process::Command, UnixStream)> {
-        let mut cmd = async_process::Command::new("borg");
+        let wrapper = self.performance.wrapper(systemd_scope_available());
+        let mut cmd = if let Some((program, args)) = wrapper.split_first() {
+            let mut cmd = async_process::Command::new(program);
+            cmd.args(args).arg("borg");
+            cmd
+        } else {
+            async_process::Command::new("borg")
+        };
 
         let unix_stream = self.stream_password(&mut cmd)?;
 
@@ -299,7 +506,20 @@ impl BorgCall {
     >(
         &self,
     ) -> Result<S> {
-        let communication = Communication::<super::task::Generic>::default();
+        self.output_abortable(Communication::<super::task::Generic>::default())
+            .await
+    }
+
+    /// Calls the borg command like [`Self::output_generic`]
+    ///
+    /// The process can be aborted via the instructions of `communication`.
+    pub async fn output_abortable<
+        T: Task,
+        S: std::fmt::Debug + serde::de::DeserializeOwned + Send + Sync + 'static,
+    >(
+        &self,
+        communication: Communication<T>,
+    ) -> Result<S> {
         let sender = communication.new_sender();
 
         let managed_process = BorgProcess::new(self, communication, sender).await?;
@@ -494,18 +714,57 @@ impl<'a, T: Task> BorgProcess<'a, T> {
             .ok_or_else(|| String::from("Failed to get stdin"))?;
 
         let mut stdout_content = Vec::new();
+        let stream_lines = TypeId::of::<S>() == TypeId::of::<JsonLines>();
+        let stream_array = TypeId::of::<S>() == TypeId::of::<JsonArray>();
+
+        let read_stdout = async {
+            let mut items = 0;
+
+            // Keep reading even if nobody receives the items to avoid pipe stall
+            if stream_lines {
+                let mut line = Vec::new();
+                while stdout.read_until(b'\n', &mut line).await? > 0 {
+                    if line.iter().any(|byte| !byte.is_ascii_whitespace()) {
+                        if let Err(err) = self.sender.send_item(line.clone()).await {
+                            debug!("Failed to pass on stdout line: {}", err);
+                        }
+                        items += 1;
+                    }
+                    line.clear();
+                }
+            } else if stream_array {
+                let mut splitter = utils::ArrayItems::default();
+                let mut buffer = vec![0; 64 * 1024];
+                loop {
+                    let len = stdout.read(&mut buffer).await?;
+                    if len == 0 {
+                        break;
+                    }
+
+                    for item in splitter.push(&buffer[..len]) {
+                        if let Err(err) = self.sender.send_item(item).await {
+                            debug!("Failed to pass on array element: {}", err);
+                        }
+                        items += 1;
+                    }
+                }
+            } else {
+                let _ = stdout.read_to_end(&mut stdout_content).await;
+            }
+
+            Ok::<_, Error>(items)
+        };
 
         // Handle stderr and collect stdout to avoid pipe stall
-        let (return_message, _) = futures::join!(
-            self.handle_stderr(stderr, stdin, process.id()),
-            stdout.read_to_end(&mut stdout_content)
-        );
+        let (return_message, stdout_result) =
+            futures::join!(self.handle_stderr(stderr, stdin, process.id()), read_stdout);
 
         let status: async_process::ExitStatus = process.status().await?;
         debug!("Process terminated");
 
         // Return with potential errors from stderr handling
         return_message?;
+        let items = stdout_result?;
 
         // Don't JSON decode some return types
         let result: Result<Box<S>> = if TypeId::of::<S>() == TypeId::of::<()>() {
@@ -518,6 +777,16 @@ impl<'a, T: Task> BorgProcess<'a, T> {
             }) as Box<dyn Any>)
                 .downcast()
                 .unwrap())
+        } else if stream_lines {
+            // Lines have already been passed on
+            Ok((Box::new(JsonLines { lines: items }) as Box<dyn Any>)
+                .downcast()
+                .unwrap())
+        } else if stream_array {
+            // Elements have already been passed on
+            Ok((Box::new(JsonArray { items }) as Box<dyn Any>)
+                .downcast()
+                .unwrap())
         } else {
             // JSON decode for all other return types
             serde_json::from_slice(&stdout_content).map_err(Into::into)
@@ -559,22 +828,44 @@ impl<'a, T: Task> BorgProcess<'a, T> {
         let mut unresponsive = Duration::ZERO;
         let mut stderr_line = String::new();
 
+        let started = std::time::Instant::now();
+        let mut injected_failure = super::fault_injection::Failure::configured();
+
         loop {
             // react to instructions before potentially listening for messages again
 
             match &**self.communication.instruction.load() {
                 Instruction::Abort(ref reason) => {
-                    self.communication.set_status(Run::Stopping);
                     debug!("Sending SIGINT to borg process");
-                    nix::sys::signal::kill(
-                        nix::unistd::Pid::from_raw(pid.try_into().unwrap()),
-                        nix::sys::signal::Signal::SIGINT,
-                    )?;
+                    stop(pid, &self.communication)?;
                     // Do not return immediately to get further progress information
                     // and be able to send signal again.
                     return_message = Err(Error::Aborted(reason.clone()));
                     self.communication.set_instruction(Instruction::Nothing);
                 }
+                Instruction::SkipPath(path) | Instruction::SkipIncludeRoot(path) => {
+                    debug!("Sending SIGINT to borg process to skip {:?}", path);
+                    stop(pid, &self.communication)?;
+                    return_message = Err(Error::Aborted(Abort::Skip(path.clone())));
+                    self.communication.set_instruction(Instruction::Nothing);
+                }
+                Instruction::Pause => {
+                    if !matches!(self.communication.status(), Run::Stopping) {
+                        debug!("Sending SIGSTOP to borg process");
+                        send_signal(pid, nix::sys::signal::Signal::SIGSTOP)?;
+                        self.communication.set_status(Run::Paused);
+                    }
+                    self.communication.set_instruction(Instruction::Nothing);
+                }
+                Instruction::Resume => {
+                    if matches!(self.communication.status(), Run::Paused) {
+                        debug!("Sending SIGCONT to borg process");
+                        send_signal(pid, nix::sys::signal::Signal::SIGCONT)?;
+                        unresponsive = Duration::ZERO;
+                        self.communication.set_status(Run::Running);
+                    }
+                    self.communication.set_instruction(Instruction::Nothing);
+                }
                 Instruction::Response(response) => {
                     warn!("Sending response “{response}” to borg process");
                     stdin.write_all(format!("{response}\n").as_bytes()).await?;
@@ -583,6 +874,21 @@ impl<'a, T: Task> BorgProcess<'a, T> {
                 Instruction::Nothing => {}
             }
 
+            if let Some(failure) = injected_failure.filter(|x| x.is_due(started.elapsed())) {
+                if let Some(line) = failure.log_line() {
+                    warn!("Injecting failure {:?} into borg output", failure.kind);
+                    injected_failure = None;
+
+                    let msg = utils::check_line(&line);
+                    self.communication.general_info.update(|status| {
+                        status.add_message(&msg);
+                    });
+                    self.sender.send(log_json::Output::LogEntry(msg)).await?;
+
+                    send_signal(pid, nix::sys::signal::Signal::SIGTERM)?;
+                }
+            }
+
             stderr_line.clear();
             // Listen to stderr with timeout to also handle instructions in-between
             let stderr_result = async_std::io::timeout(
@@ -594,6 +900,10 @@ impl<'a, T: Task> BorgProcess<'a, T> {
             match stderr_result {
                 // nothing new to read
                 Err(err) if err.kind() == async_std::io::ErrorKind::TimedOut => {
+                    if matches!(self.communication.status(), Run::Paused) {
+                        continue;
+                    }
+
                     unresponsive += super::MESSAGE_POLL_TIMEOUT;
                     if unresponsive > super::STALL_THRESHOLD
                         && !matches!(self.communication.status(), Run::Reconnecting(_))
@@ -605,6 +915,20 @@ impl<'a, T: Task> BorgProcess<'a, T> {
                 Err(err) => return Err(err.into()),
                 // end of stream
                 Ok(0) => return return_message,
+                // injected stall, output is discarded
+                Ok(_)
+                    if injected_failure.map_or(false, |x| {
+                        x.kind == super::fault_injection::Kind::Stall && x.is_due(started.elapsed())
+                    }) =>
+                {
+                    let stalled_since = injected_failure.map_or(Duration::ZERO, |x| x.after);
+                    if started.elapsed() > stalled_since + super::STALL_THRESHOLD
+                        && matches!(self.communication.status(), Run::Running)
+                    {
+                        self.communication.set_status(Run::Stalled);
+                    }
+                    continue;
+                }
                 // one line read
                 Ok(_) => {
                     unresponsive = Duration::ZERO;
@@ -638,3 +962,113 @@ impl<'a, T: Task> BorgProcess<'a, T> {
         }
     }
 }
+
+fn send_signal(pid: u32, signal: nix::sys::signal::Signal) -> Result<()> {
+    nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid.try_into().unwrap()), signal)?;
+    Ok(())
+}
+
+/// Ask borg to stop, which also saves a checkpoint when creating an archive
+fn stop<T: Task>(pid: u32, communication: &super::Communication<T>) -> Result<()> {
+    let paused = matches!(communication.status(), Run::Paused);
+    communication.set_status(Run::Stopping);
+    send_signal(pid, nix::sys::signal::Signal::SIGINT)?;
+
+    if paused {
+        // A suspended process only handles the signal after it continues
+        send_signal(pid, nix::sys::signal::Signal::SIGCONT)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_non_utf8_args() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = |bytes: &[u8]| std::path::PathBuf::from(std::ffi::OsStr::from_bytes(bytes));
+
+    let mut config = config::Backup::test_new_mock();
+    config.repo = config::local::Repository::from_path(path(b"/tmp/repo-\xff")).into_config();
+    config.include.insert(path(b"/home/user/caf\xe9"));
+    config
+        .exclude
+        .insert(config::Exclude::from_pattern(config::Pattern::PathPrefix(
+            path(b"/home/user/caf\xe9/cache"),
+        )));
+
+    let command = Command::<super::task::Create>::new(config);
+    let mut call = BorgCall::new("create");
+    call.add_archive(&command).add_include_exclude(&command);
+
+    let args = call.args();
+    assert!(args[args.len() - 2]
+        .as_bytes()
+        .starts_with(b"/tmp/repo-\xff::"));
+    assert_eq!(args.last().unwrap().as_bytes(), b"/home/user/caf\xe9");
+    assert!(args
+        .iter()
+        .any(|arg| arg.as_bytes() == b"--exclude=pp:/home/user/caf\xe9/cache"));
+}
+
+#[test]
+fn test_command_preview() {
+    let mut call = BorgCall::new("list");
+    call.add_envs([
+        ("BORG_NEW_PASSPHRASE", "secret"),
+        ("BORG_RELOCATED_REPO_ACCESS_IS_OK", "yes"),
+    ])
+    .add_positional("/tmp/my repo");
+
+    let preview = call.preview(true);
+    assert!(!preview.shell_line().contains("secret"));
+    assert_eq!(
+        preview.envs,
+        vec![
+            ("BORG_NEW_PASSPHRASE".to_string(), "***".to_string()),
+            ("BORG_PASSPHRASE".to_string(), "***".to_string()),
+            (
+                "BORG_RELOCATED_REPO_ACCESS_IS_OK".to_string(),
+                "yes".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        preview.shell_line(),
+        "BORG_NEW_PASSPHRASE='***' BORG_PASSPHRASE='***' BORG_RELOCATED_REPO_ACCESS_IS_OK=yes \
+         borg list --rsh 'ssh -o BatchMode=yes -o StrictHostKeyChecking=accept-new' -- '/tmp/my repo'"
+    );
+
+    assert_eq!(shell_quote("it's"), r"'it'\''s'");
+}
+
+#[test]
+fn test_host_key_policy() {
+    let mut call = BorgCall::new("list");
+    call.set_host_key_policy(config::remote::HostKeyPolicy::Off);
+
+    assert_eq!(
+        call.args()[1..3],
+        [
+            OsString::from("--rsh"),
+            OsString::from(
+                "ssh -o BatchMode=yes -o StrictHostKeyChecking=no -o UserKnownHostsFile=/dev/null"
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_reuse_connection() {
+    let mut call = BorgCall::new("list");
+    call.set_reuse_connection(std::path::Path::new("/run/user/1000/pika-backup/ssh"));
+
+    assert_eq!(
+        call.args()[2],
+        OsString::from(
+            "ssh -o BatchMode=yes -o StrictHostKeyChecking=accept-new \
+             -o ControlMaster=auto -o ControlPersist=10m \
+             -o ControlPath=/run/user/1000/pika-backup/ssh/%C"
+        )
+    );
+}
@@ -218,23 +218,32 @@ pub fn script_env_post(
     env
 }
 
-/// Run a script on the flatpak host
+/// Command running `command` on the flatpak host with only the variables `envs`
 ///
 /// Will be executed with `flatpak-spawn` and `bash -c`
-pub async fn run_script(
-    command: &str,
-    env: HashMap<ShellVariable, String>,
-    kind: UserScriptKind,
-    communication: super::Communication<super::task::UserScript>,
-) -> Result<()> {
-    let envs: HashMap<&str, &str> = env.iter().map(|(k, v)| (k.name(), v.as_str())).collect();
+pub fn host_command(command: &str, envs: &HashMap<&str, &str>) -> async_std::process::Command {
+    host_command_with_options(command, envs, false)
+}
 
-    debug!(
-        "Running shell script:\nbash -c \"{}\"\nenv: {:#?}",
-        command, envs
-    );
+/// Like [`host_command`], but the command ends when the started process is killed
+///
+/// In the sandbox, `kill_on_drop` only reaches the `flatpak-spawn` wrapper. The
+/// process on the host is ended via `--watch-bus` once the wrapper is gone.
+pub fn host_command_kill_on_drop(
+    command: &str,
+    envs: &HashMap<&str, &str>,
+) -> async_std::process::Command {
+    let mut cmd = host_command_with_options(command, envs, true);
+    cmd.kill_on_drop(true);
+    cmd
+}
 
-    let mut cmd = if *APP_IS_SANDBOXED {
+fn host_command_with_options(
+    command: &str,
+    envs: &HashMap<&str, &str>,
+    watch_bus: bool,
+) -> async_std::process::Command {
+    if *APP_IS_SANDBOXED {
         let mut cmd = async_std::process::Command::new("flatpak-spawn");
 
         // Don't remove the entire env, flatpak-spawn needs some of it
@@ -243,10 +252,14 @@ pub async fn run_script(
         cmd.env_remove("G_LOG_DOMAIN");
         cmd.env_remove("G_MESSAGES_DEBUG");
 
-        for (name, value) in &envs {
+        for (name, value) in envs {
             cmd.arg(format!("--env={name}={value}"));
         }
 
+        if watch_bus {
+            cmd.arg("--watch-bus");
+        }
+
         cmd.args(["--clear-env", "--host", "bash", "-c", command]);
         cmd
     } else {
@@ -257,7 +270,24 @@ pub async fn run_script(
 
         cmd.args(["-c", command]);
         cmd
-    };
+    }
+}
+
+/// Run a script on the flatpak host via [`host_command`]
+pub async fn run_script(
+    command: &str,
+    env: HashMap<ShellVariable, String>,
+    kind: UserScriptKind,
+    communication: super::Communication<super::task::UserScript>,
+) -> Result<()> {
+    let envs: HashMap<&str, &str> = env.iter().map(|(k, v)| (k.name(), v.as_str())).collect();
+
+    debug!(
+        "Running shell script:\nbash -c \"{}\"\nenv: {:#?}",
+        command, envs
+    );
+
+    let mut cmd = host_command(command, &envs);
 
     let output = cmd
         .output_with_communication(communication)
@@ -1,12 +1,16 @@
 use super::*;
 use crate::config;
 use crate::prelude::*;
+use crate::utils::backup_tools;
 
 use std::collections::BTreeSet;
 
 /// Empirical value for the space that borg needs
 pub static DIRECTORY_SIZE: u64 = 109;
 
+/// Number of files after which the count of found files is updated
+const REPORT_FILES_INTERVAL: u64 = 1000;
+
 struct Exclude {
     exclude: BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
 }
@@ -65,12 +69,18 @@ pub fn calculate(
     };
     let include = config.include_dirs().into_iter().filter(duplicate_check);
 
+    communication
+        .specific_info
+        .update(|status| status.estimating_files = Some(0));
+
     let exclude_previously = Exclude::new(last_run.map(|x| x.exclude.clone()).unwrap_or_default());
     let include_previously = last_run.map(|x| x.include.clone()).unwrap_or_default();
 
     let mut size_total = 0;
     let mut size_touched = 0;
+    let mut files = 0;
     let mut unreadable_paths = Vec::new();
+    let mut other_backup_paths = BTreeSet::new();
 
     for dir in include {
         for entry_result in walkdir::WalkDir::new(dir)
@@ -83,11 +93,28 @@ pub fn calculate(
                         return None;
                     }
 
+                    files += 1;
+                    if files % REPORT_FILES_INTERVAL == 0 {
+                        communication
+                            .specific_info
+                            .update(move |status| status.estimating_files = Some(files));
+                    }
+
                     if entry.file_type().is_dir() {
                         size_total += DIRECTORY_SIZE;
+
+                        if backup_tools::folder_of(entry.path()).is_some() {
+                            other_backup_paths.insert(entry.path().to_path_buf());
+                        }
                     } else if let Ok(metadata) = entry.metadata() {
                         size_total += metadata.len();
 
+                        if backup_tools::is_deja_dup_backup_file(entry.path()) {
+                            if let Some(parent) = entry.path().parent() {
+                                other_backup_paths.insert(parent.to_path_buf());
+                            }
+                        }
+
                         // Workaround for rust std assertion about broken mtime / ctime on btrfs
                         let result = std::panic::catch_unwind(|| {
                             // check if file is new/modified since last backup
@@ -136,10 +163,185 @@ pub fn calculate(
         unreadable_paths.len()
     );
     trace!("Unreadable files/directories: {:#?}", unreadable_paths);
+    debug!("Folders of other backup tools: {:?}", other_backup_paths);
+
+    communication
+        .specific_info
+        .update(|status| status.estimating_files = None);
 
     Some(SizeEstimate {
         total: size_total,
         changed: size_touched,
         unreadable_paths,
+        other_backup_paths: other_backup_paths.into_iter().collect(),
     })
 }
+
+/// Size of a single included folder, considering the excludes
+///
+/// Returns `None` if the calculation was cancelled.
+pub fn include_size(
+    dir: &std::path::Path,
+    exclude: BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
+    cancel: &std::sync::atomic::AtomicBool,
+) -> Option<u64> {
+    let exclude = Exclude::new(exclude);
+    let mut size = 0;
+
+    for entry in walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| exclude.is_included(entry))
+        .flatten()
+    {
+        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            return None;
+        }
+
+        if entry.file_type().is_dir() {
+            size += DIRECTORY_SIZE;
+        } else if let Ok(metadata) = entry.metadata() {
+            size += metadata.len();
+        }
+    }
+
+    Some(size)
+}
+
+/// Present files that an additional exclusion rule would skip
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExcludeImpact {
+    pub files: u64,
+    /// Sum of the file sizes in bytes
+    pub size: u64,
+}
+
+impl ExcludeImpact {
+    fn add_entry(&mut self, entry: &walkdir::DirEntry) {
+        if !entry.file_type().is_dir() {
+            self.files += 1;
+            self.size += entry.metadata().map(|x| x.len()).unwrap_or_default();
+        }
+    }
+}
+
+/// Files in `include` that `new` excludes, in addition to the existing `exclude`
+///
+/// Directories matched by `new` are counted with all their content, since borg
+/// does not look into them. Returns `None` if the calculation was cancelled.
+pub fn exclude_impact(
+    include: &BTreeSet<std::path::PathBuf>,
+    exclude: BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
+    new: &config::Exclude<{ config::ABSOLUTE }>,
+    cancel: &std::sync::atomic::AtomicBool,
+) -> Option<ExcludeImpact> {
+    let exclude = Exclude::new(exclude);
+    let mut impact = ExcludeImpact::default();
+
+    for dir in include {
+        let mut walk = walkdir::WalkDir::new(dir)
+            .into_iter()
+            .filter_entry(|entry| exclude.is_included(entry));
+
+        while let Some(entry) = walk.next() {
+            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                return None;
+            }
+
+            let Ok(entry) = entry else {
+                continue;
+            };
+
+            if !new.is_match(entry.path()) {
+                continue;
+            }
+
+            if entry.file_type().is_dir() {
+                walk.skip_current_dir();
+
+                for entry in walkdir::WalkDir::new(entry.path())
+                    .into_iter()
+                    .filter_entry(|entry| exclude.is_included(entry))
+                    .flatten()
+                {
+                    if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        return None;
+                    }
+                    impact.add_entry(&entry);
+                }
+            } else {
+                impact.add_entry(&entry);
+            }
+        }
+    }
+
+    Some(impact)
+}
+
+#[test]
+fn test_include_size() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("cache")).unwrap();
+    std::fs::write(dir.path().join("file"), [0; 100]).unwrap();
+    std::fs::write(dir.path().join("cache/file"), [0; 1000]).unwrap();
+
+    let cancel = std::sync::atomic::AtomicBool::new(false);
+
+    assert_eq!(
+        include_size(dir.path(), BTreeSet::new(), &cancel),
+        Some(2 * DIRECTORY_SIZE + 1100)
+    );
+
+    let exclude = BTreeSet::from([config::Exclude::from_pattern(config::Pattern::path_prefix(
+        dir.path().join("cache"),
+    ))]);
+    assert_eq!(
+        include_size(dir.path(), exclude, &cancel),
+        Some(DIRECTORY_SIZE + 100)
+    );
+
+    cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+    assert_eq!(include_size(dir.path(), BTreeSet::new(), &cancel), None);
+}
+
+#[test]
+fn test_exclude_impact() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("project/target/debug")).unwrap();
+    std::fs::write(dir.path().join("project/main.rs"), [0; 10]).unwrap();
+    std::fs::write(dir.path().join("project/target/a"), [0; 100]).unwrap();
+    std::fs::write(dir.path().join("project/target/debug/b"), [0; 1000]).unwrap();
+
+    let include = BTreeSet::from([dir.path().to_path_buf()]);
+    let cancel = std::sync::atomic::AtomicBool::new(false);
+
+    // Only the directory itself matches, but its content is skipped as well
+    let new = config::Exclude::from_pattern(
+        config::Pattern::from_regular_expression("/target$").unwrap(),
+    );
+    assert_eq!(
+        exclude_impact(&include, BTreeSet::new(), &new, &cancel),
+        Some(ExcludeImpact {
+            files: 2,
+            size: 1100
+        })
+    );
+
+    // Already excluded files are not counted again
+    let exclude = BTreeSet::from([config::Exclude::from_pattern(config::Pattern::path_prefix(
+        dir.path().join("project/target/debug"),
+    ))]);
+    assert_eq!(
+        exclude_impact(&include, exclude, &new, &cancel),
+        Some(ExcludeImpact {
+            files: 1,
+            size: 100
+        })
+    );
+
+    // A typo matches nothing
+    let typo = config::Exclude::from_pattern(config::Pattern::fnmatch("*/taget"));
+    assert_eq!(
+        exclude_impact(&include, BTreeSet::new(), &typo, &cancel),
+        Some(ExcludeImpact::default())
+    );
+}
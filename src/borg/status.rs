@@ -16,11 +16,15 @@ pub struct GeneralStatus {
 #[derive(Default, Debug, Clone)]
 pub struct Status {
     pub estimated_size: Option<SizeEstimate>,
+    /// Number of files found while the size estimate is running
+    pub estimating_files: Option<u64>,
     pub started: Option<chrono::DateTime<chrono::Local>>,
     pub total: f64,
     pub copied: f64,
     pub stalled: bool,
     pub data_rate_history: DataRateHistory,
+    /// Most recently processed files, oldest first
+    pub recent_files: VecDeque<FileStatus>,
 }
 
 fn positive(n: f64) -> f64 {
@@ -79,6 +83,16 @@ impl GeneralStatus {
 }
 
 impl Status {
+    pub const RECENT_FILES_LENGTH: usize = 20;
+
+    pub fn add_recent_file(&mut self, file: FileStatus) {
+        self.recent_files.push_back(file);
+
+        while self.recent_files.len() > Self::RECENT_FILES_LENGTH {
+            self.recent_files.pop_front();
+        }
+    }
+
     pub fn time_remaining(&self) -> Option<chrono::Duration> {
         if let (Some(skip_remaining_size), Some(copy_remaining_size)) =
             (self.skip_remaining(), self.copy_remaining())
@@ -140,6 +154,8 @@ pub struct SizeEstimate {
     pub total: u64,
     pub changed: u64,
     pub unreadable_paths: Vec<std::path::PathBuf>,
+    /// Folders used by other backup tools
+    pub other_backup_paths: Vec<std::path::PathBuf>,
 }
 
 impl SizeEstimate {
@@ -277,12 +293,14 @@ impl std::iter::Sum for DataRate {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Run {
     Init,
     Running,
     Stalled,
     Reconnecting(std::time::Duration),
+    /// The borg process is suspended on user request
+    Paused,
     Stopping,
 }
 
@@ -12,7 +12,11 @@ pub enum Kind {
     Check,
     Delete,
     List,
+    ListFiles,
     KeyChangePassphrase,
+    RepositoryFormat,
+    Upgrade,
+    RebuildCache,
 
     Generic,
 
@@ -20,6 +24,21 @@ pub enum Kind {
     UserScript,
 }
 
+impl Kind {
+    /// Operations that can change the archives or the used space
+    pub const fn modifies_repository(self) -> bool {
+        matches!(
+            self,
+            Self::Create | Self::Prune | Self::Compact | Self::Delete | Self::Upgrade
+        )
+    }
+
+    /// Operations that remove archives or the data belonging to them
+    pub const fn deletes_data(self) -> bool {
+        matches!(self, Self::Prune | Self::Compact | Self::Delete)
+    }
+}
+
 pub trait Task: Clone + Default + Send + Sync + 'static {
     //type Status: Clone + Default + Send + Sync;
     type Info: Clone + Default + Send + Sync;
@@ -63,7 +82,17 @@ impl Task for Create {
 }
 
 #[derive(Clone, Default)]
-pub struct Mount {}
+pub struct Mount {
+    pub(super) umask: u32,
+}
+
+impl Mount {
+    /// Permission bits removed from all files in the mount
+    pub fn set_umask(&mut self, umask: u32) -> &mut Self {
+        self.umask = umask;
+        self
+    }
+}
 
 impl Task for Mount {
     type Info = ();
@@ -122,6 +151,8 @@ impl Task for Compact {
 pub struct Check {
     verify_data: bool,
     repair: bool,
+    repository_only: bool,
+    latest_archive_only: bool,
 }
 
 impl Check {
@@ -140,6 +171,24 @@ impl Check {
     pub fn set_repair(&mut self, repair: bool) {
         self.repair = repair;
     }
+
+    pub fn repository_only(&self) -> bool {
+        self.repository_only
+    }
+
+    /// Only check the repository, not the archives
+    pub fn set_repository_only(&mut self, repository_only: bool) {
+        self.repository_only = repository_only;
+    }
+
+    pub fn latest_archive_only(&self) -> bool {
+        self.latest_archive_only
+    }
+
+    /// Only check the latest archive, not the repository
+    pub fn set_latest_archive_only(&mut self, latest_archive_only: bool) {
+        self.latest_archive_only = latest_archive_only;
+    }
 }
 
 impl Task for Check {
@@ -155,16 +204,16 @@ impl Task for Check {
 
 #[derive(Clone, Default)]
 pub struct Delete {
-    archive_name: Option<String>,
+    archive_name: Option<super::ArchiveName>,
 }
 
 impl Delete {
-    pub fn set_archive_name(&mut self, archive_name: Option<String>) -> &mut Self {
+    pub fn set_archive_name(&mut self, archive_name: Option<super::ArchiveName>) -> &mut Self {
         self.archive_name = archive_name;
         self
     }
 
-    pub fn archive_name(&self) -> Option<String> {
+    pub fn archive_name(&self) -> Option<super::ArchiveName> {
         self.archive_name.clone()
     }
 }
@@ -192,8 +241,15 @@ impl List {
     }
 }
 
+/// Progress of listing the archives
+#[derive(Clone, Default, Debug)]
+pub struct ListInfo {
+    /// Archives received so far
+    pub archives: usize,
+}
+
 impl Task for List {
-    type Info = ();
+    type Info = ListInfo;
     type Return = Vec<super::ListArchive>;
 
     const KIND: Kind = Kind::List;
@@ -203,6 +259,52 @@ impl Task for List {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct ListFiles {
+    archive_name: Option<super::ArchiveName>,
+    pub(super) entries: Option<async_std::channel::Sender<super::ArchiveFile>>,
+}
+
+impl ListFiles {
+    /// Receive the entries of the archive while they are listed
+    ///
+    /// Entries are not collected. The listing waits for the receiver if it
+    /// falls behind. Entries are dropped if this is not called.
+    pub fn entries(&mut self) -> async_std::channel::Receiver<super::ArchiveFile> {
+        let (sender, receiver) = async_std::channel::bounded(1000);
+        self.entries = Some(sender);
+        receiver
+    }
+
+    pub fn set_archive_name(&mut self, archive_name: Option<super::ArchiveName>) -> &mut Self {
+        self.archive_name = archive_name;
+        self
+    }
+
+    pub fn archive_name(&self) -> Option<super::ArchiveName> {
+        self.archive_name.clone()
+    }
+}
+
+/// Progress of listing the archive content
+#[derive(Clone, Default, Debug)]
+pub struct ListFilesInfo {
+    /// Entries received so far
+    pub files: usize,
+}
+
+impl Task for ListFiles {
+    type Info = ListFilesInfo;
+    /// Number of listed entries
+    type Return = u64;
+
+    const KIND: Kind = Kind::ListFiles;
+
+    fn name() -> String {
+        gettext("Indexing Archive Content")
+    }
+}
+
 #[derive(Clone)]
 pub(super) enum NumArchives {
     All,
@@ -249,6 +351,61 @@ impl Task for KeyChangePassphrase {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct RepositoryFormat {}
+
+impl Task for RepositoryFormat {
+    type Info = ();
+    type Return = super::RepositoryFormat;
+
+    const KIND: Kind = Kind::RepositoryFormat;
+
+    fn name() -> String {
+        gettext("Checking Repository Format")
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Upgrade {
+    format: super::RepositoryFormat,
+}
+
+impl Upgrade {
+    pub fn set_format(&mut self, format: super::RepositoryFormat) {
+        self.format = format;
+    }
+
+    pub fn format(&self) -> &super::RepositoryFormat {
+        &self.format
+    }
+}
+
+impl Task for Upgrade {
+    type Info = ();
+    type Return = ();
+
+    const KIND: Kind = Kind::Upgrade;
+
+    fn name() -> String {
+        gettext("Upgrading Repository Format")
+    }
+}
+
+/// Synchronize the local cache with the repository after it was deleted
+#[derive(Clone, Default)]
+pub struct RebuildCache {}
+
+impl Task for RebuildCache {
+    type Info = ();
+    type Return = ();
+
+    const KIND: Kind = Kind::RebuildCache;
+
+    fn name() -> String {
+        gettext("Rebuilding Cache")
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct UserScript {
     kind: Option<UserScriptKind>,
@@ -1,6 +1,27 @@
 use super::log_json::*;
 
+/// Replace control characters with their escaped representation
+///
+/// File names can contain terminal escape sequences or characters that change
+/// the text direction. Line breaks and tabs are kept.
+pub fn escape_control_chars(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if (c.is_control() && !matches!(c, '\n' | '\t'))
+            || matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+        {
+            escaped.extend(c.escape_unicode());
+        } else {
+            escaped.push(c);
+        }
+    }
+
+    escaped
+}
+
 pub fn check_line(line: &str) -> LogEntry {
+    // The message is kept unchanged and only escaped when it is displayed
     if let Ok(mut msg @ LogMessage { .. }) = serde_json::from_str(line) {
         if matches!(msg.msgid, MsgId::Undefined) {
             let msgid_helper_parsed: std::result::Result<MsgIdHelper, _> =
@@ -13,11 +34,72 @@ pub fn check_line(line: &str) -> LogEntry {
 
         LogEntry::ParsedErr(msg)
     } else {
-        error!("Parse error {}", line);
+        error!("Parse error {:?}", line);
         LogEntry::UnparsableErr(line.to_string())
     }
 }
 
+/// Split JSON output into the objects contained in top level arrays
+///
+/// Allows processing the archives of `borg list --json` while the output is
+/// still being read. Other values of the top level object are skipped.
+#[derive(Default)]
+pub struct ArrayItems {
+    depth: usize,
+    in_array: bool,
+    in_string: bool,
+    escaped: bool,
+    item: Vec<u8>,
+}
+
+impl ArrayItems {
+    /// Add the next part of the output and return the completed objects
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut items = Vec::new();
+
+        for &byte in bytes {
+            let was_in_item = self.in_array && self.depth > 2;
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+            } else {
+                match byte {
+                    b'"' => self.in_string = true,
+                    b'{' | b'[' => {
+                        self.depth += 1;
+                        if self.depth == 2 && byte == b'[' {
+                            self.in_array = true;
+                        }
+                    }
+                    b'}' | b']' => {
+                        self.depth = self.depth.saturating_sub(1);
+                        if self.depth < 2 {
+                            self.in_array = false;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if was_in_item || (self.in_array && self.depth > 2) {
+                self.item.push(byte);
+
+                if self.depth == 2 {
+                    items.push(std::mem::take(&mut self.item));
+                }
+            }
+        }
+
+        items
+    }
+}
+
 fn is_sha256_faster() -> bool {
     #[cfg(target_arch = "x86_64")]
     {
@@ -46,3 +128,73 @@ pub fn mount_base_dir() -> std::path::PathBuf {
         .join(env!("CARGO_PKG_NAME"))
         .join("mount")
 }
+
+/// Sockets of SSH connections that are shared between borg calls
+pub fn ssh_control_dir() -> std::path::PathBuf {
+    crate::utils::host::user_runtime_dir()
+        .join(env!("CARGO_PKG_NAME"))
+        .join("ssh")
+}
+
+#[test]
+fn test_escape_control_chars() {
+    assert_eq!(escape_control_chars("plain/file.txt"), "plain/file.txt");
+    assert_eq!(
+        escape_control_chars("\u{1b}[31mred\u{1b}[0m"),
+        "\\u{1b}[31mred\\u{1b}[0m"
+    );
+    assert_eq!(escape_control_chars("a\tb\nc\rd"), "a\tb\nc\\u{d}d");
+    assert_eq!(escape_control_chars("txt.\u{202E}exe"), "txt.\\u{202e}exe");
+    // Already escaped text is not changed
+    assert_eq!(
+        escape_control_chars(&escape_control_chars("\u{7}")),
+        "\\u{7}"
+    );
+}
+
+#[test]
+fn test_check_line_keeps_message() {
+    let entry = check_line(
+        r#"{"type": "log_message", "levelname": "WARNING", "name": "borg.archiver", "message": "/home/user/\u001b[2Jname: file changed while we backed it up"}"#,
+    );
+    assert_eq!(
+        entry.changed_file(),
+        Some(std::path::PathBuf::from("/home/user/\u{1b}[2Jname"))
+    );
+
+    let collection: LogCollection = vec![entry];
+    assert!(collection.to_string().contains("/home/user/\\u{1b}[2Jname"));
+}
+
+#[test]
+fn test_array_items() {
+    let output = br#"{
+    "archives": [
+        {
+            "name": "a{[\"",
+            "command_line": ["borg", "create"]
+        },
+        {"name": "b", "start": "2024"}
+    ],
+    "encryption": {"mode": "none"},
+    "repository": {"id": "x", "location": "/r"}
+}"#;
+
+    // Split the output at every position to simulate partial reads
+    for split in 0..output.len() {
+        let mut splitter = ArrayItems::default();
+        let mut items = splitter.push(&output[..split]);
+        items.extend(splitter.push(&output[split..]));
+
+        let names: Vec<String> = items
+            .iter()
+            .map(|item| {
+                serde_json::from_slice::<serde_json::Value>(item).unwrap()["name"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(names, ["a{[\"", "b"]);
+    }
+}
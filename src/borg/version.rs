@@ -0,0 +1,84 @@
+/*!
+Version of the installed borg binary
+
+Some command line options have been renamed between borg versions.
+*/
+
+use std::sync::OnceLock;
+
+static DETECTED: OnceLock<Option<Version>> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the output of `borg --version` like `borg 1.2.7`
+    pub fn parse(version_output: &str) -> Option<Self> {
+        let version_string = version_output.lines().next()?.split(' ').nth(1)?;
+        let mut version_list = version_string.split('.').map(|x| {
+            // Ignore suffixes like in "0b1" or "7.dev"
+            x.chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse::<u32>()
+        });
+
+        Some(Self::new(
+            version_list.next()?.ok()?,
+            version_list.next()?.ok()?,
+            version_list.next().and_then(Result::ok).unwrap_or_default(),
+        ))
+    }
+
+    /// Version of the installed borg binary if it has already been determined
+    pub fn cached() -> Option<Self> {
+        DETECTED.get().copied().flatten()
+    }
+
+    /// Version of the installed borg binary, determined on the first call
+    pub async fn detected() -> Option<Self> {
+        if let Some(version) = DETECTED.get() {
+            return *version;
+        }
+
+        let version = match super::version().await {
+            Ok(output) => Self::parse(&output),
+            Err(err) => {
+                warn!("Failed to detect borg version: {}", err);
+                None
+            }
+        };
+
+        *DETECTED.get_or_init(|| version)
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(Version::parse("borg 1.2.7"), Some(Version::new(1, 2, 7)));
+    assert_eq!(
+        Version::parse("borg 2.0.0b1\nsome other line"),
+        Some(Version::new(2, 0, 0))
+    );
+    assert_eq!(Version::parse("borg 1.4"), Some(Version::new(1, 4, 0)));
+    assert_eq!(Version::parse("borg"), None);
+    assert_eq!(Version::parse("borg x.2.7"), None);
+}
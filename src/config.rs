@@ -4,23 +4,31 @@ pub mod exclude;
 pub mod history;
 mod loadable;
 pub mod local;
+mod maintenance;
+pub mod migration;
+mod path_serde;
 mod pattern;
+pub mod patterns_file;
 mod prune;
 pub mod remote;
 mod repository;
 mod schedule;
 mod schedule_status;
+pub mod trash;
 mod writeable;
 
 pub use backup::*;
 pub use exclude::Exclude;
 pub use history::Histories;
-pub use loadable::{ConfigType, ConfigVersion, Loadable, TrackChanges};
+pub use loadable::{connect_reloaded, ConfigType, ConfigVersion, Loadable, TrackChanges};
+pub use maintenance::Maintenance;
 pub use pattern::*;
+pub use patterns_file::{PatternsFile, PatternsFormat};
 pub use prune::*;
 pub use repository::*;
 pub use schedule::*;
 pub use schedule_status::*;
+pub use trash::Trash;
 pub use writeable::{ArcSwapWriteable, Writeable};
 
 use crate::prelude::*;
@@ -6,8 +6,8 @@ use std::path;
 
 use super::loadable::ConfigVersion;
 use super::{
-    absolute, error, exclude, ConfigType, Exclude, Pattern, Prune, Repository, Schedule, ABSOLUTE,
-    RELATIVE,
+    absolute, error, exclude, patterns_file, ConfigType, Exclude, Pattern, PatternsFile, Prune,
+    Repository, Schedule, ABSOLUTE, RELATIVE,
 };
 
 #[derive(
@@ -66,6 +66,201 @@ pub enum UserScriptKind {
     PostBackup,
 }
 
+/// How often borg saves the progress of a running backup
+///
+/// Checkpoints allow to resume interrupted backups but take some time to write.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Checkpoints {
+    /// Borg's default of one checkpoint every 30 minutes
+    #[default]
+    Default,
+    /// Seconds between checkpoints
+    Interval(u32),
+    Disabled,
+}
+
+impl Checkpoints {
+    pub const DEFAULT_INTERVAL: u32 = 30 * 60;
+    pub const MIN_INTERVAL: u32 = 60;
+    pub const MAX_INTERVAL: u32 = 24 * 60 * 60;
+
+    pub fn from_interval(enabled: bool, seconds: u32) -> Self {
+        if !enabled {
+            Self::Disabled
+        } else if seconds == Self::DEFAULT_INTERVAL {
+            Self::Default
+        } else {
+            Self::Interval(seconds.clamp(Self::MIN_INTERVAL, Self::MAX_INTERVAL))
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+
+    /// Seconds between checkpoints, the default interval if disabled
+    pub fn interval(&self) -> u32 {
+        match self {
+            Self::Interval(seconds) => (*seconds).clamp(Self::MIN_INTERVAL, Self::MAX_INTERVAL),
+            Self::Default | Self::Disabled => Self::DEFAULT_INTERVAL,
+        }
+    }
+
+    pub fn borg_arg(&self) -> Option<String> {
+        match self {
+            Self::Default => None,
+            Self::Interval(_) => Some(format!("--checkpoint-interval={}", self.interval())),
+            // Borg has no option to disable checkpoints, only a final one is written
+            Self::Disabled => Some(format!("--checkpoint-interval={}", u32::MAX)),
+        }
+    }
+}
+
+/// Share of processor time and disk bandwidth used by borg processes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Performance {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Performance {
+    pub const VALUES: [Self; 3] = [Self::Low, Self::Normal, Self::High];
+
+    /// Command that borg is passed to as an argument
+    ///
+    /// With `systemd_scope` borg is additionally started in a transient systemd
+    /// scope with adjusted cgroup weights.
+    pub fn wrapper(&self, systemd_scope: bool) -> Vec<&'static str> {
+        let (scope, priority): (&[&str], &[&str]) = match self {
+            Self::Low => (
+                &["--property=CPUWeight=20", "--property=IOWeight=20"],
+                // Lowest CPU priority and idle IO scheduling class
+                &["nice", "-n", "19", "ionice", "-c", "3"],
+            ),
+            Self::Normal => return Vec::new(),
+            Self::High => (
+                &["--property=CPUWeight=500", "--property=IOWeight=500"],
+                // Raising the CPU priority requires privileges, the IO priority doesn't
+                &["ionice", "-c", "2", "-n", "0"],
+            ),
+        };
+
+        let mut wrapper = Vec::new();
+        if systemd_scope {
+            wrapper.extend(["systemd-run", "--user", "--scope", "--quiet"]);
+            wrapper.extend(scope);
+        }
+        wrapper.extend(priority);
+
+        wrapper
+    }
+}
+
+/// Reading back the data after backups to removable drives
+///
+/// Cheap flash drives can corrupt data without reporting errors.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteVerification {
+    #[default]
+    None,
+    /// Check the consistency of the repository
+    Repository,
+    /// Read and verify the data of the latest archive
+    LatestArchive,
+}
+
+impl WriteVerification {
+    pub const VALUES: [Self; 3] = [Self::None, Self::Repository, Self::LatestArchive];
+}
+
+/// Color label of a backup config
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccentColor {
+    #[default]
+    None,
+    Blue,
+    Green,
+    Yellow,
+    Orange,
+    Red,
+    Purple,
+    Brown,
+}
+
+impl AccentColor {
+    pub const VALUES: [Self; 8] = [
+        Self::None,
+        Self::Blue,
+        Self::Green,
+        Self::Yellow,
+        Self::Orange,
+        Self::Red,
+        Self::Purple,
+        Self::Brown,
+    ];
+
+    /// Style class of widgets shown in this color
+    pub fn css_class(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Blue => Some("backup-color-blue"),
+            Self::Green => Some("backup-color-green"),
+            Self::Yellow => Some("backup-color-yellow"),
+            Self::Orange => Some("backup-color-orange"),
+            Self::Red => Some("backup-color-red"),
+            Self::Purple => Some("backup-color-purple"),
+            Self::Brown => Some("backup-color-brown"),
+        }
+    }
+}
+
+/// File metadata that is not stored in archives
+///
+/// Useful if tools like file synchronization constantly change the metadata
+/// and thereby make borg read unchanged files again.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct SkipMetadata {
+    /// Access time
+    pub atime: bool,
+    /// Inode change time
+    pub ctime: bool,
+    /// Creation time
+    pub birthtime: bool,
+    /// Flags like immutable or nodump
+    pub flags: bool,
+}
+
+impl SkipMetadata {
+    /// Arguments for `borg create`, depending on the borg `version` if known
+    pub fn borg_args(&self, version: Option<borg::version::Version>) -> Vec<&'static str> {
+        let since_1_2 = version.map_or(true, |x| x >= borg::version::Version::new(1, 2, 0));
+        let mut args = Vec::new();
+
+        // Newer versions never store the access time unless asked to
+        if self.atime && !since_1_2 {
+            args.push("--noatime");
+        }
+        if self.ctime {
+            args.push("--noctime");
+        }
+        if self.birthtime {
+            args.push("--nobirthtime");
+        }
+        if self.flags {
+            args.push(if since_1_2 {
+                "--noflags"
+            } else {
+                "--nobsdflags"
+            });
+        }
+
+        args
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Backup {
     #[serde(default)]
@@ -79,16 +274,61 @@ pub struct Backup {
     pub encrypted: bool,
     #[serde(default)]
     pub encryption_mode: String,
+    /// Command printing the encryption password, used instead of the keyring
+    #[serde(default)]
+    pub password_command: Option<String>,
+    #[serde(with = "super::path_serde::set")]
     pub include: BTreeSet<path::PathBuf>,
+    /// Glob patterns for additional includes, evaluated on every run
+    #[serde(default, with = "super::path_serde::set")]
+    pub include_patterns: BTreeSet<path::PathBuf>,
     pub exclude: BTreeSet<Exclude<{ RELATIVE }>>,
     #[serde(default)]
     pub schedule: Schedule,
     #[serde(default)]
     pub prune: Prune,
     #[serde(default)]
+    pub maintenance: Maintenance,
+    #[serde(default)]
     pub title: String,
+    /// Color that distinguishes the backup in the overview
+    #[serde(default)]
+    pub accent_color: AccentColor,
     #[serde(default)]
     pub user_scripts: BTreeMap<UserScriptKind, String>,
+    /// Include this backup in the metrics textfile
+    #[serde(default)]
+    pub export_metrics: bool,
+    #[serde(default)]
+    pub checkpoints: Checkpoints,
+    /// Back up again if files changed while they were backed up
+    #[serde(default)]
+    pub retry_changed_files: bool,
+    #[serde(default)]
+    pub skip_metadata: SkipMetadata,
+    /// Keep the repository append-only, except while archives are deleted manually
+    #[serde(default)]
+    pub append_only: bool,
+    /// Files that exclusion rules have been imported from
+    #[serde(default)]
+    pub patterns_files: Vec<PatternsFile>,
+    /// Write a report file into this directory after each run
+    #[serde(default)]
+    pub report_dir: Option<path::PathBuf>,
+    #[serde(default)]
+    pub performance: Performance,
+    /// Use the low performance profile while power saving is active
+    #[serde(default)]
+    pub low_priority_in_power_saver: bool,
+    /// Pause the file indexing of tracker-miners while the backup is running
+    #[serde(default)]
+    pub pause_indexing: bool,
+    /// Verify the written data after backups to removable drives
+    #[serde(default)]
+    pub write_verification: WriteVerification,
+    /// The warning about an unencrypted repository has been dismissed
+    #[serde(default)]
+    pub plaintext_warning_dismissed: bool,
 }
 
 impl Backup {
@@ -106,12 +346,53 @@ impl Backup {
             repo_id: info.repository.id,
             encrypted,
             encryption_mode: info.encryption.mode,
+            password_command: Default::default(),
             include,
+            include_patterns: Default::default(),
             exclude,
             schedule: Default::default(),
             prune: Default::default(),
+            maintenance: Default::default(),
             title: Default::default(),
+            accent_color: Default::default(),
             user_scripts: Default::default(),
+            export_metrics: Default::default(),
+            checkpoints: Default::default(),
+            retry_changed_files: Default::default(),
+            skip_metadata: Default::default(),
+            append_only: Default::default(),
+            patterns_files: Default::default(),
+            report_dir: Default::default(),
+            performance: Default::default(),
+            low_priority_in_power_saver: Default::default(),
+            pause_indexing: Default::default(),
+            write_verification: Default::default(),
+            plaintext_warning_dismissed: Default::default(),
+        }
+    }
+
+    /// Backed up data is readable without a password
+    ///
+    /// Only considers the mode reported by borg when the repository was added.
+    pub fn is_plaintext(&self) -> bool {
+        self.encryption_mode == "none" || self.encryption_mode.starts_with("authenticated")
+    }
+
+    /// Unencrypted repositories on devices that can get lost or are managed by others
+    pub fn warn_plaintext(&self) -> bool {
+        self.is_plaintext()
+            && !self.plaintext_warning_dismissed
+            && (self.repo.is_drive_removable() || self.repo.is_network())
+    }
+
+    pub fn encryption_description(&self) -> String {
+        if self.encryption_mode.is_empty() {
+            gettext("Unknown")
+        } else if self.is_plaintext() {
+            gettext("Unencrypted")
+        } else {
+            // Translators: Argument is the mode like 'repokey-blake2'
+            gettextf("Encrypted ({})", &[&self.encryption_mode])
         }
     }
 
@@ -171,6 +452,11 @@ impl Backup {
         }
     }
 
+    /// The ID of the repository has been recorded during setup
+    pub fn is_repo_id_known(&self) -> bool {
+        !self.repo_id.as_str().starts_with(FAKE_REPO_ID_PREFIX)
+    }
+
     pub fn include_dirs(&self) -> BTreeSet<path::PathBuf> {
         let mut dirs = BTreeSet::new();
 
@@ -178,9 +464,44 @@ impl Backup {
             dirs.insert(absolute(dir));
         }
 
+        for pattern in &self.include_patterns {
+            dirs.extend(crate::utils::glob_paths(&absolute(pattern)));
+        }
+
         dirs
     }
 
+    /// Add the exclusion rules of a file
+    ///
+    /// Rules from a previous import of the same file are replaced. For linked
+    /// files, borg reads the rules directly from the file.
+    pub fn import_patterns_file(&mut self, mut file: PatternsFile, parsed: &patterns_file::Parsed) {
+        self.remove_patterns_file(&file.path);
+
+        if !file.linked {
+            // Rules that already existed are not removed with the file later
+            file.imported = parsed
+                .excludes()
+                .difference(&self.exclude)
+                .cloned()
+                .collect();
+            self.exclude.extend(file.imported.iter().cloned());
+        }
+
+        self.patterns_files.push(file);
+    }
+
+    /// Remove a patterns file and the rules imported from it
+    pub fn remove_patterns_file(&mut self, path: &path::Path) {
+        for file in self.patterns_files.iter().filter(|file| file.path == path) {
+            for exclude in &file.imported {
+                self.exclude.remove(exclude);
+            }
+        }
+
+        self.patterns_files.retain(|file| file.path != path);
+    }
+
     pub fn exclude_dirs_internal(&self) -> BTreeSet<Exclude<{ ABSOLUTE }>> {
         let mut dirs =
             BTreeSet::from_iter(self.exclude.clone().into_iter().map(|x| x.into_absolute()));
@@ -244,8 +565,14 @@ impl std::fmt::Display for ArchivePrefix {
     }
 }
 
+/// Configs created before the repository ID was stored use a random placeholder
+const FAKE_REPO_ID_PREFIX: &str = "-randomid-";
+
 fn fake_repo_id() -> borg::RepoId {
-    borg::RepoId::new(format!("-randomid-{}", glib::uuid_string_random()))
+    borg::RepoId::new(format!(
+        "{FAKE_REPO_ID_PREFIX}{}",
+        glib::uuid_string_random()
+    ))
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
@@ -322,3 +649,131 @@ impl ConfigVersion for Backups {
             .unwrap_or(2)
     }
 }
+
+#[test]
+fn test_repo_id_known() {
+    let mut config = Backup::test_new_mock();
+    assert!(!config.is_repo_id_known());
+
+    config.repo_id = borg::RepoId::new(String::from("e1b5fd0e4ac71e6b7a0a1c7a48d8dab2"));
+    assert!(config.is_repo_id_known());
+}
+
+#[test]
+fn test_checkpoint_interval() {
+    assert_eq!(Checkpoints::Default.borg_arg(), None);
+    assert_eq!(
+        Checkpoints::from_interval(true, 5 * 60)
+            .borg_arg()
+            .as_deref(),
+        Some("--checkpoint-interval=300")
+    );
+    assert_eq!(
+        Checkpoints::from_interval(true, Checkpoints::DEFAULT_INTERVAL),
+        Checkpoints::Default
+    );
+    assert_eq!(Checkpoints::from_interval(true, 1).interval(), 60);
+    assert_eq!(
+        Checkpoints::Interval(u32::MAX).interval(),
+        Checkpoints::MAX_INTERVAL
+    );
+    assert!(!Checkpoints::from_interval(false, 300).is_enabled());
+}
+
+#[test]
+fn test_skip_metadata_args() {
+    use borg::version::Version;
+
+    assert!(SkipMetadata::default()
+        .borg_args(Some(Version::new(1, 2, 7)))
+        .is_empty());
+
+    let skip = SkipMetadata {
+        atime: true,
+        ctime: true,
+        birthtime: false,
+        flags: true,
+    };
+    assert_eq!(
+        skip.borg_args(Some(Version::new(1, 2, 7))),
+        vec!["--noctime", "--noflags"]
+    );
+    assert_eq!(skip.borg_args(None), vec!["--noctime", "--noflags"]);
+    assert_eq!(
+        skip.borg_args(Some(Version::new(1, 1, 18))),
+        vec!["--noatime", "--noctime", "--nobsdflags"]
+    );
+}
+
+#[test]
+fn test_import_patterns_file() {
+    let mut config = Backup::test_new_mock();
+    let existing = Exclude::from_pattern(Pattern::fnmatch("*.tmp"));
+    config.exclude.insert(existing.clone());
+
+    let file = PatternsFile::new("/patterns".into(), super::PatternsFormat::Borg, false);
+    let parsed = patterns_file::parse_borg("*.tmp\n*.bak\n");
+    config.import_patterns_file(file.clone(), &parsed);
+    config.import_patterns_file(file, &parsed);
+
+    assert_eq!(config.patterns_files.len(), 1);
+    assert_eq!(config.patterns_files[0].imported.len(), 1);
+    assert!(config
+        .exclude
+        .contains(&Exclude::from_pattern(Pattern::fnmatch("*.bak"))));
+
+    config.remove_patterns_file(std::path::Path::new("/patterns"));
+    assert!(config.patterns_files.is_empty());
+    assert!(config.exclude.contains(&existing));
+    assert!(!config
+        .exclude
+        .contains(&Exclude::from_pattern(Pattern::fnmatch("*.bak"))));
+}
+
+#[test]
+fn test_performance_wrapper() {
+    assert!(Performance::Normal.wrapper(true).is_empty());
+    assert_eq!(
+        Performance::Low.wrapper(false),
+        vec!["nice", "-n", "19", "ionice", "-c", "3"]
+    );
+    assert_eq!(
+        Performance::High.wrapper(true),
+        vec![
+            "systemd-run",
+            "--user",
+            "--scope",
+            "--quiet",
+            "--property=CPUWeight=500",
+            "--property=IOWeight=500",
+            "ionice",
+            "-c",
+            "2",
+            "-n",
+            "0"
+        ]
+    );
+}
+
+#[test]
+fn test_plaintext() {
+    let mut config = Backup::test_new_mock();
+
+    config.encryption_mode = "repokey-blake2".to_string();
+    assert!(!config.is_plaintext());
+
+    config.encryption_mode = "authenticated-blake2".to_string();
+    assert!(config.is_plaintext());
+
+    config.encryption_mode = "none".to_string();
+    assert!(config.is_plaintext());
+    // Local folder on a fixed drive
+    assert!(!config.warn_plaintext());
+
+    config.repo =
+        super::remote::Repository::from_uri("ssh://example.org/./repo".to_string()).into_config();
+    assert!(config.warn_plaintext());
+
+    config.plaintext_warning_dismissed = true;
+    assert!(!config.warn_plaintext());
+}
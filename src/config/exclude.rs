@@ -1,8 +1,10 @@
 use super::*;
 
 use once_cell::sync::Lazy;
+use std::collections::BTreeSet;
 use std::ffi::OsString;
 use std::io::Read;
+use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -28,6 +30,69 @@ impl Exclude<{ RELATIVE }> {
             Self::Predefined(x) => Exclude::Predefined(x),
         }
     }
+
+    /// Representation as one line of text, like `fm:*.o` or `predefined:caches`
+    pub fn to_line(&self) -> String {
+        match self {
+            Self::Pattern(pattern) => pattern.borg_pattern().to_string_lossy().to_string(),
+            Self::Predefined(predefined) => format!("{PREDEFINED_SELECTOR}:{}", predefined.name()),
+        }
+    }
+
+    /// Parse a line created by [`Self::to_line`]
+    pub fn from_line(line: &str) -> std::result::Result<Self, String> {
+        let Some((selector, value)) = line.split_once(':') else {
+            return Err(gettext("Missing rule type like “pp:” or “fm:”"));
+        };
+
+        if value.is_empty() {
+            return Err(gettext("Empty rule"));
+        }
+
+        match selector {
+            PREDEFINED_SELECTOR => Predefined::from_name(value)
+                .map(Self::Predefined)
+                .ok_or_else(|| gettextf("Unknown predefined rule “{}”", &[value])),
+            "re" => regex::Regex::new(value)
+                .map(|regex| Self::Pattern(Pattern::RegularExpression(regex)))
+                .map_err(|err| gettextf("Invalid regular expression: {}", &[&err.to_string()])),
+            "fm" | "pp" | "pf" => Pattern::from_borg(line.to_string())
+                .map(|pattern| Self::Pattern(pattern.into_relative()))
+                .ok_or_else(|| gettext("Invalid rule")),
+            _ => Err(gettextf("Unknown rule type “{}”", &[selector])),
+        }
+    }
+}
+
+const PREDEFINED_SELECTOR: &str = "predefined";
+
+/// Parse exclusion rules given as one rule per line
+///
+/// Empty lines and lines starting with `#` are ignored. Errors contain the
+/// line number starting at one.
+pub fn excludes_from_text(
+    text: &str,
+) -> std::result::Result<BTreeSet<Exclude<{ RELATIVE }>>, (usize, String)> {
+    let mut excludes = BTreeSet::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        excludes.insert(Exclude::from_line(line).map_err(|err| (number + 1, err))?);
+    }
+
+    Ok(excludes)
+}
+
+/// Inverse of [`excludes_from_text`]
+pub fn excludes_to_text(excludes: &BTreeSet<Exclude<{ RELATIVE }>>) -> String {
+    excludes
+        .iter()
+        .map(|exclude| exclude.to_line() + "\n")
+        .collect()
 }
 
 impl<const T: Relativity> Exclude<T> {
@@ -175,6 +240,20 @@ impl Predefined {
         Self::VmsContainers,
     ];
 
+    /// Identifier used in the text representation of exclusion rules
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Caches => "caches",
+            Self::Trash => "trash",
+            Self::FlatpakApps => "flatpak-apps",
+            Self::VmsContainers => "vms-containers",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::VALUES.into_iter().find(|x| x.name() == name)
+    }
+
     pub fn symbolic_icon(&self) -> gtk::Image {
         match self {
             Self::Trash => gtk::Image::from_icon_name("user-trash-symbolic"),
@@ -256,17 +335,36 @@ impl From<&Rule<ABSOLUTE>> for BorgRule {
 impl std::fmt::Display for BorgRule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Pattern(pattern) => {
-                write!(f, "{}", pattern.clone().into_string().unwrap_or_default())
-            }
+            Self::Pattern(pattern) => write!(f, "{}", pattern.to_string_lossy()),
             Self::CacheDirTag => write!(f, "CACHEDIR.TAG"),
         }
     }
 }
 
-fn borg_regex_path(path: &Path) -> String {
-    // TODO: many unwraps
-    regex::escape(path.strip_prefix("/").unwrap().to_str().unwrap()).to_string()
+pub(super) fn borg_regex_path(path: &Path) -> String {
+    let path = path.strip_prefix("/").unwrap_or(path);
+    let mut bytes = path.as_os_str().as_bytes();
+    let mut regex = String::new();
+
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                regex.push_str(&regex::escape(valid));
+                return regex;
+            }
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                regex.push_str(&regex::escape(
+                    std::str::from_utf8(valid).unwrap_or_default(),
+                ));
+
+                // Borg decodes every byte that is not valid UTF-8 into one character
+                let invalid = err.error_len().unwrap_or(rest.len());
+                regex.push_str(&".".repeat(invalid));
+                bytes = &rest[invalid..];
+            }
+        }
+    }
 }
 
 /// <https://bford.info/cachedir/>
@@ -281,3 +379,31 @@ fn path_is_cachedir(directory: &std::path::Path) -> bool {
         false
     }
 }
+
+#[test]
+fn test_excludes_text() {
+    let mut excludes = BTreeSet::new();
+    excludes.insert(Exclude::from_predefined(Predefined::FlatpakApps));
+    excludes.insert(Exclude::from_pattern(Pattern::fnmatch("*.o")));
+    excludes.insert(Exclude::from_pattern(Pattern::PathPrefix(
+        "Downloads".into(),
+    )));
+    excludes.insert(Exclude::from_pattern(
+        Pattern::from_regular_expression("^home/[^/]+/tmp/").unwrap(),
+    ));
+
+    let text = excludes_to_text(&excludes);
+    assert!(text.contains("predefined:flatpak-apps\n"));
+    assert_eq!(excludes_from_text(&text), Ok(excludes));
+
+    assert_eq!(
+        excludes_from_text("# comment\n\n  predefined:trash  \n"),
+        Ok(BTreeSet::from([Exclude::from_predefined(
+            Predefined::Trash
+        )]))
+    );
+    assert_eq!(excludes_from_text("fm:*.o\nfoo").unwrap_err().0, 2);
+    assert_eq!(excludes_from_text("predefined:foo").unwrap_err().0, 1);
+    assert_eq!(excludes_from_text("xx:foo").unwrap_err().0, 1);
+    assert_eq!(excludes_from_text("re:(").unwrap_err().0, 1);
+}
@@ -1,3 +1,5 @@
+pub mod journal;
+
 use crate::borg;
 use crate::borg::log_json::LogCollection;
 use crate::config;
@@ -9,10 +11,27 @@ use chrono::prelude::*;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 const HISTORY_LENGTH: usize = 100;
+/// Number of months for which the data sent over the network is kept
+const NETWORK_USAGE_MONTHS: usize = 12;
+
+/// Runs taking this many times longer than the median are flagged
+pub const DURATION_ANOMALY_FACTOR: i32 = 3;
+/// Number of previous completed runs that determine the usual duration
+const DURATION_MEDIAN_RUNS: usize = 10;
+/// The usual duration is only known after some completed runs
+const DURATION_MIN_RUNS: usize = 3;
+/// Runs shorter than this are never flagged
+const DURATION_ANOMALY_MIN_SECONDS: i64 = 60;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SuggestedExcludeReason {
     PermissionDenied,
+    /// Folders used by other backup tools
+    ///
+    /// Stored in [`History::suggested_exclude_other_backup_tool`] since older
+    /// versions fail to read histories with unknown reasons.
+    #[serde(skip)]
+    OtherBackupTool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
@@ -30,10 +49,28 @@ pub struct History {
     #[serde(default)]
     pub last_check: Option<CheckRunInfo>,
 
+    /// Last upgrade of the repository format
+    #[serde(default)]
+    pub last_upgrade: Option<DateTime<Local>>,
+
+    /// Last completed maintenance run
+    #[serde(default)]
+    pub last_maintenance: Option<DateTime<Local>>,
+
     // The excludes suggested from the last size estimate. Will be overwritten every time a size estimate is performed.
     #[serde(default)]
     pub suggested_exclude:
         BTreeMap<SuggestedExcludeReason, BTreeSet<config::Exclude<{ config::RELATIVE }>>>,
+    /// Excludes suggested for [`SuggestedExcludeReason::OtherBackupTool`]
+    #[serde(default)]
+    pub suggested_exclude_other_backup_tool: BTreeSet<config::Exclude<{ config::RELATIVE }>>,
+
+    /// Data sent to remote repositories per month, like `2024-03`
+    #[serde(default)]
+    pub monthly_sent: BTreeMap<String, u64>,
+    /// Data received from remote repositories per month
+    #[serde(default)]
+    pub monthly_received: BTreeMap<String, u64>,
 }
 
 impl History {
@@ -42,11 +79,128 @@ impl History {
             self.last_completed = Some(entry.clone());
         }
 
+        for (monthly, data) in [
+            (&mut self.monthly_sent, entry.sent),
+            (&mut self.monthly_received, entry.received),
+        ] {
+            if let Some(data) = data {
+                *monthly.entry(month(entry.end)).or_default() += data;
+                while monthly.len() > NETWORK_USAGE_MONTHS {
+                    monthly.pop_first();
+                }
+            }
+        }
+
         self.running = None;
         self.run.push_front(entry);
         self.run.truncate(HISTORY_LENGTH);
     }
 
+    /// Data sent to the remote repository in the month of `date`
+    pub fn sent_in_month(&self, date: DateTime<Local>) -> u64 {
+        self.monthly_sent
+            .get(&month(date))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Data received from the remote repository in the month of `date`
+    ///
+    /// Not known if borg did not report the network usage in that month.
+    pub fn received_in_month(&self, date: DateTime<Local>) -> Option<u64> {
+        self.monthly_received.get(&month(date)).copied()
+    }
+
+    /// A backup is running that was not interrupted by the end of the session
+    ///
+    /// Interrupted backups can remain marked as running if the process has been
+    /// terminated during the session end.
+    pub fn is_running(&self) -> bool {
+        self.running
+            .as_ref()
+            .map_or(false, |running| !running.session_end)
+    }
+
+    /// The last backup was interrupted by the end of the session
+    pub fn is_interrupted_by_session_end(&self) -> bool {
+        self.running
+            .as_ref()
+            .map_or(false, |running| running.session_end)
+            || matches!(
+                self.run.front().map(|run| &run.outcome),
+                Some(borg::Outcome::Aborted(borg::error::Abort::SessionEnd))
+            )
+    }
+
+    /// Durations of completed runs, latest run first
+    pub fn completed_durations(&self) -> impl Iterator<Item = chrono::Duration> + '_ {
+        self.run
+            .iter()
+            .filter(|run| run.outcome.is_completed())
+            .filter_map(RunInfo::duration)
+    }
+
+    /// Median duration of the completed runs before the run at `index`
+    pub fn usual_duration(&self, index: usize) -> Option<chrono::Duration> {
+        let mut durations: Vec<_> = self
+            .run
+            .iter()
+            .skip(index + 1)
+            .filter(|run| run.outcome.is_completed())
+            .filter_map(RunInfo::duration)
+            .take(DURATION_MEDIAN_RUNS)
+            .collect();
+
+        if durations.len() < DURATION_MIN_RUNS {
+            return None;
+        }
+
+        durations.sort();
+        Some(durations[durations.len() / 2])
+    }
+
+    /// The run at `index` took much longer than the runs before
+    ///
+    /// Can hint at unintentionally included folders or a failing disk.
+    pub fn is_duration_anomaly(&self, index: usize) -> bool {
+        let Some(duration) = self.run.get(index).and_then(RunInfo::duration) else {
+            return false;
+        };
+
+        duration.num_seconds() >= DURATION_ANOMALY_MIN_SECONDS
+            && self
+                .usual_duration(index)
+                .is_some_and(|usual| duration > usual * DURATION_ANOMALY_FACTOR)
+    }
+
+    /// Runs that ended after `since`, latest run first
+    pub fn runs_since(&self, since: DateTime<Local>) -> impl Iterator<Item = &RunInfo> + '_ {
+        self.run.iter().take_while(move |run| run.end > since)
+    }
+
+    /// Summary of the runs that ended after `since`
+    pub fn trend_since(&self, since: DateTime<Local>) -> Trend {
+        let mut trend = Trend::default();
+        let mut durations = Vec::new();
+
+        for run in self.runs_since(since) {
+            match &run.outcome {
+                borg::Outcome::Completed { stats } => {
+                    trend.completed += 1;
+                    trend.deduplicated_size += stats.archive.stats.deduplicated_size;
+                    durations.extend(run.duration());
+                }
+                borg::Outcome::Aborted(_) => trend.aborted += 1,
+                borg::Outcome::Failed(_) => trend.failed += 1,
+            }
+        }
+
+        durations.sort();
+        trend.median_duration = durations.get(durations.len() / 2).copied();
+
+        trend
+    }
+
     pub fn set_suggested_excludes_from_absolute(
         &mut self,
         reason: SuggestedExcludeReason,
@@ -61,10 +215,40 @@ impl History {
         }
 
         // Overwrite the previous suggested exclude list
-        self.suggested_exclude.insert(reason, excludes);
+        match reason {
+            SuggestedExcludeReason::OtherBackupTool => {
+                self.suggested_exclude_other_backup_tool = excludes;
+            }
+            reason => {
+                self.suggested_exclude.insert(reason, excludes);
+            }
+        }
+    }
+
+    pub fn suggested_excludes(
+        &self,
+        reason: &SuggestedExcludeReason,
+    ) -> Option<&BTreeSet<config::Exclude<{ config::RELATIVE }>>> {
+        match reason {
+            SuggestedExcludeReason::OtherBackupTool => {
+                Some(&self.suggested_exclude_other_backup_tool)
+            }
+            reason => self.suggested_exclude.get(reason),
+        }
     }
 }
 
+/// Summary of the runs in a period
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trend {
+    pub completed: usize,
+    pub aborted: usize,
+    pub failed: usize,
+    /// Data added to the repository by the completed runs
+    pub deduplicated_size: u64,
+    pub median_duration: Option<chrono::Duration>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 pub struct Histories(pub BTreeMap<config::ConfigId, History>);
 
@@ -76,6 +260,18 @@ impl super::ConfigType for Histories {
 
         path
     }
+
+    fn read() -> std::io::Result<Self> {
+        journal::load()
+    }
+
+    fn write(written: &Self, current: &Self) -> std::io::Result<()> {
+        journal::append(written, current)
+    }
+
+    fn monitored_paths() -> Vec<std::path::PathBuf> {
+        vec![Self::path(), journal::path()]
+    }
 }
 
 impl super::ConfigVersion for Histories {
@@ -113,9 +309,11 @@ impl Histories {
 
         for (_, history) in histories.0.iter_mut() {
             if let Some(running) = &history.running {
-                history
-                    .run
-                    .push_front(RunInfo::new_left_running(&running.start));
+                history.run.push_front(if running.session_end {
+                    RunInfo::new_session_end(&running.start)
+                } else {
+                    RunInfo::new_left_running(&running.start)
+                });
                 history.running = None;
                 history.run.truncate(HISTORY_LENGTH);
             }
@@ -127,9 +325,11 @@ impl Histories {
     pub fn handle_shutdown(histories: &mut Self) {
         for (_, history) in histories.0.iter_mut() {
             if let Some(running) = &history.running {
-                history
-                    .run
-                    .push_front(RunInfo::new_shutdown(&running.start));
+                history.run.push_front(if running.session_end {
+                    RunInfo::new_session_end(&running.start)
+                } else {
+                    RunInfo::new_shutdown(&running.start)
+                });
                 history.running = None;
                 history.run.truncate(HISTORY_LENGTH);
             }
@@ -148,15 +348,53 @@ impl Histories {
         history.last_check = Some(check_info);
     }
 
+    pub fn set_last_upgrade(&mut self, config_id: ConfigId, date: DateTime<Local>) {
+        let history = self.0.entry(config_id).or_default();
+
+        history.last_upgrade = Some(date);
+    }
+
+    pub fn set_last_maintenance(&mut self, config_id: ConfigId, date: DateTime<Local>) {
+        let history = self.0.entry(config_id).or_default();
+
+        history.last_maintenance = Some(date);
+    }
+
+    /// Mark the run that ended at `end` as verified
+    pub fn set_verified(&mut self, config_id: ConfigId, end: DateTime<Local>) {
+        let history = self.0.entry(config_id).or_default();
+
+        for run_info in history
+            .run
+            .iter_mut()
+            .chain(history.last_completed.as_mut())
+            .filter(|run_info| run_info.end == end)
+        {
+            run_info.verified = true;
+        }
+    }
+
     pub fn set_running(&mut self, config_id: ConfigId) {
         debug!("Set {:?} to state running.", config_id);
         let history = self.0.entry(config_id).or_default();
 
         history.running = Some(Running {
             start: Local::now(),
+            session_end: false,
         });
     }
 
+    /// Mark the running backup as aborted because the session ends
+    ///
+    /// The mark is kept if the process is terminated before the abort completes.
+    pub fn set_session_end(&mut self, config_id: ConfigId) {
+        let history = self.0.entry(config_id).or_default();
+
+        if let Some(running) = &mut history.running {
+            running.session_end = true;
+        }
+    }
+
     pub fn remove_running(&mut self, config_id: ConfigId) {
         debug!("Set {:?} to state not running", config_id);
         let history = self.0.entry(config_id).or_default();
@@ -171,11 +409,30 @@ impl Histories {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct RunInfo {
+    /// Unknown for runs from older versions
+    #[serde(default)]
+    pub start: Option<DateTime<Local>>,
     pub end: DateTime<Local>,
     pub outcome: borg::Outcome,
     pub messages: borg::log_json::LogCollection,
+    #[serde(with = "super::path_serde::set")]
     pub include: BTreeSet<std::path::PathBuf>,
     pub exclude: BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
+    /// Unknown for runs from older versions
+    #[serde(default)]
+    pub versions: Option<SoftwareVersions>,
+    /// The written data was read back and checked after the run
+    #[serde(default)]
+    pub verified: bool,
+    /// Data sent over the network, only known for remote repositories
+    ///
+    /// Reported by borg for SSH repositories, otherwise estimated from the
+    /// deduplicated size of the archive.
+    #[serde(default)]
+    pub sent: Option<u64>,
+    /// Data received over the network, only known for SSH repositories
+    #[serde(default)]
+    pub received: Option<u64>,
 }
 
 impl RunInfo {
@@ -185,6 +442,7 @@ impl RunInfo {
         messages: borg::log_json::LogCollection,
     ) -> Self {
         Self {
+            start: None,
             end: Local::now(),
             outcome,
             messages,
@@ -196,32 +454,75 @@ impl RunInfo {
                     .into_iter()
                     .map(|x| x.into_absolute()),
             ),
+            versions: Some(SoftwareVersions::current()),
+            verified: false,
+            sent: None,
+            received: None,
+        }
+    }
+
+    /// Time the run took
+    ///
+    /// Completed runs from older versions fall back to the duration reported by borg.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        match (&self.start, &self.outcome) {
+            (Some(start), _) => Some(self.end - *start),
+            (None, borg::Outcome::Completed { stats }) => Some(chrono::Duration::milliseconds(
+                (stats.archive.duration * 1000.) as i64,
+            )),
+            _ => None,
         }
     }
 
     pub fn new_left_running(date: &DateTime<Local>) -> Self {
         Self {
+            start: None,
             end: *date,
             outcome: borg::Outcome::Aborted(borg::error::Abort::LeftRunning),
             messages: vec![],
             include: Default::default(),
             exclude: Default::default(),
+            versions: None,
+            verified: false,
+            sent: None,
+            received: None,
+        }
+    }
+
+    pub fn new_session_end(date: &DateTime<Local>) -> Self {
+        Self {
+            start: None,
+            end: *date,
+            outcome: borg::Outcome::Aborted(borg::error::Abort::SessionEnd),
+            messages: vec![],
+            include: Default::default(),
+            exclude: Default::default(),
+            versions: None,
+            verified: false,
+            sent: None,
+            received: None,
         }
     }
 
     pub fn new_shutdown(date: &DateTime<Local>) -> Self {
         Self {
+            start: None,
             end: *date,
             outcome: borg::Outcome::Aborted(borg::error::Abort::Shutdown),
             messages: vec![],
             include: Default::default(),
             exclude: Default::default(),
+            versions: None,
+            verified: false,
+            sent: None,
+            received: None,
         }
     }
 
     #[cfg(test)]
     pub fn test_new_mock(ago: chrono::Duration) -> Self {
         Self {
+            start: None,
             end: Local::now() - ago,
             outcome: borg::Outcome::Completed {
                 stats: borg::json::Stats::test_new_mock(),
@@ -229,6 +530,29 @@ impl RunInfo {
             messages: Default::default(),
             include: Default::default(),
             exclude: Default::default(),
+            versions: None,
+            verified: false,
+            sent: None,
+            received: None,
+        }
+    }
+}
+
+/// Software that performed a run
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SoftwareVersions {
+    pub pika_backup: String,
+    pub borg: Option<String>,
+    /// Only set if running sandboxed
+    pub flatpak_runtime: Option<String>,
+}
+
+impl SoftwareVersions {
+    pub fn current() -> Self {
+        Self {
+            pika_backup: env!("CARGO_PKG_VERSION").to_string(),
+            borg: borg::version::Version::cached().map(|x| x.to_string()),
+            flatpak_runtime: crate::utils::flatpak_runtime(),
         }
     }
 }
@@ -236,6 +560,9 @@ impl RunInfo {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Running {
     pub start: DateTime<Local>,
+    /// The backup is being aborted because the session ends
+    #[serde(default)]
+    pub session_end: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -281,3 +608,112 @@ pub enum CheckOutcome {
     Repair(LogCollection),
     Error(LogCollection),
 }
+
+/// Key for the month of `date` in [`History::monthly_sent`]
+fn month(date: DateTime<Local>) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+#[test]
+fn test_duration_anomaly() {
+    let mut history = History::default();
+
+    for minutes in [10, 12, 11, 9, 50] {
+        let mut run_info = RunInfo::test_new_mock(chrono::Duration::zero());
+        run_info.start = Some(run_info.end - chrono::Duration::minutes(minutes));
+        history.insert(run_info);
+    }
+
+    assert_eq!(history.completed_durations().count(), 5);
+    assert_eq!(
+        history.usual_duration(0),
+        Some(chrono::Duration::minutes(11))
+    );
+    assert!(history.is_duration_anomaly(0));
+    assert!(!history.is_duration_anomaly(1));
+    // Not enough previous runs
+    assert_eq!(history.usual_duration(3), None);
+    assert!(!history.is_duration_anomaly(3));
+}
+
+#[test]
+fn test_trend() {
+    let mut history = History::default();
+
+    for hours in [30, 3, 2, 1] {
+        let mut run_info = RunInfo::test_new_mock(chrono::Duration::hours(hours));
+        run_info.start = Some(run_info.end - chrono::Duration::minutes(hours));
+        history.insert(run_info);
+    }
+    history.insert(RunInfo::new_shutdown(&Local::now()));
+
+    let trend = history.trend_since(Local::now() - chrono::Duration::days(1));
+    assert_eq!(trend.completed, 3);
+    assert_eq!(trend.aborted, 1);
+    assert_eq!(trend.failed, 0);
+    assert_eq!(trend.median_duration, Some(chrono::Duration::minutes(2)));
+    assert_eq!(history.runs_since(Local::now()).count(), 0);
+}
+
+#[test]
+fn test_set_verified() {
+    let id = ConfigId::new("a".to_string());
+    let mut histories = Histories::default();
+    let older = RunInfo::test_new_mock(chrono::Duration::days(1));
+    let latest = RunInfo::test_new_mock(chrono::Duration::zero());
+    histories.insert(id.clone(), older.clone());
+    histories.insert(id.clone(), latest.clone());
+
+    histories.set_verified(id.clone(), latest.end);
+
+    let history = &histories.0[&id];
+    assert!(history.run[0].verified);
+    assert!(!history.run[1].verified);
+    assert!(history.last_completed.as_ref().is_some_and(|x| x.verified));
+}
+
+#[test]
+fn test_monthly_sent() {
+    let mut history = History::default();
+    let now = Local::now();
+
+    let mut run_info = RunInfo::test_new_mock(chrono::Duration::zero());
+    history.insert(run_info.clone());
+    assert_eq!(history.sent_in_month(now), 0);
+
+    run_info.sent = Some(100);
+    history.insert(run_info.clone());
+    run_info.received = Some(10);
+    history.insert(run_info.clone());
+    assert_eq!(history.sent_in_month(now), 200);
+    assert_eq!(history.received_in_month(now), Some(10));
+    assert_eq!(history.sent_in_month(now - chrono::Duration::days(40)), 0);
+
+    for months in 1..=NETWORK_USAGE_MONTHS as i64 {
+        run_info.end = now - chrono::Duration::days(31 * months);
+        history.insert(run_info.clone());
+    }
+    assert_eq!(history.monthly_sent.len(), NETWORK_USAGE_MONTHS);
+    assert_eq!(history.sent_in_month(now), 200);
+}
+
+#[test]
+fn test_suggested_exclude_other_backup_tool() {
+    let mut history = History::default();
+    history.set_suggested_excludes_from_absolute(
+        SuggestedExcludeReason::OtherBackupTool,
+        vec!["/home/user/.snapshots"],
+    );
+
+    // Older versions only know the reasons stored in `suggested_exclude`
+    let json = serde_json::to_string(&history).unwrap();
+    assert!(!json.contains("\"OtherBackupTool\""));
+
+    let history: History = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        history
+            .suggested_excludes(&SuggestedExcludeReason::OtherBackupTool)
+            .map(BTreeSet::len),
+        Some(1)
+    );
+}
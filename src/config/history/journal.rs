@@ -0,0 +1,517 @@
+/*!
+# Append-only journal of history changes
+
+Rewriting `history.json` as a whole loses entries if the UI and the daemon
+change the history at the same time. Instead, every change is appended as one
+JSON line to `history-journal.jsonl`. Loading reads `history.json` as a
+snapshot and applies the journal on top of it.
+
+Changes that can't be expressed as single events replace the history of a
+backup configuration. They are merged with the latest state in the journal
+first, such that changes of the other process are kept.
+
+Once the journal holds more than [`COMPACT_ENTRIES`] entries, the result is
+written back to `history.json` and the journal is cleared. All access holds a
+`flock` on the journal, shared for reading and exclusive for changes.
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Seek, Write};
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use super::{CheckRunInfo, Histories, History, RunInfo, Running, SuggestedExcludeReason};
+use crate::config;
+use crate::config::ConfigType;
+use crate::prelude::*;
+use chrono::prelude::*;
+
+const COMPACT_ENTRIES: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub config_id: ConfigId,
+    pub date: DateTime<Local>,
+    pub event: Event,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A run has finished
+    Run(RunInfo),
+    Running(Option<Running>),
+    Check(CheckRunInfo),
+    Upgrade(DateTime<Local>),
+    SuggestedExclude(
+        BTreeMap<SuggestedExcludeReason, BTreeSet<config::Exclude<{ config::RELATIVE }>>>,
+    ),
+    /// Changes that are not covered by the other events
+    Replace(Box<History>),
+    /// The backup configuration has been removed
+    Remove,
+}
+
+impl Event {
+    fn apply(self, history: &mut History) {
+        match self {
+            Self::Run(run_info) => history.insert(run_info),
+            Self::Running(running) => history.running = running,
+            Self::Check(check_info) => history.last_check = Some(check_info),
+            Self::Upgrade(date) => history.last_upgrade = Some(date),
+            Self::SuggestedExclude(suggested_exclude) => {
+                history.suggested_exclude = suggested_exclude
+            }
+            Self::Replace(new) => *history = *new,
+            Self::Remove => *history = Default::default(),
+        }
+    }
+}
+
+fn apply(histories: &mut Histories, entry: Entry) {
+    match entry.event {
+        Event::Remove => {
+            histories.0.remove(&entry.config_id);
+        }
+        event => event.apply(histories.0.entry(entry.config_id).or_default()),
+    }
+}
+
+/// Events that turn the `written` history into the `current` one
+fn events(written: Option<&History>, current: Option<&History>) -> Vec<Event> {
+    let Some(current) = current else {
+        return if written.is_some() {
+            vec![Event::Remove]
+        } else {
+            Vec::new()
+        };
+    };
+
+    let mut replayed = written.cloned().unwrap_or_default();
+    if replayed == *current {
+        return Vec::new();
+    }
+
+    let mut events = Vec::new();
+
+    // New runs are added in front
+    let new_runs = replayed.run.front().map_or(current.run.len(), |front| {
+        current
+            .run
+            .iter()
+            .position(|run| run == front)
+            .unwrap_or(current.run.len())
+    });
+    for run_info in current.run.iter().take(new_runs).rev() {
+        events.push(Event::Run(run_info.clone()));
+    }
+    for event in &events {
+        event.clone().apply(&mut replayed);
+    }
+
+    if replayed.running != current.running {
+        events.push(Event::Running(current.running.clone()));
+    }
+    if replayed.last_check != current.last_check {
+        if let Some(check_info) = &current.last_check {
+            events.push(Event::Check(check_info.clone()));
+        }
+    }
+    if replayed.last_upgrade != current.last_upgrade {
+        if let Some(date) = current.last_upgrade {
+            events.push(Event::Upgrade(date));
+        }
+    }
+    if replayed.suggested_exclude != current.suggested_exclude {
+        events.push(Event::SuggestedExclude(current.suggested_exclude.clone()));
+    }
+
+    let mut replayed = written.cloned().unwrap_or_default();
+    for event in &events {
+        event.clone().apply(&mut replayed);
+    }
+
+    if replayed == *current {
+        events
+    } else {
+        vec![Event::Replace(Box::new(current.clone()))]
+    }
+}
+
+/// Three-way merge of the changes from `written` to `current` into `latest`
+///
+/// `latest` can contain changes of another process that are unknown to `current`.
+fn merge(written: &History, current: &History, latest: &History) -> History {
+    fn pick<T: PartialEq + Clone>(written: &T, current: &T, latest: &T) -> T {
+        if current != written {
+            current.clone()
+        } else {
+            latest.clone()
+        }
+    }
+
+    // Runs are identified by their end
+    let removed = |run: &&RunInfo| {
+        written.run.iter().any(|x| x.end == run.end)
+            && !current.run.iter().any(|x| x.end == run.end)
+    };
+    let mut run: Vec<RunInfo> = latest
+        .run
+        .iter()
+        .filter(|run| !removed(run))
+        .cloned()
+        .collect();
+    for changed in current.run.iter().filter(|run| !written.run.contains(run)) {
+        match run.iter_mut().find(|x| x.end == changed.end) {
+            Some(existing) => *existing = changed.clone(),
+            None => run.push(changed.clone()),
+        }
+    }
+    run.sort_by(|a, b| b.end.cmp(&a.end));
+    run.truncate(super::HISTORY_LENGTH);
+
+    History {
+        config_version: pick(
+            &written.config_version,
+            &current.config_version,
+            &latest.config_version,
+        ),
+        run: run.into(),
+        running: pick(&written.running, &current.running, &latest.running),
+        // Changes to the last completed run don't replace a newer run
+        last_completed: match (&current.last_completed, &latest.last_completed) {
+            (Some(current_run), Some(latest_run)) if latest_run.end > current_run.end => {
+                Some(latest_run.clone())
+            }
+            _ => pick(
+                &written.last_completed,
+                &current.last_completed,
+                &latest.last_completed,
+            ),
+        },
+        last_check: pick(&written.last_check, &current.last_check, &latest.last_check),
+        last_upgrade: pick(
+            &written.last_upgrade,
+            &current.last_upgrade,
+            &latest.last_upgrade,
+        ),
+        last_maintenance: pick(
+            &written.last_maintenance,
+            &current.last_maintenance,
+            &latest.last_maintenance,
+        ),
+        suggested_exclude: pick(
+            &written.suggested_exclude,
+            &current.suggested_exclude,
+            &latest.suggested_exclude,
+        ),
+        suggested_exclude_other_backup_tool: pick(
+            &written.suggested_exclude_other_backup_tool,
+            &current.suggested_exclude_other_backup_tool,
+            &latest.suggested_exclude_other_backup_tool,
+        ),
+        monthly_sent: pick(
+            &written.monthly_sent,
+            &current.monthly_sent,
+            &latest.monthly_sent,
+        ),
+        monthly_received: pick(
+            &written.monthly_received,
+            &current.monthly_received,
+            &latest.monthly_received,
+        ),
+    }
+}
+
+pub fn path() -> PathBuf {
+    let mut path = Histories::path();
+    path.set_file_name("history-journal.jsonl");
+
+    path
+}
+
+fn open(path: &Path) -> std::io::Result<std::fs::File> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    std::fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)
+}
+
+/// Blocks until the lock is acquired, it's released when the file is closed
+fn lock(file: &std::fs::File, arg: nix::fcntl::FlockArg) -> std::io::Result<()> {
+    nix::fcntl::flock(file.as_raw_fd(), arg)?;
+    Ok(())
+}
+
+fn read_entries(mut file: &std::fs::File) -> std::io::Result<Vec<Entry>> {
+    file.rewind()?;
+
+    let mut entries = Vec::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // A process terminated while writing can leave an incomplete line behind
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => warn!("Ignoring invalid history journal entry: {}", err),
+        }
+    }
+
+    Ok(entries)
+}
+
+fn replay(snapshot: &Path, journal: &std::fs::File) -> std::io::Result<Histories> {
+    let mut histories: Histories = config::loadable::read_file(snapshot)?;
+
+    for entry in read_entries(journal)? {
+        apply(&mut histories, entry);
+    }
+
+    Ok(histories)
+}
+
+fn load_from(snapshot: &Path, journal: &Path) -> std::io::Result<Histories> {
+    let journal = open(journal)?;
+    lock(&journal, nix::fcntl::FlockArg::LockShared)?;
+
+    replay(snapshot, &journal)
+}
+
+/// Whether the file ends in the middle of a line
+fn ends_with_incomplete_line(file: &std::fs::File) -> std::io::Result<bool> {
+    use std::os::unix::fs::FileExt;
+
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(false);
+    }
+
+    let mut last = [0];
+    file.read_at(&mut last, len - 1)?;
+
+    Ok(last != *b"\n")
+}
+
+fn append_to(
+    snapshot: &Path,
+    journal: &Path,
+    written: &Histories,
+    current: &Histories,
+) -> std::io::Result<()> {
+    let date = Local::now();
+    let config_ids: BTreeSet<_> = written.0.keys().chain(current.0.keys()).collect();
+
+    let mut file = open(journal)?;
+    lock(&file, nix::fcntl::FlockArg::LockExclusive)?;
+
+    // Only read if needed since replaying reads the whole journal
+    let mut latest: Option<Histories> = None;
+
+    let mut lines = String::new();
+    for config_id in config_ids {
+        for mut event in events(written.0.get(config_id), current.0.get(config_id)) {
+            if let (Event::Replace(_), Some(current)) = (&event, current.0.get(config_id)) {
+                if latest.is_none() {
+                    latest = Some(replay(snapshot, &file)?);
+                }
+                let latest = latest.as_ref().and_then(|x| x.0.get(config_id));
+
+                event = Event::Replace(Box::new(merge(
+                    written.0.get(config_id).unwrap_or(&Default::default()),
+                    current,
+                    latest.unwrap_or(&Default::default()),
+                )));
+            }
+
+            let entry = Entry {
+                config_id: config_id.clone(),
+                date,
+                event,
+            };
+            lines.push_str(&serde_json::to_string(&entry)?);
+            lines.push('\n');
+        }
+    }
+
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    // Don't continue a line left incomplete by a terminated process
+    if ends_with_incomplete_line(&file)? {
+        lines.insert(0, '\n');
+    }
+
+    debug!("Appending to history journal {:?}", journal);
+    file.write_all(lines.as_bytes())?;
+    file.sync_data()?;
+
+    if read_entries(&file)?.len() > COMPACT_ENTRIES {
+        compact_locked(snapshot, &file)?;
+    }
+
+    Ok(())
+}
+
+fn compact_locked(snapshot: &Path, journal: &std::fs::File) -> std::io::Result<()> {
+    info!("Compacting history journal into {:?}", snapshot);
+
+    let histories = replay(snapshot, journal)?;
+    config::writeable::write_file(snapshot, &histories)?;
+    journal.set_len(0)?;
+
+    Ok(())
+}
+
+fn compact_at(snapshot: &Path, journal: &Path) -> std::io::Result<()> {
+    let file = open(journal)?;
+    lock(&file, nix::fcntl::FlockArg::LockExclusive)?;
+
+    if file.metadata()?.len() > 0 {
+        compact_locked(snapshot, &file)?;
+    }
+
+    Ok(())
+}
+
+/// History from `history.json` with all journal entries applied
+pub fn load() -> std::io::Result<Histories> {
+    load_from(&Histories::path(), &path())
+}
+
+/// Append the changes from `written` to `current` to the journal
+pub fn append(written: &Histories, current: &Histories) -> std::io::Result<()> {
+    append_to(&Histories::path(), &path(), written, current)
+}
+
+/// Move all journal entries into `history.json`
+pub fn compact() -> std::io::Result<()> {
+    compact_at(&Histories::path(), &path())
+}
+
+#[test]
+fn test_events() {
+    let config_id = ConfigId::new("config".into());
+    let mut written = History::default();
+    written.insert(RunInfo::test_new_mock(chrono::Duration::hours(2)));
+
+    let mut current = written.clone();
+    current.insert(RunInfo::test_new_mock(chrono::Duration::hours(1)));
+    current.insert(RunInfo::test_new_mock(chrono::Duration::zero()));
+    current.last_upgrade = Some(Local::now());
+
+    let events = events(Some(&written), Some(&current));
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[0], Event::Run(_)));
+    assert!(matches!(events[2], Event::Upgrade(_)));
+
+    let mut histories = Histories(BTreeMap::from([(config_id.clone(), written.clone())]));
+    for event in events {
+        apply(
+            &mut histories,
+            Entry {
+                config_id: config_id.clone(),
+                date: Local::now(),
+                event,
+            },
+        );
+    }
+    assert_eq!(histories.0.get(&config_id), Some(&current));
+
+    // Removing the last check is not an event of its own
+    let mut checked = written.clone();
+    checked.last_check = Some(CheckRunInfo::new_success());
+    assert!(matches!(
+        self::events(Some(&checked), Some(&written)).as_slice(),
+        [Event::Replace(_)]
+    ));
+
+    assert_eq!(
+        self::events(Some(&written), None).as_slice(),
+        [Event::Remove]
+    );
+    assert!(self::events(Some(&written), Some(&written)).is_empty());
+}
+
+#[test]
+fn test_journal() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = dir.path().join("history.json");
+    let journal = dir.path().join("history-journal.jsonl");
+    let config_id = ConfigId::new("config".into());
+
+    let empty = Histories::default();
+    let mut first = empty.clone();
+    first.set_running(config_id.clone());
+    append_to(&snapshot, &journal, &empty, &first).unwrap();
+
+    // Another process that has not seen the first change
+    let mut second = empty.clone();
+    second.insert(
+        config_id.clone(),
+        RunInfo::test_new_mock(chrono::Duration::zero()),
+    );
+    append_to(&snapshot, &journal, &empty, &second).unwrap();
+
+    let loaded = load_from(&snapshot, &journal).unwrap();
+    let history = loaded.0.get(&config_id).unwrap();
+    assert_eq!(history.run.len(), 1);
+    assert!(history.running.is_none());
+
+    compact_at(&snapshot, &journal).unwrap();
+    assert_eq!(std::fs::metadata(&journal).unwrap().len(), 0);
+    assert_eq!(load_from(&snapshot, &journal).unwrap(), loaded);
+
+    // A line left incomplete by a terminated process
+    std::fs::write(&journal, "{\"config_id\":").unwrap();
+    append_to(&snapshot, &journal, &loaded, &first).unwrap();
+    assert!(load_from(&snapshot, &journal).unwrap().0[&config_id]
+        .running
+        .is_some());
+}
+
+#[test]
+fn test_journal_replace() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = dir.path().join("history.json");
+    let journal = dir.path().join("history-journal.jsonl");
+    let config_id = ConfigId::new("config".into());
+
+    let mut written = Histories::default();
+    written.insert(
+        config_id.clone(),
+        RunInfo::test_new_mock(chrono::Duration::hours(1)),
+    );
+    append_to(&snapshot, &journal, &Histories::default(), &written).unwrap();
+
+    // Another process adds a run
+    let mut other = written.clone();
+    other.insert(
+        config_id.clone(),
+        RunInfo::test_new_mock(chrono::Duration::zero()),
+    );
+    append_to(&snapshot, &journal, &written, &other).unwrap();
+
+    // Changes that are written as a replacement without knowing the new run
+    let mut current = written.clone();
+    let end = current.0[&config_id].run[0].end;
+    current.set_verified(config_id.clone(), end);
+    append_to(&snapshot, &journal, &written, &current).unwrap();
+
+    let loaded = load_from(&snapshot, &journal).unwrap();
+    let history = &loaded.0[&config_id];
+    assert_eq!(history.run.len(), 2);
+    assert!(history.run[1].verified);
+    assert!(!history.run[0].verified);
+    assert_eq!(
+        history.last_completed.as_ref().map(|x| x.end),
+        Some(history.run[0].end)
+    );
+}
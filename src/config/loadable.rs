@@ -3,7 +3,7 @@ use gio::prelude::*;
 
 use arc_swap::ArcSwap;
 use once_cell::sync::Lazy;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
 pub trait Loadable: Sized {
     fn from_file() -> Result<Self, std::io::Error>;
@@ -11,31 +11,38 @@ pub trait Loadable: Sized {
 
 impl<C: ConfigType + ConfigVersion + serde::de::DeserializeOwned + Default> Loadable for C {
     fn from_file() -> Result<Self, std::io::Error> {
-        let path = Self::path();
-        info!("Loading file {:?}", path);
-
-        let file_result = std::fs::File::open(&path);
-        if let Err(err) = &file_result {
-            if matches!(err.kind(), std::io::ErrorKind::NotFound) {
-                info!("File not found. Using default value.");
-                return Ok(Default::default());
-            }
+        Self::read()
+    }
+}
+
+/// Read a JSON file, falling back to the default value if it doesn't exist
+pub(super) fn read_file<C>(path: &std::path::Path) -> Result<C, std::io::Error>
+where
+    C: ConfigVersion + serde::de::DeserializeOwned + Default,
+{
+    info!("Loading file {:?}", path);
+
+    let file_result = std::fs::File::open(path);
+    if let Err(err) = &file_result {
+        if matches!(err.kind(), std::io::ErrorKind::NotFound) {
+            info!("File not found. Using default value.");
+            return Ok(Default::default());
         }
+    }
 
-        let file = file_result?;
+    let file = file_result?;
 
-        // Deserialize the file as an untyped json value
-        let json: serde_json::Value = serde_json::from_reader(file)?;
+    // Deserialize the file as an untyped json value
+    let json: serde_json::Value = serde_json::from_reader(file)?;
 
-        // Check the config version to figure out if we are compatible
-        let version = Self::extract_version(&json);
-        if Self::version_compatible(version) {
-            // Deserialize value as Self
-            Ok(serde_json::from_value(json)?)
-        } else {
-            // The config is incompatible with this app version
-            Err(std::io::Error::new(std::io::ErrorKind::InvalidData, gettextf("The loaded configuration file version {} is incompatible with this version of Pika Backup", &[&version.to_string()])))
-        }
+    // Check the config version to figure out if we are compatible
+    let version = C::extract_version(&json);
+    if C::version_compatible(version) {
+        // Deserialize value as Self
+        Ok(serde_json::from_value(json)?)
+    } else {
+        // The config is incompatible with this app version
+        Err(std::io::Error::new(std::io::ErrorKind::InvalidData, gettextf("The loaded configuration file version {} is incompatible with this version of Pika Backup", &[&version.to_string()])))
     }
 }
 
@@ -50,6 +57,12 @@ pub trait TrackChanges: Sized {
 
 thread_local! {
 static FILE_MONITORS: Cell<Vec<gio::FileMonitor>> = Default::default();
+static RELOAD_HANDLERS: RefCell<Vec<Box<dyn Fn()>>> = Default::default();
+}
+
+/// Call `handler` whenever a file tracked via [`TrackChanges`] has been reloaded
+pub fn connect_reloaded(handler: impl Fn() + 'static) {
+    RELOAD_HANDLERS.with(|handlers| handlers.borrow_mut().push(Box::new(handler)));
 }
 
 impl<C> TrackChanges for C
@@ -63,40 +76,53 @@ where
     where
         H: Fn(std::io::Error) + 'static,
     {
-        let path = Self::path();
-        let file = gio::File::for_path(&path);
-        let monitor = file
-            .monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
-            .unwrap_or_else(|err| panic!("Failed to initiate file monitor for {path:?} ({err})"));
-
-        monitor.connect_changed(
-            move |_monitor: &gio::FileMonitor,
-                  file: &gio::File,
-                  _other_file: Option<&gio::File>,
-                  event: gio::FileMonitorEvent| {
-                if event == gio::FileMonitorEvent::ChangesDoneHint {
-                    info!("Reloading file after change {:?}", file.path());
-                    // TODO send notification?
-                    match Self::from_file() {
-                        Ok(new) => store.update(|s| *s = new.clone()),
-                        Err(err) => {
-                            error!("Failed to reload {:?}: {}", file.path(), err);
-                            error_handler(err);
+        let error_handler = std::rc::Rc::new(error_handler);
+
+        for path in Self::monitored_paths() {
+            let error_handler = error_handler.clone();
+            let file = gio::File::for_path(&path);
+            let monitor = file
+                .monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+                .unwrap_or_else(|err| {
+                    panic!("Failed to initiate file monitor for {path:?} ({err})")
+                });
+
+            monitor.connect_changed(
+                move |_monitor: &gio::FileMonitor,
+                      file: &gio::File,
+                      _other_file: Option<&gio::File>,
+                      event: gio::FileMonitorEvent| {
+                    if event == gio::FileMonitorEvent::ChangesDoneHint {
+                        info!("Reloading file after change {:?}", file.path());
+                        // TODO send notification?
+                        match Self::from_file() {
+                            Ok(new) => {
+                                store.update(|s| *s = new.clone());
+                                RELOAD_HANDLERS.with(|handlers| {
+                                    for handler in handlers.borrow().iter() {
+                                        handler();
+                                    }
+                                });
+                            }
+                            Err(err) => {
+                                error!("Failed to reload {:?}: {}", file.path(), err);
+                                error_handler(err);
+                            }
                         }
                     }
-                }
-            },
-        );
+                },
+            );
 
-        debug!("File monitor connected for {:?}", path);
+            debug!("File monitor connected for {:?}", path);
 
-        FILE_MONITORS.with(|file_monitors| {
-            let mut new = file_monitors.take();
-            new.push(monitor);
-            file_monitors.set(new);
-        });
+            FILE_MONITORS.with(|file_monitors| {
+                let mut new = file_monitors.take();
+                new.push(monitor);
+                file_monitors.set(new);
+            });
+        }
 
-        info!("Initial load for {:?}", path);
+        info!("Initial load for {:?}", Self::path());
         let new = Self::from_file()?;
         store.update(|s| *s = new.clone());
 
@@ -106,6 +132,31 @@ where
 
 pub trait ConfigType {
     fn path() -> std::path::PathBuf;
+
+    /// Load the stored value
+    ///
+    /// Reads the JSON file at [`Self::path`] by default.
+    fn read() -> Result<Self, std::io::Error>
+    where
+        Self: Sized + ConfigVersion + serde::de::DeserializeOwned + Default,
+    {
+        read_file(&Self::path())
+    }
+
+    /// Store the changes made since `written`
+    ///
+    /// Rewrites the JSON file at [`Self::path`] by default.
+    fn write(_written: &Self, current: &Self) -> Result<(), std::io::Error>
+    where
+        Self: serde::Serialize,
+    {
+        super::writeable::write_file(&Self::path(), current)
+    }
+
+    /// Files that are watched by [`TrackChanges`]
+    fn monitored_paths() -> Vec<std::path::PathBuf> {
+        vec![Self::path()]
+    }
 }
 
 /// This trait needs to be implemented for all config files
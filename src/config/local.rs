@@ -3,8 +3,9 @@ use gio::prelude::*;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Repository {
     /// If not absulte, this path is prefixed with the `mount_path`
+    #[serde(with = "super::path_serde")]
     pub path: std::path::PathBuf,
-    #[serde(default = "default_mount_path")]
+    #[serde(default = "default_mount_path", with = "super::path_serde")]
     pub mount_path: std::path::PathBuf,
     pub uri: Option<String>,
     #[serde(alias = "device")]
@@ -0,0 +1,33 @@
+/// Integrity check, cleanup and compaction of the repository in one run
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Maintenance {
+    /// Run after the first scheduled backup of each month
+    pub enabled: bool,
+    /// Read and verify all backed up data during the integrity check
+    #[serde(default)]
+    pub verify_data: bool,
+}
+
+impl Maintenance {
+    pub fn is_due(
+        &self,
+        last: Option<chrono::DateTime<chrono::Local>>,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> bool {
+        self.enabled && last.map_or(true, |last| chronoutil::delta::shift_months(last, 1) <= now)
+    }
+}
+
+#[test]
+fn test_maintenance_is_due() {
+    let now = chrono::Local::now();
+    let enabled = Maintenance {
+        enabled: true,
+        verify_data: false,
+    };
+
+    assert!(enabled.is_due(None, now));
+    assert!(enabled.is_due(Some(now - chrono::Duration::days(40)), now));
+    assert!(!enabled.is_due(Some(now - chrono::Duration::days(3)), now));
+    assert!(!Maintenance::default().is_due(None, now));
+}
@@ -0,0 +1,153 @@
+/*!
+# Migrations of configuration files written by older versions
+
+The migrations run before the configuration is loaded, by the UI as well as by
+the daemon, whichever starts first. Each migration only changes files that need
+it, so running them again has no effect.
+
+Include and exclude paths below the home directory are stored relative to it.
+Absolute paths below the home directory, like those written by older versions
+or by hand, are converted to this format.
+*/
+
+use std::path;
+
+use crate::config;
+use crate::prelude::*;
+use config::{Exclude, Loadable, Pattern, RELATIVE};
+
+/// Run all migrations
+///
+/// Returns a description of every change for the user.
+pub fn run() -> std::io::Result<Vec<String>> {
+    rename_config_file()?;
+
+    let mut backups = config::Writeable::<config::Backups>::from_file()?;
+    let mut changes = Vec::new();
+
+    for config in backups.iter_mut() {
+        update_version(config);
+        changes.extend(relative_paths(config));
+    }
+
+    backups.write_file()?;
+
+    for change in &changes {
+        info!("Migrated configuration: {}", change);
+    }
+
+    Ok(changes)
+}
+
+/// The file `backup.json` was called `config.json` before
+fn rename_config_file() -> std::io::Result<()> {
+    let dir = glib::user_config_dir().join(env!("CARGO_PKG_NAME"));
+    let legacy_path = dir.join("config.json");
+    let path = dir.join("backup.json");
+
+    if legacy_path.is_file() && !path.is_file() {
+        info!("Renaming {:?} to {:?}", legacy_path, path);
+        std::fs::rename(legacy_path, path)?;
+    }
+
+    Ok(())
+}
+
+fn update_version(config: &mut config::Backup) {
+    if config.config_version.0 < config::VERSION {
+        config.config_version = Default::default();
+    }
+}
+
+fn relative_path(path: &path::Path) -> Option<path::PathBuf> {
+    path.strip_prefix(glib::home_dir())
+        .ok()
+        .map(path::Path::to_path_buf)
+}
+
+fn relative_exclude(exclude: &Exclude<{ RELATIVE }>) -> Option<Exclude<{ RELATIVE }>> {
+    match exclude {
+        Exclude::Pattern(Pattern::PathPrefix(path)) => {
+            relative_path(path).map(|path| Exclude::Pattern(Pattern::PathPrefix(path)))
+        }
+        Exclude::Pattern(Pattern::PathFullMatch(path)) => {
+            relative_path(path).map(|path| Exclude::Pattern(Pattern::PathFullMatch(path)))
+        }
+        _ => None,
+    }
+}
+
+/// Store paths below the home directory relative to it
+///
+/// Returns a description of the change if any path was converted.
+fn relative_paths(config: &mut config::Backup) -> Option<String> {
+    let mut converted = 0;
+
+    config.include = std::mem::take(&mut config.include)
+        .into_iter()
+        .map(|path| match relative_path(&path) {
+            Some(relative) => {
+                converted += 1;
+                relative
+            }
+            None => path,
+        })
+        .collect();
+
+    config.exclude = std::mem::take(&mut config.exclude)
+        .into_iter()
+        .map(|exclude| match relative_exclude(&exclude) {
+            Some(relative) => {
+                converted += 1;
+                relative
+            }
+            None => exclude,
+        })
+        .collect();
+
+    (converted > 0).then(|| {
+        ngettextf(
+            "Backup “{}”: One path in the home folder updated to the current format",
+            "Backup “{}”: {} paths in the home folder updated to the current format",
+            converted,
+            &[&config.title(), &converted.to_string()],
+        )
+    })
+}
+
+#[test]
+fn test_relative_paths() {
+    let mut config = config::Backup::test_new_mock();
+    config.include.clear();
+    config.exclude.clear();
+
+    config.include.insert(glib::home_dir().join("Documents"));
+    config.include.insert("Pictures".into());
+    config.include.insert("/etc".into());
+    config.exclude.insert(Exclude::Pattern(Pattern::PathPrefix(
+        glib::home_dir().join("Documents/tmp"),
+    )));
+    config
+        .exclude
+        .insert(Exclude::Pattern(Pattern::PathPrefix("/var/tmp".into())));
+
+    assert!(relative_paths(&mut config).is_some());
+    assert_eq!(
+        config.include,
+        ["Documents", "Pictures", "/etc"]
+            .into_iter()
+            .map(path::PathBuf::from)
+            .collect()
+    );
+    assert!(config
+        .exclude
+        .contains(&Exclude::Pattern(Pattern::PathPrefix(
+            "Documents/tmp".into()
+        ))));
+    assert!(config
+        .exclude
+        .contains(&Exclude::Pattern(Pattern::PathPrefix("/var/tmp".into()))));
+
+    // Nothing left to migrate
+    assert_eq!(relative_paths(&mut config), None);
+}
@@ -0,0 +1,64 @@
+//! Store paths that are not valid UTF-8 without altering them
+//!
+//! Valid UTF-8 paths are stored as strings, like serde does by default. All
+//! other paths are stored as a list of their raw bytes instead of failing to
+//! serialize.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<&Path> for Repr {
+    fn from(path: &Path) -> Self {
+        match path.to_str() {
+            Some(string) => Self::String(string.to_string()),
+            None => Self::Bytes(path.as_os_str().as_bytes().to_vec()),
+        }
+    }
+}
+
+impl From<Repr> for PathBuf {
+    fn from(repr: Repr) -> Self {
+        match repr {
+            Repr::String(string) => string.into(),
+            Repr::Bytes(bytes) => OsString::from_vec(bytes).into(),
+        }
+    }
+}
+
+pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    Repr::from(path).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    Repr::deserialize(deserializer).map(Into::into)
+}
+
+pub mod set {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    pub fn serialize<S: Serializer>(
+        paths: &BTreeSet<PathBuf>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(paths.iter().map(|path| Repr::from(path.as_path())))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeSet<PathBuf>, D::Error> {
+        Ok(Vec::<Repr>::deserialize(deserializer)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
@@ -14,7 +14,9 @@ pub type Relativity = bool;
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum Pattern<const T: Relativity> {
     Fnmatch(OsString),
+    #[serde(with = "super::path_serde")]
     PathFullMatch(PathBuf),
+    #[serde(with = "super::path_serde")]
     PathPrefix(PathBuf),
     #[serde(
         deserialize_with = "deserialize_regex",
@@ -0,0 +1,264 @@
+//! Import exclusion rules from pattern files
+//!
+//! Supports files in the format of `borg --patterns-from` or `--exclude-from`
+//! and `.gitignore` files.
+
+use super::exclude::borg_regex_path;
+use super::{Exclude, Pattern, ABSOLUTE, RELATIVE};
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PatternsFormat {
+    Borg,
+    Gitignore,
+}
+
+impl PatternsFormat {
+    /// Guess the format from the file name
+    pub fn from_path(path: &Path) -> Self {
+        match path.file_name().and_then(|x| x.to_str()) {
+            Some(name) if name.ends_with("ignore") => Self::Gitignore,
+            _ => Self::Borg,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PatternsFile {
+    #[serde(with = "super::path_serde")]
+    pub path: PathBuf,
+    pub format: PatternsFormat,
+    /// Pass the file to borg via `--patterns-from` on every run instead of
+    /// copying the patterns. Only possible for the borg format.
+    pub linked: bool,
+    /// Exclusions added by the last import
+    #[serde(default)]
+    pub imported: BTreeSet<Exclude<{ RELATIVE }>>,
+}
+
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub patterns: Vec<Pattern<{ ABSOLUTE }>>,
+    /// Number of include rules and unsupported pattern styles that were ignored
+    pub ignored: usize,
+}
+
+impl Parsed {
+    pub fn excludes(&self) -> BTreeSet<Exclude<{ RELATIVE }>> {
+        self.patterns
+            .iter()
+            .cloned()
+            .map(|pattern| Exclude::from_pattern(pattern.into_relative()))
+            .collect()
+    }
+}
+
+impl PatternsFile {
+    pub fn new(path: PathBuf, format: PatternsFormat, linked: bool) -> Self {
+        Self {
+            path,
+            format,
+            linked: linked && format == PatternsFormat::Borg,
+            imported: Default::default(),
+        }
+    }
+
+    pub fn read(&self) -> std::io::Result<Parsed> {
+        let content = std::fs::read_to_string(&self.path)?;
+
+        Ok(match self.format {
+            PatternsFormat::Borg => parse_borg(&content),
+            PatternsFormat::Gitignore => parse_gitignore(
+                &content,
+                self.path.parent().unwrap_or_else(|| Path::new("/")),
+            ),
+        })
+    }
+}
+
+fn has_style_prefix(pattern: &str) -> bool {
+    pattern
+        .split_once(':')
+        .is_some_and(|(style, _)| style.len() == 2 && style.chars().all(|c| c.is_ascii_lowercase()))
+}
+
+/// Exclusion rules in the format of `--patterns-from` or `--exclude-from`
+///
+/// Include rules (`+`) and root paths (`R`) are ignored.
+pub fn parse_borg(content: &str) -> Parsed {
+    let mut parsed = Parsed::default();
+    let mut default_style = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(style) = line.strip_prefix("P ") {
+            default_style = Some(style.trim().to_string());
+            continue;
+        } else if line.starts_with("+ ") || line.starts_with("R ") {
+            parsed.ignored += 1;
+            continue;
+        }
+
+        let pattern = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("! "))
+            .map_or(line, str::trim);
+
+        let pattern = match &default_style {
+            Some(style) if !has_style_prefix(pattern) => format!("{style}:{pattern}"),
+            _ => pattern.to_string(),
+        };
+
+        match Pattern::from_borg(pattern) {
+            Some(pattern) => parsed.patterns.push(pattern),
+            None => parsed.ignored += 1,
+        }
+    }
+
+    parsed
+}
+
+/// Translate a gitignore glob to a regular expression
+fn glob_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let class = chars.by_ref().take_while(|c| *c != ']').collect::<String>();
+                let class = class.replace('\\', r"\\").replace('[', r"\[");
+                regex.push('[');
+                match class.strip_prefix('!') {
+                    Some(negated) => {
+                        regex.push('^');
+                        regex.push_str(negated);
+                    }
+                    None => regex.push_str(&class),
+                }
+                regex.push(']');
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex
+}
+
+/// Exclusion rules from a `.gitignore` file located in `base_dir`
+///
+/// Negated rules (`!`) are ignored. Rules that only match directories also
+/// match files with the same name.
+pub fn parse_gitignore(content: &str, base_dir: &Path) -> Parsed {
+    let mut parsed = Parsed::default();
+    let base = borg_regex_path(base_dir);
+
+    for line in content.lines().map(str::trim_end) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('!') {
+            parsed.ignored += 1;
+            continue;
+        }
+
+        let line = line.trim_end_matches('/');
+        // Rules with a separator are relative to the directory of the file
+        let anchored = line.contains('/');
+        let glob = glob_regex(line.trim_start_matches('/'));
+
+        let regex = if anchored {
+            format!("^{base}/{glob}(/|$)")
+        } else {
+            format!("^{base}/(.*/)?{glob}(/|$)")
+        };
+
+        match Pattern::from_regular_expression(regex) {
+            Ok(pattern) => parsed.patterns.push(pattern),
+            Err(_) => parsed.ignored += 1,
+        }
+    }
+
+    parsed
+}
+
+#[test]
+fn test_parse_borg() {
+    let parsed = parse_borg(
+        "# comment\n\
+         P fm\n\
+         R /home\n\
+         + home/user/keep\n\
+         - */.cache\n\
+         ! re:^home/user/tmp/\n\
+         sh:**/build\n\
+         pp:/home/user/Downloads\n",
+    );
+
+    assert_eq!(parsed.ignored, 3);
+    assert_eq!(
+        parsed
+            .patterns
+            .iter()
+            .map(|x| x.borg_pattern().to_string_lossy().to_string())
+            .collect::<Vec<_>>(),
+        vec![
+            "fm:*/.cache".to_string(),
+            "re:^home/user/tmp/".to_string(),
+            "pp:/home/user/Downloads".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_gitignore() {
+    let parsed = parse_gitignore(
+        "# build output\n\
+         target/\n\
+         /docs/*.html\n\
+         **/node_modules\n\
+         !keep.log\n\
+         *.lo[!g]\n",
+        Path::new("/home/user/project"),
+    );
+
+    assert_eq!(parsed.ignored, 1);
+
+    let matches = |path: &str| {
+        parsed
+            .patterns
+            .iter()
+            .any(|pattern| pattern.is_match(Path::new(path)))
+    };
+
+    assert!(matches("/home/user/project/target"));
+    assert!(matches("/home/user/project/sub/target/debug"));
+    assert!(matches("/home/user/project/docs/index.html"));
+    assert!(!matches("/home/user/project/sub/docs/index.html"));
+    assert!(matches("/home/user/project/web/node_modules/x"));
+    assert!(matches("/home/user/project/a.lox"));
+    assert!(!matches("/home/user/project/a.log"));
+    assert!(!matches("/home/user/other/target"));
+}
@@ -2,6 +2,11 @@
 pub struct Prune {
     pub enabled: bool,
     pub keep: Keep,
+    /// Delete the checkpoints of interrupted backups during cleanup
+    ///
+    /// Borg only deletes checkpoints once a newer complete archive exists.
+    #[serde(default)]
+    pub delete_checkpoints: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -11,6 +16,9 @@ pub struct Keep {
     pub weekly: u32,
     pub monthly: u32,
     pub yearly: u32,
+    /// Latest archives that are kept regardless of their age
+    #[serde(default)]
+    pub last: u32,
 }
 
 impl Default for Keep {
@@ -21,6 +29,7 @@ impl Default for Keep {
             weekly: 4,
             monthly: 12,
             yearly: 10,
+            last: 0,
         }
     }
 }
@@ -32,5 +41,6 @@ impl Keep {
             && self.weekly >= other.weekly
             && self.monthly >= other.monthly
             && self.yearly >= other.yearly
+            && self.last >= other.last
     }
 }
@@ -1,7 +1,66 @@
+use crate::prelude::*;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Repository {
     pub uri: String,
     pub settings: Option<super::BackupSettings>,
+    #[serde(default)]
+    pub host_key_policy: HostKeyPolicy,
+    /// Keep the SSH connection open to share it between borg calls
+    #[serde(default)]
+    pub reuse_connection: bool,
+    /// Repository created at a hosting provider during the setup
+    #[serde(default)]
+    pub hosted: Option<Hosted>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Hosted {
+    /// Name of the provider as shown to the user
+    pub provider: String,
+    pub repository: crate::provider::RemoteRepository,
+}
+
+/// Verification of the SSH server identity against the known host keys
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HostKeyPolicy {
+    /// Only connect to servers with a known host key
+    Strict,
+    /// Store the host key on the first connection, refuse changed keys
+    #[default]
+    AcceptNew,
+    /// Connect to any server without verification
+    Off,
+}
+
+impl HostKeyPolicy {
+    pub const VALUES: [Self; 3] = [Self::Strict, Self::AcceptNew, Self::Off];
+
+    /// Options for `ssh` like `-o StrictHostKeyChecking=accept-new`
+    pub fn ssh_options(&self) -> Vec<&'static str> {
+        match self {
+            Self::Strict => vec!["-o", "StrictHostKeyChecking=yes"],
+            Self::AcceptNew => vec!["-o", "StrictHostKeyChecking=accept-new"],
+            Self::Off => vec![
+                "-o",
+                "StrictHostKeyChecking=no",
+                "-o",
+                "UserKnownHostsFile=/dev/null",
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for HostKeyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = match self {
+            Self::Strict => gettext("Only Known Servers"),
+            Self::AcceptNew => gettext("Trust on First Connection"),
+            Self::Off => gettext("Never Verify"),
+        };
+
+        write!(f, "{text}")
+    }
 }
 
 impl Repository {
@@ -9,6 +68,9 @@ impl Repository {
         Self {
             uri,
             settings: None,
+            host_key_policy: HostKeyPolicy::AcceptNew,
+            reuse_connection: false,
+            hosted: None,
         }
     }
 
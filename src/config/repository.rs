@@ -191,6 +191,22 @@ impl Repository {
         } = settings;
     }
 
+    /// Verification of the SSH server identity, only for remote repositories
+    pub fn host_key_policy(&self) -> Option<remote::HostKeyPolicy> {
+        match self {
+            Self::Local(_) => None,
+            Self::Remote(remote) => Some(remote.host_key_policy),
+        }
+    }
+
+    /// Whether the SSH connection is shared between borg calls
+    pub fn reuse_connection(&self) -> bool {
+        match self {
+            Self::Local(_) => false,
+            Self::Remote(remote) => remote.reuse_connection,
+        }
+    }
+
     pub fn settings(&self) -> Option<BackupSettings> {
         match self {
             Self::Local(local) => &local.settings,
@@ -198,14 +214,21 @@ impl Repository {
         }
         .clone()
     }
+
+    /// Location as passed to borg
+    ///
+    /// Unlike the `Display` implementation this keeps local paths that are not
+    /// valid UTF-8 intact.
+    pub fn borg_location(&self) -> std::ffi::OsString {
+        match self {
+            Self::Local(local) => local.path().into_os_string(),
+            Self::Remote(remote) => remote.uri.clone().into(),
+        }
+    }
 }
 
 impl std::fmt::Display for Repository {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let repo = match self {
-            Self::Local(local) => local.path().to_string_lossy().to_string(),
-            Self::Remote(remote) => remote.uri.to_string(),
-        };
-        write!(f, "{repo}")
+        write!(f, "{}", self.borg_location().to_string_lossy())
     }
 }
@@ -9,10 +9,113 @@ pub struct Schedule {
 }
 
 /// User configured settings to the schedule algorithm.
-#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Settings {
     /// Run backups regardless of battery status
     pub run_on_battery: bool,
+    /// Resume backups at the next login that were interrupted by the session end
+    #[serde(default = "default_resume_interrupted")]
+    pub resume_interrupted: bool,
+    /// Minutes after boot or login during which no scheduled backups are started
+    #[serde(default)]
+    pub startup_holdoff: u32,
+    /// Run backups while the power saver profile is active
+    #[serde(default = "default_run_in_power_saver")]
+    pub run_in_power_saver: bool,
+    /// Daily period during which no scheduled backups are started
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Command that can postpone scheduled backups, see [`crate::schedule::veto`]
+    #[serde(default)]
+    pub veto_command: Option<String>,
+    /// Maximum minutes by which scheduled backups are randomly delayed
+    #[serde(default)]
+    pub jitter: u32,
+}
+
+impl Settings {
+    /// Delay of scheduled backups for this configuration
+    ///
+    /// Derived from the config id such that it is stable between processes and
+    /// differs between computers using the same backup location. Backups are
+    /// only delayed, never started early, such that a completed run always
+    /// ends after the scheduled time.
+    pub fn jitter_offset(&self, config_id: &super::ConfigId) -> chrono::Duration {
+        if self.jitter == 0 {
+            return chrono::Duration::zero();
+        }
+
+        // FNV-1a, stable across builds unlike the std hasher
+        let hash = config_id
+            .as_str()
+            .bytes()
+            .fold(0xcbf29ce484222325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+
+        chrono::Duration::seconds((hash % (u64::from(self.jitter) * 60 + 1)) as i64)
+    }
+}
+
+/// Daily period from `start` to `end`, possibly past midnight
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct QuietHours {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// End of the quiet hours if they are active at `now`
+    pub fn end_after(
+        &self,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> Option<chrono::DateTime<chrono::Local>> {
+        if !self.contains(now.time()) {
+            return None;
+        }
+
+        let date = if now.time() >= self.end {
+            now.date_naive().succ_opt()?
+        } else {
+            now.date_naive()
+        };
+
+        Some(crate::schedule::local_time::resolve(
+            &chrono::Local,
+            date,
+            self.end,
+        ))
+    }
+}
+
+const fn default_resume_interrupted() -> bool {
+    true
+}
+
+const fn default_run_in_power_saver() -> bool {
+    true
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            run_on_battery: false,
+            resume_interrupted: default_resume_interrupted(),
+            startup_holdoff: 0,
+            run_in_power_saver: default_run_in_power_saver(),
+            quiet_hours: None,
+            veto_command: None,
+            jitter: 0,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -40,4 +143,51 @@ impl Frequency {
             Self::Monthly { .. } => gettext("Monthly"),
         }
     }
+
+    /// Time within which a completed backup is expected
+    ///
+    /// Includes a day of slack for days on which the computer is not in use.
+    pub fn expected_window(&self) -> chrono::Duration {
+        match self {
+            Self::Hourly => chrono::Duration::days(1),
+            Self::Daily { .. } => chrono::Duration::days(2),
+            Self::Weekly { .. } => chrono::Duration::days(8),
+            Self::Monthly { .. } => chrono::Duration::days(32),
+        }
+    }
+}
+
+#[test]
+fn test_quiet_hours_contains() {
+    let time = |hour| chrono::NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+
+    let night = QuietHours {
+        start: time(22),
+        end: time(7),
+    };
+    assert!(night.contains(time(23)));
+    assert!(night.contains(time(3)));
+    assert!(!night.contains(time(7)));
+    assert!(!night.contains(time(12)));
+
+    let noon = QuietHours {
+        start: time(12),
+        end: time(14),
+    };
+    assert!(noon.contains(time(13)));
+    assert!(!noon.contains(time(14)));
+    assert!(!noon.contains(time(23)));
+}
+
+#[test]
+fn test_jitter_offset() {
+    let id = super::ConfigId::new(String::from("a0b1c2"));
+    let mut settings = Settings::default();
+    assert_eq!(settings.jitter_offset(&id), chrono::Duration::zero());
+
+    settings.jitter = 20;
+    let offset = settings.jitter_offset(&id);
+    assert!(offset >= chrono::Duration::zero());
+    assert!(offset <= chrono::Duration::minutes(20));
+    assert_eq!(offset, settings.jitter_offset(&id));
 }
@@ -1,11 +1,101 @@
 use crate::config;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Number of events kept in the log of every backup
+pub const EVENT_LOG_LENGTH: usize = 50;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ScheduleStatus {
     #[serde(default)]
     pub config_version: super::Version,
     pub activity: BTreeMap<config::ConfigId, Activity>,
+    /// Start of the daemon, usually the time of the login
+    #[serde(default)]
+    pub session_start: Option<chrono::DateTime<chrono::Local>>,
+    /// Recent decisions of the scheduler, oldest first
+    #[serde(default)]
+    pub events: BTreeMap<config::ConfigId, VecDeque<ScheduleEvent>>,
+    /// Last time the summary of missed backups was checked
+    #[serde(default)]
+    pub last_missed_summary: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl ScheduleStatus {
+    /// Add an event to the log of the backup, dropping the oldest ones
+    pub fn log(&mut self, config_id: &config::ConfigId, kind: ScheduleEventKind) {
+        let events = self.events.entry(config_id.clone()).or_default();
+        events.push_back(ScheduleEvent {
+            date: chrono::Local::now(),
+            kind,
+        });
+
+        while events.len() > EVENT_LOG_LENGTH {
+            events.pop_front();
+        }
+    }
+
+    /// Add the outcome of a probe
+    ///
+    /// Repeated probes with the same outcome are not logged again, such that the
+    /// event keeps the date since which the outcome applies. Returns whether the
+    /// status changed.
+    pub fn log_probe(&mut self, config_id: &config::ConfigId, outcome: ProbeOutcome) -> bool {
+        let repeated = self
+            .events
+            .get(config_id)
+            .and_then(|events| events.back())
+            .is_some_and(|event| event.kind == ScheduleEventKind::Probed { outcome });
+
+        if !repeated {
+            self.log(config_id, ScheduleEventKind::Probed { outcome });
+        }
+
+        !repeated
+    }
+
+    /// Last event that is not the outcome of a probe
+    fn last_decision(&self, config_id: &config::ConfigId) -> Option<&ScheduleEvent> {
+        self.events
+            .get(config_id)?
+            .iter()
+            .rev()
+            .find(|event| !matches!(event.kind, ScheduleEventKind::Probed { .. }))
+    }
+
+    /// Veto that still postpones scheduled backups at `now`
+    pub fn active_veto(
+        &self,
+        config_id: &config::ConfigId,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> Option<(String, chrono::DateTime<chrono::Local>)> {
+        match &self.last_decision(config_id)?.kind {
+            ScheduleEventKind::Vetoed { reason, until } if *until > now => {
+                Some((reason.clone(), *until))
+            }
+            _ => None,
+        }
+    }
+
+    /// End of a skipped scheduled backup that is still pending at `now`
+    ///
+    /// Any backup completed after skipping, including manual ones, ends the skip.
+    pub fn active_skip(
+        &self,
+        config_id: &config::ConfigId,
+        now: chrono::DateTime<chrono::Local>,
+        last_completed: Option<chrono::DateTime<chrono::Local>>,
+    ) -> Option<chrono::DateTime<chrono::Local>> {
+        let event = self.last_decision(config_id)?;
+
+        match &event.kind {
+            ScheduleEventKind::Skipped { until }
+                if *until > now && last_completed.map_or(true, |end| end < event.date) =>
+            {
+                Some(*until)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl super::ConfigType for ScheduleStatus {
@@ -70,3 +160,101 @@ impl Default for Activity {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduleEvent {
+    pub date: chrono::DateTime<chrono::Local>,
+    pub kind: ScheduleEventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ScheduleEventKind {
+    /// A scheduled backup was started
+    Started,
+    /// The veto command postponed the backup
+    Vetoed {
+        reason: String,
+        until: chrono::DateTime<chrono::Local>,
+    },
+    /// The veto command could not be run, the backup was started anyway
+    VetoFailed { message: String },
+    /// The next scheduled backup was skipped by the user
+    Skipped {
+        until: chrono::DateTime<chrono::Local>,
+    },
+    /// The schedule was checked without starting or postponing the backup
+    Probed { outcome: ProbeOutcome },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// The backup is not due yet
+    NotDue,
+    /// The backup is due but requirements like a power connection are not met
+    RequirementsUnmet,
+    /// The backup is due but the backup device is not connected
+    DeviceMissing,
+}
+
+#[test]
+fn test_event_log() {
+    let id = config::ConfigId::new("a".to_string());
+    let mut status = ScheduleStatus::default();
+    let now = chrono::Local::now();
+
+    assert_eq!(status.active_veto(&id, now), None);
+
+    status.log(
+        &id,
+        ScheduleEventKind::Vetoed {
+            reason: "Rendering video".to_string(),
+            until: now + chrono::Duration::hours(2),
+        },
+    );
+    assert_eq!(
+        status.active_veto(&id, now),
+        Some((
+            "Rendering video".to_string(),
+            now + chrono::Duration::hours(2)
+        ))
+    );
+    assert_eq!(
+        status.active_veto(&id, now + chrono::Duration::hours(3)),
+        None
+    );
+
+    status.log(&id, ScheduleEventKind::Started);
+    assert_eq!(status.active_veto(&id, now), None);
+
+    status.log(
+        &id,
+        ScheduleEventKind::Skipped {
+            until: now + chrono::Duration::days(1),
+        },
+    );
+    assert_eq!(status.active_veto(&id, now), None);
+    assert_eq!(
+        status.active_skip(&id, now, None),
+        Some(now + chrono::Duration::days(1))
+    );
+    assert_eq!(
+        status.active_skip(&id, now + chrono::Duration::days(2), None),
+        None
+    );
+
+    assert!(status.log_probe(&id, ProbeOutcome::NotDue));
+    assert!(!status.log_probe(&id, ProbeOutcome::NotDue));
+    assert_eq!(status.events[&id].len(), 4);
+    assert_eq!(
+        status.active_skip(&id, now, None),
+        Some(now + chrono::Duration::days(1))
+    );
+
+    status.log(&id, ScheduleEventKind::Started);
+    assert_eq!(status.active_skip(&id, now, None), None);
+
+    for _ in 0..EVENT_LOG_LENGTH {
+        status.log(&id, ScheduleEventKind::Started);
+    }
+    assert_eq!(status.events[&id].len(), EVENT_LOG_LENGTH);
+}
@@ -0,0 +1,141 @@
+//! Removed backup configurations that can still be restored
+
+use crate::config;
+
+/// Removed configurations are kept this many days before they are deleted
+pub const RETENTION_DAYS: i64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrashedBackup {
+    pub config: config::Backup,
+    pub removed: chrono::DateTime<chrono::Local>,
+}
+
+impl TrashedBackup {
+    /// Time after which the configuration is permanently deleted
+    pub fn expires(&self) -> chrono::DateTime<chrono::Local> {
+        self.removed + chrono::Duration::days(RETENTION_DAYS)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Trash {
+    #[serde(default)]
+    pub config_version: super::Version,
+    backups: Vec<TrashedBackup>,
+}
+
+impl Trash {
+    pub fn insert(&mut self, config: config::Backup, removed: chrono::DateTime<chrono::Local>) {
+        self.backups.retain(|x| x.config.id != config.id);
+        self.backups.push(TrashedBackup { config, removed });
+    }
+
+    /// Remove the configuration from the trash to restore it
+    pub fn take(
+        &mut self,
+        id: &config::ConfigId,
+    ) -> Result<config::Backup, config::error::BackupNotFound> {
+        let position = self
+            .backups
+            .iter()
+            .position(|x| x.config.id == *id)
+            .ok_or_else(|| config::error::BackupNotFound::new(id.clone()))?;
+
+        Ok(self.backups.remove(position).config)
+    }
+
+    /// Remove and return all configurations that expired at `now`
+    pub fn take_expired(&mut self, now: chrono::DateTime<chrono::Local>) -> Vec<config::Backup> {
+        let (expired, kept) = std::mem::take(&mut self.backups)
+            .into_iter()
+            .partition(|x| x.expires() <= now);
+        self.backups = kept;
+
+        expired.into_iter().map(|x| x.config).collect()
+    }
+
+    /// Whether a removed configuration uses the repository
+    pub fn uses_repo(&self, repo_id: &crate::borg::RepoId) -> bool {
+        self.iter().any(|x| x.config.repo_id == *repo_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.backups.is_empty()
+    }
+
+    /// Most recently removed first
+    pub fn iter(&self) -> impl Iterator<Item = &TrashedBackup> {
+        self.backups.iter().rev()
+    }
+}
+
+impl crate::utils::LookupConfigId for Trash {
+    type Item = TrashedBackup;
+
+    fn try_get_mut(
+        &mut self,
+        key: &config::ConfigId,
+    ) -> Result<&mut TrashedBackup, config::error::BackupNotFound> {
+        self.backups
+            .iter_mut()
+            .find(|x| x.config.id == *key)
+            .ok_or_else(|| config::error::BackupNotFound::new(key.clone()))
+    }
+
+    fn try_get(
+        &self,
+        key: &config::ConfigId,
+    ) -> Result<&TrashedBackup, config::error::BackupNotFound> {
+        self.backups
+            .iter()
+            .find(|x| x.config.id == *key)
+            .ok_or_else(|| config::error::BackupNotFound::new(key.clone()))
+    }
+}
+
+impl super::ConfigType for Trash {
+    fn path() -> std::path::PathBuf {
+        let mut path = glib::user_config_dir();
+        path.push(env!("CARGO_PKG_NAME"));
+        path.push("trash.json");
+
+        path
+    }
+}
+
+impl super::ConfigVersion for Trash {
+    /// Contains backup configurations that are only compatible from version 2
+    fn version_compatible(version: u64) -> bool {
+        (2..=super::VERSION).contains(&version)
+    }
+
+    fn extract_version(json: &serde_json::Value) -> u64 {
+        json.as_object()
+            .and_then(|d| d.get("config_version"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(2)
+    }
+}
+
+#[test]
+fn test_trash() {
+    let now = chrono::Local::now();
+    let old = config::Backup::test_new_mock();
+    let recent = config::Backup::test_new_mock();
+
+    let mut trash = Trash::default();
+    trash.insert(
+        old.clone(),
+        now - chrono::Duration::days(RETENTION_DAYS + 1),
+    );
+    trash.insert(recent.clone(), now - chrono::Duration::days(1));
+    assert!(trash.uses_repo(&old.repo_id));
+
+    assert_eq!(trash.take_expired(now), vec![old.clone()]);
+    assert!(!trash.uses_repo(&old.repo_id));
+
+    assert_eq!(trash.take(&recent.id).map(|x| x.id), Ok(recent.id));
+    assert!(trash.is_empty());
+    assert!(trash.take(&old.id).is_err());
+}
@@ -61,16 +61,7 @@ where
         debug!("Request to rewrite {:?}", path);
 
         if self.is_changed() {
-            let dir = path.parent().map(|x| x.to_path_buf()).unwrap_or_default();
-
-            std::fs::create_dir_all(&dir)?;
-
-            let config_file = tempfile::NamedTempFile::new_in(dir)?;
-            debug!("Writing new file to {:?}", config_file);
-            serde_json::ser::to_writer_pretty(&config_file, &self.current_config)?;
-
-            debug!("Moving new file to {:?}", path);
-            config_file.persist(&path)?;
+            C::write(&self.written_config, &self.current_config)?;
             self.written_config = self.current_config.clone();
         } else {
             debug!("Not rewriting because data is unchanged.");
@@ -80,6 +71,25 @@ where
     }
 }
 
+/// Atomically replace the JSON file at `path`
+pub(super) fn write_file<C: serde::Serialize>(
+    path: &std::path::Path,
+    value: &C,
+) -> Result<(), std::io::Error> {
+    let dir = path.parent().map(|x| x.to_path_buf()).unwrap_or_default();
+
+    std::fs::create_dir_all(&dir)?;
+
+    let config_file = tempfile::NamedTempFile::new_in(dir)?;
+    debug!("Writing new file to {:?}", config_file);
+    serde_json::ser::to_writer_pretty(&config_file, value)?;
+
+    debug!("Moving new file to {:?}", path);
+    config_file.persist(path)?;
+
+    Ok(())
+}
+
 pub trait ArcSwapWriteable {
     fn write_file(&self) -> Result<(), std::io::Error>;
 }
@@ -9,6 +9,9 @@ mod init;
 mod notification;
 mod prelude;
 mod schedule;
+mod shared_status;
+#[cfg(feature = "tray")]
+mod tray;
 
 pub(crate) use globals::{BACKUP_CONFIG, BACKUP_HISTORY, SCHEDULE_STATUS};
 
@@ -2,6 +2,8 @@ use super::prelude::*;
 use gio::prelude::*;
 
 use super::dbus;
+use crate::config;
+use crate::schedule;
 
 pub trait Action {
     const NAME: &'static str;
@@ -51,9 +53,9 @@ impl Action for StartBackup {
     fn action() -> gio::SimpleAction {
         let action = gio::SimpleAction::new(Self::NAME, Some(glib::VariantTy::STRING));
         action.connect_activate(|_, config_id| {
-            if let Some(config_id) = config_id.and_then(glib::FromVariant::from_variant) {
+            if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
                 glib::MainContext::default().spawn(async move {
-                    dbus::PikaBackup::start_backup(&ConfigId::new(config_id))
+                    dbus::PikaBackup::start_backup(&config_id)
                         .await
                         .handle(gettext("Failed to start backup from daemon"));
                 });
@@ -65,6 +67,30 @@ impl Action for StartBackup {
     }
 }
 
+pub struct StartBackups;
+
+impl Action for StartBackups {
+    const NAME: &'static str = "start-backups";
+
+    fn action() -> gio::SimpleAction {
+        let action = gio::SimpleAction::new(Self::NAME, Some(glib::VariantTy::STRING_ARRAY));
+        action.connect_activate(|_, config_ids| {
+            if let Some(config_ids) = config_ids.and_then(|v| v.get::<Vec<String>>()) {
+                glib::MainContext::default().spawn(async move {
+                    for config_id in config_ids {
+                        dbus::PikaBackup::start_backup(&ConfigId::new(config_id))
+                            .await
+                            .handle(gettext("Failed to start backup from daemon"));
+                    }
+                });
+            } else {
+                error!("Invalid parameter for {}: {:?}", Self::NAME, config_ids);
+            }
+        });
+        action
+    }
+}
+
 pub struct ShowOverview;
 
 impl Action for ShowOverview {
@@ -91,9 +117,9 @@ impl Action for ShowSchedule {
     fn action() -> gio::SimpleAction {
         let action = gio::SimpleAction::new(Self::NAME, Some(glib::VariantTy::STRING));
         action.connect_activate(|_, config_id| {
-            if let Some(config_id) = config_id.and_then(glib::FromVariant::from_variant) {
+            if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
                 glib::MainContext::default().spawn(async move {
-                    dbus::PikaBackup::show_schedule(&ConfigId::new(config_id))
+                    dbus::PikaBackup::show_schedule(&config_id)
                         .await
                         .handle(gettext("Failed to show schedule from daemon"));
                 });
@@ -104,3 +130,87 @@ impl Action for ShowSchedule {
         action
     }
 }
+
+pub struct SkipScheduledBackup;
+
+impl Action for SkipScheduledBackup {
+    const NAME: &'static str = "skip-scheduled-backup";
+
+    fn action() -> gio::SimpleAction {
+        let action = gio::SimpleAction::new(Self::NAME, Some(glib::VariantTy::STRING));
+        action.connect_activate(|_, config_id| {
+            let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) else {
+                error!("Invalid parameter for {}: {:?}", Self::NAME, config_id);
+                return;
+            };
+
+            let Ok(config) = BACKUP_CONFIG.load().try_get(&config_id).cloned() else {
+                error!("Backup {:?} to skip not found", config_id);
+                return;
+            };
+
+            let until = schedule::simulation::skip_until(
+                &config,
+                BACKUP_HISTORY.load().try_get(&config_id).ok(),
+                chrono::Local::now(),
+            );
+
+            if let Some(until) = until {
+                info!("Skipping scheduled backup {:?} until {}", config_id, until);
+                SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+                    schedule_status.log(&config_id, config::ScheduleEventKind::Skipped { until });
+                });
+                super::schedule::status::write();
+            } else {
+                warn!("No scheduled backup of {:?} to skip", config_id);
+            }
+        });
+        action
+    }
+}
+
+pub struct StartScheduledBackup;
+
+impl Action for StartScheduledBackup {
+    const NAME: &'static str = "start-scheduled-backup";
+
+    fn action() -> gio::SimpleAction {
+        let action = gio::SimpleAction::new(Self::NAME, Some(glib::VariantTy::STRING));
+        action.connect_activate(|_, config_id| {
+            if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
+                info!("Starting scheduled backup {:?} ahead of time", config_id);
+                SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+                    schedule_status.log(&config_id, config::ScheduleEventKind::Started);
+                });
+                super::schedule::status::write();
+
+                glib::MainContext::default().spawn(async move {
+                    dbus::PikaBackup::start_scheduled_backup(
+                        &config_id,
+                        schedule::DueCause::Regular,
+                    )
+                    .await
+                    .handle(gettext("Failed to start scheduled backup"));
+                });
+            } else {
+                error!("Invalid parameter for {}: {:?}", Self::NAME, config_id);
+            }
+        });
+        action
+    }
+}
+
+pub struct ProbeSchedule;
+
+impl Action for ProbeSchedule {
+    const NAME: &'static str = "probe-schedule";
+
+    fn action() -> gio::SimpleAction {
+        let action = gio::SimpleAction::new(Self::NAME, None);
+        action.connect_activate(|_, _| {
+            info!("Probing schedules on demand");
+            super::schedule::init::probe_all();
+        });
+        action
+    }
+}
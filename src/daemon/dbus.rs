@@ -31,6 +31,8 @@ trait PikaBackup {
     fn show_overview(&self) -> Result<()>;
 
     fn show_schedule(&self, config_id: &ConfigId) -> Result<()>;
+
+    fn stop_operation(&self, config_id: &ConfigId) -> Result<()>;
 }
 
 pub struct PikaBackup;
@@ -76,4 +78,9 @@ impl PikaBackup {
     pub async fn show_schedule(config_id: &ConfigId) -> Result<()> {
         Self::proxy().await?.show_schedule(config_id).await
     }
+
+    #[cfg(feature = "tray")]
+    pub async fn stop_operation(config_id: &ConfigId) -> Result<()> {
+        Self::proxy().await?.stop_operation(config_id).await
+    }
 }
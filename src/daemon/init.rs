@@ -20,15 +20,30 @@ thread_local! {
     static APP_RUNNING: Cell<bool> = Cell::default();
 }
 
+/// Inform about the changes made by the configuration migrations
+fn notify_migration(changes: &[String]) {
+    if changes.is_empty() {
+        return;
+    }
+
+    let notification = gio::Notification::new(&gettext("Backup Configuration Updated"));
+    notification.set_body(Some(&changes.join("\n")));
+    gio_app().send_notification(None, &notification);
+}
+
 fn on_startup(_app: &gio::Application) {
     HOLD.with(|hold| hold.set(gio_app().hold()).unwrap());
 
     crate::utils::init_gettext();
 
-    let config_load_result =
-        config::Histories::update_on_change(&BACKUP_HISTORY, config_reload_error_handler).and_then(
-            |_| config::Backups::update_on_change(&BACKUP_CONFIG, config_reload_error_handler),
-        );
+    let config_load_result = config::migration::run()
+        .map(|changes| notify_migration(&changes))
+        .and_then(|_| {
+            config::Histories::update_on_change(&BACKUP_HISTORY, config_reload_error_handler)
+        })
+        .and_then(|_| {
+            config::Backups::update_on_change(&BACKUP_CONFIG, config_reload_error_handler)
+        });
 
     if let Err(err) = &config_load_result {
         let msg = gettext("Error loading configuration");
@@ -46,12 +61,19 @@ fn on_startup(_app: &gio::Application) {
 
     daemon::connect::init::init();
     daemon::schedule::init::init();
+    daemon::shared_status::init();
+    #[cfg(feature = "tray")]
+    daemon::tray::init();
 
     gio_app().add_action(&action::Restart::action());
     gio_app().add_action(&action::Quit::action());
     gio_app().add_action(&action::StartBackup::action());
+    gio_app().add_action(&action::StartBackups::action());
     gio_app().add_action(&action::ShowOverview::action());
     gio_app().add_action(&action::ShowSchedule::action());
+    gio_app().add_action(&action::SkipScheduledBackup::action());
+    gio_app().add_action(&action::StartScheduledBackup::action());
+    gio_app().add_action(&action::ProbeSchedule::action());
 
     glib::MainContext::default().spawn(async {
         match ashpd::desktop::background::BackgroundProxy::new().await {
@@ -119,7 +141,7 @@ fn app_running(is_running: bool) {
         let backups_running = BACKUP_HISTORY
             .load()
             .iter()
-            .filter(|(_, x)| x.running.is_some())
+            .filter(|(_, x)| x.is_running())
             .count();
 
         if backups_running > 0 {
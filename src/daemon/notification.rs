@@ -4,6 +4,7 @@ pub enum Note<'a> {
     Postponed(&'a config::ConfigId),
     DeviceRequired(&'a config::ConfigId),
     DeviceAvailable(&'a str),
+    MissedBackups,
 }
 
 impl<'a> std::fmt::Display for Note<'a> {
@@ -12,6 +13,7 @@ impl<'a> std::fmt::Display for Note<'a> {
             Self::Postponed(id) => write!(f, "postponed-{id}"),
             Self::DeviceRequired(id) => write!(f, "device-required-{id}"),
             Self::DeviceAvailable(id) => write!(f, "device-available-{id}"),
+            Self::MissedBackups => write!(f, "missed-backups"),
         }
     }
 }
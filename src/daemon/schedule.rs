@@ -3,12 +3,40 @@
 */
 
 pub mod init;
+pub mod missed;
 pub mod status;
 
+use gio::prelude::*;
 use std::time::Duration;
 
-// TODO: Adjust before release
-pub static PROBE_FREQUENCY: Duration = Duration::from_secs(60);
+const SETTING_PROBE_INTERVAL: &str = "schedule-probe-interval";
+
+/// Time between probes if not configured otherwise
+pub static DEFAULT_PROBE_FREQUENCY: Duration = Duration::from_secs(60);
 
 /// Remind daily about backups currently not happening due to unmet criteria
 pub static REMIND_UNMET_CRITERIA: Duration = Duration::from_secs(24 * 60 * 60);
+
+thread_local!(
+    static SETTINGS: Option<gio::Settings> = crate::utils::app_settings();
+);
+
+/// Time between probes of the schedules
+///
+/// Can be shortened via the `schedule-probe-interval` setting for testing.
+pub fn probe_frequency() -> Duration {
+    SETTINGS.with(|settings| {
+        settings.as_ref().map_or(DEFAULT_PROBE_FREQUENCY, |x| {
+            Duration::from_secs(x.uint(SETTING_PROBE_INTERVAL).into())
+        })
+    })
+}
+
+/// Call `f` whenever the probe frequency setting changes
+pub fn connect_probe_frequency_changed(f: impl Fn() + 'static) {
+    SETTINGS.with(|settings| {
+        if let Some(settings) = settings {
+            settings.connect_changed(Some(SETTING_PROBE_INTERVAL), move |_, _| f());
+        }
+    });
+}
@@ -3,6 +3,7 @@
 */
 use crate::daemon::prelude::*;
 use gio::prelude::*;
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 use crate::config;
@@ -12,10 +13,42 @@ use crate::schedule::requirements;
 pub fn init() {
     super::status::load();
 
-    glib::timeout_add_seconds(schedule::PROBE_FREQUENCY.as_secs() as u32, minutely);
+    SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+        schedule_status.session_start = Some(chrono::Local::now());
+    });
+    super::status::write();
+
+    start_timer();
+    schedule::connect_probe_frequency_changed(start_timer);
+}
+
+thread_local!(
+    static TIMER: RefCell<Option<glib::SourceId>> = Default::default();
+);
+
+/// (Re)start probing in the configured frequency
+fn start_timer() {
+    let frequency = schedule::probe_frequency();
+    debug!("Probing schedules every {:?}", frequency);
+
+    let source_id = glib::timeout_add_seconds(frequency.as_secs() as u32, tick);
+    if let Some(previous) = TIMER.with(|timer| timer.replace(Some(source_id))) {
+        previous.remove();
+    }
+}
+
+fn tick() -> glib::ControlFlow {
+    probe_all();
+    track_activity();
+    schedule::missed::check();
+
+    glib::ControlFlow::Continue
 }
 
-fn minutely() -> glib::ControlFlow {
+/// Probe all enabled schedules
+///
+/// Also used to probe on demand without waiting for the next tick.
+pub fn probe_all() {
     debug!("Probing schedules");
 
     for config in BACKUP_CONFIG.load().iter() {
@@ -23,9 +56,18 @@ fn minutely() -> glib::ControlFlow {
             glib::MainContext::default().block_on(probe(config));
         }
     }
-    track_activity();
+}
 
-    glib::ControlFlow::Continue
+/// Log the outcome of a probe, the status is only written if it changed
+fn log_probe(config: &config::Backup, outcome: config::ProbeOutcome) {
+    let changed = std::cell::Cell::new(false);
+    SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+        changed.set(schedule_status.log_probe(&config.id, outcome));
+    });
+
+    if changed.get() {
+        super::status::write();
+    }
 }
 
 fn track_activity() {
@@ -47,7 +89,7 @@ fn track_activity() {
                     Some(last_completed) if activity.last_update < last_completed.end => {
                         activity.reset()
                     }
-                    _ => activity.tick(schedule::PROBE_FREQUENCY),
+                    _ => activity.tick(schedule::probe_frequency()),
                 }
             });
         }
@@ -60,7 +102,8 @@ pub struct Reminder;
 
 impl Reminder {
     fn is_remind_again(id: &ConfigId) -> bool {
-        !matches!(LAST_REMINDED.load().get(id), Some(instant) if instant.elapsed() < super::REMIND_UNMET_CRITERIA)
+        !schedule::missed::is_enabled()
+            && !matches!(LAST_REMINDED.load().get(id), Some(instant) if instant.elapsed() < super::REMIND_UNMET_CRITERIA)
     }
 
     fn reminded_now(id: &ConfigId) {
@@ -72,6 +115,26 @@ impl Reminder {
     }
 }
 
+/// Run the veto command, failures are logged and don't prevent the backup
+async fn ask_veto(config: &config::Backup) -> Option<crate::schedule::veto::Veto> {
+    match crate::schedule::veto::ask(config).await {
+        Ok(veto) => veto,
+        Err(err) => {
+            warn!("Failed to run veto command: {}", err);
+            SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+                schedule_status.log(
+                    &config.id,
+                    config::ScheduleEventKind::VetoFailed {
+                        message: err.to_string(),
+                    },
+                );
+            });
+            super::status::write();
+            None
+        }
+    }
+}
+
 async fn probe(config: &config::Backup) {
     let schedule = &config.schedule;
     debug!("---");
@@ -86,10 +149,21 @@ async fn probe(config: &config::Backup) {
             let global = requirements::Global::check(config, BACKUP_HISTORY.load().as_ref()).await;
             if let Some(global_first) = global.first() {
                 debug!("Global requirements are not met: {:#?}", global);
+                if !matches!(
+                    global_first,
+                    requirements::Global::Vetoed { .. } | requirements::Global::Skipped { .. }
+                ) {
+                    log_probe(config, config::ProbeOutcome::RequirementsUnmet);
+                }
+
                 if Reminder::is_remind_again(&config.id) {
                     let body = match global_first {
-                        requirements::Global::ThisBackupRunning => None,
-                        requirements::Global::OtherBackupRunning(_) => {
+                        requirements::Global::ThisBackupRunning
+                        | requirements::Global::StartupHoldoff { .. }
+                        | requirements::Global::Vetoed { .. }
+                        | requirements::Global::Skipped { .. } => None,
+                        requirements::Global::OtherBackupRunning(_)
+                        | requirements::Global::OperationInBackground => {
                             Some(gettext("The backup repository is already in use."))
                         }
                         requirements::Global::MeteredConnection => {
@@ -98,6 +172,9 @@ async fn probe(config: &config::Backup) {
                         requirements::Global::OnBattery => {
                             Some(gettext("Device not connected to power."))
                         }
+                        requirements::Global::PowerSaver => {
+                            Some(gettext("Power saver mode is active."))
+                        }
                     };
 
                     if body.is_some() {
@@ -122,6 +199,7 @@ async fn probe(config: &config::Backup) {
                 if hint.contains(&requirements::Hint::DeviceMissing) {
                     // TODO: check if path maybe still exists despite device being undetected
                     debug!("Backup device is not connected");
+                    log_probe(config, config::ProbeOutcome::DeviceMissing);
 
                     if Reminder::is_remind_again(&config.id) {
                         debug!("Send reminding notification");
@@ -137,8 +215,42 @@ async fn probe(config: &config::Backup) {
                         );
                         Reminder::reminded_now(&config.id);
                     }
+                } else if let Some(veto) = ask_veto(config).await {
+                    info!("Scheduled backup {:?} vetoed: {:?}", config.id, veto);
+                    let until = veto.until(chrono::Local::now());
+
+                    let notification =
+                        gio::Notification::new(&gettext("Scheduled Backup Postponed"));
+                    notification.set_body(Some(&gettextf(
+                        "Next attempt at {}: {}",
+                        &[&until.format("%H:%M").to_string(), &veto.reason],
+                    )));
+                    notification.set_default_action_and_target_value(
+                        &action::ShowSchedule::name(),
+                        Some(&config.id.to_variant()),
+                    );
+                    gio_app().send_notification(
+                        Some(&Note::Postponed(&config.id).to_string()),
+                        &notification,
+                    );
+
+                    SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+                        schedule_status.log(
+                            &config.id,
+                            config::ScheduleEventKind::Vetoed {
+                                reason: veto.reason.clone(),
+                                until,
+                            },
+                        );
+                    });
+                    super::status::write();
                 } else {
                     info!("Trying to start backup {:?}", config.id);
+                    SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+                        schedule_status.log(&config.id, config::ScheduleEventKind::Started);
+                    });
+                    super::status::write();
+
                     dbus::PikaBackup::start_scheduled_backup(&config.id, due_cause)
                         .await
                         .handle(gettext("Failed to start scheduled backup"));
@@ -154,6 +266,7 @@ async fn probe(config: &config::Backup) {
         }
         Err(err) => {
             debug!("Backup is not yet due: {:?}", err);
+            log_probe(config, config::ProbeOutcome::NotDue);
         }
     }
 }
@@ -0,0 +1,90 @@
+/*!
+# Daily summary of missed backups
+*/
+
+use crate::daemon::prelude::*;
+use gio::prelude::*;
+
+use crate::daemon::{action, notification::Note};
+use crate::schedule::missed;
+
+const SETTING_ENABLED: &str = "missed-backups-summary";
+const SETTING_HOUR: &str = "missed-backups-summary-hour";
+
+thread_local!(
+    static SETTINGS: Option<gio::Settings> = crate::utils::app_settings();
+);
+
+/// The summary replaces reminders about individual postponed backups
+pub fn is_enabled() -> bool {
+    SETTINGS.with(|settings| {
+        settings
+            .as_ref()
+            .map_or(false, |x| x.boolean(SETTING_ENABLED))
+    })
+}
+
+/// Send the summary if it is due today
+pub fn check() {
+    let Some(hour) = SETTINGS.with(|settings| {
+        settings
+            .as_ref()
+            .filter(|x| x.boolean(SETTING_ENABLED))
+            .map(|x| x.uint(SETTING_HOUR))
+    }) else {
+        return;
+    };
+
+    let now = chrono::Local::now();
+    if !missed::is_summary_due(hour, SCHEDULE_STATUS.load().last_missed_summary, now) {
+        return;
+    }
+
+    SCHEDULE_STATUS.update_no_commit(|schedule_status| {
+        schedule_status.last_missed_summary = Some(now);
+    });
+    super::status::write();
+
+    let histories = BACKUP_HISTORY.load();
+    let missed_configs: Vec<_> = BACKUP_CONFIG
+        .load()
+        .iter()
+        .filter(|config| missed::is_missed(config, histories.try_get(&config.id).ok(), now))
+        .cloned()
+        .collect();
+
+    if missed_configs.is_empty() {
+        debug!("No missed backups");
+        return;
+    }
+
+    info!("Sending summary of {} missed backups", missed_configs.len());
+
+    let notification = gio::Notification::new(&ngettext(
+        "Backup Missed",
+        "Backups Missed",
+        missed_configs.len() as u32,
+    ));
+    notification.set_body(Some(&gettextf(
+        "No recent backups of {}",
+        &[&missed_configs
+            .iter()
+            .map(|config| format!("“{}”", config.title()))
+            .collect::<Vec<_>>()
+            .join(", ")],
+    )));
+    notification.set_default_action(&action::ShowOverview::name());
+    notification.add_button_with_target_value(
+        &gettext("Back Up Now"),
+        &action::StartBackups::name(),
+        Some(
+            &missed_configs
+                .iter()
+                .map(|config| config.id.as_str().to_string())
+                .collect::<Vec<_>>()
+                .to_variant(),
+        ),
+    );
+
+    gio_app().send_notification(Some(&Note::MissedBackups.to_string()), &notification);
+}
@@ -0,0 +1,76 @@
+//! Publish the backup status on the system bus if the user has opted in
+
+use gio::prelude::*;
+use once_cell::unsync::OnceCell;
+
+use crate::daemon::prelude::*;
+use crate::utils::shared_status;
+
+const SETTING: &str = "share-status";
+
+thread_local! {
+    static SETTINGS: OnceCell<gio::Settings> = OnceCell::default();
+}
+
+struct SharedStatus;
+
+#[zbus::dbus_interface(name = "org.gnome.World.PikaBackup.SharedStatus1")]
+impl SharedStatus {
+    async fn backups(&self) -> Vec<shared_status::Summary> {
+        let histories = BACKUP_HISTORY.load();
+        let now = chrono::Local::now();
+
+        BACKUP_CONFIG
+            .load()
+            .iter()
+            .map(|config| {
+                shared_status::Summary::new(config, histories.try_get(&config.id).ok(), now)
+            })
+            .collect()
+    }
+}
+
+static CONNECTION: async_lock::Mutex<Option<zbus::Connection>> = async_lock::Mutex::new(None);
+
+async fn set_shared(shared: bool) -> zbus::Result<()> {
+    let mut connection = CONNECTION.lock().await;
+
+    if !shared {
+        if connection.take().is_some() {
+            debug!("Stopped sharing backup status");
+        }
+    } else if connection.is_none() {
+        let name = shared_status::bus_name(nix::unistd::getuid().as_raw());
+        let new_connection = zbus::ConnectionBuilder::system()?
+            .name(name.as_str())?
+            .serve_at(shared_status::PATH, SharedStatus)?
+            .build()
+            .await?;
+        debug!("Sharing backup status as {}", name);
+
+        *connection = Some(new_connection);
+    }
+
+    Ok(())
+}
+
+fn update(settings: &gio::Settings) {
+    let shared = settings.boolean(SETTING);
+
+    glib::MainContext::default().spawn(async move {
+        set_shared(shared)
+            .await
+            .handle("Cannot share backup status with other users.")
+    });
+}
+
+pub fn init() {
+    let Some(settings) = crate::utils::app_settings() else {
+        return;
+    };
+
+    settings.connect_changed(Some(SETTING), update);
+    update(&settings);
+
+    SETTINGS.with(|s| s.set(settings)).unwrap();
+}
@@ -0,0 +1,444 @@
+//! Tray icon for desktops without background integration like GNOME Shell
+//!
+//! Implements the StatusNotifierItem specification with a menu provided via the
+//! `com.canonical.dbusmenu` interface. The icon is only registered if a
+//! StatusNotifierWatcher is running, which is usually not the case on GNOME.
+//!
+//! The icon is provided by the monitor process such that it stays available
+//! while the app is not running.
+
+use crate::daemon::prelude::*;
+use async_std::prelude::*;
+
+use arc_swap::ArcSwap;
+use async_std::channel::Sender;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use zbus::zvariant::{ObjectPath, OwnedValue, StructureBuilder, Value};
+
+use crate::borg;
+use crate::daemon::dbus;
+
+/// Default path of items according to the specification
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+
+/// Current content of the tray icon, updated from the main thread
+static STATE: Lazy<ArcSwap<State>> = Lazy::new(Default::default);
+
+static CONNECTION: async_lock::Mutex<Option<zbus::Connection>> = async_lock::Mutex::new(None);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct State {
+    status: &'static str,
+    text_direction: &'static str,
+    tooltip: String,
+    entries: Vec<Entry>,
+    revision: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    Item { label: String, command: Command },
+    Separator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Open,
+    StartBackup(ConfigId),
+    StopOperation(ConfigId),
+}
+
+impl Command {
+    /// Forward the command to the app, which is started if necessary
+    async fn run(self) -> zbus::Result<()> {
+        match self {
+            Self::Open => dbus::PikaBackup::show_overview().await,
+            Self::StartBackup(config_id) => dbus::PikaBackup::start_backup(&config_id).await,
+            // The app asks for confirmation like for the stop button
+            Self::StopOperation(config_id) => dbus::PikaBackup::stop_operation(&config_id).await,
+        }
+    }
+}
+
+struct Item {
+    command: Sender<Command>,
+}
+
+#[zbus::dbus_interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    #[dbus_interface(property)]
+    fn category(&self) -> &str {
+        "ApplicationStatus"
+    }
+
+    #[dbus_interface(property)]
+    fn id(&self) -> &str {
+        crate::APP_ID
+    }
+
+    #[dbus_interface(property)]
+    fn title(&self) -> String {
+        gettext("Pika Backup")
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> &str {
+        STATE.load().status
+    }
+
+    #[dbus_interface(property)]
+    fn icon_name(&self) -> &str {
+        crate::APP_ID
+    }
+
+    #[dbus_interface(property)]
+    fn tool_tip(&self) -> (String, Vec<(i32, i32, Vec<u8>)>, String, String) {
+        (
+            crate::APP_ID.to_string(),
+            Vec::new(),
+            gettext("Pika Backup"),
+            STATE.load().tooltip.clone(),
+        )
+    }
+
+    #[dbus_interface(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[dbus_interface(property)]
+    fn menu(&self) -> ObjectPath<'_> {
+        ObjectPath::from_static_str_unchecked(MENU_PATH)
+    }
+
+    async fn activate(&self, _x: i32, _y: i32) {
+        if let Err(err) = self.command.send(Command::Open).await {
+            error!("{}", err);
+        }
+    }
+
+    async fn secondary_activate(&self, _x: i32, _y: i32) {}
+
+    async fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[dbus_interface(signal)]
+    async fn new_status(ctxt: &zbus::SignalContext<'_>, status: &str) -> zbus::Result<()>;
+
+    #[dbus_interface(signal)]
+    async fn new_tool_tip(ctxt: &zbus::SignalContext<'_>) -> zbus::Result<()>;
+}
+
+struct Menu {
+    command: Sender<Command>,
+}
+
+#[zbus::dbus_interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    #[dbus_interface(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[dbus_interface(property)]
+    fn text_direction(&self) -> &str {
+        STATE.load().text_direction
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[dbus_interface(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[dbus_interface(out_args("revision", "layout"))]
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        _recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> (u32, (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>)) {
+        let state = STATE.load();
+
+        // All entries are direct children of the root
+        let children = if parent_id == 0 {
+            (1..=state.entries.len() as i32)
+                .map(|id| {
+                    Value::from(
+                        StructureBuilder::new()
+                            .add_field(id)
+                            .add_field(properties(&state, id))
+                            .add_field(Vec::<OwnedValue>::new())
+                            .build(),
+                    )
+                    .into()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        (
+            state.revision,
+            (parent_id, properties(&state, parent_id), children),
+        )
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        let state = STATE.load();
+
+        ids.into_iter()
+            .map(|id| (id, properties(&state, id)))
+            .collect()
+    }
+
+    fn get_property(&self, id: i32, name: &str) -> OwnedValue {
+        properties(&STATE.load(), id)
+            .remove(name)
+            .unwrap_or_else(|| Value::from("").into())
+    }
+
+    async fn event(&self, id: i32, event_id: &str, _data: Value<'_>, _timestamp: u32) {
+        if event_id != "clicked" {
+            return;
+        }
+
+        let command = usize::try_from(id - 1)
+            .ok()
+            .and_then(|index| STATE.load().entries.get(index).cloned());
+
+        if let Some(Entry::Item { command, .. }) = command {
+            if let Err(err) = self.command.send(command).await {
+                error!("{}", err);
+            }
+        }
+    }
+
+    async fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        for (id, event_id, data, timestamp) in events {
+            self.event(id, &event_id, data.into(), timestamp).await;
+        }
+
+        Vec::new()
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+
+    #[dbus_interface(signal)]
+    async fn layout_updated(
+        ctxt: &zbus::SignalContext<'_>,
+        revision: u32,
+        parent: i32,
+    ) -> zbus::Result<()>;
+}
+
+/// Menu properties of the entry `id`, where `0` is the root
+fn properties(state: &State, id: i32) -> HashMap<String, OwnedValue> {
+    let mut properties = HashMap::new();
+
+    if id == 0 {
+        properties.insert("children-display".into(), Value::from("submenu").into());
+    }
+
+    match usize::try_from(id - 1)
+        .ok()
+        .and_then(|index| state.entries.get(index))
+    {
+        Some(Entry::Item { label, .. }) => {
+            properties.insert("label".into(), Value::from(label.as_str()).into());
+        }
+        Some(Entry::Separator) => {
+            properties.insert("type".into(), Value::from("separator").into());
+        }
+        None => {}
+    }
+
+    properties
+}
+
+#[zbus::dbus_proxy(
+    interface = "org.kde.StatusNotifierWatcher",
+    default_service = "org.kde.StatusNotifierWatcher",
+    default_path = "/StatusNotifierWatcher",
+    gen_blocking = false
+)]
+trait StatusNotifierWatcher {
+    fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+fn spawn_command_listener() -> Sender<Command> {
+    let (sender, mut receiver) = async_std::channel::unbounded::<Command>();
+
+    glib::MainContext::default().spawn_local(async move {
+        while let Some(command) = receiver.next().await {
+            debug!("Received tray command {command:?}");
+            command
+                .run()
+                .await
+                .handle(gettext("Failed to contact Pika Backup"));
+        }
+    });
+
+    sender
+}
+
+async fn register() -> zbus::Result<()> {
+    let command = spawn_command_listener();
+    let connection = zbus::ConnectionBuilder::session()?
+        .serve_at(
+            ITEM_PATH,
+            Item {
+                command: command.clone(),
+            },
+        )?
+        .serve_at(MENU_PATH, Menu { command })?
+        .build()
+        .await?;
+
+    let watcher = StatusNotifierWatcherProxy::new(&connection).await?;
+    let name = connection
+        .unique_name()
+        .map(|x| x.to_string())
+        .unwrap_or_default();
+    watcher.register_status_notifier_item(&name).await?;
+    debug!("Tray icon registered as {}", name);
+
+    *CONNECTION.lock().await = Some(connection);
+
+    Ok(())
+}
+
+/// Register the tray icon if the desktop supports it
+pub fn init() {
+    glib::MainContext::default().spawn_local(async {
+        if let Err(err) = register().await {
+            debug!("No tray icon available: {}", err);
+            return;
+        }
+
+        // Running operations are recorded in the backup history
+        crate::config::connect_reloaded(refresh);
+        refresh();
+    });
+}
+
+/// Update the status and menu after changes of backups or their history
+fn refresh() {
+    glib::MainContext::default().spawn_local(async {
+        let Some(connection) = CONNECTION.lock().await.clone() else {
+            return;
+        };
+
+        let state = state();
+        let previous = STATE.load();
+        let state = State {
+            revision: previous.revision,
+            ..state
+        };
+        if state == **previous {
+            return;
+        }
+
+        let state = State {
+            revision: previous.revision + 1,
+            ..state
+        };
+        STATE.store(std::sync::Arc::new(state.clone()));
+
+        let result = async {
+            let ctxt = zbus::SignalContext::new(&connection, ITEM_PATH)?;
+            Item::new_status(&ctxt, state.status).await?;
+            Item::new_tool_tip(&ctxt).await?;
+
+            let ctxt = zbus::SignalContext::new(&connection, MENU_PATH)?;
+            Menu::layout_updated(&ctxt, state.revision, 0).await
+        }
+        .await;
+
+        if let Err(err) = result {
+            warn!("Failed to update tray icon: {}", err);
+        }
+    });
+}
+
+fn state() -> State {
+    let mut status = "Passive";
+    let mut tooltip = Vec::new();
+    let mut entries = vec![
+        Entry::Item {
+            label: gettext("Open Pika Backup"),
+            command: Command::Open,
+        },
+        Entry::Separator,
+    ];
+
+    let histories = BACKUP_HISTORY.load();
+
+    for config in BACKUP_CONFIG.load().iter() {
+        let history = histories.try_get(&config.id).ok();
+
+        if history.is_some_and(|history| history.is_running()) {
+            status = "Active";
+            tooltip.push(format!("{}: {}", config.title(), gettext("Backup Running")));
+            entries.push(Entry::Item {
+                // Translators: Argument is the title of a backup setup
+                label: gettextf("Stop “{}”", &[&config.title()]),
+                command: Command::StopOperation(config.id.clone()),
+            });
+        } else {
+            let last_run = history.and_then(|history| history.run.front());
+            let last_completed = history.and_then(|history| history.last_completed.as_ref());
+
+            let text = if let Some(borg::Outcome::Failed(_)) = last_run.map(|run| &run.outcome) {
+                if status == "Passive" {
+                    status = "NeedsAttention";
+                }
+                gettext("Last backup failed")
+            } else if let Some(date) = last_completed.and_then(|run| {
+                glib::DateTime::from_unix_local(run.end.timestamp())
+                    .and_then(|date| date.format("%c"))
+                    .ok()
+            }) {
+                // Translators: Argument is the date and time of the last backup
+                gettextf("Last backup {}", &[date.as_str()])
+            } else {
+                gettext("No backup yet")
+            };
+
+            tooltip.push(format!("{}: {}", config.title(), text));
+            entries.push(Entry::Item {
+                // Translators: Argument is the title of a backup setup
+                label: gettextf("Back Up “{}”", &[&config.title()]),
+                command: Command::StartBackup(config.id.clone()),
+            });
+        }
+    }
+
+    State {
+        status,
+        // Same convention as used by GTK to determine the text direction
+        text_direction: if gettextrs::dgettext("gtk40", "default:LTR") == "default:RTL" {
+            "rtl"
+        } else {
+            "ltr"
+        },
+        tooltip: tooltip.join("\n"),
+        entries,
+        revision: 0,
+    }
+}
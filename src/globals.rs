@@ -37,6 +37,55 @@ pub fn schedule_status() -> std::sync::Arc<dyn LookupConfigId<Item = config::Act
     }
 }
 
+/// Time the daemon was started in the current session
+pub fn session_start() -> Option<chrono::DateTime<chrono::Local>> {
+    if matches!(LIB_USER.get(), Some(&LibUser::Daemon)) {
+        Lazy::force(&crate::daemon::SCHEDULE_STATUS)
+            .load()
+            .session_start
+    } else {
+        Lazy::force(&crate::ui::SCHEDULE_STATUS)
+            .load()
+            .session_start
+    }
+}
+
+/// Reason and end of a veto that postpones scheduled backups of `config_id`
+pub fn active_veto(
+    config_id: &config::ConfigId,
+) -> Option<(String, chrono::DateTime<chrono::Local>)> {
+    let now = chrono::Local::now();
+
+    if matches!(LIB_USER.get(), Some(&LibUser::Daemon)) {
+        Lazy::force(&crate::daemon::SCHEDULE_STATUS)
+            .load()
+            .active_veto(config_id, now)
+    } else {
+        Lazy::force(&crate::ui::SCHEDULE_STATUS)
+            .load()
+            .active_veto(config_id, now)
+    }
+}
+
+/// End of a skipped scheduled backup of `config_id`
+pub fn active_skip(config_id: &config::ConfigId) -> Option<chrono::DateTime<chrono::Local>> {
+    let now = chrono::Local::now();
+    let last_completed = backup_history()
+        .try_get(config_id)
+        .ok()
+        .and_then(|history| history.last_completed.as_ref().map(|run| run.end));
+
+    if matches!(LIB_USER.get(), Some(&LibUser::Daemon)) {
+        Lazy::force(&crate::daemon::SCHEDULE_STATUS)
+            .load()
+            .active_skip(config_id, now, last_completed)
+    } else {
+        Lazy::force(&crate::ui::SCHEDULE_STATUS)
+            .load()
+            .active_skip(config_id, now, last_completed)
+    }
+}
+
 #[derive(Debug)]
 pub enum LibUser {
     Daemon,
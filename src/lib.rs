@@ -31,6 +31,8 @@ const DBUS_API_NAME: &str = const_str::concat!(APP_ID, ".Api");
 const DBUS_API_PATH: &str = const_str::concat!("/", const_str::replace!(APP_ID, ".", "/"));
 
 const DAEMON_APP_ID: &str = const_str::concat!(APP_ID, ".Monitor");
+const DAEMON_DBUS_PATH: &str =
+    const_str::concat!("/", const_str::replace!(DAEMON_APP_ID, ".", "/"));
 const DAEMON_BINARY: &str = concat!(env!("CARGO_PKG_NAME"), "-monitor");
 
 mod action;
@@ -38,6 +40,7 @@ pub mod borg;
 pub mod config;
 pub mod daemon;
 mod globals;
+pub mod provider;
 mod schedule;
 pub mod ui;
 mod utils;
@@ -0,0 +1,127 @@
+//! Hosting providers that create repositories on request
+//!
+//! A provider creates a remote repository that is reachable via SSH and
+//! accessible with the public key of the user. The repository is initialized
+//! by borg afterwards, like any other remote location.
+
+pub mod borgbase;
+
+use crate::prelude::*;
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Http(err: crate::utils::http::Error) {
+            from()
+            display("{}", err)
+        }
+        Api(message: String) {
+            display("{}", message)
+        }
+        NoPublicKey {
+            display("{}", gettext("No SSH key found. Create a key pair with “ssh-keygen” first."))
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A repository created by a provider
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemoteRepository {
+    /// Identifier used by the provider
+    pub id: String,
+    pub name: String,
+    /// Location in borg syntax
+    pub url: String,
+}
+
+/// Storage usage of a repository in bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+    pub used: u64,
+    /// `None` if the storage is not limited
+    pub limit: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SshDetails {
+    pub user: String,
+    pub host: String,
+    pub path: String,
+}
+
+impl SshDetails {
+    /// Location in the `ssh://` syntax
+    pub fn url(&self) -> String {
+        if self.path.starts_with('/') {
+            format!("ssh://{}@{}{}", self.user, self.host, self.path)
+        } else {
+            format!("ssh://{}@{}/./{}", self.user, self.host, self.path)
+        }
+    }
+
+    /// Parse a location in the `user@host:path` syntax
+    pub fn from_borg_location(location: &str) -> Option<Self> {
+        let (user, rest) = location.split_once('@')?;
+        let (host, path) = rest.split_once(':')?;
+
+        if user.is_empty() || host.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            user: user.to_string(),
+            host: host.to_string(),
+            path: path.to_string(),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+pub trait Provider {
+    /// Name of the provider as shown to the user
+    fn name(&self) -> String;
+
+    /// Create a repository that is accessible with `public_key`
+    async fn create_repository(&self, name: &str, public_key: &str) -> Result<RemoteRepository>;
+
+    /// Delete a repository that has been created by [`Self::create_repository`]
+    async fn delete_repository(&self, repository: &RemoteRepository) -> Result<()>;
+
+    async fn quota(&self, repository: &RemoteRepository) -> Result<Quota>;
+
+    async fn ssh_details(&self, repository: &RemoteRepository) -> Result<SshDetails>;
+}
+
+/// Public SSH key of the user, preferring modern key types
+pub fn ssh_public_key() -> Result<String> {
+    let ssh_dir = glib::home_dir().join(".ssh");
+
+    ["id_ed25519.pub", "id_ecdsa.pub", "id_rsa.pub"]
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(ssh_dir.join(file)).ok())
+        .map(|key| key.trim().to_string())
+        .find(|key| !key.is_empty())
+        .ok_or(Error::NoPublicKey)
+}
+
+#[test]
+fn test_ssh_details() {
+    assert_eq!(
+        SshDetails::from_borg_location("x1y2z3@x1y2z3.repo.borgbase.com:repo"),
+        Some(SshDetails {
+            user: String::from("x1y2z3"),
+            host: String::from("x1y2z3.repo.borgbase.com"),
+            path: String::from("repo"),
+        })
+    );
+    assert_eq!(
+        SshDetails::from_borg_location("x1y2z3@x1y2z3.repo.borgbase.com:repo")
+            .unwrap()
+            .url(),
+        "ssh://x1y2z3@x1y2z3.repo.borgbase.com/./repo"
+    );
+    assert_eq!(SshDetails::from_borg_location("example.org:repo"), None);
+    assert_eq!(SshDetails::from_borg_location("@example.org:repo"), None);
+}
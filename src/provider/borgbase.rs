@@ -0,0 +1,253 @@
+//! BorgBase repository hosting via its GraphQL API
+//!
+//! API tokens are created in the account settings at <https://www.borgbase.com>.
+
+use super::{Error, Provider, Quota, RemoteRepository, Result, SshDetails};
+use crate::prelude::*;
+
+const API_URL: &str = "https://api.borgbase.com/graphql";
+const BYTES_PER_MB: f64 = 1_000_000.;
+
+const SSH_LIST: &str = "query { sshList { id keyData } }";
+const SSH_ADD: &str = "mutation sshAdd($name: String!, $keyData: String!) {
+  sshAdd(name: $name, keyData: $keyData) { keyAdded { id } }
+}";
+const REPO_ADD: &str =
+    "mutation repoAdd($name: String!, $region: String!, $fullAccessKeys: [String]) {
+  repoAdd(name: $name, region: $region, fullAccessKeys: $fullAccessKeys) {
+    repoAdded { id name repoPath }
+  }
+}";
+const REPO_DELETE: &str = "mutation repoDelete($id: String!) {
+  repoDelete(id: $id) { ok }
+}";
+const REPO_LIST: &str = "query { repoList { id repoPath currentUsage quota quotaEnabled } }";
+
+/// Storage regions as used by the API
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Region {
+    #[default]
+    Eu,
+    Us,
+}
+
+impl Region {
+    pub fn list() -> [Self; 2] {
+        [Self::Eu, Self::Us]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Eu => gettext("European Union"),
+            Self::Us => gettext("United States"),
+        }
+    }
+
+    const fn id(self) -> &'static str {
+        match self {
+            Self::Eu => "eu",
+            Self::Us => "us",
+        }
+    }
+}
+
+pub struct BorgBase {
+    token: String,
+    region: Region,
+}
+
+impl BorgBase {
+    pub fn new(token: String, region: Region) -> Self {
+        Self { token, region }
+    }
+
+    async fn query(&self, query: &str, variables: serde_json::Value) -> Result<serde_json::Value> {
+        let response = crate::utils::http::post_json(
+            API_URL,
+            Some(&self.token),
+            &serde_json::json!({ "query": query, "variables": variables }),
+        )
+        .await?;
+
+        data(response)
+    }
+
+    /// Id of the registered key, registering it if required
+    async fn key_id(&self, public_key: &str) -> Result<String> {
+        let keys = self.query(SSH_LIST, serde_json::json!({})).await?;
+        let wanted = key_data(public_key);
+
+        let existing = keys["sshList"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|key| key["keyData"].as_str().map(key_data).as_ref() == Some(&wanted));
+
+        let id = if let Some(key) = existing {
+            key["id"].clone()
+        } else {
+            let added = self
+                .query(
+                    SSH_ADD,
+                    serde_json::json!({
+                        "name": format!("Pika Backup {}", glib::host_name()),
+                        "keyData": public_key,
+                    }),
+                )
+                .await?;
+            added["sshAdd"]["keyAdded"]["id"].clone()
+        };
+
+        id_string(&id).ok_or_else(invalid_response)
+    }
+
+    async fn find_repository(&self, id: &str) -> Result<serde_json::Value> {
+        let repos = self.query(REPO_LIST, serde_json::json!({})).await?;
+
+        repos["repoList"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|repo| id_string(&repo["id"]).as_deref() == Some(id))
+            .cloned()
+            .ok_or_else(|| Error::Api(gettext("The repository does not exist anymore.")))
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for BorgBase {
+    fn name(&self) -> String {
+        String::from("BorgBase")
+    }
+
+    async fn create_repository(&self, name: &str, public_key: &str) -> Result<RemoteRepository> {
+        let key_id = self.key_id(public_key).await?;
+
+        let added = self
+            .query(
+                REPO_ADD,
+                serde_json::json!({
+                    "name": name,
+                    "region": self.region.id(),
+                    "fullAccessKeys": [key_id],
+                }),
+            )
+            .await?;
+
+        repository(&added["repoAdd"]["repoAdded"]).ok_or_else(invalid_response)
+    }
+
+    async fn delete_repository(&self, repository: &RemoteRepository) -> Result<()> {
+        let deleted = self
+            .query(REPO_DELETE, serde_json::json!({ "id": repository.id }))
+            .await?;
+
+        if deleted["repoDelete"]["ok"].as_bool() == Some(true) {
+            Ok(())
+        } else {
+            Err(invalid_response())
+        }
+    }
+
+    async fn quota(&self, repository: &RemoteRepository) -> Result<Quota> {
+        let repo = self.find_repository(&repository.id).await?;
+
+        let used = repo["currentUsage"].as_f64().unwrap_or_default() * BYTES_PER_MB;
+        let limit = if repo["quotaEnabled"].as_bool() == Some(true) {
+            repo["quota"].as_f64().map(|x| (x * BYTES_PER_MB) as u64)
+        } else {
+            None
+        };
+
+        Ok(Quota {
+            used: used as u64,
+            limit,
+        })
+    }
+
+    async fn ssh_details(&self, repository: &RemoteRepository) -> Result<SshDetails> {
+        let repo = self.find_repository(&repository.id).await?;
+
+        repo["repoPath"]
+            .as_str()
+            .and_then(SshDetails::from_borg_location)
+            .ok_or_else(invalid_response)
+    }
+}
+
+fn invalid_response() -> Error {
+    Error::Http(crate::utils::http::Error::InvalidResponse)
+}
+
+/// Data of a GraphQL response, or the first reported error
+fn data(mut response: serde_json::Value) -> Result<serde_json::Value> {
+    if let Some(message) = response["errors"]
+        .as_array()
+        .and_then(|errors| errors.first())
+        .map(|error| error["message"].as_str().unwrap_or_default().to_string())
+    {
+        return Err(Error::Api(message));
+    }
+
+    match response["data"].take() {
+        serde_json::Value::Null => Err(invalid_response()),
+        data => Ok(data),
+    }
+}
+
+/// Ids are returned as strings or numbers
+fn id_string(id: &serde_json::Value) -> Option<String> {
+    match id {
+        serde_json::Value::String(id) => Some(id.clone()),
+        serde_json::Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Key type and key without the comment
+fn key_data(key: &str) -> String {
+    key.split_whitespace().take(2).collect::<Vec<_>>().join(" ")
+}
+
+fn repository(repo: &serde_json::Value) -> Option<RemoteRepository> {
+    Some(RemoteRepository {
+        id: id_string(&repo["id"])?,
+        name: repo["name"].as_str()?.to_string(),
+        url: repo["repoPath"].as_str()?.to_string(),
+    })
+}
+
+#[test]
+fn test_data() {
+    let response = serde_json::json!({
+        "data": { "repoAdd": { "repoAdded": {
+            "id": "a1b2c3",
+            "name": "backup",
+            "repoPath": "a1b2c3@a1b2c3.repo.borgbase.com:repo",
+        } } }
+    });
+    let result = data(response).unwrap();
+    assert_eq!(
+        repository(&result["repoAdd"]["repoAdded"]),
+        Some(RemoteRepository {
+            id: String::from("a1b2c3"),
+            name: String::from("backup"),
+            url: String::from("a1b2c3@a1b2c3.repo.borgbase.com:repo"),
+        })
+    );
+
+    let response = serde_json::json!({
+        "errors": [{ "message": "Authentication required" }],
+        "data": null,
+    });
+    assert!(matches!(data(response), Err(Error::Api(msg)) if msg == "Authentication required"));
+}
+
+#[test]
+fn test_key_data() {
+    assert_eq!(
+        key_data("ssh-ed25519 AAAAC3Nza user@host\n"),
+        "ssh-ed25519 AAAAC3Nza"
+    );
+    assert_eq!(key_data("ssh-ed25519 AAAAC3Nza"), "ssh-ed25519 AAAAC3Nza");
+}
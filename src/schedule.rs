@@ -1,4 +1,8 @@
+pub mod local_time;
+pub mod missed;
 pub mod requirements;
+pub mod simulation;
+pub mod veto;
 
 pub use requirements::DueCause;
 
@@ -0,0 +1,175 @@
+/*!
+# Local times across time zone changes
+
+Preferred times are stored as local times without a time zone. When the clock
+is set forward for daylight saving time, a local time can be skipped. When it's
+set back, a local time occurs twice. [`resolve`] maps every local time to
+exactly one instant, such that scheduled backups are neither skipped nor
+started twice.
+
+All comparisons use instants. Backups that ran before the time zone changed,
+for example while travelling, are still recognized afterwards.
+*/
+
+use chrono::prelude::*;
+
+/// Longest skipped period that is bridged
+const MAX_GAP_MINUTES: i64 = 3 * 60;
+
+/// The instant of the local `time` on `date`
+///
+/// Times that occur twice resolve to the first occurrence. Skipped times
+/// resolve to the end of the skipped period.
+pub fn resolve<Tz: TimeZone>(tz: &Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+    let datetime = date.and_time(time);
+
+    match tz.from_local_datetime(&datetime) {
+        LocalResult::Single(resolved) => resolved,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        LocalResult::None => (1..=MAX_GAP_MINUTES)
+            .find_map(|minutes| {
+                tz.from_local_datetime(&(datetime + chrono::Duration::minutes(minutes)))
+                    .earliest()
+            })
+            // Only happens with broken time zone data
+            .unwrap_or_else(|| tz.from_utc_datetime(&datetime)),
+    }
+}
+
+/// The instant the local `date` starts
+pub fn start_of_day<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> DateTime<Tz> {
+    resolve(tz, date, NaiveTime::from_hms(0, 0, 0))
+}
+
+/// The clocks are changed during the local `date`
+pub fn clock_change_on<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> bool {
+    let start = start_of_day(tz, date);
+    let end = start_of_day(tz, date.succ());
+
+    start.offset().fix() != end.offset().fix()
+}
+
+/// Central European time, which switches to summer time from
+/// 2023-03-26 02:00 to 2023-10-29 03:00
+#[cfg(test)]
+#[derive(Clone, Copy, Debug)]
+struct TestZone;
+
+#[cfg(test)]
+impl TestZone {
+    fn standard() -> FixedOffset {
+        FixedOffset::east(3600)
+    }
+
+    fn summer() -> FixedOffset {
+        FixedOffset::east(2 * 3600)
+    }
+}
+
+#[cfg(test)]
+impl TimeZone for TestZone {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Self {
+        Self
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_hms(0, 0, 0))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        // Earlier instants first
+        let valid: Vec<_> = [Self::summer(), Self::standard()]
+            .into_iter()
+            .filter(|offset| {
+                let utc = *local - chrono::Duration::seconds(offset.local_minus_utc().into());
+                self.offset_from_utc_datetime(&utc) == *offset
+            })
+            .collect();
+
+        match valid[..] {
+            [offset] => LocalResult::Single(offset),
+            [earliest, latest] => LocalResult::Ambiguous(earliest, latest),
+            _ => LocalResult::None,
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        self.offset_from_utc_datetime(&utc.and_hms(0, 0, 0))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        let summer_start = NaiveDate::from_ymd(2023, 3, 26).and_hms(1, 0, 0);
+        let summer_end = NaiveDate::from_ymd(2023, 10, 29).and_hms(1, 0, 0);
+
+        if (summer_start..summer_end).contains(utc) {
+            Self::summer()
+        } else {
+            Self::standard()
+        }
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let utc = |month, day, hour, minute| {
+        Utc.ymd(2023, month, day)
+            .and_hms(hour, minute, 0)
+            .with_timezone(&TestZone)
+    };
+    let time = |hour, minute| NaiveTime::from_hms(hour, minute, 0);
+    let date = |month, day| NaiveDate::from_ymd(2023, month, day);
+
+    assert_eq!(
+        resolve(&TestZone, date(6, 1), time(12, 0)),
+        utc(6, 1, 10, 0)
+    );
+    // Skipped when the clock is set forward
+    assert_eq!(
+        resolve(&TestZone, date(3, 26), time(2, 30)),
+        utc(3, 26, 1, 0)
+    );
+    // Occurs twice when the clock is set back
+    assert_eq!(
+        resolve(&TestZone, date(10, 29), time(2, 30)),
+        utc(10, 29, 0, 30)
+    );
+}
+
+#[test]
+fn test_daily_across_clock_change() {
+    let time = NaiveTime::from_hms(2, 30, 0);
+    let date = NaiveDate::from_ymd(2023, 3, 25);
+
+    // Exactly one scheduled time per day, also on days with 23 or 25 hours
+    let spring: Vec<_> = date
+        .iter_days()
+        .take(3)
+        .map(|date| resolve(&TestZone, date, time))
+        .collect();
+    assert_eq!(
+        spring[1] - spring[0],
+        chrono::Duration::hours(23) + chrono::Duration::minutes(30)
+    );
+    assert_eq!(
+        spring[2] - spring[1],
+        chrono::Duration::hours(23) + chrono::Duration::minutes(30)
+    );
+
+    let date = NaiveDate::from_ymd(2023, 10, 28);
+    let autumn: Vec<_> = date
+        .iter_days()
+        .take(3)
+        .map(|date| resolve(&TestZone, date, time))
+        .collect();
+    assert_eq!(autumn[1] - autumn[0], chrono::Duration::hours(24));
+    assert_eq!(autumn[2] - autumn[1], chrono::Duration::hours(25));
+
+    assert!(clock_change_on(&TestZone, NaiveDate::from_ymd(2023, 3, 26)));
+    assert!(clock_change_on(
+        &TestZone,
+        NaiveDate::from_ymd(2023, 10, 29)
+    ));
+    assert!(!clock_change_on(&TestZone, NaiveDate::from_ymd(2023, 6, 1)));
+}
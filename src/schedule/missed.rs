@@ -0,0 +1,77 @@
+/*!
+# Missed backups
+
+Scheduled backups that did not complete within the
+[expected window](crate::config::Frequency::expected_window) of their frequency
+are summarized in one notification per day instead of reminding about every
+unmet requirement.
+*/
+
+use chrono::prelude::*;
+
+use crate::config;
+
+/// No completed backup within the expected window
+pub fn is_missed(
+    config: &config::Backup,
+    history: Option<&config::history::History>,
+    now: DateTime<Local>,
+) -> bool {
+    config.schedule.enabled
+        && history
+            .and_then(|x| x.last_completed.as_ref())
+            .map_or(true, |last_completed| {
+                now - last_completed.end > config.schedule.frequency.expected_window()
+            })
+}
+
+/// The summary has not been sent today and it's past the configured `hour`
+pub fn is_summary_due(
+    hour: u32,
+    last_summary: Option<DateTime<Local>>,
+    now: DateTime<Local>,
+) -> bool {
+    now.hour() >= hour && last_summary.map_or(true, |last| last.date_naive() < now.date_naive())
+}
+
+#[test]
+fn test_is_missed() {
+    let mut config = config::Backup::test_new_mock();
+    let now = Local::now();
+    config.schedule.enabled = true;
+
+    assert!(is_missed(&config, None, now));
+
+    let mut history = config::history::History::default();
+    history.insert(config::history::RunInfo::test_new_mock(
+        chrono::Duration::days(1),
+    ));
+    assert!(!is_missed(&config, Some(&history), now));
+    assert!(is_missed(
+        &config,
+        Some(&history),
+        now + chrono::Duration::days(2)
+    ));
+
+    config.schedule.frequency = config::Frequency::Weekly {
+        preferred_weekday: Weekday::Mon,
+    };
+    assert!(!is_missed(
+        &config,
+        Some(&history),
+        now + chrono::Duration::days(2)
+    ));
+
+    config.schedule.enabled = false;
+    assert!(!is_missed(&config, None, now));
+}
+
+#[test]
+fn test_is_summary_due() {
+    let time = |day, hour| Local.ymd(2023, 5, day).and_hms(hour, 0, 0);
+
+    assert!(!is_summary_due(9, None, time(2, 8)));
+    assert!(is_summary_due(9, None, time(2, 9)));
+    assert!(is_summary_due(9, Some(time(1, 9)), time(2, 10)));
+    assert!(!is_summary_due(9, Some(time(2, 9)), time(2, 10)));
+}
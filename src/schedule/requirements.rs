@@ -32,11 +32,50 @@ Daily backups try to ensure that a backup exists for every day the system is use
 
 - Retried every day after failure.
 
+## Session End
+
+Backups that were interrupted by logging out or shutting down are resumed at the next
+login, independent of the interval, if [resume_interrupted] is enabled.
+
+[resume_interrupted]: crate::config::Settings::resume_interrupted
+
+## Startup Holdoff
+
+No scheduled backups are started during the first [startup_holdoff] minutes
+after the system booted or the user logged in.
+
+[startup_holdoff]: crate::config::Settings::startup_holdoff
+
+## Time Zones
+
+Preferred times are local times. Skipped or repeated local times due to
+daylight saving time are resolved by [`local_time::resolve`].
+
+## Quiet Hours
+
+No scheduled backups are started during the [quiet_hours]. Backups that become due
+during this period are postponed to its end.
+
+[quiet_hours]: crate::config::Settings::quiet_hours
+
+## Veto
+
+A [veto_command] can postpone scheduled backups that are about to start. See
+[`super::veto`] for details.
+
+[veto_command]: crate::config::Settings::veto_command
+
+## Skipping
+
+The user can skip the next scheduled backup. No scheduled backups are started
+until the following backup is due, see [`super::simulation::skip_until`].
+
 */
 
 use chrono::prelude::*;
 use gio::prelude::*;
 
+use super::local_time;
 use crate::config;
 use crate::prelude::*;
 use crate::utils::upower::UPower;
@@ -53,9 +92,26 @@ pub enum Global {
     /// Backup must not be running
     ThisBackupRunning,
     OtherBackupRunning(config::ConfigId),
+    /// Operation on the repository started by another process
+    OperationInBackground,
     /// May not use metered connection
     MeteredConnection,
     OnBattery,
+    /// The power saver profile is active
+    PowerSaver,
+    /// Scheduled backups are held off for some time after boot or login
+    StartupHoldoff {
+        until: DateTime<Local>,
+    },
+    /// The veto command postponed scheduled backups
+    Vetoed {
+        reason: String,
+        until: DateTime<Local>,
+    },
+    /// The user skipped the next scheduled backup
+    Skipped {
+        until: DateTime<Local>,
+    },
 }
 
 impl Global {
@@ -66,7 +122,7 @@ impl Global {
 
         let running_backup = histories
             .iter()
-            .filter(|(_, history)| history.running.is_some())
+            .filter(|(_, history)| history.is_running())
             .find(|(config_id, _)| {
                 backup_config().try_get(config_id).map(|x| &x.repo_id) == Ok(&config.repo_id)
             });
@@ -78,6 +134,8 @@ impl Global {
             } else {
                 vec.push(Self::OtherBackupRunning(running_config_id.clone()))
             }
+        } else if crate::borg::operation_lock::holder(&config.repo_id).is_some() {
+            vec.push(Self::OperationInBackground)
         }
 
         if gio::NetworkMonitor::default().is_network_metered()
@@ -90,8 +148,41 @@ impl Global {
             vec.push(Self::OnBattery)
         }
 
+        if !settings.run_in_power_saver && crate::utils::power_saver_enabled() {
+            vec.push(Self::PowerSaver)
+        }
+
+        let startup = crate::utils::boot_time().max(crate::globals::session_start());
+        if let Some(until) = Self::startup_holdoff_end(settings, startup, chrono::Local::now()) {
+            vec.push(Self::StartupHoldoff { until })
+        }
+
+        if let Some((reason, until)) = crate::globals::active_veto(&config.id) {
+            vec.push(Self::Vetoed { reason, until })
+        }
+
+        if let Some(until) = crate::globals::active_skip(&config.id) {
+            vec.push(Self::Skipped { until })
+        }
+
         vec
     }
+
+    /// End of the holdoff if it's still active at `now`
+    ///
+    /// `startup` is the time of the boot or login, whichever is later.
+    fn startup_holdoff_end(
+        settings: &config::Settings,
+        startup: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        if settings.startup_holdoff == 0 {
+            return None;
+        }
+
+        let until = startup? + chrono::Duration::minutes(settings.startup_holdoff.into());
+        (until > now).then_some(until)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -148,17 +239,63 @@ impl Due {
         config: &config::Backup,
         history: Option<&config::history::History>,
         activity: Option<&config::Activity>,
+    ) -> Result<DueCause, Self> {
+        Self::check_at(config, history, activity, chrono::Local::now())
+    }
+
+    /// Check as if the current time was `now`
+    pub fn check_at(
+        config: &config::Backup,
+        history: Option<&config::history::History>,
+        activity: Option<&config::Activity>,
+        now: DateTime<Local>,
+    ) -> Result<DueCause, Self> {
+        // Evaluating the schedule at an earlier time delays all scheduled times
+        let jitter = config.schedule.settings.jitter_offset(&config.id);
+        let due = match Self::check_frequency_at(config, history, activity, now - jitter) {
+            Err(Self::NotDue { next }) => Err(Self::NotDue {
+                next: next + jitter,
+            }),
+            due => due,
+        };
+
+        let Some(quiet_hours) = &config.schedule.settings.quiet_hours else {
+            return due;
+        };
+
+        match due {
+            Ok(_) => match quiet_hours.end_after(now) {
+                Some(next) => Err(Self::NotDue { next }),
+                None => due,
+            },
+            Err(Self::NotDue { next }) => Err(Self::NotDue {
+                next: quiet_hours.end_after(next).unwrap_or(next),
+            }),
+            Err(Self::Running) => due,
+        }
+    }
+
+    /// Check the frequency without considering quiet hours
+    fn check_frequency_at(
+        config: &config::Backup,
+        history: Option<&config::history::History>,
+        activity: Option<&config::Activity>,
+        now: DateTime<Local>,
     ) -> Result<DueCause, Self> {
         let schedule = &config.schedule;
         let activity = activity.map(|x| x.used).unwrap_or_default();
         let last_completed = history.and_then(|x| x.last_completed.as_ref());
 
-        if history.map(|x| x.running.is_some()) == Some(true) {
+        if history.map(|x| x.is_running()) == Some(true) {
             Err(Self::Running)
+        } else if schedule.settings.resume_interrupted
+            && history.map(|x| x.is_interrupted_by_session_end()) == Some(true)
+        {
+            Ok(DueCause::Retry)
         } else if let Some(last_run) = history.and_then(|x| x.run.front()) {
             match schedule.frequency {
                 config::Frequency::Hourly => {
-                    let last_run_ago = chrono::Local::now() - last_run.end;
+                    let last_run_ago = now - last_run.end;
                     if last_run_ago >= chrono::Duration::hours(1) {
                         Ok(DueCause::Regular)
                     } else {
@@ -168,47 +305,42 @@ impl Due {
                     }
                 }
                 config::Frequency::Daily { preferred_time } => {
-                    let now = chrono::Local::now();
-
-                    let scheduled_datetime = {
-                        let datetime = now
-                            .date()
-                            .and_time(preferred_time)
-                            .unwrap_or_else(|| now.date().pred().and_hms(0, 0, 0));
-
-                        if datetime > now {
-                            datetime - chrono::Duration::days(1)
-                        } else {
-                            datetime
-                        }
+                    let today = now.date_naive();
+                    let scheduled_date = if local_time::resolve(&Local, today, preferred_time) > now
+                    {
+                        today.pred()
+                    } else {
+                        today
                     };
+                    let scheduled_datetime =
+                        local_time::resolve(&Local, scheduled_date, preferred_time);
 
                     if last_run.end < scheduled_datetime {
                         if activity >= super::USED_THRESHOLD {
                             Ok(DueCause::Regular)
                         } else {
                             Err(Self::NotDue {
-                                next: chrono::Local::now()
+                                next: now
                                     + chrono::Duration::from_std(super::USED_THRESHOLD - activity)
                                         .unwrap_or_else(|_| chrono::Duration::zero()),
                             })
                         }
                     } else {
                         Err(Self::NotDue {
-                            next: scheduled_datetime
-                                .date()
-                                .succ()
-                                .and_time(preferred_time)
-                                .unwrap_or_else(|| scheduled_datetime + chrono::Duration::days(1)),
+                            next: local_time::resolve(
+                                &Local,
+                                scheduled_date.succ(),
+                                preferred_time,
+                            ),
                         })
                     }
                 }
                 config::Frequency::Weekly { preferred_weekday } => {
-                    let today = chrono::Local::today();
+                    let today = now.date_naive();
 
                     let scheduled_date = {
                         let iso_week = today.iso_week();
-                        let schedule_date = chrono::Local.isoywd(
+                        let schedule_date = NaiveDate::from_isoywd(
                             iso_week.year(),
                             iso_week.week(),
                             preferred_weekday,
@@ -221,23 +353,23 @@ impl Due {
                         }
                     };
 
-                    if last_run.end.date() < scheduled_date {
+                    if last_run.end.date_naive() < scheduled_date {
                         if activity >= super::USED_THRESHOLD {
                             Ok(DueCause::Regular)
                         } else {
                             Err(Self::NotDue {
-                                next: chrono::Local::now()
+                                next: now
                                     + chrono::Duration::from_std(super::USED_THRESHOLD - activity)
                                         .unwrap_or_else(|_| chrono::Duration::zero()),
                             })
                         }
-                    } else if last_completed.map(|x| x.end.date()) < Some(scheduled_date) {
-                        if last_run.end.date() == today {
-                            let next = last_run.end.date().succ().and_hms(0, 0, 0);
+                    } else if last_completed.map(|x| x.end.date_naive()) < Some(scheduled_date) {
+                        if last_run.end.date_naive() == today {
+                            let next = local_time::start_of_day(&Local, today.succ());
                             Err(Self::NotDue { next })
                         } else if activity < super::USED_THRESHOLD {
                             Err(Self::NotDue {
-                                next: chrono::Local::now()
+                                next: now
                                     + chrono::Duration::from_std(super::USED_THRESHOLD - activity)
                                         .unwrap_or_else(|_| chrono::Duration::zero()),
                             })
@@ -246,14 +378,17 @@ impl Due {
                         }
                     } else {
                         Err(Self::NotDue {
-                            next: (scheduled_date + chrono::Duration::weeks(1)).and_hms(0, 0, 0),
+                            next: local_time::start_of_day(
+                                &Local,
+                                scheduled_date + chrono::Duration::weeks(1),
+                            ),
                         })
                     }
                 }
 
                 // TODO: repeat after error missing
                 config::Frequency::Monthly { preferred_day } => {
-                    let today = chrono::Local::today();
+                    let today = now.date_naive();
 
                     let scheduled_date = {
                         if preferred_day > today.day() as u8 {
@@ -266,23 +401,23 @@ impl Due {
                     }
                     .unwrap_or(today);
 
-                    if last_run.end.date() < scheduled_date {
+                    if last_run.end.date_naive() < scheduled_date {
                         if activity >= super::USED_THRESHOLD {
                             Ok(DueCause::Regular)
                         } else {
                             Err(Self::NotDue {
-                                next: chrono::Local::now()
+                                next: now
                                     + chrono::Duration::from_std(super::USED_THRESHOLD - activity)
                                         .unwrap_or_else(|_| chrono::Duration::zero()),
                             })
                         }
-                    } else if last_completed.map(|x| x.end.date()) < Some(scheduled_date) {
-                        if last_run.end.date() == today {
-                            let next = last_run.end.date().succ().and_hms(0, 0, 0);
+                    } else if last_completed.map(|x| x.end.date_naive()) < Some(scheduled_date) {
+                        if last_run.end.date_naive() == today {
+                            let next = local_time::start_of_day(&Local, today.succ());
                             Err(Self::NotDue { next })
                         } else if activity < super::USED_THRESHOLD {
                             Err(Self::NotDue {
-                                next: chrono::Local::now()
+                                next: now
                                     + chrono::Duration::from_std(super::USED_THRESHOLD - activity)
                                         .unwrap_or_else(|_| chrono::Duration::zero()),
                             })
@@ -291,8 +426,10 @@ impl Due {
                         }
                     } else {
                         Err(Self::NotDue {
-                            next: (chronoutil::delta::shift_months(scheduled_date, 1))
-                                .and_hms(0, 0, 0),
+                            next: local_time::start_of_day(
+                                &Local,
+                                chronoutil::delta::shift_months(scheduled_date, 1),
+                            ),
                         })
                     }
                 }
@@ -315,6 +452,7 @@ fn test_check_running() {
 
     history.running = Some(config::history::Running {
         start: chrono::Local::now(),
+        session_end: false,
     });
 
     let due = Due::check_full(&config, Some(&history), Some(&activity));
@@ -559,3 +697,134 @@ fn test_check_monthly() {
         _ => false,
     });
 }
+
+#[test]
+fn test_check_session_end() {
+    let mut config = config::Backup::test_new_mock();
+    let mut history = config::history::History::default();
+    let activity = config::Activity {
+        used: super::USED_THRESHOLD,
+        last_update: chrono::Local::now(),
+    };
+
+    config.schedule.frequency = config::Frequency::Hourly;
+    history.insert(config::history::RunInfo::test_new_mock(
+        chrono::Duration::minutes(10),
+    ));
+
+    // Process was terminated before the abort completed
+    history.running = Some(config::history::Running {
+        start: chrono::Local::now() - chrono::Duration::minutes(5),
+        session_end: true,
+    });
+
+    let due = Due::check_full(&config, Some(&history), Some(&activity));
+    matches::assert_matches!(due, Ok(DueCause::Retry));
+
+    // Abort recorded in history
+    history.insert(config::history::RunInfo::new_session_end(
+        &(chrono::Local::now() - chrono::Duration::minutes(5)),
+    ));
+
+    let due = Due::check_full(&config, Some(&history), Some(&activity));
+    matches::assert_matches!(due, Ok(DueCause::Retry));
+
+    // Regular schedule when resuming is disabled
+    config.schedule.settings.resume_interrupted = false;
+
+    let due = Due::check_full(&config, Some(&history), Some(&activity));
+    matches::assert_matches!(due, Err(Due::NotDue { .. }));
+}
+
+#[test]
+fn test_startup_holdoff() {
+    let mut settings = config::Settings::default();
+    let startup = Local.ymd(2023, 5, 1).and_hms(8, 0, 0);
+    let now = startup + chrono::Duration::minutes(3);
+
+    assert_eq!(
+        Global::startup_holdoff_end(&settings, Some(startup), now),
+        None
+    );
+
+    settings.startup_holdoff = 5;
+    assert_eq!(
+        Global::startup_holdoff_end(&settings, Some(startup), now),
+        Some(startup + chrono::Duration::minutes(5))
+    );
+    assert_eq!(
+        Global::startup_holdoff_end(&settings, Some(startup), now + chrono::Duration::minutes(2)),
+        None
+    );
+    assert_eq!(Global::startup_holdoff_end(&settings, None, now), None);
+}
+
+#[test]
+fn test_quiet_hours() {
+    let mut config = config::Backup::test_new_mock();
+    let activity = config::Activity {
+        used: super::USED_THRESHOLD,
+        last_update: chrono::Local::now(),
+    };
+
+    config.schedule.frequency = config::Frequency::Hourly;
+    config.schedule.settings.quiet_hours = Some(config::QuietHours {
+        start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+    });
+
+    let night = Local.ymd(2023, 5, 1).and_hms(23, 0, 0);
+    let morning = Local.ymd(2023, 5, 2).and_hms(7, 0, 0);
+
+    // Never ran before, but postponed to the end of the quiet hours
+    matches::assert_matches!(
+        Due::check_at(&config, None, Some(&activity), night),
+        Err(Due::NotDue { next }) if next == morning
+    );
+    matches::assert_matches!(
+        Due::check_at(&config, None, Some(&activity), morning),
+        Ok(DueCause::Regular)
+    );
+
+    // The next regular backup would be during the quiet hours
+    let mut history = config::history::History::default();
+    let mut run_info = config::history::RunInfo::test_new_mock(chrono::Duration::zero());
+    run_info.end = night - chrono::Duration::minutes(90);
+    history.insert(run_info);
+
+    matches::assert_matches!(
+        Due::check_at(&config, Some(&history), Some(&activity), night - chrono::Duration::hours(1)),
+        Err(Due::NotDue { next }) if next == morning
+    );
+}
+
+#[test]
+fn test_jitter() {
+    let mut config = config::Backup::test_new_mock();
+    let activity = config::Activity {
+        used: super::USED_THRESHOLD,
+        last_update: chrono::Local::now(),
+    };
+
+    config.schedule.frequency = config::Frequency::Daily {
+        preferred_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+    };
+    config.schedule.settings.jitter = 20;
+    let jitter = config.schedule.settings.jitter_offset(&config.id);
+
+    let scheduled = Local.ymd(2023, 5, 2).and_hms(12, 0, 0) + jitter;
+
+    let mut history = config::history::History::default();
+    let mut run_info = config::history::RunInfo::test_new_mock(chrono::Duration::zero());
+    run_info.end = Local.ymd(2023, 5, 1).and_hms(13, 0, 0);
+    history.insert(run_info);
+
+    matches::assert_matches!(
+        Due::check_at(&config, Some(&history), Some(&activity), scheduled - chrono::Duration::seconds(1)),
+        Err(Due::NotDue { next }) if next == scheduled
+    );
+    matches::assert_matches!(
+        Due::check_at(&config, Some(&history), Some(&activity), scheduled),
+        Ok(DueCause::Regular)
+    );
+}
@@ -0,0 +1,167 @@
+/*!
+# Predict scheduled backups
+
+Replays the scheduler decisions of [`Due::check_at`] for a period of time.
+
+The simulation assumes that the computer is in use the whole time and that every
+backup completes instantly. Global requirements like not running on battery and
+hints like a connected backup device are not considered.
+*/
+
+use chrono::prelude::*;
+
+use super::requirements::Due;
+use super::DueCause;
+use crate::borg;
+use crate::config;
+
+/// The daemon checks at most this often whether a backup is due
+const STEP_MINUTES: i64 = 1;
+
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub time: DateTime<Local>,
+    pub cause: DueCause,
+}
+
+/// Backups that would be started between `start` and `start + duration`
+pub fn simulate(
+    config: &config::Backup,
+    history: Option<&config::history::History>,
+    start: DateTime<Local>,
+    duration: chrono::Duration,
+) -> Vec<Run> {
+    let mut history = history.cloned().unwrap_or_default();
+    // A currently running backup is assumed to complete right away
+    history.running = None;
+
+    let activity = config::Activity {
+        used: super::USED_THRESHOLD,
+        last_update: start,
+    };
+
+    let end = start + duration;
+    let mut now = start;
+    let mut runs = Vec::new();
+
+    while now < end {
+        match Due::check_at(config, Some(&history), Some(&activity), now) {
+            Ok(cause) => {
+                runs.push(Run { time: now, cause });
+
+                let mut run_info = config::history::RunInfo::new(
+                    config,
+                    borg::Outcome::Completed {
+                        stats: borg::Stats::new_example(),
+                    },
+                    Default::default(),
+                );
+                run_info.end = now;
+                history.insert(run_info);
+
+                now += chrono::Duration::minutes(STEP_MINUTES);
+            }
+            Err(Due::NotDue { next }) => {
+                now = next.max(now + chrono::Duration::minutes(STEP_MINUTES));
+            }
+            Err(Due::Running) => break,
+        }
+    }
+
+    runs
+}
+
+/// Long enough to contain two backups of every frequency
+const SKIP_HORIZON_DAYS: i64 = 70;
+
+/// Time until which scheduled backups are held back to skip the next one
+///
+/// This is the time the backup after the next one would start.
+pub fn skip_until(
+    config: &config::Backup,
+    history: Option<&config::history::History>,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    simulate(
+        config,
+        history,
+        now,
+        chrono::Duration::days(SKIP_HORIZON_DAYS),
+    )
+    .get(1)
+    .map(|run| run.time)
+}
+
+#[cfg(test)]
+fn test_time(day: u32, hour: u32) -> DateTime<Local> {
+    Local.ymd(2023, 5, day).and_hms(hour, 0, 0)
+}
+
+#[test]
+fn test_simulate_daily() {
+    let mut config = config::Backup::test_new_mock();
+    let start = test_time(1, 8);
+
+    config.schedule.frequency = config::Frequency::Daily {
+        preferred_time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+    };
+
+    let mut history = config::history::History::default();
+    let mut run_info = config::history::RunInfo::test_new_mock(chrono::Duration::zero());
+    run_info.end = start - chrono::Duration::hours(1);
+    history.insert(run_info);
+
+    let runs = simulate(&config, Some(&history), start, chrono::Duration::days(7));
+
+    assert_eq!(runs.len(), 7);
+    assert_eq!(runs[0].time, test_time(1, 10));
+    assert_eq!(runs[6].time, test_time(7, 10));
+}
+
+#[test]
+fn test_skip_until() {
+    let mut config = config::Backup::test_new_mock();
+    let start = test_time(1, 8);
+
+    config.schedule.frequency = config::Frequency::Daily {
+        preferred_time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+    };
+
+    let mut history = config::history::History::default();
+    let mut run_info = config::history::RunInfo::test_new_mock(chrono::Duration::zero());
+    run_info.end = start - chrono::Duration::hours(1);
+    history.insert(run_info);
+
+    assert_eq!(
+        skip_until(&config, Some(&history), start),
+        Some(test_time(2, 10))
+    );
+
+    config.schedule.frequency = config::Frequency::Monthly { preferred_day: 1 };
+    assert_eq!(
+        skip_until(&config, Some(&history), start),
+        Some(Local.ymd(2023, 7, 1).and_hms(0, 0, 0))
+    );
+}
+
+#[test]
+fn test_simulate_hourly_and_weekly() {
+    let mut config = config::Backup::test_new_mock();
+    let start = test_time(1, 8);
+
+    // Never ran before, so the first backup is due immediately
+    config.schedule.frequency = config::Frequency::Hourly;
+    let runs = simulate(&config, None, start, chrono::Duration::days(1));
+    assert_eq!(runs.len(), 24);
+    assert_eq!(runs[0].time, start);
+    assert!(matches!(runs[0].cause, DueCause::Regular));
+
+    config.schedule.frequency = config::Frequency::Weekly {
+        preferred_weekday: Weekday::Wed,
+    };
+    let runs = simulate(&config, None, start, chrono::Duration::days(7));
+    assert_eq!(
+        runs.iter().map(|run| run.time).collect::<Vec<_>>(),
+        vec![start, test_time(3, 0)]
+    );
+}
@@ -0,0 +1,132 @@
+/*!
+# Veto of scheduled backups by a command
+
+Before an otherwise due scheduled backup starts, the [veto_command] is run on
+the host with the same variables as the pre-backup command. It can ask other
+programs via D-Bus, for example with `gdbus call`.
+
+- Exit code 0 lets the backup start.
+- Any other exit code postpones the backup. The output is shown as the reason.
+  A line `retry-after=<minutes>` in the output sets when the backup is tried
+  again, by default after [`DEFAULT_DELAY`].
+
+A command that can't be run or doesn't finish within [`TIMEOUT`] does not
+prevent the backup.
+
+[veto_command]: crate::config::Settings::veto_command
+*/
+
+use std::collections::HashMap;
+
+use crate::config;
+use crate::prelude::*;
+
+/// Maximum time the command may take to decide
+pub const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// Postponement if the command doesn't request a specific one
+pub const DEFAULT_DELAY: u32 = 60;
+/// Longest postponement in minutes a single veto can request
+pub const MAX_DELAY: u32 = 24 * 60;
+
+const RETRY_AFTER: &str = "retry-after=";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Veto {
+    pub reason: String,
+    /// Minutes until the backup is tried again
+    pub delay: u32,
+}
+
+impl Veto {
+    pub fn until(&self, now: chrono::DateTime<chrono::Local>) -> chrono::DateTime<chrono::Local> {
+        now + chrono::Duration::minutes(self.delay.into())
+    }
+}
+
+/// Veto from the output of a command that returned a failure code
+fn parse(output: &str) -> Veto {
+    let mut reason = Vec::new();
+    let mut delay = DEFAULT_DELAY;
+
+    for line in output.lines().map(str::trim).filter(|x| !x.is_empty()) {
+        match line
+            .strip_prefix(RETRY_AFTER)
+            .map(|x| x.trim().parse::<u32>())
+        {
+            Some(Ok(minutes)) => delay = minutes.clamp(1, MAX_DELAY),
+            Some(Err(_)) => warn!("Invalid delay requested by veto command: {:?}", line),
+            None => reason.push(line),
+        }
+    }
+
+    Veto {
+        reason: if reason.is_empty() {
+            gettext("No reason given")
+        } else {
+            reason.join(" ")
+        },
+        delay,
+    }
+}
+
+/// Ask the veto command whether the scheduled backup may start now
+///
+/// Returns `None` if no command is configured or the command allows the backup.
+pub async fn ask(config: &config::Backup) -> std::io::Result<Option<Veto>> {
+    let Some(command) = config
+        .schedule
+        .settings
+        .veto_command
+        .as_deref()
+        .filter(|x| !x.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let env = crate::borg::scripts::script_env_pre(config, true);
+    let envs: HashMap<&str, &str> = env.iter().map(|(k, v)| (k.name(), v.as_str())).collect();
+
+    debug!("Running veto command:\nbash -c \"{}\"", command);
+
+    let mut cmd = crate::borg::scripts::host_command_kill_on_drop(command, &envs);
+    cmd.stdin(async_std::process::Stdio::null())
+        .stderr(async_std::process::Stdio::null());
+
+    let output = async_std::future::timeout(TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                gettext("The veto command did not finish in time."),
+            )
+        })??;
+
+    debug!("Veto command finished with: {}", output.status);
+
+    if output.status.success() {
+        Ok(None)
+    } else {
+        Ok(Some(parse(&String::from_utf8_lossy(&output.stdout))))
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        parse("Rendering video\nretry-after=120\n"),
+        Veto {
+            reason: "Rendering video".to_string(),
+            delay: 120,
+        }
+    );
+    assert_eq!(
+        parse("  Game running  \n\n"),
+        Veto {
+            reason: "Game running".to_string(),
+            delay: DEFAULT_DELAY,
+        }
+    );
+    assert_eq!(parse("retry-after=100000").delay, MAX_DELAY);
+    assert_eq!(parse("retry-after=soon").delay, DEFAULT_DELAY);
+    assert_eq!(parse("").reason, "No reason given");
+}
@@ -5,9 +5,13 @@ mod app_window;
 mod backup_status;
 #[allow(dead_code)]
 mod builder;
+mod confirm;
 mod dbus;
 mod dialog_about;
 mod dialog_archive_prefix;
+mod dialog_borg_command;
+mod dialog_browse_repository;
+mod dialog_cache_recovery;
 mod dialog_check;
 mod dialog_check_result;
 mod dialog_delete_archive;
@@ -15,28 +19,39 @@ mod dialog_device_missing;
 mod dialog_encryption_password;
 mod dialog_exclude;
 mod dialog_exclude_pattern;
+mod dialog_exclude_text;
+mod dialog_file_history;
+mod dialog_flatpak_apps;
 mod dialog_info;
 mod dialog_preferences;
 mod dialog_prune;
 mod dialog_prune_review;
+mod dialog_restore_archive;
 mod dialog_setup;
+mod dialog_shared_status;
 mod dialog_storage;
 mod error;
 mod export;
 mod globals;
 mod headerbar;
+mod maintenance;
 mod operation;
+mod operation_status;
 mod page_archives;
 mod page_backup;
 mod page_detail;
 mod page_overview;
 mod page_schedule;
 mod prelude;
+mod problems;
+mod remote_operation;
 mod shell;
 mod status;
 mod toast_size_estimate;
 mod utils;
 mod widget;
+mod window_backup;
+mod window_state;
 
 pub(crate) use globals::{BACKUP_CONFIG, BACKUP_HISTORY, SCHEDULE_STATUS};
 
@@ -71,6 +86,7 @@ pub fn main() {
     adw_app().connect_startup(on_startup);
     adw_app().connect_activate(on_activate);
     adw_app().connect_shutdown(on_shutdown);
+    adw_app().connect_query_end(on_query_end);
 
     // Ctrl-C handling
     glib::unix_signal_add(nix::sys::signal::Signal::SIGINT as i32, on_ctrlc);
@@ -95,6 +111,27 @@ fn on_ctrlc() -> glib::ControlFlow {
     glib::ControlFlow::Continue
 }
 
+/// The session is about to end because of logout or shutdown
+///
+/// Aborting lets borg write a checkpoint such that the backup can be resumed at
+/// the next login.
+fn on_query_end(_app: &adw::Application) {
+    debug!("Signal 'query-end'");
+
+    BORG_OPERATION.with(|operations| {
+        for (config_id, op) in operations.load().iter() {
+            if op.task_kind() == borg::task::Kind::Create {
+                Handler::handle(BACKUP_HISTORY.try_update(|histories| {
+                    histories.set_session_end(config_id.clone());
+                    Ok(())
+                }));
+            }
+
+            op.set_instruction(borg::Instruction::Abort(borg::Abort::SessionEnd));
+        }
+    });
+}
+
 fn on_shutdown(_app: &adw::Application) {
     IS_SHUTDOWN.swap(std::sync::Arc::new(true));
 
@@ -119,6 +156,8 @@ fn on_shutdown(_app: &adw::Application) {
         })
     }
 
+    async_std::task::block_on(borg::functions::close_ssh_connections());
+
     debug!("Good bye!");
 }
 
@@ -129,6 +168,7 @@ fn on_startup(_app: &adw::Application) {
         Err::<(), std::io::Error>(err).handle("Failed to load Schedule Status")
     })
     .handle("Failed to Load Schedule Status");
+    config::connect_reloaded(ui::page_schedule::refresh_status);
 
     // Force adwaita icon theme
     if let Some(settings) = gtk::Settings::default() {
@@ -152,14 +192,28 @@ fn on_startup(_app: &adw::Application) {
 
     // init status tracking
     status_tracking();
+    ui::remote_operation::attach_running();
+    glib::MainContext::default().spawn_local(ui::utils::append_only::restore_after_termination());
 
     adw_app().set_accels_for_action("app.help", &["F1"]);
     adw_app().set_accels_for_action("app.quit", &["<Ctrl>Q"]);
     adw_app().set_accels_for_action("app.setup", &["<Ctrl>N"]);
+    adw_app().set_accels_for_action("app.search", &["<Ctrl>F"]);
     adw_app().set_accels_for_action("app.backup-preferences", &["<Ctrl>comma"]);
     adw_app().set_accels_for_action("win.show-help-overlay", &["<Ctrl>question"]);
+    adw_app().set_accels_for_action("app.backup-run", &["<Ctrl>Return"]);
+    adw_app().set_accels_for_action("app.backup-abort", &["<Ctrl>period"]);
+    adw_app().set_accels_for_action("app.view-archives", &["<Ctrl>L"]);
+    for position in 1..=9 {
+        adw_app().set_accels_for_action(
+            &format!("app.view-backup({position})"),
+            &[&format!("<Ctrl>{position}")],
+        );
+    }
+
+    ui::window_state::init();
 
-    if BACKUP_CONFIG.load().iter().count() == 1 {
+    if !ui::window_state::restore() && BACKUP_CONFIG.load().iter().count() == 1 {
         if let Some(config) = BACKUP_CONFIG.load().iter().next() {
             ui::page_backup::view_backup_conf(&config.id);
         }
@@ -185,7 +239,8 @@ async fn quit() -> Result<()> {
                 Err(err) => {
                     err.show().await;
 
-                    ui::utils::confirmation_dialog(
+                    ui::confirm::confirm(
+                        ui::confirm::Category::Stop,
                         &gettext("Abort running backup creation?"),
                         &gettext("The backup will remain incomplete if aborted now."),
                         &gettext("Continue"),
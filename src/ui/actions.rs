@@ -5,25 +5,61 @@ use adw::prelude::*;
 pub fn init() {
     let action = crate::action::backup_show();
     action.connect_activate(|_, config_id| {
-        if let Some(config_id) = config_id.and_then(|v| v.str()) {
-            ui::page_backup::view_backup_conf(&ConfigId::new(config_id.to_string()));
+        if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
+            ui::page_backup::view_backup_conf(&config_id);
             adw_app().activate();
         }
     });
     adw_app().add_action(&action);
 
+    let action = crate::action::backup_window();
+    action.connect_activate(|_, config_id| {
+        if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
+            Handler::handle(ui::window_backup::show(&config_id));
+        }
+    });
+    adw_app().add_action(&action);
+
     let action = crate::action::backup_start();
     action.connect_activate(|_, config_id| {
         info!("action backup.start: called");
-        if let Some(config_id) = config_id.and_then(|v| v.str()).map(ToString::to_string) {
+        if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
             let guard = QuitGuard::default();
-            ui::page_backup::start_backup(ConfigId::new(config_id), None, guard);
+            ui::page_backup::start_backup(config_id, None, guard);
         } else {
             error!("action backup.start: Did not receive valid config id");
         }
     });
     adw_app().add_action(&action);
 
+    let action = crate::action::backup_abort();
+    action.connect_activate(|_, config_id| {
+        info!("action backup.abort: called");
+        if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
+            ui::utils::borg::dequeue(&config_id);
+            BORG_OPERATION.with(|operations| {
+                if let Some(operation) = operations.load().get(&config_id) {
+                    operation
+                        .set_instruction(crate::borg::Instruction::Abort(crate::borg::Abort::User));
+                }
+            });
+        } else {
+            error!("action backup.abort: Did not receive valid config id");
+        }
+    });
+    adw_app().add_action(&action);
+
+    let action = crate::action::backup_stop();
+    action.connect_activate(|_, config_id| {
+        info!("action backup.stop: called");
+        if let Some(config_id) = config_id.and_then(|v| v.get::<ConfigId>()) {
+            Handler::run(async move { ui::page_backup::stop_operation(&config_id).await });
+        } else {
+            error!("action backup.stop: Did not receive valid config id");
+        }
+    });
+    adw_app().add_action(&action);
+
     let action = gio::SimpleAction::new("about", None);
     action.connect_activate(|_, _| ui::dialog_about::show());
     adw_app().add_action(&action);
@@ -32,6 +68,30 @@ pub fn init() {
     action.connect_activate(|_, _| ui::dialog_setup::show());
     adw_app().add_action(&action);
 
+    let action = gio::SimpleAction::new("browse-repository", None);
+    action.connect_activate(|_, _| ui::dialog_browse_repository::show());
+    adw_app().add_action(&action);
+
+    let action = gio::SimpleAction::new("search", None);
+    action.connect_activate(|_, _| ui::page_overview::show_search());
+    adw_app().add_action(&action);
+
+    let action = gio::SimpleAction::new("shared-status", None);
+    action.connect_activate(|_, _| ui::dialog_shared_status::show());
+    action.set_enabled(false);
+    if let Some(settings) = crate::utils::app_settings() {
+        settings
+            .bind("admin-mode", &action, "enabled")
+            .get()
+            .build();
+    }
+    adw_app().add_action(&action);
+
+    // Debugging aid to not wait for the next regular check of the schedules
+    let action = gio::SimpleAction::new("probe-schedule", None);
+    action.connect_activate(|_, _| Handler::handle(ui::dbus::probe_schedule()));
+    adw_app().add_action(&action);
+
     let action = gio::SimpleAction::new("help", None);
     let context = adw_app()
         .active_window()
@@ -63,6 +123,86 @@ pub fn init() {
     });
     adw_app().add_action(&action);
 
+    let action = gio::SimpleAction::new("borg-command", None);
+    action.connect_activate(|_, _| {
+        if let Some(id) = &**ui::ACTIVE_BACKUP_ID.load() {
+            let id = id.clone();
+            Handler::run(async move { ui::dialog_borg_command::show(&id).await });
+        }
+    });
+    action.set_enabled(false);
+    if let Some(settings) = crate::utils::app_settings() {
+        settings
+            .bind("show-borg-command", &action, "enabled")
+            .get()
+            .build();
+    }
+    adw_app().add_action(&action);
+
+    let action = gio::SimpleAction::new("backup-run", None);
+    action.connect_activate(|_, _| {
+        // Same conditions as for the button on the backup page
+        if ui::page_detail::is_navigation_page_visible() && main_ui().backup_run().is_sensitive() {
+            let guard = QuitGuard::default();
+            Handler::run(async move { ui::page_backup::on_backup_run(&guard).await });
+        }
+    });
+    adw_app().add_action(&action);
+
+    let action = gio::SimpleAction::new("backup-abort", None);
+    action.connect_activate(|_, _| {
+        if ui::page_detail::is_navigation_page_visible()
+            && main_ui().stop_backup_create().is_visible()
+        {
+            Handler::run(ui::page_backup::on_stop_backup_create());
+        }
+    });
+    adw_app().add_action(&action);
+
+    let action = gio::SimpleAction::new("view-archives", None);
+    action.connect_activate(|_, _| {
+        if let Some(id) = &**ui::ACTIVE_BACKUP_ID.load() {
+            if ui::page_detail::is_navigation_page_visible() {
+                main_ui()
+                    .detail_stack()
+                    .set_visible_child(&main_ui().page_archives());
+            } else {
+                ui::page_archives::view(id);
+            }
+        }
+    });
+    adw_app().add_action(&action);
+
+    // Position of the backup in the overview, starting at 1
+    let action = gio::SimpleAction::new("view-backup", Some(glib::VariantTy::INT32));
+    action.connect_activate(|_, position| {
+        let Some(index) = position
+            .and_then(|v| v.get::<i32>())
+            .and_then(|x| usize::try_from(x - 1).ok())
+        else {
+            error!("action app.view-backup: Did not receive valid position");
+            return;
+        };
+
+        let Some(id) = BACKUP_CONFIG.load().iter().nth(index).map(|x| x.id.clone()) else {
+            return;
+        };
+
+        main_ui()
+            .navigation_view()
+            .pop_to_page(&main_ui().navigation_page_overview());
+        ui::page_backup::view_backup_conf(&id);
+    });
+    adw_app().add_action(&action);
+
+    let action = gio::SimpleAction::new("import-settings", None);
+    action.connect_activate(|_, _| {
+        if let Some(id) = &**ui::ACTIVE_BACKUP_ID.load() {
+            Handler::handle(ui::dialog_setup::show_import_settings(id));
+        }
+    });
+    adw_app().add_action(&action);
+
     let action = gio::SimpleAction::new("remove", None);
     action.connect_activate(|_, _| ui::page_overview::remove_backup());
     adw_app().add_action(&action);
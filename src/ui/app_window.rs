@@ -71,6 +71,7 @@ pub fn show() {
 
 fn on_delete() -> glib::Propagation {
     debug!("Potential quit: ApplicationWindow delete event");
+    ui::window_state::save_window_size();
 
     Handler::run(super::quit());
     glib::Propagation::Stop
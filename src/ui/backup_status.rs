@@ -1,3 +1,8 @@
+//! Status of backups and integrity checks
+//!
+//! [`Status`] describes the state without any presentation. It is rendered
+//! into the translated strings and icons of a [`Display`].
+
 use chrono::prelude::*;
 
 use crate::borg;
@@ -10,7 +15,10 @@ use crate::ui::prelude::*;
 use crate::ui::utils;
 use std::fmt::Write;
 
-#[derive(Debug)]
+/// Last integrity checks older than this are considered out of date
+const CHECK_OUTDATED_DAYS: i64 = 120;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Display {
     pub title: String,
     pub subtitle: Option<String>,
@@ -19,13 +27,13 @@ pub struct Display {
     pub stats: Option<Stats>,
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum Stats {
     Progress(log_json::ProgressArchive),
     Final(history::RunInfo),
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum Graphic {
     OkIcon(String),
     WarningIcon(String),
@@ -33,11 +41,179 @@ pub enum Graphic {
     Spinner,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    NeverRan,
+    NoCheck,
+    LastRun {
+        result: RunResult,
+        age: chrono::Duration,
+        run_info: history::RunInfo,
+    },
+    LastCheck {
+        result: CheckResult,
+        age: chrono::Duration,
+    },
+    /// Backup that is currently running
+    Backup(BackupProgress),
+    /// Any other operation that is currently running
+    Operation {
+        name: String,
+        message: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunResult {
+    Completed,
+    CompletedWithWarnings,
+    Aborted,
+    /// Aborted by logging out or shutting down
+    Interrupted,
+    Failed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckResult {
+    Success,
+    SuccessOutdated,
+    Aborted,
+    Repaired,
+    Errors,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupProgress {
+    pub run: Run,
+    pub archive: Option<log_json::ProgressArchive>,
+    /// Processed fraction of the estimated size
+    pub fraction: Option<f64>,
+    /// Only available while the backup is progressing normally
+    pub time_remaining: Option<chrono::Duration>,
+    /// Last log message if it is not an archive progress
+    pub message: Option<String>,
+    /// Files found so far while the size estimate is still running
+    pub estimating_files: Option<u64>,
+}
+
+impl Status {
+    pub fn from_run_info(run_info: &history::RunInfo, now: DateTime<Local>) -> Self {
+        let result = match run_info.outcome {
+            borg::Outcome::Completed { .. }
+                if run_info.messages.clone().filter_handled().max_log_level()
+                    > Some(log_json::LogLevel::Info) =>
+            {
+                RunResult::CompletedWithWarnings
+            }
+            borg::Outcome::Completed { .. } => RunResult::Completed,
+            borg::Outcome::Aborted(borg::error::Abort::User) => RunResult::Aborted,
+            borg::Outcome::Aborted(borg::error::Abort::SessionEnd) => RunResult::Interrupted,
+            _ => RunResult::Failed,
+        };
+
+        Self::LastRun {
+            result,
+            age: now - run_info.end,
+            run_info: run_info.clone(),
+        }
+    }
+
+    pub fn from_check_run_info(run_info: &history::CheckRunInfo, now: DateTime<Local>) -> Self {
+        let age = now - run_info.end;
+
+        let result = match run_info.outcome {
+            history::CheckOutcome::Success if age > chrono::Duration::days(CHECK_OUTDATED_DAYS) => {
+                CheckResult::SuccessOutdated
+            }
+            history::CheckOutcome::Success => CheckResult::Success,
+            history::CheckOutcome::Aborted => CheckResult::Aborted,
+            history::CheckOutcome::Repair(_) => CheckResult::Repaired,
+            history::CheckOutcome::Error(_) => CheckResult::Errors,
+        };
+
+        Self::LastCheck { result, age }
+    }
+
+    pub fn from_backup(
+        run: Run,
+        last_log: Option<&log_json::Output>,
+        estimated_total: Option<u64>,
+        estimating_files: Option<u64>,
+        time_remaining: Option<chrono::Duration>,
+    ) -> Self {
+        let mut progress = BackupProgress {
+            run,
+            archive: None,
+            fraction: None,
+            time_remaining: None,
+            message: None,
+            // The estimate replaces this stage once it is complete
+            estimating_files: estimating_files.filter(|_| estimated_total.is_none()),
+        };
+
+        match last_log {
+            Some(log_json::Output::Progress(log_json::Progress::Archive(archive))) => {
+                if let Some(total) = estimated_total {
+                    progress.fraction = Some(if archive.finished {
+                        1.
+                    } else {
+                        archive.original_size as f64 / total as f64
+                    });
+
+                    // Do not show estimate when stalled for example
+                    if run == Run::Running && !archive.finished {
+                        progress.time_remaining = time_remaining;
+                    }
+                }
+                progress.archive = Some(archive.clone());
+            }
+            Some(other_message) => progress.message = Some(other_message.to_string()),
+            None => {}
+        }
+
+        Self::Backup(progress)
+    }
+
+    pub fn from_operation(op: &dyn ui::operation::OperationExt) -> Self {
+        if let Some(op_create) = op.try_as_create() {
+            let info = op_create.communication().specific_info.get();
+
+            Self::from_backup(
+                op_create.communication().status(),
+                op_create.last_log().as_deref(),
+                info.estimated_size.as_ref().map(|x| x.total),
+                info.estimating_files,
+                info.time_remaining(),
+            )
+        } else {
+            Self::Operation {
+                name: op.name(),
+                message: op.last_log().map(|x| x.to_string()),
+            }
+        }
+    }
+}
+
 impl Display {
     pub fn new_from_id(config_id: &ConfigId) -> Self {
         BORG_OPERATION.with(|operations| {
             if let Some(op) = operations.load().get(config_id) {
-                Self::from(op.as_ref())
+                let mut display = Self::from(op.as_ref());
+                if let Some((step, steps)) = MAINTENANCE_STEP.load().get(config_id) {
+                    display.title = gettextf(
+                        "Maintenance {} of {}: {}",
+                        &[&step.to_string(), &steps.to_string(), &display.title],
+                    );
+                }
+                display
+            } else if let Some(name) = REPOSITORY_QUEUE.load().get(config_id) {
+                Self::simple(
+                    gettextf("Waiting: {}", &[name]),
+                    gettext("Starts once the operation of another backup on this repository is completed"),
+                    Graphic::Spinner,
+                )
+            } else if let Some(remote) = ui::remote_operation::status(config_id) {
+                Self::clone(&remote)
             } else if let Some(last_run) = BACKUP_HISTORY
                 .load()
                 .try_get(config_id)
@@ -46,7 +222,7 @@ impl Display {
             {
                 Self::from(last_run)
             } else {
-                Self::never_ran()
+                Self::from(&Status::NeverRan)
             }
         })
     }
@@ -67,116 +243,108 @@ impl Display {
             {
                 Self::from(&check_run_info)
             } else {
-                Self::no_check()
+                Self::from(&Status::NoCheck)
             }
         })
     }
 
-    fn never_ran() -> Self {
+    fn simple(title: String, subtitle: String, graphic: Graphic) -> Self {
         Self {
-            title: gettext("Backup Never Ran"),
-            subtitle: Some(gettext("Begin by carrying out your first backup")),
-            graphic: Graphic::WarningIcon("dialog-information-symbolic".to_string()),
-            progress: None,
-            stats: None,
-        }
-    }
-
-    fn no_check() -> Self {
-        Self {
-            title: gettext("No Integrity Check"),
-            subtitle: Some(gettext("Archives integrity check not yet performed")),
-            graphic: Graphic::WarningIcon("diagnostics-symbolic".to_string()),
+            title,
+            subtitle: Some(subtitle),
+            graphic,
             progress: None,
             stats: None,
         }
     }
 }
 
-impl From<&history::RunInfo> for Display {
-    fn from(run_info: &history::RunInfo) -> Self {
-        match run_info.outcome {
-            borg::Outcome::Completed { .. }
-                if run_info.messages.clone().filter_handled().max_log_level()
-                    > Some(log_json::LogLevel::Info) =>
-            {
+impl From<&Status> for Display {
+    fn from(status: &Status) -> Self {
+        match status {
+            Status::NeverRan => Self::simple(
+                gettext("Backup Never Ran"),
+                gettext("Begin by carrying out your first backup"),
+                Graphic::WarningIcon("dialog-information-symbolic".to_string()),
+            ),
+            Status::NoCheck => Self::simple(
+                gettext("No Integrity Check"),
+                gettext("Archives integrity check not yet performed"),
+                Graphic::WarningIcon("diagnostics-symbolic".to_string()),
+            ),
+            Status::LastRun {
+                result,
+                age,
+                run_info,
+            } => {
+                let (title, graphic) = match result {
+                    RunResult::CompletedWithWarnings => (
+                        gettext("Last backup completed with warnings"),
+                        Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
+                    ),
+                    RunResult::Completed => (
+                        gettext("Last Backup Successful"),
+                        Graphic::OkIcon("emblem-default-symbolic".to_string()),
+                    ),
+                    RunResult::Aborted => (
+                        gettext("Last Backup Aborted"),
+                        Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
+                    ),
+                    RunResult::Interrupted => (
+                        gettext("Last Backup Interrupted"),
+                        Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
+                    ),
+                    RunResult::Failed => (
+                        gettext("Last Backup Failed"),
+                        Graphic::ErrorIcon("dialog-error-symbolic".to_string()),
+                    ),
+                };
+
                 Self {
-                    title: gettext("Last backup completed with warnings"),
-                    subtitle: Some(utils::duration::ago(&(Local::now() - run_info.end))),
-                    graphic: Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
+                    title,
+                    subtitle: Some(utils::duration::ago(age)),
+                    graphic,
                     progress: None,
                     stats: Some(Stats::Final(run_info.clone())),
                 }
             }
-            borg::Outcome::Completed { .. } => Self {
-                title: gettext("Last Backup Successful"),
-                subtitle: Some(utils::duration::ago(&(Local::now() - run_info.end))),
-                graphic: Graphic::OkIcon("emblem-default-symbolic".to_string()),
-                progress: None,
-                stats: Some(Stats::Final(run_info.clone())),
-            },
-            borg::Outcome::Aborted(borg::error::Abort::User) => Self {
-                title: gettext("Last Backup Aborted"),
-                subtitle: Some(utils::duration::ago(&(Local::now() - run_info.end))),
-                graphic: Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
-                progress: None,
-                stats: Some(Stats::Final(run_info.clone())),
-            },
-            _ => Self {
-                title: gettext("Last Backup Failed"),
-                subtitle: Some(utils::duration::ago(&(Local::now() - run_info.end))),
-                graphic: Graphic::ErrorIcon("dialog-error-symbolic".to_string()),
-                progress: None,
-                stats: Some(Stats::Final(run_info.clone())),
-            },
-        }
-    }
-}
+            Status::LastCheck { result, age } => {
+                let when = utils::duration::ago(age);
 
-impl From<&history::CheckRunInfo> for Display {
-    fn from(run_info: &history::CheckRunInfo) -> Self {
-        let when = utils::duration::ago(&(Local::now() - run_info.end));
-
-        match run_info.outcome {
-            history::CheckOutcome::Success => {
-                // Last check is 120 days ago or older
-                if Local::now() - run_info.end > chrono::Duration::days(120) {
-                    Self {
+                match result {
+                    CheckResult::SuccessOutdated => Self::simple(
                         // Translators: Argument is 'x months ago'
-                        title: gettextf("Successful Integrity Check {}", &[&when]),
-                        subtitle: Some(gettext("Result might be out of date")),
-                        graphic: Graphic::WarningIcon("emblem-default-symbolic".to_string()),
-                        progress: None,
-                        stats: None,
-                    }
-                } else {
-                    Self {
-                        title: gettext("Last Integrity Check Successful"),
-                        subtitle: Some(when),
-                        graphic: Graphic::OkIcon("emblem-default-symbolic".to_string()),
-                        progress: None,
-                        stats: None,
-                    }
+                        gettextf("Successful Integrity Check {}", &[&when]),
+                        gettext("Result might be out of date"),
+                        Graphic::WarningIcon("emblem-default-symbolic".to_string()),
+                    ),
+                    CheckResult::Success => Self::simple(
+                        gettext("Last Integrity Check Successful"),
+                        when,
+                        Graphic::OkIcon("emblem-default-symbolic".to_string()),
+                    ),
+                    CheckResult::Aborted => Self::simple(
+                        gettext("Integrity Check Aborted"),
+                        when,
+                        Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
+                    ),
+                    CheckResult::Repaired => Self::simple(
+                        gettext("Errors Found and Repaired"),
+                        when,
+                        Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
+                    ),
+                    CheckResult::Errors => Self::simple(
+                        gettext("Integrity Errors Found"),
+                        when,
+                        Graphic::ErrorIcon("dialog-error-symbolic".to_string()),
+                    ),
                 }
             }
-            history::CheckOutcome::Aborted => Self {
-                title: gettext("Integrity Check Aborted"),
-                subtitle: Some(when),
-                graphic: Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
-                progress: None,
-                stats: None,
-            },
-            history::CheckOutcome::Repair(_) => Self {
-                title: gettext("Errors Found and Repaired"),
-                subtitle: Some(when),
-                graphic: Graphic::WarningIcon("dialog-warning-symbolic".to_string()),
-                progress: None,
-                stats: None,
-            },
-            history::CheckOutcome::Error(_) => Self {
-                title: gettext("Integrity Errors Found"),
-                subtitle: Some(when),
-                graphic: Graphic::ErrorIcon("dialog-error-symbolic".to_string()),
+            Status::Backup(progress) => Self::from(progress),
+            Status::Operation { name, message } => Self {
+                title: name.clone(),
+                subtitle: message.clone(),
+                graphic: Graphic::Spinner,
                 progress: None,
                 stats: None,
             },
@@ -184,67 +352,34 @@ impl From<&history::CheckRunInfo> for Display {
     }
 }
 
-impl From<&dyn ui::operation::OperationExt> for Display {
-    fn from(op: &dyn ui::operation::OperationExt) -> Self {
-        if let Some(op_create) = op.try_as_create() {
-            Self::from(op_create)
-        } else {
-            Self {
-                title: op.name(),
-                subtitle: op.last_log().map(|x| x.to_string()),
-                graphic: Graphic::Spinner,
-                progress: None,
-                stats: None,
-            }
-        }
-    }
-}
+impl From<&BackupProgress> for Display {
+    fn from(progress: &BackupProgress) -> Self {
+        let mut subtitle = if let Some(fraction) = progress.fraction {
+            let mut sub = gettextf(
+                // xgettext:no-c-format
+                "{} % finished",
+                &[&format!("{:.1}", fraction * 100.0)],
+            );
 
-impl From<&ui::operation::Operation<borg::task::Create>> for Display {
-    fn from(op: &ui::operation::Operation<borg::task::Create>) -> Self {
-        let status = op.communication().specific_info.get();
-
-        let mut progress = None;
-        let mut stats = None;
-        let mut subtitle = None;
-
-        if let Some(ref last_message) = op.last_log() {
-            match last_message.as_ref() {
-                log_json::Output::Progress(log_json::Progress::Archive(progress_archive)) => {
-                    stats = Some(Stats::Progress(progress_archive.clone()));
-                    if let Some(size) = &status.estimated_size {
-                        let fraction = if progress_archive.finished {
-                            1.
-                        } else {
-                            progress_archive.original_size as f64 / size.total as f64
-                        };
-                        progress = Some(fraction);
-
-                        let mut sub = gettextf(
-                            // xgettext:no-c-format
-                            "{} % finished",
-                            &[&format!("{:.1}", fraction * 100.0)],
-                        );
-
-                        // Do not show estimate when stalled for example
-                        if matches!(op.communication().status(), borg::status::Run::Running)
-                            && !progress_archive.finished
-                        {
-                            if let Some(remaining) = status.time_remaining() {
-                                let _ = write!(sub, " – {}", utils::duration::left(&remaining));
-                            }
-                        }
-
-                        subtitle = Some(sub);
-                    }
-                }
-                ref other_message => {
-                    subtitle = Some(other_message.to_string());
-                }
+            if let Some(remaining) = &progress.time_remaining {
+                let _ = write!(sub, " – {}", utils::duration::left(remaining));
             }
-        }
 
-        let title = match op.communication().status() {
+            Some(sub)
+        } else if let Some(files) = progress.estimating_files {
+            Some(ngettextf(
+                "Estimating backup size, one file found",
+                "Estimating backup size, {} files found",
+                files as u32,
+                &[&files.to_string()],
+            ))
+        } else if let Some(archive) = &progress.archive {
+            Some(archive.to_string())
+        } else {
+            progress.message.clone()
+        };
+
+        let title = match progress.run {
             Run::Init => gettext("Preparing backup"),
             Run::Running => gettext("Backup Running"),
             Run::Stalled => gettext("Backup Destination Unresponsive"),
@@ -257,21 +392,262 @@ impl From<&ui::operation::Operation<borg::task::Create>> for Display {
                 ));
                 gettext("Reconnecting")
             }
+            Run::Paused => gettext("Backup Paused"),
             Run::Stopping => gettext("Stopping Backup"),
         };
 
-        if subtitle.is_none() {
-            if let Some(log) = op.last_log() {
-                subtitle = Some(log.to_string());
-            }
-        }
-
         Self {
             title,
             subtitle,
             graphic: Graphic::Spinner,
-            progress,
-            stats,
+            progress: progress.fraction,
+            stats: progress.archive.clone().map(Stats::Progress),
         }
     }
 }
+
+impl From<&history::RunInfo> for Display {
+    fn from(run_info: &history::RunInfo) -> Self {
+        Self::from(&Status::from_run_info(run_info, Local::now()))
+    }
+}
+
+impl From<&history::CheckRunInfo> for Display {
+    fn from(run_info: &history::CheckRunInfo) -> Self {
+        Self::from(&Status::from_check_run_info(run_info, Local::now()))
+    }
+}
+
+impl From<&dyn ui::operation::OperationExt> for Display {
+    fn from(op: &dyn ui::operation::OperationExt) -> Self {
+        Self::from(&Status::from_operation(op))
+    }
+}
+
+#[cfg(test)]
+fn test_progress_log(original_size: u64, finished: bool) -> log_json::Output {
+    log_json::Output::Progress(log_json::Progress::Archive(log_json::ProgressArchive {
+        original_size,
+        compressed_size: 0,
+        deduplicated_size: 0,
+        nfiles: 0,
+        path: String::new(),
+        finished,
+    }))
+}
+
+#[test]
+fn test_status_last_run() {
+    let now = Local::now();
+    let config = Backup::test_new_mock();
+    let completed = || borg::Outcome::Completed {
+        stats: borg::json::Stats::test_new_mock(),
+    };
+    let warning = log_json::LogEntry::ParsedErr(log_json::LogMessage {
+        levelname: log_json::LogLevel::Warning,
+        name: String::from("borg.archiver"),
+        message: String::from("/home/user/secret: [Errno 13] Permission denied"),
+        msgid: log_json::MsgId::Undefined,
+    });
+
+    let cases = [
+        (completed(), vec![], RunResult::Completed),
+        (completed(), vec![warning], RunResult::CompletedWithWarnings),
+        (
+            borg::Outcome::Aborted(borg::error::Abort::User),
+            vec![],
+            RunResult::Aborted,
+        ),
+        (
+            borg::Outcome::Aborted(borg::error::Abort::SessionEnd),
+            vec![],
+            RunResult::Interrupted,
+        ),
+        (
+            borg::Outcome::Aborted(borg::error::Abort::OnBattery),
+            vec![],
+            RunResult::Failed,
+        ),
+        (
+            borg::Outcome::Aborted(borg::error::Abort::Shutdown),
+            vec![],
+            RunResult::Failed,
+        ),
+        (
+            borg::Outcome::Failed(borg::error::Failure::Undefined),
+            vec![],
+            RunResult::Failed,
+        ),
+    ];
+
+    for (outcome, messages, expected) in cases {
+        let mut run_info = history::RunInfo::new(&config, outcome, messages);
+        run_info.end = now - chrono::Duration::hours(2);
+
+        let status = Status::from_run_info(&run_info, now);
+        assert_eq!(
+            status,
+            Status::LastRun {
+                result: expected,
+                age: chrono::Duration::hours(2),
+                run_info: run_info.clone(),
+            }
+        );
+
+        let display = Display::from(&status);
+        assert!(matches!(display.stats, Some(Stats::Final(_))));
+        assert!(display.progress.is_none());
+        match expected {
+            RunResult::Completed => assert!(matches!(display.graphic, Graphic::OkIcon(_))),
+            RunResult::CompletedWithWarnings | RunResult::Aborted | RunResult::Interrupted => {
+                assert!(matches!(display.graphic, Graphic::WarningIcon(_)))
+            }
+            RunResult::Failed => assert!(matches!(display.graphic, Graphic::ErrorIcon(_))),
+        }
+    }
+}
+
+#[test]
+fn test_status_last_check() {
+    let now = Local::now();
+    let check = |outcome, days| history::CheckRunInfo {
+        end: now - chrono::Duration::days(days),
+        outcome,
+    };
+
+    let cases = [
+        (history::CheckOutcome::Success, 1, CheckResult::Success),
+        (
+            history::CheckOutcome::Success,
+            CHECK_OUTDATED_DAYS + 1,
+            CheckResult::SuccessOutdated,
+        ),
+        (history::CheckOutcome::Aborted, 1, CheckResult::Aborted),
+        (
+            history::CheckOutcome::Repair(vec![]),
+            1,
+            CheckResult::Repaired,
+        ),
+        (history::CheckOutcome::Error(vec![]), 1, CheckResult::Errors),
+    ];
+
+    for (outcome, days, expected) in cases {
+        assert_eq!(
+            Status::from_check_run_info(&check(outcome, days), now),
+            Status::LastCheck {
+                result: expected,
+                age: chrono::Duration::days(days),
+            }
+        );
+    }
+}
+
+#[test]
+fn test_status_backup() {
+    let remaining = Some(chrono::Duration::minutes(5));
+    let runs = [
+        Run::Init,
+        Run::Running,
+        Run::Stalled,
+        Run::Reconnecting(std::time::Duration::from_secs(10)),
+        Run::Paused,
+        Run::Stopping,
+    ];
+
+    for run in runs {
+        // No output from borg yet
+        let status = Status::from_backup(run, None, Some(100), None, remaining);
+        let Status::Backup(progress) = &status else {
+            panic!("Unexpected status {status:?}");
+        };
+        assert_eq!(progress.run, run);
+        assert_eq!(progress.fraction, None);
+        assert_eq!(progress.time_remaining, None);
+        assert_eq!(progress.message, None);
+
+        let display = Display::from(&status);
+        assert!(matches!(display.graphic, Graphic::Spinner));
+        assert_eq!(
+            display.subtitle.is_some(),
+            matches!(run, Run::Reconnecting(_))
+        );
+
+        // Archive progress with size estimate
+        let log = test_progress_log(25, false);
+        let Status::Backup(progress) =
+            Status::from_backup(run, Some(&log), Some(100), None, remaining)
+        else {
+            unreachable!()
+        };
+        assert_eq!(progress.fraction, Some(0.25));
+        // Only show estimate while the backup is progressing normally
+        assert_eq!(
+            progress.time_remaining,
+            if run == Run::Running { remaining } else { None }
+        );
+        assert!(progress.archive.is_some());
+        let display = Display::from(&progress);
+        assert_eq!(display.progress, Some(0.25));
+        assert!(matches!(display.stats, Some(Stats::Progress(_))));
+
+        // Finished archive without remaining time
+        let log = test_progress_log(25, true);
+        let Status::Backup(progress) =
+            Status::from_backup(run, Some(&log), Some(100), None, remaining)
+        else {
+            unreachable!()
+        };
+        assert_eq!(progress.fraction, Some(1.));
+        assert_eq!(progress.time_remaining, None);
+
+        // Archive progress without size estimate
+        let log = test_progress_log(25, false);
+        let Status::Backup(progress) = Status::from_backup(run, Some(&log), None, None, remaining)
+        else {
+            unreachable!()
+        };
+        assert_eq!(progress.fraction, None);
+        assert_eq!(progress.time_remaining, None);
+        assert!(Display::from(&progress).subtitle.is_some());
+
+        // Other log messages
+        let log = log_json::Output::Progress(log_json::Progress::QuestionAcceptedTrue);
+        let Status::Backup(progress) =
+            Status::from_backup(run, Some(&log), Some(100), None, remaining)
+        else {
+            unreachable!()
+        };
+        assert!(progress.archive.is_none());
+        assert_eq!(progress.message, Some(log.to_string()));
+        if !matches!(run, Run::Reconnecting(_)) {
+            assert_eq!(Display::from(&progress).subtitle, Some(log.to_string()));
+        }
+    }
+}
+
+#[test]
+fn test_status_backup_estimating() {
+    let log = test_progress_log(25, false);
+
+    // Archive progress while the size estimate is still running
+    let Status::Backup(progress) =
+        Status::from_backup(Run::Running, Some(&log), None, Some(2000), None)
+    else {
+        unreachable!()
+    };
+    assert_eq!(progress.estimating_files, Some(2000));
+    assert_eq!(progress.fraction, None);
+    assert!(progress.archive.is_some());
+    let display = Display::from(&progress);
+    assert_ne!(display.subtitle, Some(log.to_string()));
+    assert!(matches!(display.stats, Some(Stats::Progress(_))));
+
+    // Percentage once the estimate is complete
+    let Status::Backup(progress) =
+        Status::from_backup(Run::Running, Some(&log), Some(100), Some(2000), None)
+    else {
+        unreachable!()
+    };
+    assert_eq!(progress.estimating_files, None);
+    assert_eq!(Display::from(&progress).progress, Some(0.25));
+}
@@ -59,6 +59,10 @@ impl AppWindow {
         self.get("add_include")
     }
 
+    pub fn add_include_apps(&self) -> gtk::Button {
+        self.get("add_include_apps")
+    }
+
     pub fn archive_list(&self) -> gtk::ListBox {
         self.get("archive_list")
     }
@@ -83,10 +87,22 @@ impl AppWindow {
         self.get("archives_eject_button")
     }
 
+    pub fn archives_file_history(&self) -> adw::ActionRow {
+        self.get("archives_file_history")
+    }
+
     pub fn archives_fs_usage(&self) -> gtk::LevelBar {
         self.get("archives_fs_usage")
     }
 
+    pub fn archives_host_filter(&self) -> gtk::DropDown {
+        self.get("archives_host_filter")
+    }
+
+    pub fn archives_host_filter_model(&self) -> gtk::StringList {
+        self.get("archives_host_filter_model")
+    }
+
     pub fn archives_location_icon(&self) -> gtk::Image {
         self.get("archives_location_icon")
     }
@@ -115,6 +131,26 @@ impl AppWindow {
         self.get("archives_prefix_edit")
     }
 
+    pub fn archives_queued(&self) -> gtk::Box {
+        self.get("archives_queued")
+    }
+
+    pub fn archives_queued_cancel(&self) -> gtk::Button {
+        self.get("archives_queued_cancel")
+    }
+
+    pub fn archives_queued_label(&self) -> gtk::Label {
+        self.get("archives_queued_label")
+    }
+
+    pub fn archives_reloading(&self) -> gtk::Box {
+        self.get("archives_reloading")
+    }
+
+    pub fn archives_reloading_cancel(&self) -> gtk::Button {
+        self.get("archives_reloading_cancel")
+    }
+
     pub fn archives_reloading_spinner(&self) -> gtk::Spinner {
         self.get("archives_reloading_spinner")
     }
@@ -127,6 +163,18 @@ impl AppWindow {
         self.get("archives_stack")
     }
 
+    pub fn archives_usage_bar(&self) -> crate::ui::export::UsageBar {
+        self.get("archives_usage_bar")
+    }
+
+    pub fn archives_usage_group(&self) -> adw::PreferencesGroup {
+        self.get("archives_usage_group")
+    }
+
+    pub fn archives_usage_list(&self) -> gtk::ListBox {
+        self.get("archives_usage_list")
+    }
+
     pub fn backup_disk_disconnected(&self) -> gtk::Box {
         self.get("backup_disk_disconnected")
     }
@@ -155,6 +203,26 @@ impl AppWindow {
         self.get("detail_deduplicated_size")
     }
 
+    pub fn detail_duration(&self) -> gtk::ListBox {
+        self.get("detail_duration")
+    }
+
+    pub fn detail_duration_row(&self) -> adw::ActionRow {
+        self.get("detail_duration_row")
+    }
+
+    pub fn detail_duration_sparkline(&self) -> gtk::Label {
+        self.get("detail_duration_sparkline")
+    }
+
+    pub fn detail_duration_warning(&self) -> gtk::Image {
+        self.get("detail_duration_warning")
+    }
+
+    pub fn detail_files_row(&self) -> adw::ExpanderRow {
+        self.get("detail_files_row")
+    }
+
     pub fn detail_header_bar(&self) -> adw::HeaderBar {
         self.get("detail_header_bar")
     }
@@ -187,6 +255,14 @@ impl AppWindow {
         self.get("detail_path_row")
     }
 
+    pub fn detail_pause(&self) -> gtk::Button {
+        self.get("detail_pause")
+    }
+
+    pub fn detail_recent_files(&self) -> gtk::Label {
+        self.get("detail_recent_files")
+    }
+
     pub fn detail_repo_icon(&self) -> gtk::Image {
         self.get("detail_repo_icon")
     }
@@ -199,6 +275,18 @@ impl AppWindow {
         self.get("detail_running_backup_info")
     }
 
+    pub fn detail_skip_include(&self) -> gtk::Button {
+        self.get("detail_skip_include")
+    }
+
+    pub fn detail_skip_path(&self) -> gtk::Button {
+        self.get("detail_skip_path")
+    }
+
+    pub fn detail_skip_row(&self) -> adw::ActionRow {
+        self.get("detail_skip_row")
+    }
+
     pub fn detail_stack(&self) -> adw::ViewStack {
         self.get("detail_stack")
     }
@@ -211,6 +299,18 @@ impl AppWindow {
         self.get("detail_status_row")
     }
 
+    pub fn detail_verified_badge(&self) -> gtk::Box {
+        self.get("detail_verified_badge")
+    }
+
+    pub fn detail_versions(&self) -> gtk::ListBox {
+        self.get("detail_versions")
+    }
+
+    pub fn detail_versions_row(&self) -> adw::ActionRow {
+        self.get("detail_versions_row")
+    }
+
     pub fn detail_view_switcher(&self) -> adw::ViewSwitcher {
         self.get("detail_view_switcher")
     }
@@ -223,10 +323,22 @@ impl AppWindow {
         self.get("dialog_check_result")
     }
 
+    pub fn edit_exclude_text(&self) -> gtk::Button {
+        self.get("edit_exclude_text")
+    }
+
     pub fn include(&self) -> gtk::ListBox {
         self.get("include")
     }
 
+    pub fn include_pattern_entry(&self) -> adw::EntryRow {
+        self.get("include_pattern_entry")
+    }
+
+    pub fn include_patterns(&self) -> gtk::ListBox {
+        self.get("include_patterns")
+    }
+
     pub fn main_backups(&self) -> gtk::ListBox {
         self.get("main_backups")
     }
@@ -235,6 +347,18 @@ impl AppWindow {
         self.get("main_stack")
     }
 
+    pub fn maintenance_enabled(&self) -> adw::SwitchRow {
+        self.get("maintenance_enabled")
+    }
+
+    pub fn maintenance_run(&self) -> gtk::Button {
+        self.get("maintenance_run")
+    }
+
+    pub fn maintenance_verify_data(&self) -> adw::SwitchRow {
+        self.get("maintenance_verify_data")
+    }
+
     pub fn navigation_page_detail(&self) -> adw::NavigationPage {
         self.get("navigation_page_detail")
     }
@@ -251,6 +375,14 @@ impl AppWindow {
         self.get("overview")
     }
 
+    pub fn overview_search_bar(&self) -> gtk::SearchBar {
+        self.get("overview_search_bar")
+    }
+
+    pub fn overview_search_entry(&self) -> gtk::SearchEntry {
+        self.get("overview_search_entry")
+    }
+
     pub fn page_archives(&self) -> adw::PreferencesPage {
         self.get("page_archives")
     }
@@ -275,6 +407,10 @@ impl AppWindow {
         self.get("page_schedule")
     }
 
+    pub fn page_search(&self) -> gtk::Stack {
+        self.get("page_search")
+    }
+
     pub fn pending_menu(&self) -> gtk::MenuButton {
         self.get("pending_menu")
     }
@@ -283,6 +419,10 @@ impl AppWindow {
         self.get("pending_menu_spinner")
     }
 
+    pub fn plaintext_banner(&self) -> adw::Banner {
+        self.get("plaintext_banner")
+    }
+
     pub fn preferred_time_row(&self) -> adw::ActionRow {
         self.get("preferred_time_row")
     }
@@ -295,6 +435,22 @@ impl AppWindow {
         self.get("primary_menu_button")
     }
 
+    pub fn problems_button(&self) -> gtk::MenuButton {
+        self.get("problems_button")
+    }
+
+    pub fn problems_count(&self) -> gtk::Label {
+        self.get("problems_count")
+    }
+
+    pub fn problems_list(&self) -> gtk::ListBox {
+        self.get("problems_list")
+    }
+
+    pub fn prune_delete_checkpoints(&self) -> adw::SwitchRow {
+        self.get("prune_delete_checkpoints")
+    }
+
     pub fn prune_detail(&self) -> adw::ExpanderRow {
         self.get("prune_detail")
     }
@@ -307,6 +463,10 @@ impl AppWindow {
         self.get("prune_preset")
     }
 
+    pub fn prune_preview(&self) -> adw::ExpanderRow {
+        self.get("prune_preview")
+    }
+
     pub fn prune_save(&self) -> gtk::Button {
         self.get("prune_save")
     }
@@ -319,6 +479,14 @@ impl AppWindow {
         self.get("refresh_archives")
     }
 
+    pub fn restore_backup_empty(&self) -> gtk::Button {
+        self.get("restore_backup_empty")
+    }
+
+    pub fn same_drive_banner(&self) -> adw::Banner {
+        self.get("same_drive_banner")
+    }
+
     pub fn schedule_active(&self) -> adw::ExpanderRow {
         self.get("schedule_active")
     }
@@ -335,6 +503,10 @@ impl AppWindow {
         self.get("schedule_keep_hourly")
     }
 
+    pub fn schedule_keep_last(&self) -> adw::SpinRow {
+        self.get("schedule_keep_last")
+    }
+
     pub fn schedule_keep_monthly(&self) -> adw::SpinRow {
         self.get("schedule_keep_monthly")
     }
@@ -359,6 +531,10 @@ impl AppWindow {
         self.get("schedule_preferred_minute")
     }
 
+    pub fn schedule_preferred_period(&self) -> gtk::DropDown {
+        self.get("schedule_preferred_period")
+    }
+
     pub fn schedule_preferred_time_button(&self) -> gtk::MenuButton {
         self.get("schedule_preferred_time_button")
     }
@@ -367,6 +543,18 @@ impl AppWindow {
         self.get("schedule_preferred_time_popover")
     }
 
+    pub fn schedule_quick_actions(&self) -> gtk::Box {
+        self.get("schedule_quick_actions")
+    }
+
+    pub fn schedule_run_now(&self) -> gtk::Button {
+        self.get("schedule_run_now")
+    }
+
+    pub fn schedule_skip(&self) -> gtk::Button {
+        self.get("schedule_skip")
+    }
+
     pub fn schedule_status(&self) -> crate::ui::export::StatusRow {
         self.get("schedule_status")
     }
@@ -375,6 +563,34 @@ impl AppWindow {
         self.get("schedule_status_list")
     }
 
+    pub fn schedule_upcoming(&self) -> adw::ExpanderRow {
+        self.get("schedule_upcoming")
+    }
+
+    pub fn search_archives(&self) -> gtk::ToggleButton {
+        self.get("search_archives")
+    }
+
+    pub fn search_index(&self) -> gtk::Button {
+        self.get("search_index")
+    }
+
+    pub fn search_page_empty(&self) -> adw::StatusPage {
+        self.get("search_page_empty")
+    }
+
+    pub fn search_page_no_results(&self) -> adw::StatusPage {
+        self.get("search_page_no_results")
+    }
+
+    pub fn search_page_results(&self) -> adw::PreferencesPage {
+        self.get("search_page_results")
+    }
+
+    pub fn search_results(&self) -> gtk::Box {
+        self.get("search_results")
+    }
+
     pub fn stop_backup_create(&self) -> gtk::Button {
         self.get("stop_backup_create")
     }
@@ -502,6 +718,164 @@ impl DialogArchivePrefix {
     }
 }
 
+#[derive(Clone)]
+pub struct DialogBorgCommand {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogBorgCommandWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogBorgCommand {
+    type Weak = DialogBorgCommandWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogBorgCommandWeak {
+    type Strong = DialogBorgCommand;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogBorgCommand {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_borg_command.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_borg_command.ui'")
+        })
+    }
+
+    pub fn copy(&self) -> gtk::Button {
+        self.get("copy")
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn text(&self) -> gtk::TextView {
+        self.get("text")
+    }
+}
+
+#[derive(Clone)]
+pub struct DialogBrowseRepository {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogBrowseRepositoryWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogBrowseRepository {
+    type Weak = DialogBrowseRepositoryWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogBrowseRepositoryWeak {
+    type Strong = DialogBrowseRepository;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogBrowseRepository {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_browse_repository.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_browse_repository.ui'")
+        })
+    }
+
+    pub fn archive_list(&self) -> gtk::ListBox {
+        self.get("archive_list")
+    }
+
+    pub fn archives_group(&self) -> adw::PreferencesGroup {
+        self.get("archives_group")
+    }
+
+    pub fn choose_folder(&self) -> gtk::Button {
+        self.get("choose_folder")
+    }
+
+    pub fn connect_button(&self) -> gtk::Button {
+        self.get("connect_button")
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn location(&self) -> adw::EntryRow {
+        self.get("location")
+    }
+
+    pub fn navigation_view(&self) -> adw::NavigationView {
+        self.get("navigation_view")
+    }
+
+    pub fn page_archives(&self) -> adw::NavigationPage {
+        self.get("page_archives")
+    }
+
+    pub fn page_connect(&self) -> adw::NavigationPage {
+        self.get("page_connect")
+    }
+
+    pub fn page_pending(&self) -> adw::NavigationPage {
+        self.get("page_pending")
+    }
+
+    pub fn password(&self) -> adw::PasswordEntryRow {
+        self.get("password")
+    }
+
+    pub fn pending_cancel(&self) -> gtk::Button {
+        self.get("pending_cancel")
+    }
+
+    pub fn pending_spinner(&self) -> gtk::Spinner {
+        self.get("pending_spinner")
+    }
+}
+
 #[derive(Clone)]
 pub struct DialogDeleteArchive {
     builder: gtk::Builder,
@@ -564,10 +938,18 @@ impl DialogDeleteArchive {
         self.get("dialog")
     }
 
+    pub fn group(&self) -> adw::PreferencesGroup {
+        self.get("group")
+    }
+
     pub fn name(&self) -> gtk::Label {
         self.get("name")
     }
 
+    pub fn origin(&self) -> gtk::Label {
+        self.get("origin")
+    }
+
     pub fn page_decision(&self) -> adw::ToolbarView {
         self.get("page_decision")
     }
@@ -682,9 +1064,29 @@ impl DialogEncryptionPassword {
         self.get("dialog")
     }
 
+    pub fn error(&self) -> gtk::Label {
+        self.get("error")
+    }
+
+    pub fn forgot_password(&self) -> gtk::Expander {
+        self.get("forgot_password")
+    }
+
+    pub fn forgot_password_text(&self) -> gtk::Label {
+        self.get("forgot_password_text")
+    }
+
     pub fn password(&self) -> gtk::PasswordEntry {
         self.get("password")
     }
+
+    pub fn password_command(&self) -> gtk::Entry {
+        self.get("password_command")
+    }
+
+    pub fn password_command_expander(&self) -> gtk::Expander {
+        self.get("password_command_expander")
+    }
 }
 
 #[derive(Clone)]
@@ -749,6 +1151,14 @@ impl DialogExclude {
         self.get("exclude_pattern")
     }
 
+    pub fn exclude_patterns_file(&self) -> adw::ActionRow {
+        self.get("exclude_patterns_file")
+    }
+
+    pub fn other_backup_paths(&self) -> adw::PreferencesGroup {
+        self.get("other_backup_paths")
+    }
+
     pub fn suggestions(&self) -> adw::PreferencesGroup {
         self.get("suggestions")
     }
@@ -812,6 +1222,14 @@ impl DialogExcludePattern {
         self.get("dialog")
     }
 
+    pub fn impact_row(&self) -> adw::ActionRow {
+        self.get("impact_row")
+    }
+
+    pub fn impact_spinner(&self) -> gtk::Spinner {
+        self.get("impact_spinner")
+    }
+
     pub fn pattern(&self) -> adw::EntryRow {
         self.get("pattern")
     }
@@ -819,19 +1237,292 @@ impl DialogExcludePattern {
     pub fn pattern_type(&self) -> adw::ComboRow {
         self.get("pattern_type")
     }
+
+    pub fn preview(&self) -> gtk::Button {
+        self.get("preview")
+    }
 }
 
 #[derive(Clone)]
-pub struct DialogPrune {
+pub struct DialogExcludeText {
     builder: gtk::Builder,
 }
 
 #[derive(Clone)]
-pub struct DialogPruneWeak {
+pub struct DialogExcludeTextWeak {
     builder: glib::WeakRef<gtk::Builder>,
 }
 
-impl glib::clone::Downgrade for DialogPrune {
+impl glib::clone::Downgrade for DialogExcludeText {
+    type Weak = DialogExcludeTextWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogExcludeTextWeak {
+    type Strong = DialogExcludeText;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogExcludeText {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_exclude_text.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_exclude_text.ui'")
+        })
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn save(&self) -> gtk::Button {
+        self.get("save")
+    }
+
+    pub fn text(&self) -> gtk::TextView {
+        self.get("text")
+    }
+}
+
+#[derive(Clone)]
+pub struct DialogFileHistory {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogFileHistoryWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogFileHistory {
+    type Weak = DialogFileHistoryWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogFileHistoryWeak {
+    type Strong = DialogFileHistory;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogFileHistory {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_file_history.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_file_history.ui'")
+        })
+    }
+
+    pub fn cancel(&self) -> gtk::Button {
+        self.get("cancel")
+    }
+
+    pub fn choose_group(&self) -> adw::PreferencesGroup {
+        self.get("choose_group")
+    }
+
+    pub fn choose_list(&self) -> gtk::ListBox {
+        self.get("choose_list")
+    }
+
+    pub fn date(&self) -> gtk::Label {
+        self.get("date")
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn index(&self) -> gtk::Button {
+        self.get("index")
+    }
+
+    pub fn modified(&self) -> gtk::Label {
+        self.get("modified")
+    }
+
+    pub fn modified_row(&self) -> adw::ActionRow {
+        self.get("modified_row")
+    }
+
+    pub fn newest(&self) -> gtk::Label {
+        self.get("newest")
+    }
+
+    pub fn oldest(&self) -> gtk::Label {
+        self.get("oldest")
+    }
+
+    pub fn page_choose(&self) -> adw::PreferencesPage {
+        self.get("page_choose")
+    }
+
+    pub fn page_empty(&self) -> adw::StatusPage {
+        self.get("page_empty")
+    }
+
+    pub fn page_loading(&self) -> adw::StatusPage {
+        self.get("page_loading")
+    }
+
+    pub fn page_versions(&self) -> adw::PreferencesPage {
+        self.get("page_versions")
+    }
+
+    pub fn path_group(&self) -> adw::PreferencesGroup {
+        self.get("path_group")
+    }
+
+    pub fn restore(&self) -> gtk::Button {
+        self.get("restore")
+    }
+
+    pub fn show_in_archive(&self) -> adw::ActionRow {
+        self.get("show_in_archive")
+    }
+
+    pub fn size(&self) -> gtk::Label {
+        self.get("size")
+    }
+
+    pub fn size_row(&self) -> adw::ActionRow {
+        self.get("size_row")
+    }
+
+    pub fn stack(&self) -> gtk::Stack {
+        self.get("stack")
+    }
+
+    pub fn state(&self) -> gtk::Label {
+        self.get("state")
+    }
+
+    pub fn state_row(&self) -> adw::ActionRow {
+        self.get("state_row")
+    }
+
+    pub fn time_adjustment(&self) -> gtk::Adjustment {
+        self.get("time_adjustment")
+    }
+
+    pub fn time_scale(&self) -> gtk::Scale {
+        self.get("time_scale")
+    }
+}
+
+#[derive(Clone)]
+pub struct DialogFlatpakApps {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogFlatpakAppsWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogFlatpakApps {
+    type Weak = DialogFlatpakAppsWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogFlatpakAppsWeak {
+    type Strong = DialogFlatpakApps;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogFlatpakApps {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_flatpak_apps.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_flatpak_apps.ui'")
+        })
+    }
+
+    pub fn apps(&self) -> adw::PreferencesGroup {
+        self.get("apps")
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn page_apps(&self) -> adw::PreferencesPage {
+        self.get("page_apps")
+    }
+
+    pub fn page_empty(&self) -> adw::StatusPage {
+        self.get("page_empty")
+    }
+
+    pub fn stack(&self) -> gtk::Stack {
+        self.get("stack")
+    }
+}
+
+#[derive(Clone)]
+pub struct DialogPrune {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogPruneWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogPrune {
     type Weak = DialogPruneWeak;
 
     fn downgrade(&self) -> Self::Weak {
@@ -975,6 +1666,117 @@ impl DialogPruneReview {
     }
 }
 
+#[derive(Clone)]
+pub struct DialogRestoreArchive {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogRestoreArchiveWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogRestoreArchive {
+    type Weak = DialogRestoreArchiveWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogRestoreArchiveWeak {
+    type Strong = DialogRestoreArchive;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogRestoreArchive {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_restore_archive.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_restore_archive.ui'")
+        })
+    }
+
+    pub fn abort(&self) -> gtk::Button {
+        self.get("abort")
+    }
+
+    pub fn affected(&self) -> gtk::ListBox {
+        self.get("affected")
+    }
+
+    pub fn app_data(&self) -> adw::PreferencesGroup {
+        self.get("app_data")
+    }
+
+    pub fn conflict_policy(&self) -> adw::ComboRow {
+        self.get("conflict_policy")
+    }
+
+    pub fn date(&self) -> gtk::Label {
+        self.get("date")
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn name(&self) -> gtk::Label {
+        self.get("name")
+    }
+
+    pub fn ownership(&self) -> adw::PreferencesGroup {
+        self.get("ownership")
+    }
+
+    pub fn ownership_policy(&self) -> adw::ActionRow {
+        self.get("ownership_policy")
+    }
+
+    pub fn page_decision(&self) -> adw::ToolbarView {
+        self.get("page_decision")
+    }
+
+    pub fn page_progress(&self) -> adw::ToolbarView {
+        self.get("page_progress")
+    }
+
+    pub fn progress(&self) -> gtk::ProgressBar {
+        self.get("progress")
+    }
+
+    pub fn progress_status(&self) -> adw::StatusPage {
+        self.get("progress_status")
+    }
+
+    pub fn restore(&self) -> gtk::Button {
+        self.get("restore")
+    }
+
+    pub fn stack(&self) -> gtk::Stack {
+        self.get("stack")
+    }
+
+    pub fn summary(&self) -> adw::ActionRow {
+        self.get("summary")
+    }
+}
+
 #[derive(Clone)]
 pub struct DialogSetup {
     builder: gtk::Builder,
@@ -1045,6 +1847,10 @@ impl DialogSetup {
         self.get("ask_password")
     }
 
+    pub fn ask_password_error(&self) -> gtk::Label {
+        self.get("ask_password_error")
+    }
+
     pub fn button_stack(&self) -> gtk::Stack {
         self.get("button_stack")
     }
@@ -1053,6 +1859,10 @@ impl DialogSetup {
         self.get("command_line_args_entry")
     }
 
+    pub fn creating_cancel(&self) -> gtk::Button {
+        self.get("creating_cancel")
+    }
+
     pub fn creating_repository_spinner(&self) -> gtk::Spinner {
         self.get("creating_repository_spinner")
     }
@@ -1073,6 +1883,10 @@ impl DialogSetup {
         self.get("init_dir")
     }
 
+    pub fn init_group(&self) -> adw::PreferencesGroup {
+        self.get("init_group")
+    }
+
     pub fn init_local_row(&self) -> adw::ActionRow {
         self.get("init_local_row")
     }
@@ -1173,6 +1987,10 @@ impl DialogSetup {
         self.get("page_transfer_stack")
     }
 
+    pub fn pending_cancel(&self) -> gtk::Button {
+        self.get("pending_cancel")
+    }
+
     pub fn pending_spinner(&self) -> gtk::Spinner {
         self.get("pending_spinner")
     }
@@ -1185,6 +2003,22 @@ impl DialogSetup {
         self.get("prefix_submit")
     }
 
+    pub fn provider_create(&self) -> gtk::Button {
+        self.get("provider_create")
+    }
+
+    pub fn provider_group(&self) -> adw::PreferencesGroup {
+        self.get("provider_group")
+    }
+
+    pub fn provider_region(&self) -> adw::ComboRow {
+        self.get("provider_region")
+    }
+
+    pub fn provider_token(&self) -> adw::PasswordEntryRow {
+        self.get("provider_token")
+    }
+
     pub fn show_settings(&self) -> gtk::ToggleButton {
         self.get("show_settings")
     }
@@ -1273,6 +2107,73 @@ impl DialogSetupTransferOption {
     }
 }
 
+#[derive(Clone)]
+pub struct DialogSharedStatus {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct DialogSharedStatusWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for DialogSharedStatus {
+    type Weak = DialogSharedStatusWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for DialogSharedStatusWeak {
+    type Strong = DialogSharedStatus;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl DialogSharedStatus {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/dialog_shared_status.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/dialog_shared_status.ui'")
+        })
+    }
+
+    pub fn dialog(&self) -> adw::Window {
+        self.get("dialog")
+    }
+
+    pub fn page_empty(&self) -> adw::StatusPage {
+        self.get("page_empty")
+    }
+
+    pub fn page_loading(&self) -> gtk::Spinner {
+        self.get("page_loading")
+    }
+
+    pub fn page_users(&self) -> adw::PreferencesPage {
+        self.get("page_users")
+    }
+
+    pub fn stack(&self) -> gtk::Stack {
+        self.get("stack")
+    }
+}
+
 #[derive(Clone)]
 pub struct DialogStorage {
     builder: gtk::Builder,
@@ -1319,6 +2220,46 @@ impl DialogStorage {
         })
     }
 
+    pub fn cache(&self) -> adw::PreferencesGroup {
+        self.get("cache")
+    }
+
+    pub fn cache_delete(&self) -> gtk::Button {
+        self.get("cache_delete")
+    }
+
+    pub fn cache_size(&self) -> adw::ActionRow {
+        self.get("cache_size")
+    }
+
+    pub fn connection_borg(&self) -> adw::ActionRow {
+        self.get("connection_borg")
+    }
+
+    pub fn connection_borg_icon(&self) -> gtk::Image {
+        self.get("connection_borg_icon")
+    }
+
+    pub fn connection_lock(&self) -> adw::ActionRow {
+        self.get("connection_lock")
+    }
+
+    pub fn connection_lock_icon(&self) -> gtk::Image {
+        self.get("connection_lock_icon")
+    }
+
+    pub fn connection_ssh(&self) -> adw::ActionRow {
+        self.get("connection_ssh")
+    }
+
+    pub fn connection_ssh_icon(&self) -> gtk::Image {
+        self.get("connection_ssh_icon")
+    }
+
+    pub fn connection_verify(&self) -> gtk::Button {
+        self.get("connection_verify")
+    }
+
     pub fn device(&self) -> adw::ActionRow {
         self.get("device")
     }
@@ -1331,6 +2272,30 @@ impl DialogStorage {
         self.get("disk")
     }
 
+    pub fn encryption(&self) -> adw::ActionRow {
+        self.get("encryption")
+    }
+
+    pub fn format(&self) -> adw::PreferencesGroup {
+        self.get("format")
+    }
+
+    pub fn format_cancel(&self) -> gtk::Button {
+        self.get("format_cancel")
+    }
+
+    pub fn format_check(&self) -> gtk::Button {
+        self.get("format_check")
+    }
+
+    pub fn format_status(&self) -> adw::ActionRow {
+        self.get("format_status")
+    }
+
+    pub fn format_upgrade(&self) -> gtk::Button {
+        self.get("format_upgrade")
+    }
+
     pub fn fs(&self) -> adw::PreferencesGroup {
         self.get("fs")
     }
@@ -1347,6 +2312,30 @@ impl DialogStorage {
         self.get("fs_usage")
     }
 
+    pub fn host_key(&self) -> adw::ActionRow {
+        self.get("host_key")
+    }
+
+    pub fn host_key_policy(&self) -> adw::ComboRow {
+        self.get("host_key_policy")
+    }
+
+    pub fn host_key_reset(&self) -> gtk::Button {
+        self.get("host_key_reset")
+    }
+
+    pub fn network(&self) -> adw::PreferencesGroup {
+        self.get("network")
+    }
+
+    pub fn network_last_month(&self) -> adw::ActionRow {
+        self.get("network_last_month")
+    }
+
+    pub fn network_this_month(&self) -> adw::ActionRow {
+        self.get("network_this_month")
+    }
+
     pub fn path(&self) -> adw::ActionRow {
         self.get("path")
     }
@@ -1355,6 +2344,10 @@ impl DialogStorage {
         self.get("remote")
     }
 
+    pub fn reuse_connection(&self) -> adw::SwitchRow {
+        self.get("reuse_connection")
+    }
+
     pub fn uri(&self) -> adw::ActionRow {
         self.get("uri")
     }
@@ -1418,6 +2411,10 @@ impl OverviewItem {
         self.get("location")
     }
 
+    pub fn location_archives(&self) -> gtk::Label {
+        self.get("location_archives")
+    }
+
     pub fn location_icon(&self) -> gtk::Image {
         self.get("location_icon")
     }
@@ -1442,3 +2439,130 @@ impl OverviewItem {
         self.get("widget")
     }
 }
+
+#[derive(Clone)]
+pub struct WindowBackup {
+    builder: gtk::Builder,
+}
+
+#[derive(Clone)]
+pub struct WindowBackupWeak {
+    builder: glib::WeakRef<gtk::Builder>,
+}
+
+impl glib::clone::Downgrade for WindowBackup {
+    type Weak = WindowBackupWeak;
+
+    fn downgrade(&self) -> Self::Weak {
+        Self::Weak {
+            builder: self.builder.downgrade(),
+        }
+    }
+}
+
+impl glib::clone::Upgrade for WindowBackupWeak {
+    type Strong = WindowBackup;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Some(Self::Strong {
+            builder: self.builder.upgrade()?,
+        })
+    }
+}
+
+impl WindowBackup {
+    pub fn new() -> Self {
+        Self {
+            builder: gtk::Builder::from_string(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/ui/window_backup.ui"
+            ))),
+        }
+    }
+
+    fn get<T: glib::IsA<glib::object::Object>>(&self, id: &str) -> T {
+        gtk::Builder::object(&self.builder, id).unwrap_or_else(|| {
+            panic!("Object with id '{id}' not found in 'src/ui/window_backup.ui'")
+        })
+    }
+
+    pub fn abort(&self) -> gtk::Button {
+        self.get("abort")
+    }
+
+    pub fn current_path(&self) -> gtk::Label {
+        self.get("current_path")
+    }
+
+    pub fn deduplicated_size(&self) -> gtk::Label {
+        self.get("deduplicated_size")
+    }
+
+    pub fn edit(&self) -> gtk::Button {
+        self.get("edit")
+    }
+
+    pub fn error(&self) -> gtk::Label {
+        self.get("error")
+    }
+
+    pub fn exclude_group(&self) -> adw::PreferencesGroup {
+        self.get("exclude_group")
+    }
+
+    pub fn exclude_list(&self) -> gtk::ListBox {
+        self.get("exclude_list")
+    }
+
+    pub fn include_list(&self) -> gtk::ListBox {
+        self.get("include_list")
+    }
+
+    pub fn nfiles(&self) -> gtk::Label {
+        self.get("nfiles")
+    }
+
+    pub fn original_size(&self) -> gtk::Label {
+        self.get("original_size")
+    }
+
+    pub fn path_row(&self) -> adw::ActionRow {
+        self.get("path_row")
+    }
+
+    pub fn progress(&self) -> gtk::ProgressBar {
+        self.get("progress")
+    }
+
+    pub fn repo_icon(&self) -> gtk::Image {
+        self.get("repo_icon")
+    }
+
+    pub fn repo_row(&self) -> adw::ActionRow {
+        self.get("repo_row")
+    }
+
+    pub fn schedule_list(&self) -> gtk::ListBox {
+        self.get("schedule_list")
+    }
+
+    pub fn start(&self) -> gtk::Button {
+        self.get("start")
+    }
+
+    pub fn stats(&self) -> gtk::ListBox {
+        self.get("stats")
+    }
+
+    pub fn status(&self) -> crate::ui::export::StatusRow {
+        self.get("status")
+    }
+
+    pub fn title(&self) -> adw::WindowTitle {
+        self.get("title")
+    }
+
+    pub fn window(&self) -> adw::Window {
+        self.get("window")
+    }
+}
@@ -0,0 +1,77 @@
+//! Ask for confirmation before performing user actions
+//!
+//! Confirmations of some categories can be turned off in the preferences.
+
+use adw::prelude::*;
+
+use crate::ui;
+use crate::ui::prelude::*;
+
+thread_local!(
+    static SETTINGS: Option<gio::Settings> = crate::utils::app_settings();
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    /// Stopping running backups and other operations
+    Stop,
+    /// Removing folders or backup setups from the configuration
+    Remove,
+    /// Disabling features like scheduled backups
+    Disable,
+    /// Actions that can not be undone easily, always confirmed
+    Critical,
+}
+
+impl Category {
+    /// GSettings key storing whether the category is confirmed
+    pub const fn settings_key(self) -> Option<&'static str> {
+        match self {
+            Self::Stop => Some("confirm-stop"),
+            Self::Remove => Some("confirm-remove"),
+            Self::Disable => Some("confirm-disable"),
+            Self::Critical => None,
+        }
+    }
+
+    pub fn is_confirmed(self) -> bool {
+        let Some(key) = self.settings_key() else {
+            return true;
+        };
+
+        SETTINGS.with(|settings| settings.as_ref().map_or(true, |x| x.boolean(key)))
+    }
+}
+
+/// Show a confirmation dialog if actions of the `category` are confirmed
+///
+/// Returns [`Error::UserCanceled`] if the action is not confirmed.
+pub async fn confirm(
+    category: Category,
+    title: &str,
+    message: &str,
+    cancel: &str,
+    accept: &str,
+) -> Result<()> {
+    if !category.is_confirmed() {
+        debug!("Not asking for confirmation of {:?}: {}", category, title);
+        return Ok(());
+    }
+
+    ui::utils::ConfirmationDialog::new(title, message, cancel, accept)
+        .ask()
+        .await
+}
+
+/// Bind the confirmation setting of `category` to a boolean property
+pub fn bind_setting(category: Category, object: &impl IsA<glib::Object>, property: &str) {
+    let Some(key) = category.settings_key() else {
+        return;
+    };
+
+    SETTINGS.with(|settings| {
+        if let Some(settings) = settings {
+            settings.bind(key, object, property).build();
+        }
+    });
+}
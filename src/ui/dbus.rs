@@ -1,7 +1,8 @@
 use crate::ui::prelude::*;
 use async_std::prelude::*;
+use gio::prelude::*;
 
-use crate::{schedule, ui};
+use crate::{borg, schedule, ui};
 use async_std::channel::Sender;
 
 struct PikaBackup {
@@ -13,6 +14,9 @@ enum Command {
     StartBackup(ConfigId, Option<schedule::DueCause>),
     ShowOverview,
     ShowSchedule(ConfigId),
+    OperationStatus(ConfigId, Sender<String>),
+    AbortOperation(ConfigId),
+    StopOperation(ConfigId),
 }
 
 #[zbus::dbus_interface(name = "org.gnome.World.PikaBackup1")]
@@ -57,6 +61,75 @@ impl PikaBackup {
             error!("{}", err);
         }
     }
+
+    /// Status of the running operation as JSON, empty if there is none
+    async fn operation_status(&self, config_id: ConfigId) -> String {
+        let (sender, receiver) = async_std::channel::bounded(1);
+
+        if let Err(err) = self
+            .command
+            .send(Command::OperationStatus(config_id, sender))
+            .await
+        {
+            error!("{}", err);
+            return String::new();
+        }
+
+        receiver.recv().await.unwrap_or_default()
+    }
+
+    async fn abort_operation(&self, config_id: ConfigId) {
+        info!("Request to abort operation {:?}", config_id);
+        if let Err(err) = self.command.send(Command::AbortOperation(config_id)).await {
+            error!("{}", err);
+        }
+    }
+
+    /// Stop the operation after asking for confirmation
+    async fn stop_operation(&self, config_id: ConfigId) {
+        info!("Request to stop operation {:?}", config_id);
+        if let Err(err) = self.command.send(Command::StopOperation(config_id)).await {
+            error!("{}", err);
+        }
+    }
+}
+
+/// Access to the instance that owns the D-Bus name from other processes
+#[zbus::dbus_proxy(
+    interface = "org.gnome.World.PikaBackup1",
+    assume_defaults = false,
+    gen_blocking = false
+)]
+pub trait PikaBackupRemote {
+    fn operation_status(&self, config_id: &ConfigId) -> zbus::Result<String>;
+
+    fn abort_operation(&self, config_id: &ConfigId) -> zbus::Result<()>;
+}
+
+fn daemon_actions() -> Result<gio::DBusActionGroup> {
+    let connection = adw_app()
+        .dbus_connection()
+        .ok_or_else(|| Message::short(gettext("Background process not available")))?;
+
+    Ok(gio::DBusActionGroup::get(
+        &connection,
+        Some(crate::DAEMON_APP_ID),
+        crate::DAEMON_DBUS_PATH,
+    ))
+}
+
+/// Activate an action of the background process for the backup `config_id`
+pub fn activate_daemon_action(name: &str, config_id: &ConfigId) -> Result<()> {
+    daemon_actions()?.activate_action(name, Some(&config_id.to_variant()));
+
+    Ok(())
+}
+
+/// Let the background process check all schedules now
+pub fn probe_schedule() -> Result<()> {
+    daemon_actions()?.activate_action("probe-schedule", None);
+
+    Ok(())
 }
 
 pub async fn init() {
@@ -84,6 +157,34 @@ async fn spawn_command_listener() -> Sender<Command> {
                 }
                 Command::ShowOverview => ui::page_overview::dbus_show(),
                 Command::ShowSchedule(backup_id) => ui::page_schedule::dbus_show(backup_id),
+                Command::OperationStatus(config_id, sender) => {
+                    let status = BORG_OPERATION.with(|operations| {
+                        operations
+                            .load()
+                            .get(&config_id)
+                            .map(|op| ui::backup_status::Display::from(op.as_ref()))
+                    });
+
+                    let status = status
+                        .and_then(|status| serde_json::to_string(&status).ok())
+                        .unwrap_or_default();
+
+                    if let Err(err) = sender.send(status).await {
+                        error!("{}", err);
+                    }
+                }
+                Command::StopOperation(config_id) => {
+                    ui::app_window::show();
+                    Handler::run(async move { ui::page_backup::stop_operation(&config_id).await });
+                }
+                Command::AbortOperation(config_id) => {
+                    ui::utils::borg::dequeue(&config_id);
+                    BORG_OPERATION.with(|operations| {
+                        if let Some(op) = operations.load().get(&config_id) {
+                            op.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+                        }
+                    })
+                }
             }
         }
 
@@ -110,7 +211,37 @@ pub async fn session_connection() -> zbus::Result<zbus::Connection> {
             .await?;
         debug!("D-Bus listening on {}", crate::DBUS_API_NAME);
 
+        // Other instances query the status of our operations via this name
+        if let Some(unique_name) = new_connection.unique_name() {
+            borg::operation_lock::set_bus_name(unique_name.to_string());
+        }
+
         *connection = Some(new_connection.clone());
         Ok(new_connection)
     }
 }
+
+/// Proxy for the D-Bus API of the process with the unique name `bus_name`
+///
+/// The well-known name is owned by this process if it runs the UI. Operations
+/// of other processes are therefore addressed via the name recorded in their
+/// operation lock.
+pub async fn remote_proxy(bus_name: &str) -> zbus::Result<PikaBackupRemoteProxy<'static>> {
+    static PROXIES: async_lock::Mutex<
+        std::collections::BTreeMap<String, PikaBackupRemoteProxy<'static>>,
+    > = async_lock::Mutex::new(std::collections::BTreeMap::new());
+
+    let mut proxies = PROXIES.lock().await;
+
+    if let Some(proxy) = proxies.get(bus_name) {
+        Ok(proxy.clone())
+    } else {
+        let new_proxy = PikaBackupRemoteProxy::builder(&zbus::Connection::session().await?)
+            .destination(bus_name.to_string())?
+            .path(crate::DBUS_API_PATH)?
+            .build()
+            .await?;
+        proxies.insert(bus_name.to_string(), new_proxy.clone());
+        Ok(new_proxy)
+    }
+}
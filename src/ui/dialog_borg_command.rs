@@ -0,0 +1,35 @@
+//! Show the borg command of the next backup to reproduce it outside of the app
+
+use adw::prelude::*;
+
+use crate::borg;
+use crate::ui;
+use ui::builder::DialogBorgCommand;
+use ui::prelude::*;
+
+pub async fn show(config_id: &ConfigId) -> Result<()> {
+    let config = BACKUP_CONFIG.load().try_get(config_id)?.clone();
+    let preview = borg::Command::<borg::task::Create>::new(config)
+        .preview()
+        .await;
+    let line = preview.shell_line();
+
+    let ui = DialogBorgCommand::new();
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+    ui.text().buffer().set_text(&line);
+
+    ui.copy().connect_clicked(move |button| {
+        button.clipboard().set_text(&line);
+    });
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    ui.dialog().present();
+
+    Ok(())
+}
@@ -0,0 +1,382 @@
+//! Browse repositories without setting up a backup
+//!
+//! Neither a backup configuration nor the password is stored. The repository
+//! is unmounted again when the dialog is closed and no restore is running.
+
+use adw::prelude::*;
+
+use crate::borg;
+use crate::borg::BorgRunConfig;
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+use ui::builder::DialogBrowseRepository;
+use ui::dialog_setup::RemoteLocation;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Repository opened in the dialog
+#[derive(Clone)]
+struct Viewer {
+    /// Only exists in memory and is never added to the backup configurations
+    config: config::Backup,
+    password: Option<config::Password>,
+}
+
+/// Repository mounted by the dialog
+#[derive(Default)]
+struct Mounted {
+    repo_id: Cell<Option<borg::RepoId>>,
+    /// Number of restores copying files from the mounted archives
+    restoring: Cell<usize>,
+    /// The dialog was closed
+    closed: Cell<bool>,
+}
+
+impl Mounted {
+    /// Unmount once the dialog is closed and the last restore is completed
+    fn unmount_if_unused(&self) {
+        if !self.closed.get() || self.restoring.get() > 0 {
+            return;
+        }
+
+        if let Some(repo_id) = self.repo_id.take() {
+            Handler::run(async move { ui::utils::borg::unmount(&repo_id).await });
+        }
+    }
+}
+
+impl Viewer {
+    fn command<T: borg::Task>(&self) -> borg::Command<T> {
+        let mut command = borg::Command::<T>::new(self.config.clone());
+        if let Some(password) = &self.password {
+            command.set_password(password.clone());
+        }
+
+        command
+    }
+}
+
+pub fn show() {
+    let ui = DialogBrowseRepository::new();
+    let mounted = Rc::new(Mounted::default());
+
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+
+    ui.choose_folder()
+        .connect_clicked(clone!(@weak ui => move |_| execute(on_choose_folder(ui.clone()), &ui)));
+
+    let connect = clone!(@weak ui, @strong mounted => move || {
+        execute(on_connect(ui.clone(), mounted.clone()), &ui)
+    });
+    ui.connect_button()
+        .connect_clicked(enclose!((connect) move |_| connect()));
+    ui.location()
+        .connect_entry_activated(enclose!((connect) move |_| connect()));
+    ui.password().connect_entry_activated(move |_| connect());
+
+    ui.dialog().present();
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+
+        mounted.closed.set(true);
+        mounted.unmount_if_unused();
+
+        glib::Propagation::Proceed
+    });
+}
+
+fn execute<F: std::future::Future<Output = Result<()>> + 'static>(
+    f: F,
+    ui: &DialogBrowseRepository,
+) {
+    Handler::new().error_transient_for(ui.dialog()).spawn(f);
+}
+
+async fn on_choose_folder(ui: DialogBrowseRepository) -> Result<()> {
+    let path = ui::utils::folder_chooser_dialog(&gettext("Browse Repository"), None)
+        .await?
+        .path();
+
+    if let Some(path) = path {
+        ui.location().set_text(&path.display().to_string());
+    }
+
+    Ok(())
+}
+
+/// Repository from the entered location
+async fn repository(location: &str) -> Result<config::Repository> {
+    let path = if location.starts_with('/') {
+        Some(std::path::PathBuf::from(location))
+    } else if location.starts_with("file://") {
+        gio::File::for_uri(location).path()
+    } else {
+        None
+    };
+
+    if let Some(path) = path {
+        if !ui::utils::is_backup_repo(&path).await {
+            return Err(Message::new(
+                gettext("Location is not a valid backup repository."),
+                gettext("The repository must originate from Pika Backup or compatible software."),
+            )
+            .into());
+        }
+
+        return Ok(config::local::Repository::from_path(path).into_config());
+    }
+
+    let remote_location = RemoteLocation::from_user_input(location.to_string())
+        .err_to_msg(gettext("Invalid Remote Location"))?;
+
+    if remote_location.is_borg_host() {
+        Ok(config::remote::Repository::from_uri(remote_location.url()).into_config())
+    } else {
+        Ok(
+            ui::dialog_setup::mount_fuse_and_config(&remote_location.as_gio_file(), false)
+                .await?
+                .into_config(),
+        )
+    }
+}
+
+async fn on_connect(ui: DialogBrowseRepository, mounted: Rc<Mounted>) -> Result<()> {
+    let repo = repository(ui.location().text().trim()).await?;
+
+    let password = Some(ui.password().text().to_string())
+        .filter(|x| !x.is_empty())
+        .map(config::Password::new);
+
+    let mut borg = borg::CommandOnlyRepo::new(repo.clone());
+    borg.password = password.clone();
+
+    let communication = borg::Communication::<borg::task::Generic>::default();
+    let handler = ui
+        .pending_cancel()
+        .connect_clicked(enclose!((communication) move |_| {
+            info!("Canceling repository check");
+            communication.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+        }));
+    ui.navigation_view().push(&ui.page_pending());
+
+    let result =
+        ui::utils::borg::exec_repo_only(&gettext("Loading Backup Repository"), borg, |borg| {
+            borg.peek_abortable(communication)
+        })
+        .await;
+
+    if result.is_err() {
+        ui.pending_cancel().disconnect(handler);
+        ui.navigation_view().pop_to_page(&ui.page_connect());
+    }
+
+    if matches!(
+        result,
+        Err(ui::error::Combined::Borg(borg::Error::Failed(
+            borg::Failure::PassphraseWrong
+        )))
+    ) {
+        ui.password().grab_focus();
+
+        return Err(Message::new(
+            gettext("Wrong Encryption Password"),
+            gettext(
+                "The repository is encrypted. Enter the correct encryption password to open it.",
+            ),
+        )
+        .into());
+    }
+
+    let info = result.into_message(gettext("Failed to Open Repository"))?;
+
+    let viewer = Viewer {
+        config: config::Backup::new(
+            repo,
+            info.clone(),
+            info.encryption.mode != "none" && password.is_some(),
+        ),
+        password,
+    };
+
+    ui.pending_cancel().disconnect(handler);
+
+    let command = viewer.command::<borg::task::List>();
+    let communication = command.communication.clone();
+    let handler = ui.pending_cancel().connect_clicked(move |_| {
+        info!("Canceling archive listing");
+        communication.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+    });
+
+    let result = ui::utils::borg::exec_unstored(command).await;
+    ui.pending_cancel().disconnect(handler);
+
+    if result.is_err() {
+        ui.navigation_view().pop_to_page(&ui.page_connect());
+    }
+
+    let archives = result.into_message(gettext("Failed to List Archives"))?;
+
+    show_archives(&ui, &viewer, archives, mounted);
+    ui.navigation_view()
+        .replace(&[ui.page_connect(), ui.page_archives()]);
+
+    Ok(())
+}
+
+fn show_archives(
+    ui: &DialogBrowseRepository,
+    viewer: &Viewer,
+    archives: Vec<borg::ListArchive>,
+    mounted: Rc<Mounted>,
+) {
+    ui::utils::clear(&ui.archive_list());
+
+    ui.archives_group().set_description(Some(&ngettextf_(
+        "One archive",
+        "{} archives",
+        archives.len() as u32,
+    )));
+
+    // Newest archives first
+    for archive in archives.into_iter().rev() {
+        ui.archive_list()
+            .append(&archive_row(ui, viewer, archive, mounted.clone()));
+    }
+}
+
+fn archive_row(
+    ui: &DialogBrowseRepository,
+    viewer: &Viewer,
+    archive: borg::ListArchive,
+    mounted: Rc<Mounted>,
+) -> adw::ExpanderRow {
+    let row = adw::ExpanderRow::builder()
+        .title(
+            archive
+                .start
+                .to_locale()
+                .unwrap_or_else(|| archive.start.to_string()),
+        )
+        .subtitle(format!(
+            "{hostname}, {username}",
+            hostname = archive.hostname,
+            username = archive.username
+        ))
+        .build();
+
+    let browse_row = adw::ActionRow::builder()
+        .title(gettext("Browse saved files"))
+        .activatable(true)
+        .build();
+    browse_row.add_prefix(&gtk::Image::from_icon_name("folder-open-symbolic"));
+    browse_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    browse_row.connect_activated(
+        clone!(@weak ui, @strong viewer, @strong archive, @strong mounted => move |_| {
+            execute(browse(ui.clone(), viewer.clone(), archive.clone(), mounted.clone()), &ui)
+        }),
+    );
+    row.add_row(&browse_row);
+
+    let restore_row = adw::ActionRow::builder()
+        .title(gettext("Restore entire archive"))
+        .activatable(true)
+        .build();
+    restore_row.add_prefix(&gtk::Image::from_icon_name("edit-undo-symbolic"));
+    restore_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    restore_row.connect_activated(
+        clone!(@weak ui, @strong viewer, @strong archive => move |_| {
+            execute(restore(viewer.clone(), archive.clone(), mounted.clone()), &ui)
+        }),
+    );
+    row.add_row(&restore_row);
+
+    row
+}
+
+/// Mount the repository if necessary and return the path of the archive
+///
+/// Browsing and restoring both go through here, such that they are protected
+/// by the authentication setting.
+async fn mount(
+    viewer: &Viewer,
+    archive: &borg::ListArchive,
+    mounted: &Mounted,
+) -> Result<std::path::PathBuf> {
+    ui::utils::authentication::require().await?;
+
+    let repo_id = &viewer.config.repo_id;
+
+    ui::utils::borg::cleanup_mounts().await?;
+
+    // The repository might also be set up as a backup and already be mounted
+    if !ACTIVE_MOUNTS.load().contains(repo_id) {
+        ACTIVE_MOUNTS.update(|mounts| {
+            mounts.insert(repo_id.clone());
+        });
+
+        let mut command = viewer.command::<borg::task::Mount>();
+        ui::utils::permissions::configure_mount(&mut command.task);
+
+        let result = ui::utils::borg::exec_unstored(command).await;
+
+        if result.is_err() {
+            ACTIVE_MOUNTS.update(|mounts| {
+                mounts.remove(repo_id);
+            });
+        }
+
+        result.into_message(gettext("Failed to make archives available for browsing."))?;
+        mounted.repo_id.set(Some(repo_id.clone()));
+    }
+
+    Ok(borg::functions::mount_point(repo_id).join(archive.name.as_str()))
+}
+
+async fn browse(
+    ui: DialogBrowseRepository,
+    viewer: Viewer,
+    archive: borg::ListArchive,
+    mounted: Rc<Mounted>,
+) -> Result<()> {
+    let path = mount(&viewer, &archive, &mounted).await?;
+
+    let first_populated_dir = ui::utils::spawn_thread("open_archive", move || {
+        ui::page_archives::find_first_populated_dir(&path)
+    })
+    .await?;
+
+    gtk::FileLauncher::new(Some(&gio::File::for_path(first_populated_dir)))
+        .launch_future(Some(&ui.dialog()))
+        .await
+        .err_to_msg(gettext("Failed to open archive."))
+}
+
+async fn restore(viewer: Viewer, archive: borg::ListArchive, mounted: Rc<Mounted>) -> Result<()> {
+    // Keep the archives mounted until the restore dialog is closed
+    mounted.restoring.set(mounted.restoring.get() + 1);
+    scopeguard::defer! {
+        mounted.restoring.set(mounted.restoring.get() - 1);
+        mounted.unmount_if_unused();
+    }
+
+    let path = mount(&viewer, &archive, &mounted).await?;
+
+    let archive_date = archive
+        .start
+        .to_locale()
+        .unwrap_or_else(|| archive.start.to_string());
+
+    // Files are restored with the ownership of the current user
+    ui::dialog_restore_archive::run(
+        path,
+        archive.name.as_str(),
+        &archive_date,
+        Default::default(),
+    )
+    .await
+}
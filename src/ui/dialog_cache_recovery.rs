@@ -0,0 +1,96 @@
+//! Recover from errors caused by the local cache of a repository
+//!
+//! The cache is deleted and rebuilt from the information in the repository.
+
+use adw::prelude::*;
+
+use crate::borg;
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+/// Explain the cache error and offer to rebuild the cache
+pub async fn run(config: &config::Backup, failure: &borg::Failure) -> Result<()> {
+    let replay = *failure == borg::Failure::CacheRepositoryReplay;
+
+    let (heading, body) = if replay {
+        (
+            gettext("Cache Newer Than Repository"),
+            gettext("The local cache knows a newer state of the backup repository than the repository itself. This happens if the repository was restored from an older copy or if another repository with the same identity was used. It could also mean that the repository was replaced by an older version on purpose.\n\nOnly rebuild the cache if the current state of the repository is expected."),
+        )
+    } else {
+        (
+            gettext("Cache Damaged"),
+            gettext("The local cache of the backup repository is damaged or does not match the repository anymore. This can happen after a crash or if the disk ran out of space.\n\nRebuilding the cache reads information about all archives from the backup repository. For remote repositories this can transfer a considerable amount of data."),
+        )
+    };
+
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(&main_ui().window())
+        .modal(true)
+        .heading(heading)
+        .body(body)
+        .build();
+
+    dialog.add_responses(&[
+        ("cancel", &gettext("Cancel")),
+        ("rebuild", &gettext("Rebuild Cache")),
+    ]);
+    dialog.set_response_appearance(
+        "rebuild",
+        if replay {
+            adw::ResponseAppearance::Destructive
+        } else {
+            adw::ResponseAppearance::Suggested
+        },
+    );
+
+    if dialog.choose_future().await != "rebuild" {
+        return Ok(());
+    }
+
+    rebuild(config).await?;
+
+    ui::utils::show_notice(gettext(
+        "The cache was rebuilt. Start the backup again to continue.",
+    ));
+
+    Ok(())
+}
+
+async fn rebuild(config: &config::Backup) -> Result<()> {
+    let repo_id = &config.repo_id;
+
+    let repo_in_use = BORG_OPERATION.with(|operations| {
+        operations
+            .load()
+            .values()
+            .any(|operation| operation.repo_id() == repo_id)
+    });
+
+    if repo_in_use || ACTIVE_MOUNTS.load().contains(repo_id) {
+        return Err(Message::new(
+            gettext("Repository already in use"),
+            gettext("The cache can only be rebuilt while no operation is running and no archives are being browsed."),
+        )
+        .into());
+    }
+
+    info!("Rebuilding cache of repository {:?}", repo_id);
+
+    ui::utils::spawn_thread(
+        "cache_reset",
+        enclose!((repo_id) move || borg::reset_cache(&repo_id)),
+    )
+    .await?
+    .err_to_msg(gettext("Failed to Delete Cache"))?;
+
+    // The progress is shown in the backup status like for other operations
+    let guard = QuitGuard::default();
+    ui::utils::borg::exec(
+        borg::Command::<borg::task::RebuildCache>::new(config.clone()),
+        &guard,
+    )
+    .await
+    .into_message(gettext("Failed to Rebuild Cache"))
+}
@@ -6,10 +6,14 @@ use crate::ui;
 use crate::ui::prelude::*;
 use ui::builder::DialogDeleteArchive;
 
-pub async fn run(config: &config::Backup, archive_name: &str, archive_date: &str) -> Result<()> {
+pub async fn run(
+    config: &config::Backup,
+    archive_name: &borg::ArchiveName,
+    archive: &borg::ListArchive,
+) -> Result<()> {
     let ui = DialogDeleteArchive::new();
 
-    let result = show(config, archive_name, archive_date, &ui).await;
+    let result = show(config, archive_name, archive, &ui).await;
     if result.is_err() {
         ui.dialog().destroy();
     }
@@ -18,23 +22,39 @@ pub async fn run(config: &config::Backup, archive_name: &str, archive_date: &str
 
 async fn show(
     config: &config::Backup,
-    archive_name: &str,
-    archive_date: &str,
+    archive_name: &borg::ArchiveName,
+    archive: &borg::ListArchive,
     ui: &DialogDeleteArchive,
 ) -> Result<()> {
     ui.dialog().set_transient_for(Some(&main_ui().window()));
     ui.dialog().present();
 
-    let archive_name = archive_name.to_string();
-    ui.name().set_label(&archive_name);
-
-    let archive_date = archive_date.to_string();
-    ui.date().set_label(&archive_date);
+    let archive_name = archive_name.clone();
+    ui.name().set_label(archive_name.as_str());
+
+    ui.date().set_label(
+        &archive
+            .start
+            .to_locale()
+            .unwrap_or_else(|| archive.start.to_string()),
+    );
+    ui.origin().set_label(&format!(
+        "{hostname}, {username}",
+        hostname = archive.hostname,
+        username = archive.username
+    ));
+
+    if !archive.is_from_this_computer() {
+        ui.group().set_description(Some(&gettextf(
+            "This archive was created by another computer, “{}”. Proceeding with this operation will irretrievably delete this archive for everyone using this repository.",
+            &[&archive.hostname],
+        )));
+    }
 
     ui.delete()
         .connect_clicked(clone!(@weak ui, @strong config, @strong archive_name =>
            move |_|  Handler::new().error_transient_for(ui.dialog()).spawn(enclose!((config, archive_name) async move {
-               let result = delete(ui.clone(), config.clone(), &archive_name.clone()).await;
+               let result = delete(ui.clone(), config.clone(), archive_name.clone()).await;
                ui.dialog().destroy();
                result
            }))
@@ -54,14 +74,28 @@ async fn show(
     Ok(())
 }
 
-async fn delete(ui: DialogDeleteArchive, config: config::Backup, archive_name: &str) -> Result<()> {
+async fn delete(
+    ui: DialogDeleteArchive,
+    config: config::Backup,
+    archive_name: borg::ArchiveName,
+) -> Result<()> {
     ui.dialog().destroy();
 
+    ui::utils::append_only::lifted(&config, delete_and_compact(&config, archive_name)).await?;
+
+    let _ = ui::page_archives::cache::refresh_archives(config, None).await;
+
+    Ok(())
+}
+
+async fn delete_and_compact(
+    config: &config::Backup,
+    archive_name: borg::ArchiveName,
+) -> Result<()> {
     let guard = QuitGuard::default();
-    let archive_name = Some(archive_name.to_string());
 
     let mut command = borg::Command::<borg::task::Delete>::new(config.clone());
-    command.task.set_archive_name(archive_name);
+    command.task.set_archive_name(Some(archive_name));
     let result = ui::utils::borg::exec(command, &guard).await;
 
     result.into_message(gettext("Delete Archive Failed"))?;
@@ -73,7 +107,5 @@ async fn delete(ui: DialogDeleteArchive, config: config::Backup, archive_name: &
     .await
     .into_message("Reclaiming Free Space Failed")?;
 
-    let _ = ui::page_archives::cache::refresh_archives(config, None).await;
-
     Ok(())
 }
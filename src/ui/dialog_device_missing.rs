@@ -60,6 +60,19 @@ pub async fn ensure_repo_available(
     match &config.repo {
         config::Repository::Local(repo) => {
             if !ui::utils::is_backup_repo(&repo.path()).await {
+                // the original drive is gone, the repository might have been moved
+                if config.repo.uri_fuse().is_none()
+                    && (!repo.removable || find_volume(repo).is_none())
+                {
+                    if let Some(path) = find_moved_repo(config).await {
+                        match relink_moved_repo(config, path).await {
+                            Ok(relinked_config) => return Ok(relinked_config),
+                            Err(Error::UserCanceled) => {}
+                            Err(err) => return Err(err),
+                        }
+                    }
+                }
+
                 if let Some(uri) = config.repo.uri_fuse() {
                     info!("Remote gvfs repo not available");
                     mount_enclosing(&gio::File::for_uri(&uri)).await?;
@@ -105,6 +118,79 @@ pub async fn ensure_repo_available(
     Ok(new_config)
 }
 
+/// Search mounted volumes for a repository with the same id at another location
+///
+/// Candidates are the configured path relative to each mount point and all
+/// top-level directories of the mounts.
+async fn find_moved_repo(config: &config::Backup) -> Option<std::path::PathBuf> {
+    let config::Repository::Local(repo) = &config.repo else {
+        return None;
+    };
+
+    let roots: Vec<std::path::PathBuf> = gio::VolumeMonitor::get()
+        .mounts()
+        .iter()
+        .filter_map(|mount| mount.root().path())
+        .collect();
+
+    // Reading the mounts can take a while, especially for network shares
+    ui::utils::spawn_thread(
+        "find_moved_repo",
+        enclose!((config, repo) move || {
+            for root in roots {
+                let mut candidates = vec![root.join(&repo.path)];
+                if let Ok(entries) = std::fs::read_dir(&root) {
+                    candidates.extend(entries.flatten().map(|entry| entry.path()));
+                }
+
+                for candidate in candidates {
+                    if candidate != repo.path()
+                        && async_std::task::block_on(ui::utils::is_backup_repo(&candidate))
+                        && ui::utils::local_repo_id(&candidate).as_ref() == Some(&config.repo_id)
+                    {
+                        info!("Found repository at new location {:?}", candidate);
+                        return Some(candidate);
+                    }
+                }
+            }
+
+            None
+        }),
+    )
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Ask to use the repository at its new location and store it in the configuration
+async fn relink_moved_repo(
+    config: &config::Backup,
+    path: std::path::PathBuf,
+) -> Result<config::Backup> {
+    ui::confirm::confirm(
+        ui::confirm::Category::Critical,
+        &gettext("Backup Repository Found at a Different Location"),
+        &gettextf(
+            "The backup repository is no longer available at its configured location. A repository with the same identity was found at “{}”. Should this location be used from now on?",
+            &[&path.display()],
+        ),
+        &gettext("Cancel"),
+        &gettext("Use New Location"),
+    )
+    .await?;
+
+    let mut new_config = config.clone();
+    new_config.repo = config::local::Repository::from_path(path).into_config();
+    new_config.repo.set_settings(config.repo.settings());
+
+    BACKUP_CONFIG.try_update(enclose!((new_config) move |settings| {
+        settings.try_get_mut(&new_config.id)?.repo = new_config.repo.clone();
+        Ok(())
+    }))?;
+
+    Ok(new_config)
+}
+
 pub async fn mount_enclosing(file: &gio::File) -> Result<()> {
     info!("Trying to mount '{}'", file.uri());
     let mount_result = file.mount_enclosing_volume_future(
@@ -9,6 +9,8 @@ pub struct Ask {
     repo: config::Repository,
     purpose: String,
     keyring_error: Option<String>,
+    failed_attempts: u32,
+    config: Option<config::Backup>,
 }
 
 impl Ask {
@@ -21,9 +23,31 @@ impl Ask {
             repo,
             purpose,
             keyring_error,
+            failed_attempts: 0,
+            config: None,
         }
     }
 
+    /// Offer to obtain the password from a command configured for `config`
+    pub fn config(mut self, config: Option<config::Backup>) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Number of previously entered passwords that were wrong
+    pub const fn failed_attempts(mut self, failed_attempts: u32) -> Self {
+        self.failed_attempts = failed_attempts;
+        self
+    }
+
+    fn forgot_password_text() -> String {
+        format!(
+            "{}\n\n{}",
+            gettext("The password can't be recovered and the data in the repository can't be accessed without it. Try passwords you used at the time the backup was set up. Capitalization matters."),
+            gettext("If the repository uses a key file, the key file is stored in the folder “~/.config/borg/keys” of the computer the backup was set up on. After reinstalling the system, the key file has to be restored from a copy to this folder. The password of the key file is still required."),
+        )
+    }
+
     pub async fn run(&self) -> Option<config::Password> {
         let ui = ui::builder::DialogEncryptionPassword::new();
 
@@ -40,17 +64,91 @@ impl Ask {
 
         ui.dialog().set_body(&body);
 
-        ui.password().grab_focus();
+        if self.failed_attempts > 0 {
+            ui.error().set_label(&ngettextf_(
+                "Invalid encryption password. {} failed attempt.",
+                "Invalid encryption password. {} failed attempts.",
+                self.failed_attempts,
+            ));
+            ui.error().set_visible(true);
+
+            ui.forgot_password_text()
+                .set_label(&Self::forgot_password_text());
+            ui.forgot_password().set_visible(true);
+        }
+
+        let expander = ui.password_command_expander();
+        if let Some(config) = &self.config {
+            expander.set_visible(true);
+            if let Some(command) = &config.password_command {
+                ui.password_command().set_text(command);
+                expander.set_expanded(true);
+            }
+        }
+        expander
+            .bind_property("expanded", &ui.password(), "sensitive")
+            .invert_boolean()
+            .sync_create()
+            .build();
+
+        if expander.is_expanded() {
+            ui.password_command().grab_focus();
+        } else {
+            ui.password().grab_focus();
+        }
 
         ui.dialog().present();
 
         let response = ui.dialog().choose_future().await;
         let password = config::Password::new(ui.password().text().to_string());
 
-        if response == "apply" {
-            Some(password)
+        if response != "apply" {
+            return None;
+        }
+
+        let Some(config) = &self.config else {
+            return Some(password);
+        };
+
+        let command = Some(ui.password_command().text().trim().to_string())
+            .filter(|x| expander.is_expanded() && !x.is_empty());
+        Handler::handle(set_password_command(config, command.clone()));
+
+        if let Some(command) = command {
+            match crate::utils::password::from_command(config, &command).await {
+                Ok(password) => Some(password),
+                Err(err) => {
+                    Handler::handle(Err(Message::new(
+                        gettext("Failed to Obtain Encryption Password"),
+                        err,
+                    )
+                    .into()));
+                    None
+                }
+            }
         } else {
-            None
+            Some(password)
         }
     }
 }
+
+/// Store the password command for all backups using the repository of `config`
+fn set_password_command(config: &config::Backup, command: Option<String>) -> Result<()> {
+    let unchanged = BACKUP_CONFIG
+        .load()
+        .try_get(&config.id)
+        .is_ok_and(|x| x.password_command == command);
+    if unchanged {
+        return Ok(());
+    }
+
+    BACKUP_CONFIG.try_update(|backups| {
+        for backup in backups.iter_mut() {
+            if backup.repo_id == config.repo_id {
+                backup.password_command = command.clone();
+            }
+        }
+
+        Ok(())
+    })
+}
@@ -31,6 +31,12 @@ pub fn show() {
             Handler::run(exclude_pattern())
         }));
 
+    ui.exclude_patterns_file()
+        .connect_activated(glib::clone!(@weak ui => move |_| {
+            ui.dialog().destroy();
+            Handler::run(import_patterns_file())
+        }));
+
     // ensure lifetime until window closes
     let mutex = std::sync::Mutex::new(Some(ui.clone()));
     ui.dialog().connect_close_request(move |_| {
@@ -39,7 +45,14 @@ pub fn show() {
     });
 
     Handler::handle(fill_suggestions(&ui));
-    Handler::handle(fill_unreadable(&ui));
+    Handler::handle(fill_suggested_excludes(
+        &ui.unreadable_paths(),
+        config::history::SuggestedExcludeReason::PermissionDenied,
+    ));
+    Handler::handle(fill_suggested_excludes(
+        &ui.other_backup_paths(),
+        config::history::SuggestedExcludeReason::OtherBackupTool,
+    ));
 
     ui.dialog().present();
 }
@@ -131,19 +144,22 @@ fn on_suggested_toggle(buttons: &[(config::exclude::Predefined, gtk::CheckButton
     Ok(())
 }
 
-pub fn fill_unreadable(dialog: &DialogExclude) -> Result<()> {
-    dialog.unreadable_paths().set_visible(false);
+/// Show the excludes suggested for `reason` in `group`
+pub fn fill_suggested_excludes(
+    group: &adw::PreferencesGroup,
+    reason: config::history::SuggestedExcludeReason,
+) -> Result<()> {
+    group.set_visible(false);
 
     let configs = BACKUP_CONFIG.load();
     let exclude = &configs.active()?.exclude;
 
     let histories = BACKUP_HISTORY.load();
     // If the history is missing we don't have any suggested excludes and shouldn't fail
-    let suggested_excludes = histories.active().ok().and_then(|history| {
-        history
-            .suggested_exclude
-            .get(&config::history::SuggestedExcludeReason::PermissionDenied)
-    });
+    let suggested_excludes = histories
+        .active()
+        .ok()
+        .and_then(|history| history.suggested_excludes(&reason));
 
     let Some(suggested_excludes) = suggested_excludes else {
         return Ok(());
@@ -151,7 +167,7 @@ pub fn fill_unreadable(dialog: &DialogExclude) -> Result<()> {
 
     for suggested in suggested_excludes {
         // We have at least one entry
-        dialog.unreadable_paths().set_visible(true);
+        group.set_visible(true);
 
         let add_button = gtk::CheckButton::builder()
             .tooltip_text(&gettext("Add exclusion rule"))
@@ -166,10 +182,10 @@ pub fn fill_unreadable(dialog: &DialogExclude) -> Result<()> {
 
         row.add_prefix(&add_button);
 
-        dialog.unreadable_paths().add(&row);
+        group.add(&row);
 
         add_button.connect_toggled(
-            glib::clone!(@strong suggested_excludes, @strong suggested, @weak row, @weak dialog => move |button| {
+            glib::clone!(@strong suggested_excludes, @strong suggested, @weak row => move |button| {
                 Handler::handle((|| {
                     BACKUP_CONFIG.try_update(glib::clone!(@strong suggested_excludes, @strong suggested, @weak button => @default-return Ok(()), move |settings| {
                         let active = settings.active_mut()?;
@@ -226,24 +242,13 @@ async fn exclude_base_folder() -> Result<gio::File> {
 }
 
 pub async fn exclude_folder() -> Result<()> {
-    let chooser = gtk::FileDialog::builder()
-        .initial_folder(&exclude_base_folder().await?)
-        .title(gettext("Exclude Directory"))
-        .accept_label(gettext("Select"))
-        .modal(true)
-        .build();
-
-    let paths = ui::utils::paths_from_model(
-        chooser
-            .select_multiple_folders_future(Some(&main_ui().window()))
-            .await
-            .map_err(|err| match err.kind::<gtk::DialogError>() {
-                Some(gtk::DialogError::Cancelled | gtk::DialogError::Dismissed) => {
-                    Error::UserCanceled
-                }
-                _ => Message::short(err.to_string()).into(),
-            })?,
-    )?;
+    let chooser = ui::utils::file_chooser::FileChooser::new(
+        &gettext("Exclude Directory"),
+        &gettext("Select"),
+        Some(&exclude_base_folder().await?),
+    );
+
+    let paths = ui::utils::paths_from_model(chooser.select_folders(&main_ui().window()).await?)?;
 
     BACKUP_CONFIG.try_update(|settings| {
         for path in &paths {
@@ -262,24 +267,14 @@ pub async fn exclude_folder() -> Result<()> {
 }
 
 pub async fn exclude_file() -> Result<()> {
-    let chooser = gtk::FileDialog::builder()
-        .initial_folder(&exclude_base_folder().await?)
-        .title(gettext("Exclude File"))
-        .accept_label(gettext("Select"))
-        .modal(true)
-        .build();
-
-    let paths = ui::utils::paths_from_model(Some(
-        chooser
-            .open_multiple_future(Some(&main_ui().window()))
-            .await
-            .map_err(|err| match err.kind::<gtk::DialogError>() {
-                Some(gtk::DialogError::Cancelled | gtk::DialogError::Dismissed) => {
-                    Error::UserCanceled
-                }
-                _ => Message::short(err.to_string()).into(),
-            })?,
-    ))?;
+    let chooser = ui::utils::file_chooser::FileChooser::new(
+        &gettext("Exclude File"),
+        &gettext("Select"),
+        Some(&exclude_base_folder().await?),
+    );
+
+    let paths =
+        ui::utils::paths_from_model(Some(chooser.open_multiple(&main_ui().window()).await?))?;
 
     BACKUP_CONFIG.try_update(|settings| {
         for path in &paths {
@@ -301,3 +296,66 @@ pub async fn exclude_pattern() -> Result<()> {
     ui::dialog_exclude_pattern::show(None);
     Ok(())
 }
+
+pub async fn import_patterns_file() -> Result<()> {
+    let chooser = ui::utils::file_chooser::FileChooser::new(
+        &gettext("Import Patterns File"),
+        &gettext("Import"),
+        Some(&exclude_base_folder().await?),
+    );
+
+    let path = chooser
+        .open(&main_ui().window())
+        .await?
+        .path()
+        .ok_or_else(|| Message::short(gettext("Only local files can be imported.")))?;
+
+    let format = config::PatternsFormat::from_path(&path);
+
+    // Borg can read its own format directly on every run
+    let linked = format == config::PatternsFormat::Borg
+        && {
+            let dialog = adw::MessageDialog::builder()
+            .transient_for(&main_ui().window())
+            .modal(true)
+            .heading(gettext("Keep Linked to File?"))
+            .body(gettext("Linked files are read again for every backup, such that later changes to the file are used. Otherwise, the rules are copied into the list of exclusions."))
+            .build();
+
+            dialog.add_responses(&[
+                ("copy", &gettext("Copy Rules")),
+                ("link", &gettext("Keep Linked")),
+            ]);
+            dialog.set_default_response(Some("link"));
+
+            dialog.choose_future().await == "link"
+        };
+
+    sync_patterns_file(config::PatternsFile::new(path, format, linked))
+}
+
+/// Import the rules of the file again, replacing the ones of the last import
+pub fn sync_patterns_file(file: config::PatternsFile) -> Result<()> {
+    let parsed = file.read().err_to_msg(gettextf(
+        "Failed to Read “{}”",
+        &[&file.path.display().to_string()],
+    ))?;
+
+    if !file.linked && parsed.ignored > 0 {
+        ui::utils::show_notice(ngettextf_(
+            "One rule is not supported and was ignored",
+            "{} rules are not supported and were ignored",
+            parsed.ignored as u32,
+        ));
+    }
+
+    BACKUP_CONFIG.try_update(|settings| {
+        settings
+            .active_mut()?
+            .import_patterns_file(file.clone(), &parsed);
+        Ok(())
+    })?;
+
+    ui::page_backup::refresh()?;
+    Ok(())
+}
@@ -1,5 +1,10 @@
 use adw::prelude::*;
+use num_format::ToFormattedString;
 
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::borg;
 use crate::config;
 use crate::config::RELATIVE;
 use crate::ui;
@@ -23,12 +28,25 @@ pub fn show(edit_exclude: Option<config::Exclude<{ RELATIVE }>>) {
 
     dialog.set_transient_for(Some(&main_ui().window()));
     ui.add().connect_clicked(
-        clone!(@weak ui => move |_| Handler::run(clicked(ui, edit_exclude.clone()))),
+        clone!(@weak ui, @strong edit_exclude => move |_| Handler::run(clicked(ui, edit_exclude.clone()))),
+    );
+
+    let cancel: Rc<RefCell<Arc<AtomicBool>>> = Default::default();
+    ui.preview()
+        .connect_clicked(clone!(@weak ui, @strong cancel => move |_| {
+            let cancel = restart(&cancel);
+            Handler::run(preview(ui.clone(), edit_exclude.clone(), cancel))
+        }));
+    ui.pattern()
+        .connect_changed(clone!(@weak ui, @strong cancel => move |_| reset_impact(&ui, &cancel)));
+    ui.pattern_type().connect_selected_notify(
+        clone!(@weak ui, @strong cancel => move |_| reset_impact(&ui, &cancel)),
     );
 
     // ensure lifetime until window closes
     let mutex = std::sync::Mutex::new(Some(ui.clone()));
     ui.dialog().connect_close_request(move |_| {
+        cancel.borrow().store(true, Ordering::Relaxed);
         *mutex.lock().unwrap() = None;
         glib::Propagation::Proceed
     });
@@ -36,21 +54,90 @@ pub fn show(edit_exclude: Option<config::Exclude<{ RELATIVE }>>) {
     dialog.present();
 }
 
-async fn clicked(
-    ui: DialogExcludePattern,
-    edit_exclude: Option<config::Exclude<{ RELATIVE }>>,
-) -> Result<()> {
+/// Cancel the running preview and return the flag for a new one
+fn restart(cancel: &RefCell<Arc<AtomicBool>>) -> Arc<AtomicBool> {
+    let new = Arc::<AtomicBool>::default();
+    cancel.replace(new.clone()).store(true, Ordering::Relaxed);
+    new
+}
+
+/// The previewed impact doesn't apply to a changed pattern
+fn reset_impact(ui: &DialogExcludePattern, cancel: &RefCell<Arc<AtomicBool>>) {
+    restart(cancel);
+    ui.impact_spinner().set_visible(false);
+    ui.preview().set_sensitive(true);
+    ui.impact_row().set_subtitle(&gettext(
+        "Files currently present that would no longer be backed up",
+    ));
+}
+
+fn exclude(ui: &DialogExcludePattern) -> Result<config::Exclude<{ RELATIVE }>> {
     let selected = ui.pattern_type().selected();
     let pattern = ui.pattern().text();
 
-    let exclude = config::Exclude::from_pattern(match selected {
+    Ok(config::Exclude::from_pattern(match selected {
         // FIXME: Manual construction
         0 => Ok(config::Pattern::fnmatch(pattern.as_str())),
         1 => config::Pattern::from_regular_expression(pattern)
             .err_to_msg(gettext("Invalid Regular Expression")),
         // Not translated because this should not happen
         _ => Err(Message::short("No valid pattern type selected").into()),
-    }?);
+    }?))
+}
+
+/// Count the files the pattern would newly exclude
+async fn preview(
+    ui: DialogExcludePattern,
+    edit_exclude: Option<config::Exclude<{ RELATIVE }>>,
+    cancel: Arc<AtomicBool>,
+) -> Result<()> {
+    let new = exclude(&ui)?.into_absolute();
+    let config = BACKUP_CONFIG.load().active()?.clone();
+
+    let include = config.include_dirs();
+    let mut existing = config.exclude_dirs_internal();
+    if let Some(edit_exclude) = edit_exclude {
+        existing.remove(&edit_exclude.into_absolute());
+    }
+
+    ui.preview().set_sensitive(false);
+    ui.impact_spinner().set_visible(true);
+
+    let impact = ui::utils::spawn_thread("exclude_impact", move || {
+        borg::size_estimate::exclude_impact(&include, existing, &new, &cancel)
+    })
+    .await;
+
+    // Cancelled because the pattern changed or the dialog was closed
+    let Some(impact) = impact? else {
+        return Ok(());
+    };
+
+    ui.impact_spinner().set_visible(false);
+    ui.preview().set_sensitive(true);
+
+    ui.impact_row().set_subtitle(&if impact.files == 0 {
+        gettext("No present files would be excluded. Check the pattern for typos.")
+    } else {
+        ngettextf(
+            "{} file would be excluded, {} in total",
+            "{} files would be excluded, {} in total",
+            impact.files.min(u32::MAX.into()) as u32,
+            &[
+                &impact.files.to_formatted_string(&*LC_LOCALE),
+                &glib::format_size(impact.size),
+            ],
+        )
+    });
+
+    Ok(())
+}
+
+async fn clicked(
+    ui: DialogExcludePattern,
+    edit_exclude: Option<config::Exclude<{ RELATIVE }>>,
+) -> Result<()> {
+    let exclude = exclude(&ui)?;
 
     BACKUP_CONFIG.try_update(move |config| {
         let active = config.active_mut()?;
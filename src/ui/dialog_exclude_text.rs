@@ -0,0 +1,53 @@
+use adw::prelude::*;
+
+use crate::config;
+use crate::ui;
+use crate::ui::builder::DialogExcludeText;
+use crate::ui::prelude::*;
+
+pub fn show() -> Result<()> {
+    let ui = DialogExcludeText::new();
+    let dialog = ui.dialog();
+
+    let exclude = BACKUP_CONFIG.load().active()?.exclude.clone();
+    ui.text()
+        .buffer()
+        .set_text(&config::exclude::excludes_to_text(&exclude));
+
+    dialog.set_transient_for(Some(&main_ui().window()));
+    ui.save()
+        .connect_clicked(clone!(@weak ui => move |_| Handler::run(save(ui))));
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    dialog.present();
+
+    Ok(())
+}
+
+async fn save(ui: DialogExcludeText) -> Result<()> {
+    let buffer = ui.text().buffer();
+    let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+
+    let exclude = config::exclude::excludes_from_text(&text).map_err(|(line, err)| {
+        Message::new(
+            gettext("Invalid Exclusion Rule"),
+            gettextf("Line {}: {}", &[&line.to_string(), &err]),
+        )
+    })?;
+
+    BACKUP_CONFIG.try_update(move |config| {
+        config.active_mut()?.exclude = exclude.clone();
+        Ok(())
+    })?;
+
+    ui.dialog().destroy();
+    ui::page_backup::refresh()?;
+
+    Ok(())
+}
@@ -0,0 +1,352 @@
+//! Restore a file or folder from a chosen point in time
+//!
+//! The user picks a path from the indexed archives first and then moves through
+//! the archives to see how the path changed. Picking from the index instead of
+//! the file system allows to find files and folders that have been deleted.
+
+use adw::prelude::*;
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+use ui::builder::DialogFileHistory;
+use ui::utils::file_chooser;
+use ui::utils::file_index::{self, FileIndex, FileVersion};
+
+pub async fn run(config: &config::Backup) -> Result<()> {
+    ui::utils::authentication::require().await?;
+
+    let ui = DialogFileHistory::new();
+
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+    ui.stack().set_visible_child(&ui.page_loading());
+    ui.dialog().present();
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    ui.dialog().connect_destroy(|_| {
+        debug!("Destroy dialog");
+    });
+
+    ui.index()
+        .connect_clicked(clone!(@weak ui, @strong config => move |_| {
+            Handler::new().error_transient_for(ui.dialog()).spawn(
+                enclose!((ui, config) async move { index_archives(ui, config).await }),
+            );
+        }));
+
+    Handler::new()
+        .error_transient_for(ui.dialog())
+        .spawn(enclose!((ui, config) async move { init(ui, config).await }));
+
+    Ok(())
+}
+
+async fn init(ui: DialogFileHistory, config: config::Backup) -> Result<()> {
+    let index = Arc::new(
+        ui::utils::spawn_thread(
+            "file_history_load",
+            enclose!((config) move || FileIndex::load(&config.repo_id)),
+        )
+        .await?,
+    );
+
+    let missing = file_index::missing(&config, &index).len();
+    ui.index().set_visible(missing > 0);
+
+    // Start in the first backed up folder if it has been indexed
+    let dir = config
+        .include_dirs()
+        .into_iter()
+        .next()
+        .filter(|dir| !file_index::children(&config, &index, dir).is_empty())
+        .unwrap_or_else(|| PathBuf::from("/"));
+
+    show_folder(ui, config, index, dir).await
+}
+
+/// Index the archives of this backup that have not been indexed yet
+async fn index_archives(ui: DialogFileHistory, config: config::Backup) -> Result<()> {
+    ui.stack().set_visible_child(&ui.page_loading());
+
+    let result = file_index::update_missing(&config, |current, total| {
+        ui.page_loading().set_description(Some(&gettextf(
+            "Indexing archive {} of {}…",
+            &[&current.to_string(), &total.to_string()],
+        )));
+    })
+    .await;
+
+    ui.page_loading().set_description(None);
+
+    if ui::page_archives::is_visible() {
+        Handler::run(ui::page_archives::refresh_usage());
+    }
+
+    init(ui, config).await?;
+    result
+}
+
+/// List the indexed files and folders in `dir`
+async fn show_folder(
+    ui: DialogFileHistory,
+    config: config::Backup,
+    index: Arc<FileIndex>,
+    dir: PathBuf,
+) -> Result<()> {
+    let children = ui::utils::spawn_thread(
+        "file_history_children",
+        enclose!((config, index, dir) move || file_index::children(&config, &index, &dir)),
+    )
+    .await?;
+
+    if children.is_empty() && dir.parent().is_none() {
+        ui.page_empty().set_description(Some(&gettext(
+            "No archive of this backup has been indexed yet. Indexing reads the list of files from the backup repository.",
+        )));
+        ui.stack().set_visible_child(&ui.page_empty());
+        return Ok(());
+    }
+
+    ui.choose_group()
+        .set_title(&glib::markup_escape_text(&dir.display().to_string()));
+    ui::utils::clear(&ui.choose_list());
+
+    let open_folder = enclose!((config, index) move |ui: &DialogFileHistory, dir: PathBuf| {
+        Handler::new().error_transient_for(ui.dialog()).spawn(
+            enclose!((ui, config, index) async move { show_folder(ui, config, index, dir).await }),
+        );
+    });
+
+    if let Some(parent) = dir.parent().map(Path::to_path_buf) {
+        let row = adw::ActionRow::builder()
+            .title(gettext("Parent Folder"))
+            .activatable(true)
+            .build();
+        row.add_prefix(&gtk::Image::from_icon_name("go-up-symbolic"));
+        row.connect_activated(
+            clone!(@weak ui, @strong open_folder, @strong parent => move |_| {
+                open_folder(&ui, parent.clone());
+            }),
+        );
+        ui.choose_list().append(&row);
+    }
+
+    for entry in children
+        .into_iter()
+        .filter(|entry| file_chooser::is_listed(&entry.path))
+    {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(
+                entry
+                    .path
+                    .file_name()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            )
+            .activatable(true)
+            .build();
+
+        let icon = if entry.is_dir {
+            "folder-symbolic"
+        } else {
+            "text-x-generic-symbolic"
+        };
+        row.add_prefix(&gtk::Image::from_icon_name(icon));
+
+        if entry.is_dir {
+            // Folders open on activation, their own history is available via a button
+            let history = gtk::Button::builder()
+                .icon_name("document-open-recent-symbolic")
+                .tooltip_text(gettext("Previous Versions"))
+                .valign(gtk::Align::Center)
+                .build();
+            history.add_css_class("flat");
+            history.connect_clicked(
+                clone!(@weak ui, @strong config, @strong index, @strong entry => move |_| {
+                    choose(&ui, &config, &index, entry.path.clone());
+                }),
+            );
+            row.add_suffix(&history);
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+            row.connect_activated(
+                clone!(@weak ui, @strong open_folder, @strong entry => move |_| {
+                    open_folder(&ui, entry.path.clone());
+                }),
+            );
+        } else {
+            row.connect_activated(
+                clone!(@weak ui, @strong config, @strong index, @strong entry => move |_| {
+                    choose(&ui, &config, &index, entry.path.clone());
+                }),
+            );
+        }
+
+        ui.choose_list().append(&row);
+    }
+
+    ui.stack().set_visible_child(&ui.page_choose());
+
+    Ok(())
+}
+
+/// Show the versions of the chosen `path`
+fn choose(ui: &DialogFileHistory, config: &config::Backup, index: &Arc<FileIndex>, path: PathBuf) {
+    ui.path_group().set_title(&glib::markup_escape_text(
+        &path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string()),
+    ));
+    ui.path_group()
+        .set_description(Some(&glib::markup_escape_text(
+            &path
+                .parent()
+                .map(Path::display)
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
+        )));
+    ui.stack().set_visible_child(&ui.page_loading());
+
+    Handler::new().error_transient_for(ui.dialog()).spawn(
+        enclose!((ui, config, index) async move {
+            show_versions(ui, config, index, path).await
+        }),
+    );
+}
+
+async fn show_versions(
+    ui: DialogFileHistory,
+    config: config::Backup,
+    index: Arc<FileIndex>,
+    path: PathBuf,
+) -> Result<()> {
+    let versions = ui::utils::spawn_thread(
+        "file_history_versions",
+        enclose!((config, path) move || file_index::versions(&config, &index, &path)),
+    )
+    .await?;
+
+    if versions.iter().all(|version| version.file.is_none()) {
+        ui.page_empty().set_description(Some(&gettext(
+            "No indexed archive of this backup contains this file or folder.",
+        )));
+        ui.stack().set_visible_child(&ui.page_empty());
+        return Ok(());
+    }
+
+    let versions = Rc::new(versions);
+    let last = versions.len() - 1;
+
+    ui.oldest().set_label(&date(&versions[0]));
+    ui.newest().set_label(&date(&versions[last]));
+
+    let adjustment = ui.time_adjustment();
+    adjustment.set_upper(last as f64);
+    for position in 0..=last {
+        ui.time_scale()
+            .add_mark(position as f64, gtk::PositionType::Bottom, None);
+    }
+
+    adjustment.connect_value_changed(clone!(@weak ui, @strong versions => move |adjustment| {
+        show_version(&ui, &versions, adjustment.value().round() as usize);
+    }));
+
+    ui.show_in_archive().connect_activated(
+        clone!(@weak ui, @strong config, @strong versions => move |_| {
+            if let Some(version) = selected(&ui, &versions) {
+                Handler::new().error_transient_for(ui.dialog()).spawn(
+                    enclose!((config) async move {
+                        if let Some(file) = version.file {
+                            ui::page_archives::open_archive_file(config, version.archive_name, file)
+                                .await?;
+                        }
+                        Ok(())
+                    }),
+                );
+            }
+        }),
+    );
+
+    ui.restore().connect_clicked(
+        clone!(@weak ui, @strong config, @strong versions => move |_| {
+            if let Some(version) = selected(&ui, &versions) {
+                ui.dialog().close();
+                Handler::run(enclose!((config) async move {
+                    if let Some(file) = version.file {
+                        ui::page_archives::restore_archive_file(config, version.archive_name, file)
+                            .await?;
+                    }
+                    Ok(())
+                }));
+            }
+        }),
+    );
+
+    adjustment.set_value(last as f64);
+    show_version(&ui, &versions, last);
+    ui.stack().set_visible_child(&ui.page_versions());
+    ui.time_scale().grab_focus();
+
+    Ok(())
+}
+
+fn selected(ui: &DialogFileHistory, versions: &[FileVersion]) -> Option<FileVersion> {
+    versions
+        .get(ui.time_adjustment().value().round() as usize)
+        .cloned()
+}
+
+fn date(version: &FileVersion) -> String {
+    version
+        .start
+        .to_locale()
+        .unwrap_or_else(|| version.start.to_string())
+}
+
+fn show_version(ui: &DialogFileHistory, versions: &[FileVersion], position: usize) {
+    let Some(version) = versions.get(position) else {
+        return;
+    };
+    let previous = position
+        .checked_sub(1)
+        .and_then(|position| versions.get(position));
+
+    ui.date().set_label(&date(version));
+
+    let state = match (&version.file, previous) {
+        (None, _) => gettext("Does not exist"),
+        (Some(_), None) => gettext("Oldest version"),
+        (Some(_), Some(previous)) if previous.file.is_none() => gettext("Created"),
+        (Some(_), Some(previous)) if version.is_changed(previous) => gettext("Changed"),
+        (Some(_), Some(_)) => gettext("Unchanged"),
+    };
+    ui.state().set_label(&state);
+
+    let file = version.file.as_ref();
+    ui.size_row()
+        .set_visible(file.is_some_and(|file| !file.is_dir()));
+    if let Some(file) = file {
+        ui.size().set_label(&glib::format_size(file.size));
+    }
+
+    let modified = file.and_then(|file| file.mtime);
+    ui.modified_row().set_visible(modified.is_some());
+    if let Some(modified) = modified {
+        ui.modified()
+            .set_label(&modified.to_locale().unwrap_or_else(|| modified.to_string()));
+    }
+
+    ui.show_in_archive().set_visible(file.is_some());
+    ui.restore().set_sensitive(file.is_some());
+}
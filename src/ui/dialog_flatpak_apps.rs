@@ -0,0 +1,91 @@
+use adw::prelude::*;
+
+use crate::ui;
+use crate::utils::flatpak_apps;
+use ui::builder::DialogFlatpakApps;
+use ui::prelude::*;
+
+/// Row showing the name and icon of an app if it's known to the system
+pub fn app_row(app: &flatpak_apps::App) -> adw::ActionRow {
+    let info = gio::DesktopAppInfo::new(&format!("{}.desktop", app.id));
+
+    let row = adw::ActionRow::builder()
+        .use_markup(false)
+        .title(
+            info.as_ref()
+                .map(|info| info.name().to_string())
+                .unwrap_or_else(|| app.id.clone()),
+        )
+        .build();
+
+    if info.is_some() {
+        row.set_subtitle(&app.id);
+    }
+
+    let image = match info.and_then(|info| info.icon()) {
+        Some(icon) => gtk::Image::from_gicon(&icon),
+        None => gtk::Image::from_icon_name("application-x-executable-symbolic"),
+    };
+    image.set_icon_size(gtk::IconSize::Large);
+    row.add_prefix(&image);
+
+    row
+}
+
+pub fn show() -> Result<()> {
+    let ui = DialogFlatpakApps::new();
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+
+    let home = glib::home_dir();
+    let apps = flatpak_apps::installed(&home);
+    let include = BACKUP_CONFIG.load().active()?.include.clone();
+
+    for app in &apps {
+        let path = ui::utils::rel_path(&app.path);
+
+        let check_button = gtk::CheckButton::builder()
+            .valign(gtk::Align::Center)
+            .active(include.contains(&path))
+            .build();
+
+        let row = app_row(app);
+        row.set_activatable_widget(Some(&check_button));
+        row.add_suffix(&check_button);
+
+        check_button.connect_toggled(move |button| {
+            let active = button.is_active();
+            Handler::handle((|| {
+                BACKUP_CONFIG.try_update(|settings| {
+                    let config = settings.active_mut()?;
+                    if active {
+                        config.include.insert(path.clone());
+                    } else {
+                        config.include.remove(&path);
+                    }
+                    Ok(())
+                })?;
+
+                ui::page_backup::refresh()
+            })());
+        });
+
+        ui.apps().add(&row);
+    }
+
+    if apps.is_empty() {
+        ui.stack().set_visible_child(&ui.page_empty());
+    } else {
+        ui.stack().set_visible_child(&ui.page_apps());
+    }
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    ui.dialog().present();
+
+    Ok(())
+}
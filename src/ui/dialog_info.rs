@@ -5,37 +5,216 @@ use num_format::ToFormattedString;
 use crate::borg;
 use crate::config::history::*;
 use crate::ui::backup_status;
+use crate::ui::operation_status::OperationStatus;
 use crate::ui::prelude::*;
+use crate::ui::utils::duration;
+
+/// Number of completed runs shown in the duration chart
+const DURATION_CHART_RUNS: usize = 20;
 
 fn is_visible() -> bool {
     main_ui().detail_running_backup_info().is_visible()
 }
 
+thread_local!(
+    /// Bindings to the status of the shown backup config
+    static BINDINGS: std::cell::RefCell<Vec<glib::Binding>> = Default::default();
+);
+
 pub fn show() {
+    if let Some(id) = ACTIVE_BACKUP_ID.load().as_ref().as_ref() {
+        bind_status(&OperationStatus::get(id));
+    }
+
     main_ui().detail_running_backup_info().present();
     refresh_status();
 }
 
+fn bind_status(status: &OperationStatus) {
+    let progress = main_ui().detail_info_progress();
+    let pause = main_ui().detail_pause();
+
+    let bindings = vec![
+        main_ui().detail_info_status().bind_phase(status),
+        status
+            .bind_property("fraction", &progress, "fraction")
+            .sync_create()
+            .build(),
+        status
+            .bind_property("has-progress", &progress, "visible")
+            .sync_create()
+            .build(),
+        status
+            .bind_property("pausable", &pause, "visible")
+            .sync_create()
+            .build(),
+        status
+            .bind_property("paused", &pause, "label")
+            .transform_to(|_, paused: bool| {
+                Some(if paused {
+                    gettext("Resume")
+                } else {
+                    gettext("Pause")
+                })
+            })
+            .sync_create()
+            .build(),
+        status
+            .bind_property("paused", &pause, "tooltip-text")
+            .transform_to(|_, paused: bool| {
+                Some(if paused {
+                    gettext("Continue the backup")
+                } else {
+                    gettext("Temporarily halt the backup")
+                })
+            })
+            .sync_create()
+            .build(),
+    ];
+
+    BINDINGS.with(|current| {
+        for binding in current.replace(bindings) {
+            binding.unbind();
+        }
+    });
+}
+
 pub fn refresh_status() {
     if is_visible() {
         if let Some(id) = ACTIVE_BACKUP_ID.load().as_ref().as_ref() {
-            refresh_status_display(&backup_status::Display::new_from_id(id));
+            let status = backup_status::Display::new_from_id(id);
+            refresh_status_display(&status);
+            let finished = matches!(status.stats, Some(backup_status::Stats::Final(_)));
+            refresh_duration(id, finished);
+            refresh_versions(id, finished);
         }
     }
 }
 
-fn refresh_status_display(status: &backup_status::Display) {
+/// Show the duration of the last run compared to the previous runs
+fn refresh_duration(config_id: &ConfigId, finished: bool) {
+    let histories = BACKUP_HISTORY.load();
+    let history = histories.try_get(config_id).ok();
+    let last_duration = history
+        .and_then(|history| history.run.front())
+        .and_then(RunInfo::duration);
+
+    let (Some(history), Some(last_duration), true) = (history, last_duration, finished) else {
+        main_ui().detail_duration().set_visible(false);
+        return;
+    };
+
+    let mut durations: Vec<_> = history
+        .completed_durations()
+        .take(DURATION_CHART_RUNS)
+        .collect();
+    durations.reverse();
+
     main_ui()
-        .detail_info_status()
-        .set_from_backup_status(status);
+        .detail_duration_sparkline()
+        .set_label(&duration::sparkline(&durations));
+    main_ui()
+        .detail_duration_sparkline()
+        .set_visible(durations.len() > 1);
 
-    if let Some(progress) = status.progress {
-        main_ui().detail_info_progress().set_fraction(progress);
-        main_ui().detail_info_progress().set_visible(true);
-    } else {
-        main_ui().detail_info_progress().set_visible(false);
+    let anomaly = history.is_duration_anomaly(0);
+    main_ui().detail_duration_warning().set_visible(anomaly);
+
+    let subtitle = match history.usual_duration(0) {
+        Some(usual) if anomaly => gettextf(
+            "{}, much longer than the usual {}. The messages of this backup might show the reason, like newly included large folders or a slow backup disk.",
+            &[
+                &duration::plain(&last_duration),
+                &duration::plain_lowercase(&usual),
+            ],
+        ),
+        Some(usual) => gettextf(
+            "{}, usually {}",
+            &[
+                &duration::plain(&last_duration),
+                &duration::plain_lowercase(&usual),
+            ],
+        ),
+        None => duration::plain(&last_duration),
+    };
+    main_ui().detail_duration_row().set_subtitle(&subtitle);
+
+    main_ui().detail_duration().set_visible(true);
+}
+
+fn describe_versions(versions: &SoftwareVersions) -> String {
+    let mut parts = vec![gettextf("Pika Backup {}", &[&versions.pika_backup])];
+
+    if let Some(borg) = &versions.borg {
+        parts.push(gettextf("BorgBackup {}", &[borg]));
     }
 
+    if let Some(runtime) = &versions.flatpak_runtime {
+        // Translators: Argument is a Flatpak runtime like 'org.gnome.Platform/x86_64/46'
+        parts.push(gettextf("Runtime {}", &[runtime]));
+    }
+
+    parts.join(", ")
+}
+
+/// Show the software versions of the last run and whether they changed since the run before
+fn refresh_versions(config_id: &ConfigId, finished: bool) {
+    let histories = BACKUP_HISTORY.load();
+    let mut runs = histories
+        .try_get(config_id)
+        .ok()
+        .into_iter()
+        .flat_map(|history| history.run.iter());
+
+    let (Some(versions), true) = (runs.next().and_then(|run| run.versions.as_ref()), finished)
+    else {
+        main_ui().detail_versions().set_visible(false);
+        return;
+    };
+
+    let mut subtitle = describe_versions(versions);
+    let previous = runs.find_map(|run| run.versions.as_ref());
+    if previous.map_or(false, |previous| previous != versions) {
+        subtitle.push_str(" – ");
+        subtitle.push_str(&gettext("Changed since the previous backup"));
+    }
+
+    main_ui().detail_versions_row().set_subtitle(&subtitle);
+    main_ui().detail_versions().set_visible(true);
+}
+
+/// Show the files the running backup added or modified last
+fn refresh_recent_files() {
+    let recent_files = BORG_OPERATION.with(|op| {
+        op.load()
+            .active()
+            .ok()
+            .and_then(|op| op.try_as_create())
+            .map(|op| op.communication().specific_info.get().recent_files)
+    });
+
+    let lines: Vec<String> = recent_files
+        .unwrap_or_default()
+        .iter()
+        .rev()
+        .map(|file| {
+            format!(
+                "{} /{}",
+                file.status,
+                borg::escape_control_chars(&file.path)
+            )
+        })
+        .collect();
+
+    main_ui().detail_files_row().set_visible(!lines.is_empty());
+    main_ui().detail_recent_files().set_text(&lines.join("\n"));
+}
+
+fn refresh_status_display(status: &backup_status::Display) {
+    main_ui()
+        .detail_info_status()
+        .set_details_from_backup_status(status);
+
     if let Some(backup_status::Stats::Final(run_info)) = &status.stats {
         let mut message = String::new();
 
@@ -59,6 +238,8 @@ fn refresh_status_display(status: &backup_status::Display) {
         })) => {
             main_ui().detail_stats().set_visible(true);
             main_ui().detail_path_row().set_visible(false);
+            main_ui().detail_skip_row().set_visible(false);
+            main_ui().detail_files_row().set_visible(false);
 
             main_ui()
                 .detail_original_size()
@@ -73,6 +254,9 @@ fn refresh_status_display(status: &backup_status::Display) {
         Some(backup_status::Stats::Progress(progress_archive)) => {
             main_ui().detail_stats().set_visible(true);
             main_ui().detail_path_row().set_visible(true);
+            main_ui()
+                .detail_skip_row()
+                .set_visible(!progress_archive.path.is_empty() && !progress_archive.finished);
 
             main_ui()
                 .detail_original_size()
@@ -84,12 +268,13 @@ fn refresh_status_display(status: &backup_status::Display) {
                 .detail_nfiles()
                 .set_text(&progress_archive.nfiles.to_formatted_string(&*LC_LOCALE));
 
+            let current_path = format!("/{}", borg::escape_control_chars(&progress_archive.path));
+            main_ui().detail_current_path().set_text(&current_path);
             main_ui()
                 .detail_current_path()
-                .set_text(&format!("/{}", progress_archive.path));
-            main_ui()
-                .detail_current_path()
-                .set_tooltip_text(Some(&format!("/{}", progress_archive.path)));
+                .set_tooltip_text(Some(&current_path));
+
+            refresh_recent_files();
         }
         _ => {
             main_ui().detail_stats().set_visible(false);
@@ -5,7 +5,15 @@ use crate::config::BackupSettings;
 use crate::ui::prelude::*;
 
 mod imp {
-    use crate::{borg, config::UserScriptKind, ui::widget::EncryptionPreferencesGroup};
+    use crate::{
+        borg,
+        config::UserScriptKind,
+        ui::confirm::Category,
+        ui::utils::permissions::{self, Preset},
+        ui::utils::restore::OwnershipPolicy,
+        ui::widget::EncryptionPreferencesGroup,
+        utils::polkit,
+    };
 
     use super::*;
     use glib::Properties;
@@ -27,6 +35,9 @@ mod imp {
 
         #[property(get, set)]
         config_title: RefCell<String>,
+        /// Index in [`crate::config::AccentColor::VALUES`]
+        #[property(get, set, maximum = 7)]
+        accent_color: Cell<u32>,
 
         command_line_args_error: RefCell<Option<crate::ui::error::Error>>,
         pre_backup_command_error: RefCell<Option<crate::ui::error::Error>>,
@@ -59,6 +70,136 @@ mod imp {
         // Tweaks
         #[property(get, set)]
         schedule_run_on_battery: Cell<bool>,
+        #[property(get, set)]
+        schedule_resume_interrupted: Cell<bool>,
+        /// Minutes after boot or login without scheduled backups
+        #[property(get, set, maximum = 120)]
+        schedule_startup_holdoff: Cell<u32>,
+        /// Maximum random delay of scheduled backups in minutes
+        #[property(get, set, maximum = 120)]
+        schedule_jitter: Cell<u32>,
+        #[property(get, set)]
+        schedule_run_in_power_saver: Cell<bool>,
+        #[property(get, set)]
+        schedule_quiet_hours: Cell<bool>,
+        /// Hour at which the quiet hours start
+        #[property(get, set, maximum = 23)]
+        schedule_quiet_hours_start: Cell<u32>,
+        /// Hour at which the quiet hours end
+        #[property(get, set, maximum = 23)]
+        schedule_quiet_hours_end: Cell<u32>,
+        /// Command that can postpone scheduled backups
+        #[property(get, set)]
+        schedule_veto_command: RefCell<String>,
+        #[property(get, set)]
+        low_priority_in_power_saver: Cell<bool>,
+        #[property(get, set)]
+        pause_indexing: Cell<bool>,
+        /// Index in [`crate::config::Performance::VALUES`]
+        #[property(get, set, maximum = 2, default = 1)]
+        performance: Cell<u32>,
+
+        // Checkpoints
+        #[property(get, set)]
+        checkpoints_enabled: Cell<bool>,
+        /// Minutes between checkpoints
+        #[property(get, set, minimum = 1, maximum = 1440, default = 30)]
+        checkpoint_interval: Cell<u32>,
+
+        #[property(get, set)]
+        retry_changed_files: Cell<bool>,
+
+        // File metadata
+        #[property(get, set)]
+        skip_atime: Cell<bool>,
+        #[property(get, set)]
+        skip_ctime: Cell<bool>,
+        #[property(get, set)]
+        skip_birthtime: Cell<bool>,
+        #[property(get, set)]
+        skip_flags: Cell<bool>,
+
+        // Archive protection
+        #[property(get, set)]
+        append_only: Cell<bool>,
+        #[template_child]
+        append_only_row: TemplateChild<adw::SwitchRow>,
+
+        // Write verification
+        /// Index in [`crate::config::WriteVerification::VALUES`]
+        #[property(get, set, maximum = 2)]
+        write_verification: Cell<u32>,
+        #[template_child]
+        write_verification_pref_group: TemplateChild<adw::PreferencesGroup>,
+
+        // Monitoring
+        #[template_child]
+        metrics_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        report_dir_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        report_dir_clear: TemplateChild<gtk::Button>,
+        report_dir: RefCell<Option<std::path::PathBuf>>,
+
+        // Access
+        #[template_child]
+        access_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        require_authentication_row: TemplateChild<adw::SwitchRow>,
+
+        // Selecting files
+        #[template_child]
+        files_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        show_hidden_files_row: TemplateChild<adw::SwitchRow>,
+
+        // Restoring files
+        #[template_child]
+        restore_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        restore_permissions_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        restore_ownership_row: TemplateChild<adw::ComboRow>,
+
+        // Notifications
+        #[template_child]
+        notifications_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        missed_summary_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        missed_summary_hour_row: TemplateChild<adw::SpinRow>,
+
+        // Confirmations
+        #[template_child]
+        confirmations_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        confirm_stop_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        confirm_remove_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        confirm_disable_row: TemplateChild<adw::SwitchRow>,
+
+        // Troubleshooting
+        #[template_child]
+        troubleshooting_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        show_borg_command_row: TemplateChild<adw::SwitchRow>,
+
+        // Recently removed
+        #[template_child]
+        trash_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        trash_list: TemplateChild<gtk::ListBox>,
+
+        // Other users
+        #[template_child]
+        sharing_pref_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        share_status_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        admin_mode_row: TemplateChild<adw::SwitchRow>,
+        #[property(get, set)]
+        export_metrics: Cell<bool>,
 
         // Change password page
         #[template_child]
@@ -114,6 +255,56 @@ mod imp {
             self.obj().set_transient_for(Some(&main_ui().window()));
             self.shell_commands_detail
                 .set_label(&crate::borg::scripts::ShellVariable::explanation_string_markup());
+
+            for (category, row) in [
+                (Category::Stop, &*self.confirm_stop_row),
+                (Category::Remove, &*self.confirm_remove_row),
+                (Category::Disable, &*self.confirm_disable_row),
+            ] {
+                crate::ui::confirm::bind_setting(category, row, "active");
+            }
+            self.confirmations_pref_group
+                .set_sensitive(crate::utils::app_settings().is_some());
+
+            crate::ui::page_overview::trash::fill_group(&self.trash_pref_group, &self.trash_list);
+
+            if let Some(settings) = crate::utils::app_settings() {
+                settings
+                    .bind("share-status", &*self.share_status_row, "active")
+                    .build();
+                settings
+                    .bind("admin-mode", &*self.admin_mode_row, "active")
+                    .build();
+                settings
+                    .bind("show-borg-command", &*self.show_borg_command_row, "active")
+                    .build();
+                self.init_require_authentication(&settings);
+                settings
+                    .bind("show-hidden-files", &*self.show_hidden_files_row, "active")
+                    .build();
+                self.init_restore_permissions();
+                settings
+                    .bind(
+                        "missed-backups-summary",
+                        &*self.missed_summary_row,
+                        "active",
+                    )
+                    .build();
+                settings
+                    .bind(
+                        "missed-backups-summary-hour",
+                        &*self.missed_summary_hour_row,
+                        "value",
+                    )
+                    .build();
+            } else {
+                self.access_pref_group.set_sensitive(false);
+                self.files_pref_group.set_sensitive(false);
+                self.restore_pref_group.set_sensitive(false);
+                self.notifications_pref_group.set_sensitive(false);
+                self.sharing_pref_group.set_sensitive(false);
+                self.troubleshooting_pref_group.set_sensitive(false);
+            }
         }
     }
 
@@ -121,9 +312,17 @@ mod imp {
 
     impl WindowImpl for DialogPreferences {
         fn close_request(&self) -> glib::Propagation {
+            let append_only_changed = BACKUP_CONFIG
+                .load()
+                .try_get(self.config_id.get().unwrap())
+                .is_ok_and(|backup| backup.append_only != self.append_only.get());
             let write_result = BACKUP_CONFIG.try_update(|c| {
                 let backup = c.try_get_mut(self.config_id.get().unwrap())?;
                 backup.title = self.config_title.borrow().trim().to_string();
+                backup.accent_color = crate::config::AccentColor::VALUES
+                    .get(self.accent_color.get() as usize)
+                    .copied()
+                    .unwrap_or_default();
 
                 if !self.pre_backup_command.borrow().is_empty() {
                     backup.user_scripts.insert(
@@ -148,12 +347,63 @@ mod imp {
                 }));
 
                 backup.schedule.settings.run_on_battery = self.schedule_run_on_battery.get();
+                backup.schedule.settings.resume_interrupted =
+                    self.schedule_resume_interrupted.get();
+                backup.schedule.settings.startup_holdoff = self.schedule_startup_holdoff.get();
+                backup.schedule.settings.jitter = self.schedule_jitter.get();
+                backup.schedule.settings.run_in_power_saver =
+                    self.schedule_run_in_power_saver.get();
+                let hour = |hour| chrono::NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or_default();
+                backup.schedule.settings.quiet_hours =
+                    self.schedule_quiet_hours
+                        .get()
+                        .then(|| crate::config::QuietHours {
+                            start: hour(self.schedule_quiet_hours_start.get()),
+                            end: hour(self.schedule_quiet_hours_end.get()),
+                        });
+                let veto_command = self.schedule_veto_command.borrow().trim().to_string();
+                backup.schedule.settings.veto_command =
+                    (!veto_command.is_empty()).then_some(veto_command);
+                backup.low_priority_in_power_saver = self.low_priority_in_power_saver.get();
+                backup.pause_indexing = self.pause_indexing.get();
+                backup.performance = crate::config::Performance::VALUES
+                    .get(self.performance.get() as usize)
+                    .copied()
+                    .unwrap_or_default();
+                backup.export_metrics = self.export_metrics.get();
+                backup.checkpoints = crate::config::Checkpoints::from_interval(
+                    self.checkpoints_enabled.get(),
+                    self.checkpoint_interval.get() * 60,
+                );
+                backup.retry_changed_files = self.retry_changed_files.get();
+                backup.skip_metadata = crate::config::SkipMetadata {
+                    atime: self.skip_atime.get(),
+                    ctime: self.skip_ctime.get(),
+                    birthtime: self.skip_birthtime.get(),
+                    flags: self.skip_flags.get(),
+                };
+                backup.append_only = self.append_only.get();
+                backup.write_verification = crate::config::WriteVerification::VALUES
+                    .get(self.write_verification.get() as usize)
+                    .copied()
+                    .unwrap_or_default();
+                backup.report_dir = self.report_dir.borrow().clone();
 
                 Ok(())
             });
 
+            if append_only_changed && write_result.is_ok() {
+                if let Ok(backup) = BACKUP_CONFIG.load().try_get(self.config_id.get().unwrap()) {
+                    let backup = backup.clone();
+                    Handler::run(
+                        async move { crate::ui::utils::append_only::configure(&backup).await },
+                    );
+                }
+            }
+
             Handler::handle((|| {
                 write_result?;
+                crate::ui::utils::metrics::write()?;
                 crate::ui::page_backup::refresh()?;
                 Ok(())
             })());
@@ -199,6 +449,96 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl DialogPreferences {
+        fn init_require_authentication(&self, settings: &gio::Settings) {
+            let row = &*self.require_authentication_row;
+            row.set_active(settings.boolean("require-authentication"));
+
+            // Only offer the protection if polkit can actually ask for the password
+            let group = self.access_pref_group.clone();
+            group.set_visible(false);
+            Handler::run(enclose!((settings) async move {
+                let available =
+                    polkit::is_available(&polkit::authenticate_action_id()).await;
+                // Keep the switch if it is already on, such that it can be turned off again
+                group.set_visible(available || settings.boolean("require-authentication"));
+                Ok(())
+            }));
+
+            row.connect_active_notify(enclose!((settings) move |row| {
+                let active = row.is_active();
+                if active == settings.boolean("require-authentication") {
+                    return;
+                }
+
+                if active {
+                    Handler::handle(
+                        settings
+                            .set_boolean("require-authentication", true)
+                            .err_to_msg(gettext("Failed to Change Setting")),
+                    );
+                    return;
+                }
+
+                // Turning the protection off must not be possible without the password
+                Handler::run(enclose!((settings, row) async move {
+                    // Stays locked without polkit, stopping polkit must not be enough
+                    let action_id = polkit::authenticate_action_id();
+                    let authorized = polkit::check_authorization(&action_id)
+                        .await
+                        .unwrap_or_default();
+
+                    if authorized {
+                        settings
+                            .set_boolean("require-authentication", false)
+                            .err_to_msg(gettext("Failed to Change Setting"))?;
+                    } else {
+                        row.set_active(true);
+                    }
+                    Ok(())
+                }));
+            }));
+        }
+
+        fn init_restore_permissions(&self) {
+            let presets = Preset::list();
+            let names: Vec<String> = presets.iter().map(Preset::name).collect();
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            self.restore_permissions_row
+                .set_model(Some(&gtk::StringList::new(&names)));
+
+            // Values set outside the app stay in effect until a preset is chosen
+            if let Some(position) = Preset::from_umask(permissions::umask())
+                .and_then(|preset| presets.iter().position(|x| *x == preset))
+            {
+                self.restore_permissions_row.set_selected(position as u32);
+            }
+
+            self.restore_permissions_row
+                .connect_selected_notify(move |row| {
+                    if let Some(preset) = presets.get(row.selected() as usize) {
+                        permissions::set_umask(preset.umask());
+                    }
+                });
+
+            let policies = OwnershipPolicy::list();
+            let names: Vec<String> = policies.iter().map(OwnershipPolicy::name).collect();
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            self.restore_ownership_row
+                .set_model(Some(&gtk::StringList::new(&names)));
+            if let Some(position) = policies
+                .iter()
+                .position(|x| *x == OwnershipPolicy::configured())
+            {
+                self.restore_ownership_row.set_selected(position as u32);
+            }
+            self.restore_ownership_row
+                .connect_selected_notify(move |row| {
+                    if let Some(policy) = policies.get(row.selected() as usize) {
+                        policy.set_configured();
+                    }
+                });
+        }
+
         fn config(&self) -> Result<crate::config::Backup> {
             match BACKUP_CONFIG.load().try_get(self.config_id.get().unwrap()) {
                 Ok(backup) => Ok(backup.clone()),
@@ -210,6 +550,12 @@ mod imp {
             match self.config() {
                 Ok(backup) => {
                     self.obj().set_config_title(backup.title());
+                    self.obj().set_accent_color(
+                        crate::config::AccentColor::VALUES
+                            .iter()
+                            .position(|x| *x == backup.accent_color)
+                            .unwrap_or_default() as u32,
+                    );
                     self.title_pref_group.set_description(Some(&gettextf("The title of this backup configuration. Will be displayed as “{}” when left empty.", &[&backup.repo.title_fallback()])));
 
                     self.obj().set_pre_backup_command(
@@ -238,6 +584,75 @@ mod imp {
 
                     self.obj()
                         .set_schedule_run_on_battery(backup.schedule.settings.run_on_battery);
+                    self.obj().set_schedule_resume_interrupted(
+                        backup.schedule.settings.resume_interrupted,
+                    );
+                    self.obj()
+                        .set_schedule_startup_holdoff(backup.schedule.settings.startup_holdoff);
+                    self.obj()
+                        .set_schedule_jitter(backup.schedule.settings.jitter);
+                    self.obj().set_schedule_run_in_power_saver(
+                        backup.schedule.settings.run_in_power_saver,
+                    );
+                    let quiet_hours = backup.schedule.settings.quiet_hours;
+                    self.obj().set_schedule_quiet_hours(quiet_hours.is_some());
+                    self.obj().set_schedule_quiet_hours_start(
+                        quiet_hours.map_or(22, |x| chrono::Timelike::hour(&x.start)),
+                    );
+                    self.obj().set_schedule_quiet_hours_end(
+                        quiet_hours.map_or(7, |x| chrono::Timelike::hour(&x.end)),
+                    );
+                    self.obj().set_schedule_veto_command(
+                        backup
+                            .schedule
+                            .settings
+                            .veto_command
+                            .clone()
+                            .unwrap_or_default(),
+                    );
+                    self.obj()
+                        .set_low_priority_in_power_saver(backup.low_priority_in_power_saver);
+                    self.obj().set_pause_indexing(backup.pause_indexing);
+                    self.obj().set_performance(
+                        crate::config::Performance::VALUES
+                            .iter()
+                            .position(|x| *x == backup.performance)
+                            .unwrap_or_default() as u32,
+                    );
+
+                    self.obj()
+                        .set_checkpoints_enabled(backup.checkpoints.is_enabled());
+                    self.obj()
+                        .set_checkpoint_interval(backup.checkpoints.interval() / 60);
+                    self.obj()
+                        .set_retry_changed_files(backup.retry_changed_files);
+
+                    self.obj().set_skip_atime(backup.skip_metadata.atime);
+                    self.obj().set_skip_ctime(backup.skip_metadata.ctime);
+                    self.obj()
+                        .set_skip_birthtime(backup.skip_metadata.birthtime);
+                    self.obj().set_skip_flags(backup.skip_metadata.flags);
+
+                    self.obj().set_append_only(backup.append_only);
+                    self.append_only_row
+                        .set_sensitive(crate::ui::utils::append_only::is_available(backup));
+
+                    self.obj().set_write_verification(
+                        crate::config::WriteVerification::VALUES
+                            .iter()
+                            .position(|x| *x == backup.write_verification)
+                            .unwrap_or_default() as u32,
+                    );
+                    self.write_verification_pref_group
+                        .set_visible(backup.repo.is_drive_removable());
+
+                    self.obj().set_export_metrics(backup.export_metrics);
+                    self.report_dir.replace(backup.report_dir.clone());
+                    self.refresh_report_dir();
+                    self.metrics_pref_group.set_description(Some(&gettextf(
+                        "Metrics are written to “{}”.",
+                        &[&crate::ui::utils::metrics::path().display().to_string()],
+                    )));
                 }
                 Err(err) => {
                     glib::MainContext::default().spawn_local(async move {
@@ -434,6 +849,44 @@ mod imp {
             }
         }
 
+        fn refresh_report_dir(&self) {
+            let report_dir = self.report_dir.borrow();
+
+            self.report_dir_row.set_subtitle(&match &*report_dir {
+                Some(dir) => gettextf(
+                    "Written to “{}” after each run",
+                    &[&crate::config::display_path(dir)],
+                ),
+                None => gettext("Write a report file with a checksum after each run"),
+            });
+            self.report_dir_clear.set_visible(report_dir.is_some());
+        }
+
+        #[template_callback]
+        async fn choose_report_dir(&self) {
+            let chooser = crate::ui::utils::file_chooser::FileChooser::new(
+                &gettext("Select Folder for Run Reports"),
+                &gettext("Select"),
+                None,
+            );
+
+            if let Some(path) = chooser
+                .select_folder(&*self.obj())
+                .await
+                .ok()
+                .and_then(|folder| folder.path())
+            {
+                self.report_dir.replace(Some(path));
+                self.refresh_report_dir();
+            }
+        }
+
+        #[template_callback]
+        fn clear_report_dir(&self) {
+            self.report_dir.replace(None);
+            self.refresh_report_dir();
+        }
+
         #[template_callback]
         async fn change_password(&self) {
             let encrypted = self.config().map(|cfg| cfg.encrypted).unwrap_or_default();
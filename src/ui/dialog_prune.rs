@@ -23,7 +23,72 @@ pub async fn run(config: &config::Backup) -> Result<()> {
     result
 }
 
+/// Ask before old archives are deleted as part of another operation
+///
+/// Shows the same numbers as [`run`] without deleting anything. Returns whether
+/// the deletion was confirmed or there is nothing to delete.
+pub async fn confirm(config: &config::Backup) -> Result<bool> {
+    ui::dialog_device_missing::ensure_device_plugged_in(
+        config,
+        &gettext("Identifying old Archives"),
+    )
+    .await?;
+
+    let ui = DialogPrune::new();
+
+    let (decided, wait_decided) = futures::channel::oneshot::channel();
+    let decided = std::rc::Rc::new(std::cell::Cell::new(Some(decided)));
+    ui.delete().connect_clicked(enclose!((decided) move |_| {
+        if let Some(decided) = decided.take() {
+            let _ = decided.send(true);
+        }
+    }));
+    ui.dialog().connect_destroy(move |_| {
+        if let Some(decided) = decided.take() {
+            let _ = decided.send(false);
+        }
+    });
+
+    let result = match load(config, &ui).await {
+        Ok(0) => Ok(true),
+        Ok(_) => Ok(wait_decided.await.unwrap_or_default()),
+        Err(err) => Err(err),
+    };
+    ui.dialog().destroy();
+
+    result
+}
+
 async fn show(config: &config::Backup, ui: &DialogPrune) -> Result<()> {
+    load(config, ui).await?;
+
+    ui.delete()
+        .connect_clicked(clone!(@weak ui, @strong config =>
+           move |_|  Handler::new().error_transient_for(ui.dialog()).spawn(enclose!((config) async move {
+               let result = delete(ui.clone(), config.clone()).await;
+               ui.dialog().destroy();
+               result
+           }))
+        ));
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    ui.dialog().connect_destroy(|_| {
+        debug!("Destroy dialog");
+    });
+
+    Ok(())
+}
+
+/// Present the dialog with the number of archives that would be deleted
+///
+/// Returns that number.
+async fn load(config: &config::Backup, ui: &DialogPrune) -> Result<usize> {
     ui.dialog().set_transient_for(Some(&main_ui().window()));
     ui.dialog().present();
 
@@ -58,32 +123,21 @@ async fn show(config: &config::Backup, ui: &DialogPrune) -> Result<()> {
 
     ui.stack().set_visible_child(&ui.page_decision());
 
-    ui.delete()
-        .connect_clicked(clone!(@weak ui, @strong config =>
-           move |_|  Handler::new().error_transient_for(ui.dialog()).spawn(enclose!((config) async move {
-               let result = delete(ui.clone(), config.clone()).await;
-               ui.dialog().destroy();
-               result
-           }))
-        ));
+    Ok(prune_info.prune)
+}
 
-    // ensure lifetime until window closes
-    let mutex = std::sync::Mutex::new(Some(ui.clone()));
-    ui.dialog().connect_close_request(move |_| {
-        *mutex.lock().unwrap() = None;
-        glib::Propagation::Proceed
-    });
+async fn delete(ui: DialogPrune, config: config::Backup) -> Result<()> {
+    ui.dialog().destroy();
 
-    ui.dialog().connect_destroy(|_| {
-        debug!("Destroy dialog");
-    });
+    ui::utils::append_only::lifted(&config, prune_and_compact(&config)).await?;
+
+    let _ignore = ui::page_archives::cache::refresh_archives(config.clone(), None).await;
+    let _ignore = ui::utils::df::lookup_and_cache(&config).await;
 
     Ok(())
 }
 
-async fn delete(ui: DialogPrune, config: config::Backup) -> Result<()> {
-    ui.dialog().destroy();
-
+async fn prune_and_compact(config: &config::Backup) -> Result<()> {
     let guard = QuitGuard::default();
     let result = ui::utils::borg::exec(
         borg::Command::<borg::task::Prune>::new(config.clone()),
@@ -105,8 +159,5 @@ async fn delete(ui: DialogPrune, config: config::Backup) -> Result<()> {
         result.into_message(gettext("Reclaim Free Space"))?;
     }
 
-    let _ignore = ui::page_archives::cache::refresh_archives(config.clone(), None).await;
-    let _ignore = ui::utils::df::lookup_and_cache(&config).await;
-
     Ok(())
 }
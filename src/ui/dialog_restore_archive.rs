@@ -0,0 +1,319 @@
+use adw::prelude::*;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use crate::ui;
+use crate::ui::prelude::*;
+use crate::utils::flatpak_apps;
+use ui::builder::DialogRestoreArchive;
+use ui::utils::restore::{self, ConflictPolicy, Ownership, OwnershipPolicy};
+
+/// Maximum number of affected locations listed in the preview
+const MAX_PREVIEW_ROOTS: usize = 20;
+
+/// `owners` are the archived owners from [`restore::owners`], empty unless the
+/// original owners are kept
+pub async fn run(
+    path: PathBuf,
+    archive_name: &str,
+    archive_date: &str,
+    owners: BTreeMap<PathBuf, restore::Owner>,
+) -> Result<()> {
+    let ui = DialogRestoreArchive::new();
+
+    let (destroyed, wait_destroyed) = futures::channel::oneshot::channel();
+    let destroyed = std::cell::Cell::new(Some(destroyed));
+    ui.dialog().connect_destroy(move |_| {
+        if let Some(destroyed) = destroyed.take() {
+            let _ = destroyed.send(());
+        }
+    });
+
+    let result = show(path, archive_name, archive_date, owners, &ui).await;
+    if result.is_err() {
+        ui.dialog().destroy();
+        return result;
+    }
+
+    // Callers keep the archive available until no more files are copied from it
+    let _ = wait_destroyed.await;
+
+    Ok(())
+}
+
+async fn show(
+    path: PathBuf,
+    archive_name: &str,
+    archive_date: &str,
+    owners: BTreeMap<PathBuf, restore::Owner>,
+    ui: &DialogRestoreArchive,
+) -> Result<()> {
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+    ui.dialog().present();
+
+    ui.name().set_label(archive_name);
+    ui.date().set_label(archive_date);
+
+    let policy_names: Vec<String> = ConflictPolicy::list()
+        .iter()
+        .map(ConflictPolicy::name)
+        .collect();
+    let policy_names: Vec<&str> = policy_names.iter().map(String::as_str).collect();
+    ui.conflict_policy()
+        .set_model(Some(&gtk::StringList::new(&policy_names)));
+
+    // Owners are only known if the original owners are kept
+    let foreign_files = Ownership::new(Default::default(), owners.clone()).foreign_files();
+    if foreign_files > 0 {
+        ui.ownership().set_visible(true);
+        ui.ownership_policy().set_subtitle(&ngettextf_(
+            "One file belongs to a different user",
+            "{} files belong to different users",
+            foreign_files as u32,
+        ));
+    }
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    ui.dialog().connect_destroy(|_| {
+        debug!("Destroy dialog");
+    });
+
+    let preview = ui::utils::spawn_thread(
+        "restore_preview",
+        enclose!((path) move || restore::preview(&path)),
+    )
+    .await?;
+
+    ui.summary().set_subtitle(&ngettextf(
+        "One file, {}",
+        "{} files, {}",
+        preview.files as u32,
+        &[
+            &preview.files.to_string(),
+            &glib::format_size(preview.bytes),
+        ],
+    ));
+
+    if preview.conflicts == 0 {
+        ui.conflict_policy()
+            .set_subtitle(&gettext("No existing files will be affected"));
+    } else {
+        ui.conflict_policy().set_subtitle(&ngettextf_(
+            "One file already exists",
+            "{} files already exist",
+            preview.conflicts as u32,
+        ));
+    }
+
+    for root in preview.roots.iter().take(MAX_PREVIEW_ROOTS) {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(root.display().to_string())
+            .build();
+
+        if let Some(image) = crate::utils::file_symbolic_icon(root) {
+            image.add_css_class("row-icon");
+            row.add_prefix(&image);
+        }
+
+        ui.affected().append(&row);
+    }
+
+    if preview.roots.len() > MAX_PREVIEW_ROOTS {
+        let more = (preview.roots.len() - MAX_PREVIEW_ROOTS) as u32;
+        ui.affected().append(
+            &adw::ActionRow::builder()
+                .title(ngettextf_("One more location", "{} more locations", more))
+                .build(),
+        );
+    }
+
+    let apps = ui::utils::spawn_thread(
+        "restore_find_apps",
+        enclose!((path) move || flatpak_apps::in_archive(&path)),
+    )
+    .await?;
+
+    for app in apps {
+        let button = gtk::Button::builder()
+            .label(gettext("Restore"))
+            .valign(gtk::Align::Center)
+            .build();
+
+        let row = ui::dialog_flatpak_apps::app_row(&app);
+        row.add_suffix(&button);
+        ui.app_data().add(&row);
+        ui.app_data().set_visible(true);
+
+        button.connect_clicked(clone!(@weak ui => move |button| {
+            button.set_sensitive(false);
+            Handler::new()
+                .error_transient_for(ui.dialog())
+                .spawn(enclose!((ui, app) async move { restore_app(&ui, app).await }));
+        }));
+    }
+
+    ui.restore().set_sensitive(preview.files > 0);
+    ui.stack().set_visible_child(&ui.page_decision());
+
+    let total = preview.files;
+    ui.restore()
+        .connect_clicked(clone!(@weak ui, @strong path, @strong owners => move |_| {
+            let ownership = Ownership::new(OwnershipPolicy::configured(), owners.clone());
+            Handler::new()
+                .error_transient_for(ui.dialog())
+                .spawn(enclose!((ui, path) async move {
+                    let result = restore_files(&ui, path, ownership, total).await;
+                    ui.dialog().destroy();
+                    result
+                }));
+        }));
+
+    Ok(())
+}
+
+fn selected_policy(ui: &DialogRestoreArchive) -> ConflictPolicy {
+    ConflictPolicy::list()
+        .get(ui.conflict_policy().selected() as usize)
+        .copied()
+        .unwrap_or(ConflictPolicy::Skip)
+}
+
+/// Copy the data of an app to the app data folder of the current user
+async fn restore_app(ui: &DialogRestoreArchive, app: flatpak_apps::App) -> Result<()> {
+    let _guard = QuitGuard::default();
+
+    let policy = selected_policy(ui);
+    let destination = app.data_dir(&glib::home_dir());
+
+    let summary = ui::utils::spawn_thread(
+        "restore_app",
+        enclose!((destination) move || {
+            restore::restore_path_to(&app.path, &destination, policy, &Default::default())
+        }),
+    )
+    .await?;
+
+    if let Some((path, err)) = summary.failed.first() {
+        return Err(Message::new(
+            gettext("Failed to Restore App Data"),
+            format!("{}: {}", path.display(), err),
+        )
+        .into());
+    }
+
+    ui::utils::show_notice(gettextf(
+        "Restored “{}”",
+        &[&destination.display().to_string()],
+    ));
+
+    Ok(())
+}
+
+async fn restore_files(
+    ui: &DialogRestoreArchive,
+    path: PathBuf,
+    ownership: Ownership,
+    total: u64,
+) -> Result<()> {
+    let _guard = QuitGuard::default();
+
+    let policy = selected_policy(ui);
+
+    let progress = Arc::new(restore::Progress::default());
+
+    ui.abort()
+        .connect_clicked(enclose!((progress) move |button| {
+            button.set_sensitive(false);
+            progress.cancel.store(true, Ordering::Relaxed);
+        }));
+    // The dialog is destroyed once the copy has stopped
+    ui.dialog().connect_close_request(
+        clone!(@weak ui, @strong progress => @default-return glib::Propagation::Proceed, move |_| {
+            ui.abort().set_sensitive(false);
+            progress.cancel.store(true, Ordering::Relaxed);
+            glib::Propagation::Stop
+        }),
+    );
+
+    ui.stack().set_visible_child(&ui.page_progress());
+
+    let update_progress = glib::source::timeout_add_local(
+        std::time::Duration::from_millis(250),
+        clone!(@weak ui, @strong progress => @default-return glib::ControlFlow::Break, move || {
+            let done = progress.files_done.load(Ordering::Relaxed);
+            ui.progress().set_fraction(done as f64 / total.max(1) as f64);
+            ui.progress().set_text(Some(&gettextf(
+                "{} of {} files",
+                &[&done.to_string(), &total.to_string()],
+            )));
+            glib::ControlFlow::Continue
+        }),
+    );
+
+    let summary = ui::utils::spawn_thread(
+        "restore_archive",
+        enclose!((progress) move || restore::restore(&path, policy, &ownership, &progress)),
+    )
+    .await;
+
+    update_progress.remove();
+    let summary = summary?;
+
+    info!(
+        "Restored {} files, skipped {}, {} errors, {} with adjusted ownership",
+        summary.restored,
+        summary.skipped,
+        summary.failed.len(),
+        summary.ownership_adjusted
+    );
+
+    if !summary.failed.is_empty() {
+        let failed = summary
+            .failed
+            .iter()
+            .take(10)
+            .map(|(path, err)| format!("{}: {}", path.display(), err))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        return Err(Message::new(
+            ngettextf_(
+                "One File Could Not Be Restored",
+                "{} Files Could Not Be Restored",
+                summary.failed.len() as u32,
+            ),
+            failed,
+        )
+        .into());
+    }
+
+    if !summary.cancelled {
+        let mut notice = ngettextf_(
+            "Restored one file",
+            "Restored {} files",
+            summary.restored as u32,
+        );
+
+        if summary.ownership_adjusted > 0 {
+            notice.push_str(". ");
+            notice.push_str(&ngettextf_(
+                "The owner of one file has been changed to the current user.",
+                "The owner of {} files has been changed to the current user.",
+                summary.ownership_adjusted as u32,
+            ));
+        }
+
+        ui::utils::show_notice(notice);
+    }
+
+    Ok(())
+}
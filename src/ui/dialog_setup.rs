@@ -2,9 +2,13 @@ pub mod add_task;
 mod display;
 mod event;
 pub mod folder_button;
+mod hosted;
 mod insert;
 mod remote_location;
 
+pub use insert::mount_fuse_and_config;
+pub use remote_location::RemoteLocation;
+
 use adw::prelude::*;
 use async_std::stream::StreamExt;
 
@@ -16,9 +20,59 @@ use ui::builder::DialogSetup;
 const LISTED_URI_SCHEMES: &[&str] = &["file", "smb", "sftp", "ssh"];
 
 pub fn show() {
+    show_dialog(false);
+}
+
+/// Setup an existing repository and restore the files of a previous computer
+pub fn show_restore() {
+    show_dialog(true);
+}
+
+/// Transfer settings from a previous archive to an existing backup configuration
+pub fn show_import_settings(config_id: &ConfigId) -> Result<()> {
+    let config = BACKUP_CONFIG.load().try_get(config_id)?.clone();
     let ui = DialogSetup::new();
 
     ui.dialog().set_transient_for(Some(&main_ui().window()));
+    ui.dialog().set_title(Some(&gettext("Import Settings")));
+    ui.add_task().set_import_settings(true);
+
+    ui.prefix_submit()
+        .connect_map(clone!(@weak ui => move |x| ui.dialog().set_default_widget(Some(x))));
+
+    ui.transfer_pending_spinner().connect_map(|s| s.start());
+    ui.transfer_pending_spinner().connect_unmap(|s| s.stop());
+
+    ui.navigation_view().replace(&[ui.page_transfer()]);
+
+    let dialog = ui.dialog();
+    execute(insert::import_settings(ui.clone(), config), dialog.clone());
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui));
+    dialog.connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    dialog.present();
+
+    Ok(())
+}
+
+fn show_dialog(restore: bool) {
+    let ui = DialogSetup::new();
+
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+
+    if restore {
+        ui.add_task().set_restore(true);
+        ui.dialog()
+            .set_title(Some(&gettext("Restore From Existing Backup")));
+        ui.page_overview()
+            .set_title(&gettext("Restore From Existing Backup"));
+        ui.init_group().set_visible(false);
+    }
 
     // Default buttons
 
@@ -61,6 +115,10 @@ pub fn show() {
     ui.init_path()
         .connect_folder_change(clone!(@weak ui => move || event::path_change(&ui)));
 
+    hosted::init(&ui);
+    ui.provider_create()
+        .connect_clicked(clone!(@weak ui => move |_| event::create_hosted_repo(&ui)));
+
     // Page Setup Encryption
     ui.add_button().connect_clicked(
         clone!(@weak ui => move |_| execute(event::add_remote(ui.clone()), ui.dialog())),
@@ -78,6 +136,9 @@ pub fn show() {
     ui.page_password_continue()
         .connect_clicked(clone!(@weak ui => move |_| run(event::page_password_continue(ui))));
 
+    ui.ask_password()
+        .connect_changed(clone!(@weak ui => move |_| display::hide_password_error(&ui)));
+
     ui.page_password_stack().connect_visible_child_notify(
         clone!(@weak ui => move |_| event::navigation_view_changed(&ui)),
     );
@@ -116,6 +177,8 @@ pub fn show() {
     let mutex = std::sync::Mutex::new(Some((ui, volume_monitor)));
     dialog.connect_close_request(move |_| {
         *mutex.lock().unwrap() = None;
+        // Hosted repositories are not needed if the setup is not completed
+        glib::MainContext::default().spawn_local(hosted::discard());
         glib::Propagation::Proceed
     });
 
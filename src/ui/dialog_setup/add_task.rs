@@ -47,12 +47,28 @@ macro_rules! obj {
     };
 }
 
+/// Archive that is restored after the setup is complete
+#[derive(Clone, Debug)]
+pub struct RestoreArchive {
+    pub name: crate::borg::ArchiveName,
+    pub date: String,
+}
+
 obj!(
     name => "PikaAddConfigTask",
     type => AddConfigTask,
     properties => [
         name => repo,
         type => Option<crate::config::Repository>,
-        setter => set_repo
+        setter => set_repo,
+        name => restore,
+        type => bool,
+        setter => set_restore,
+        name => restore_archive,
+        type => Option<RestoreArchive>,
+        setter => set_restore_archive,
+        name => import_settings,
+        type => bool,
+        setter => set_import_settings
     ],
 );
@@ -5,6 +5,7 @@ use itertools::Itertools;
 use std::collections::BTreeSet;
 use std::fmt::Write;
 
+use super::add_task;
 use crate::borg;
 use crate::config;
 use crate::ui;
@@ -12,6 +13,7 @@ use ui::builder::DialogSetup;
 
 #[derive(Clone, Debug)]
 struct ArchiveParams {
+    name: borg::ArchiveName,
     prefix: Option<config::ArchivePrefix>,
     parsed: borg::invert_command::Parsed,
     hostname: String,
@@ -30,6 +32,7 @@ fn extract_archive_params(archive: borg::ListArchive) -> ArchiveParams {
     let parsed = borg::invert_command::parse(archive.command_line);
 
     ArchiveParams {
+        name: archive.name,
         prefix,
         parsed,
         hostname: archive.hostname,
@@ -62,8 +65,16 @@ pub fn transfer_selection(
         .unique_by(|x| (&x.prefix, &x.parsed, &x.hostname, &x.username))
         .peekable();
 
-    if options.peek().is_none() {
+    if options.peek().is_none() && ui.add_task().import_settings() {
         ui.dialog().close();
+
+        ui::utils::show_notice(gettext(
+            "The backup repository contains no previous configurations to import.",
+        ));
+    } else if options.peek().is_none() {
+        ui.dialog().close();
+
+        restore_after_setup(ui, config_id, archive_params.first());
     } else {
         for suggestion in options.take(10) {
             let row = ui::builder::DialogSetupTransferOption::new();
@@ -90,7 +101,7 @@ pub fn transfer_selection(
 
             row.transfer().connect_activated(
                 clone!(@weak ui, @strong suggestion, @strong config_id => move |_|
-                Handler::handle(insert_transfer(ui, &suggestion, &config_id))
+                Handler::run(insert_transfer(ui.clone(), suggestion.clone(), config_id.clone()))
                 ),
             );
 
@@ -102,11 +113,18 @@ pub fn transfer_selection(
     }
 }
 
-fn insert_transfer(
+async fn insert_transfer(
     ui: DialogSetup,
-    archive_params: &ArchiveParams,
-    config_id: &ConfigId,
+    archive_params: ArchiveParams,
+    config_id: ConfigId,
 ) -> Result<()> {
+    let archive_params = &archive_params;
+    let config_id = &config_id;
+
+    if ui.add_task().import_settings() {
+        confirm_replace(archive_params, config_id).await?;
+    }
+
     BACKUP_CONFIG.try_update(enclose!((archive_params, config_id) move |config| {
         let conf = config.try_get_mut(&config_id)?;
 
@@ -116,25 +134,15 @@ fn insert_transfer(
         Ok(())
     }))?;
 
-    let entry = config::history::RunInfo {
-        end: archive_params
-            .end
-            .and_local_timezone(chrono::Local)
-            .unwrap(),
-        outcome: borg::Outcome::Completed {
-            stats: archive_params.stats.clone(),
-        },
-        messages: Default::default(),
-        include: archive_params.parsed.include.clone(),
-        exclude: archive_params.parsed.exclude.clone(),
-    };
-
-    BACKUP_HISTORY.try_update(enclose!((config_id) move |histories| {
-        histories.insert(config_id.clone(), entry.clone());
-        Ok(())
-    }))?;
-
     // Create fake history entry for duration estimate to be good for first run
+    if !ui.add_task().import_settings() {
+        insert_history_mock(archive_params, config_id)?;
+    }
+
+    if ui.add_task().restore() {
+        ui.add_task()
+            .set_restore_archive(Some(restore_archive(archive_params)));
+    }
 
     ui::page_backup::refresh()?;
 
@@ -156,6 +164,66 @@ fn insert_transfer(
     Ok(())
 }
 
+/// Ask before replacing the folders of an existing configuration
+async fn confirm_replace(archive_params: &ArchiveParams, config_id: &ConfigId) -> Result<()> {
+    let config = BACKUP_CONFIG.load().try_get(config_id)?.clone();
+
+    let exclude = BTreeSet::from_iter(
+        archive_params
+            .parsed
+            .exclude
+            .clone()
+            .into_iter()
+            .map(|x| x.into_relative()),
+    );
+
+    let is_empty = config.include.is_empty() && config.exclude.is_empty();
+    let is_same = config.include == archive_params.parsed.include && config.exclude == exclude;
+
+    if is_empty || is_same {
+        return Ok(());
+    }
+
+    ui::confirm::confirm(
+        ui::confirm::Category::Remove,
+        &gettext("Replace Included and Excluded Folders?"),
+        &gettextf(
+            "The folders currently configured for “{}” will be replaced by the ones from the selected archive.",
+            &[&config.title()],
+        ),
+        &gettext("Cancel"),
+        &gettext("Replace"),
+    )
+    .await
+}
+
+fn insert_history_mock(archive_params: &ArchiveParams, config_id: &ConfigId) -> Result<()> {
+    let entry = config::history::RunInfo {
+        start: None,
+        end: archive_params
+            .end
+            .and_local_timezone(chrono::Local)
+            .unwrap(),
+        outcome: borg::Outcome::Completed {
+            stats: archive_params.stats.clone(),
+        },
+        messages: Default::default(),
+        include: archive_params.parsed.include.clone(),
+        exclude: archive_params.parsed.exclude.clone(),
+        versions: None,
+        verified: false,
+        sent: None,
+        received: None,
+    };
+
+    BACKUP_HISTORY.try_update(enclose!((config_id) move |histories| {
+        histories.insert(config_id.clone(), entry.clone());
+        Ok(())
+    }))?;
+
+    Ok(())
+}
+
 pub fn set_prefix(ui: &DialogSetup, config_id: ConfigId) -> Result<()> {
     let prefix = ui.prefix().text();
 
@@ -171,12 +239,51 @@ pub fn set_prefix(ui: &DialogSetup, config_id: ConfigId) -> Result<()> {
 
     ui.dialog().close();
 
+    if let Some(archive) = ui.add_task().restore_archive() {
+        Handler::run(restore(config_id, archive));
+    }
+
     Ok(())
 }
 
+fn restore_archive(archive_params: &ArchiveParams) -> add_task::RestoreArchive {
+    add_task::RestoreArchive {
+        name: archive_params.name.clone(),
+        date: archive_params
+            .end
+            .to_locale()
+            .unwrap_or_else(|| archive_params.end.to_string()),
+    }
+}
+
+/// Restore without transferring settings if no previous configuration was detected
+fn restore_after_setup(ui: &DialogSetup, config_id: ConfigId, newest: Option<&ArchiveParams>) {
+    if ui.add_task().restore() {
+        if let Some(archive_params) = newest {
+            Handler::run(restore(config_id, restore_archive(archive_params)));
+        } else {
+            ui::utils::show_notice(gettext(
+                "The backup repository contains no archives to restore.",
+            ));
+        }
+    }
+}
+
+/// Restore the files of the previous computer, then continue with the schedule setup
+async fn restore(config_id: ConfigId, archive: add_task::RestoreArchive) -> Result<()> {
+    let config = BACKUP_CONFIG.load().try_get(&config_id)?.clone();
+
+    ui::page_schedule::view(&config_id);
+
+    let owners = ui::utils::restore::owners(&config, &archive.name).await;
+    let path = ui::page_archives::mount_archive(&config, &archive.name).await?;
+    ui::dialog_restore_archive::run(path, archive.name.as_str(), &archive.date, owners).await
+}
+
 pub fn show_init_remote(ui: &ui::builder::DialogSetup) {
     ui.location_group_local().set_visible(false);
     ui.location_group_remote().set_visible(true);
+    ui.provider_group().set_visible(true);
     show_init(ui);
 }
 
@@ -188,6 +295,7 @@ pub fn show_init_local(ui: &ui::builder::DialogSetup, path: Option<&std::path::P
 
     ui.location_group_local().set_visible(true);
     ui.location_group_remote().set_visible(false);
+    ui.provider_group().set_visible(false);
     show_init(ui);
 }
 
@@ -262,8 +370,22 @@ pub fn pending_check(ui: &DialogSetup) {
     }
 }
 
-pub fn ask_password(ui: &DialogSetup) {
+/// Show the password page, with an inline error if the entered password was wrong
+pub fn ask_password(ui: &DialogSetup, wrong_password: bool) {
     ui.page_password().set_can_pop(true);
     ui.page_password_stack()
         .set_visible_child(&ui.page_password_input());
+
+    if wrong_password {
+        ui.ask_password_error().set_visible(true);
+        ui.ask_password().add_css_class("error");
+        ui.ask_password().select_region(0, -1);
+    } else {
+        hide_password_error(ui);
+    }
+}
+
+pub fn hide_password_error(ui: &DialogSetup) {
+    ui.ask_password_error().set_visible(false);
+    ui.ask_password().remove_css_class("error");
 }
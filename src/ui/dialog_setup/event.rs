@@ -12,6 +12,7 @@ pub fn navigation_view_changed(ui: &DialogSetup) {
         if visible_page == ui.page_overview() {
             ui.init_path().reset();
             ui.location_url().set_text("");
+            ui.provider_token().set_text("");
             ui.encryption_preferences_group().reset(true);
         }
 
@@ -68,6 +69,7 @@ pub fn show_add_remote(ui: &DialogSetup) {
     ui.button_stack().set_visible_child(&ui.add_button());
     ui.location_group_local().set_visible(false);
     ui.location_group_remote().set_visible(true);
+    ui.provider_group().set_visible(false);
     ui.navigation_view().push(&ui.page_detail());
 }
 
@@ -83,6 +85,10 @@ pub fn add_local(ui: &DialogSetup, path: Option<&std::path::Path>) {
     }
 }
 
+pub fn create_hosted_repo(ui: &DialogSetup) {
+    execute(super::hosted::create(ui.clone()), ui.dialog());
+}
+
 pub async fn add_remote(ui: DialogSetup) -> Result<()> {
     insert::add_button_clicked(ui).await
 }
@@ -0,0 +1,166 @@
+//! Create a repository at a hosting provider during the setup
+//!
+//! The created repository is deleted again if the setup is not completed, such
+//! that no unused repositories are left behind at the provider.
+
+use adw::prelude::*;
+
+use std::cell::RefCell;
+
+use super::insert;
+use crate::config;
+use crate::provider::{self, borgbase, Provider};
+use crate::ui;
+use crate::ui::builder::DialogSetup;
+use crate::ui::prelude::*;
+
+thread_local! {
+    /// Repository created in the running setup and the token to manage it
+    static CREATED: RefCell<Option<(String, config::remote::Hosted)>> = Default::default();
+}
+
+/// Show the available regions
+pub fn init(ui: &DialogSetup) {
+    let names: Vec<String> = borgbase::Region::list()
+        .iter()
+        .map(borgbase::Region::name)
+        .collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    ui.provider_region()
+        .set_model(Some(&gtk::StringList::new(&names)));
+}
+
+fn selected_region(ui: &DialogSetup) -> borgbase::Region {
+    borgbase::Region::list()
+        .get(ui.provider_region().selected() as usize)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Create the repository and continue the setup with its location
+pub async fn create(ui: DialogSetup) -> Result<()> {
+    let token = ui.provider_token().text().trim().to_string();
+    if token.is_empty() {
+        return Err(Message::new(
+            gettext("API Token Required"),
+            gettext("Enter an API token to create a hosted repository."),
+        )
+        .into());
+    }
+
+    let name = ui.init_dir().text().trim().to_string();
+    if name.is_empty() {
+        return Err(Message::new(
+            gettext("Repository Name Required"),
+            gettext("Enter a name for the hosted repository."),
+        )
+        .into());
+    }
+
+    let provider = borgbase::BorgBase::new(token.clone(), selected_region(&ui));
+    let error_title = gettextf("Failed to Create Repository at {}", &[&provider.name()]);
+
+    ui.provider_group().set_sensitive(false);
+    ui.location_group_remote().set_sensitive(false);
+    scopeguard::defer!({
+        ui.provider_group().set_sensitive(true);
+        ui.location_group_remote().set_sensitive(true);
+    });
+
+    let reusable = CREATED.with(|created| {
+        created
+            .borrow()
+            .as_ref()
+            .filter(|(created_token, hosted)| {
+                *created_token == token && hosted.repository.name == name
+            })
+            .map(|(_, hosted)| hosted.repository.clone())
+    });
+
+    let repo = if let Some(repo) = reusable {
+        debug!("Reusing hosted repository {:?}", repo);
+        repo
+    } else {
+        // A repository from a previous try with different details is not needed anymore
+        discard().await;
+
+        let public_key = provider::ssh_public_key().err_to_msg(&error_title)?;
+        let repo = provider
+            .create_repository(&name, &public_key)
+            .await
+            .err_to_msg(&error_title)?;
+        info!("Created hosted repository {:?}", repo);
+
+        CREATED.with(|created| {
+            *created.borrow_mut() = Some((
+                token,
+                config::remote::Hosted {
+                    provider: provider.name(),
+                    repository: repo.clone(),
+                },
+            ))
+        });
+
+        repo
+    };
+
+    let result = configure(&ui, &provider, &repo).await;
+    if result.is_err() {
+        discard().await;
+    }
+
+    result.err_to_msg(&error_title)?;
+    insert::validate_detail_page(ui.clone()).await
+}
+
+async fn configure(
+    ui: &DialogSetup,
+    provider: &impl Provider,
+    repo: &provider::RemoteRepository,
+) -> provider::Result<()> {
+    let ssh_details = provider.ssh_details(repo).await?;
+    ui.location_url().set_text(&ssh_details.url());
+
+    let quota = provider.quota(repo).await?;
+    if let Some(limit) = quota.limit {
+        ui::utils::show_notice(gettextf(
+            "The repository “{}” can store up to {}",
+            &[&repo.name, &glib::format_size(limit)],
+        ));
+    }
+
+    Ok(())
+}
+
+/// The created repository if it is at `url`
+pub fn hosted(url: &str) -> Option<config::remote::Hosted> {
+    CREATED.with(|created| {
+        created
+            .borrow()
+            .as_ref()
+            .map(|(_, hosted)| hosted.clone())
+            .filter(|hosted| {
+                provider::SshDetails::from_borg_location(&hosted.repository.url)
+                    .is_some_and(|details| details.url() == url)
+                    || hosted.repository.url == url
+            })
+    })
+}
+
+/// The created repository is used by a stored backup configuration
+pub fn keep() {
+    CREATED.with(|created| created.take());
+}
+
+/// Delete the created repository since the setup did not complete
+pub async fn discard() {
+    let Some((token, hosted)) = CREATED.with(|created| created.take()) else {
+        return;
+    };
+
+    info!("Deleting unused hosted repository {:?}", hosted.repository);
+    let provider = borgbase::BorgBase::new(token, Default::default());
+    if let Err(err) = provider.delete_repository(&hosted.repository).await {
+        warn!("Failed to delete hosted repository: {}", err);
+    }
+}
@@ -108,7 +108,9 @@ async fn get_repo(ui: &builder::DialogSetup) -> Result<Repository> {
             .err_to_msg(gettext("Invalid Remote Location"))?;
 
         if remote_location.is_borg_host() {
-            Ok(config::remote::Repository::from_uri(remote_location.url()).into_config())
+            let mut repo = config::remote::Repository::from_uri(remote_location.url());
+            repo.hosted = super::hosted::hosted(&repo.uri);
+            Ok(repo.into_config())
         } else {
             mount_fuse_and_config(&remote_location.as_gio_file(), true)
                 .await
@@ -136,6 +138,102 @@ async fn init_repo(ui: builder::DialogSetup) -> Result<()> {
 
     ui.navigation_view().push(&ui.page_creating());
 
+    // Only remove the directory again if it is created by borg
+    let created_dir = match &repo {
+        config::Repository::Local(local) if !local.path().exists() => Some(local.path()),
+        _ => None,
+    };
+
+    let communication = borg::Communication::<borg::task::Generic>::default();
+    let canceled = Rc::new(std::cell::Cell::new(false));
+    let initialized = std::cell::Cell::new(false);
+
+    ui.creating_cancel().set_sensitive(true);
+    let handler =
+        ui.creating_cancel()
+            .connect_clicked(enclose!((communication, canceled) move |button| {
+                info!("Canceling repository creation");
+                button.set_sensitive(false);
+                canceled.set(true);
+                communication.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+            }));
+
+    let result = create_repo(
+        repo.clone(),
+        encrypted,
+        password.clone(),
+        communication,
+        &canceled,
+        &initialized,
+    )
+    .await;
+    ui.creating_cancel().disconnect(handler);
+
+    match result {
+        Ok(config) => {
+            super::hosted::keep();
+            ui::page_backup::view_backup_conf(&config.id);
+            Ok(())
+        }
+        Err(err) => {
+            if let Some(path) = created_dir {
+                debug!("Removing partially created repository {:?}", path);
+                if let Err(err) = async_std::fs::remove_dir_all(&path).await {
+                    warn!("Failed to remove {:?}: {}", path, err);
+                }
+            }
+
+            // A remote repository can only be removed by borg once it is complete.
+            // Since creating fails for existing repositories, it was created by us.
+            if initialized.get() && matches!(repo, config::Repository::Remote(_)) {
+                remove_remote_repo(repo, encrypted, password).await;
+            }
+
+            if canceled.get() {
+                Err(Message::new(
+                    gettext("Repository Creation Canceled"),
+                    gettext("The backup setup has not been added."),
+                )
+                .into())
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Delete a remote repository after its creation was canceled
+async fn remove_remote_repo(repo: config::Repository, encrypted: bool, password: config::Password) {
+    debug!("Removing created remote repository {}", repo);
+
+    let mut borg = borg::CommandOnlyRepo::new(repo);
+    if encrypted {
+        borg.set_password(password);
+    }
+
+    let result =
+        ui::utils::borg::exec_repo_only(&gettext("Removing Backup Repository"), borg, |borg| {
+            borg.delete_repository()
+        })
+        .await;
+
+    if let Err(err) = result {
+        warn!("Failed to remove created remote repository: {}", err);
+    }
+}
+
+/// Initialize the repository and add the backup configuration
+///
+/// The configuration and password are removed again if `canceled` is set before
+/// everything is stored. `initialized` is set once the repository exists.
+async fn create_repo(
+    repo: config::Repository,
+    encrypted: bool,
+    password: config::Password,
+    communication: borg::Communication<borg::task::Generic>,
+    canceled: &std::cell::Cell<bool>,
+    initialized: &std::cell::Cell<bool>,
+) -> Result<config::Backup> {
     let mut borg = borg::CommandOnlyRepo::new(repo.clone());
     if encrypted {
         borg.set_password(password.clone());
@@ -144,19 +242,28 @@ async fn init_repo(ui: builder::DialogSetup) -> Result<()> {
     ui::utils::borg::exec_repo_only(
         &gettext("Creating Backup Repository"),
         borg.clone(),
-        |borg| borg.init(),
+        enclose!((communication) move |borg| borg.init(communication)),
     )
     .await
     .into_message("Failed to Initialize Repository")?;
+    initialized.set(true);
+
+    if canceled.get() {
+        return Err(Error::UserCanceled);
+    }
 
     // Get repo id
     let info =
         ui::utils::borg::exec_repo_only(&gettext("Getting Repository Information"), borg, |borg| {
-            borg.peek()
+            borg.peek_abortable(communication)
         })
         .await
         .into_message("Failed to Obtain Repository Information")?;
 
+    if canceled.get() {
+        return Err(Error::UserCanceled);
+    }
+
     let config = config::Backup::new(repo.clone(), info, encrypted);
 
     insert_backup_config(config.clone())?;
@@ -166,9 +273,23 @@ async fn init_repo(ui: builder::DialogSetup) -> Result<()> {
             err.show().await;
         }
     }
-    ui::page_backup::view_backup_conf(&config.id);
 
-    Ok(())
+    if canceled.get() {
+        BACKUP_CONFIG.try_update(|s| {
+            s.remove(&config.id)?;
+            Ok(())
+        })?;
+
+        if encrypted {
+            if let Err(err) = ui::utils::password_storage::remove_password(&config, false).await {
+                warn!("Failed to remove password: {:?}", err);
+            }
+        }
+
+        return Err(Error::UserCanceled);
+    }
+
+    Ok(config)
 }
 
 pub async fn add_first_try(mut repo: config::Repository, ui: builder::DialogSetup) -> Result<()> {
@@ -188,16 +309,29 @@ pub async fn add(ui: builder::DialogSetup) -> Result<()> {
     let repo = ui.add_task().repo().unwrap();
 
     let mut borg = borg::CommandOnlyRepo::new(repo.clone());
+    let password = ui.ask_password().text().to_string();
 
-    if !ui.ask_password().text().is_empty() {
-        borg.password = Some(config::Password::new(ui.ask_password().text().to_string()));
+    if !password.is_empty() {
+        borg.password = Some(config::Password::new(password.clone()));
     }
 
+    let communication = borg::Communication::<borg::task::Generic>::default();
+
+    ui.pending_cancel().set_sensitive(true);
+    let handler = ui
+        .pending_cancel()
+        .connect_clicked(enclose!((communication) move |button| {
+            info!("Canceling repository check");
+            button.set_sensitive(false);
+            communication.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+        }));
+
     let result =
         ui::utils::borg::exec_repo_only(&gettext("Loading Backup Repository"), borg, |borg| {
-            borg.peek()
+            borg.peek_abortable(communication)
         })
         .await;
+    ui.pending_cancel().disconnect(handler);
 
     if matches!(
         result,
@@ -205,7 +339,8 @@ pub async fn add(ui: builder::DialogSetup) -> Result<()> {
             borg::Failure::PassphraseWrong
         )))
     ) {
-        display::ask_password(&ui);
+        // Only complain about the password if one has been entered
+        display::ask_password(&ui, !password.is_empty());
 
         return Err(Error::UserCanceled);
     }
@@ -216,26 +351,51 @@ pub async fn add(ui: builder::DialogSetup) -> Result<()> {
 
     let info = result.into_message(gettext("Failed to Configure Repository"))?;
 
-    let encrypted = !ui.ask_password().text().is_empty();
+    debug!("Repository encryption mode: {}", info.encryption.mode);
+
+    // Unencrypted repositories and keys without passphrase never need a password
+    let encrypted = info.encryption.mode != "none" && !password.is_empty();
 
     let config = config::Backup::new(repo.clone(), info, encrypted);
     insert_backup_config(config.clone())?;
     ui::page_backup::view_backup_conf(&config.id);
-    ui::utils::password_storage::store_password(
-        &config,
-        &config::Password::new(ui.ask_password().text().to_string()),
-    )
-    .await?;
+
+    if encrypted {
+        ui::utils::password_storage::store_password(&config, &config::Password::new(password))
+            .await?;
+    }
 
     ui.navigation_view().push(&ui.page_transfer());
+    transfer_suggestions(&ui, config, &guard).await
+}
+
+/// Offer the settings of previous archives in an already configured repository
+pub async fn import_settings(ui: builder::DialogSetup, config: config::Backup) -> Result<()> {
+    let guard = QuitGuard::default();
+    transfer_suggestions(&ui, config, &guard).await
+}
+
+async fn transfer_suggestions(
+    ui: &builder::DialogSetup,
+    config: config::Backup,
+    guard: &QuitGuard,
+) -> Result<()> {
     let mut list_command = borg::Command::<borg::task::List>::new(config.clone());
     list_command.task.set_limit_first(100);
 
-    let archives = ui::utils::borg::exec(list_command, &guard)
-        .await
-        .into_message(gettext("Failed"))?;
+    // Skipping closes the dialog, the archives are not needed anymore
+    let communication = list_command.communication.clone();
+    let handler = ui.dialog().connect_close_request(move |_| {
+        communication.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+        glib::Propagation::Proceed
+    });
+
+    let result = ui::utils::borg::exec(list_command, guard).await;
+    ui.dialog().disconnect(handler);
+
+    let archives = result.into_message(gettext("Failed"))?;
 
-    display::transfer_selection(&ui, config.id.clone(), archives);
+    display::transfer_selection(ui, config.id.clone(), archives);
 
     Ok(())
 }
@@ -262,7 +422,10 @@ fn command_line_args(ui: &builder::DialogSetup) -> Result<Vec<String>> {
     ui::utils::borg::parse_borg_command_line_args(&text)
 }
 
-async fn mount_fuse_and_config(file: &gio::File, mount_parent: bool) -> Result<local::Repository> {
+pub async fn mount_fuse_and_config(
+    file: &gio::File,
+    mount_parent: bool,
+) -> Result<local::Repository> {
     if let (Ok(mount), Some(path)) = (
         file.find_enclosing_mount(Some(&gio::Cancellable::new())),
         file.path(),
@@ -0,0 +1,88 @@
+//! Read-only status of the backups other users share on the system bus
+
+use adw::prelude::*;
+
+use crate::ui;
+use crate::utils::shared_status;
+use ui::builder::DialogSharedStatus;
+use ui::prelude::*;
+
+fn backup_row(summary: &shared_status::Summary) -> adw::ActionRow {
+    let last_completed = summary
+        .last_completed()
+        .map(|date| ui::utils::duration::ago(&(chrono::Local::now() - date)));
+
+    let row = adw::ActionRow::builder()
+        .use_markup(false)
+        .title(&summary.title)
+        .subtitle(match &last_completed {
+            // Translators: Argument is 'x days ago'
+            Some(ago) => gettextf("Last backup {}", &[ago]),
+            None => gettext("Never backed up"),
+        })
+        .build();
+
+    let (icon, css_class) = if !summary.last_run_ok {
+        ("dialog-error-symbolic", "error")
+    } else if !summary.up_to_date {
+        ("dialog-warning-symbolic", "warning")
+    } else {
+        ("emblem-default-symbolic", "success")
+    };
+
+    let image = gtk::Image::from_icon_name(icon);
+    image.add_css_class(css_class);
+    row.add_suffix(&image);
+
+    row
+}
+
+fn user_group(user: &shared_status::UserStatus) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder()
+        .title(&user.user_name)
+        .build();
+
+    if user.backups.is_empty() {
+        group.set_description(Some(&gettext("No backups configured")));
+    }
+
+    for summary in &user.backups {
+        group.add(&backup_row(summary));
+    }
+
+    group
+}
+
+pub fn show() {
+    let ui = DialogSharedStatus::new();
+    ui.dialog().set_transient_for(Some(&main_ui().window()));
+
+    // ensure lifetime until window closes
+    let mutex = std::sync::Mutex::new(Some(ui.clone()));
+    ui.dialog().connect_close_request(move |_| {
+        *mutex.lock().unwrap() = None;
+        glib::Propagation::Proceed
+    });
+
+    ui.dialog().present();
+
+    Handler::new()
+        .error_transient_for(ui.dialog())
+        .spawn(async move {
+            let users = shared_status::discover()
+                .await
+                .err_to_msg(gettext("Failed to Query Shared Backup Status"))?;
+
+            for user in &users {
+                ui.page_users().add(&user_group(user));
+            }
+
+            if users.is_empty() {
+                ui.stack().set_visible_child(&ui.page_empty());
+            } else {
+                ui.stack().set_visible_child(&ui.page_users());
+            }
+
+            Ok(())
+        });
+}
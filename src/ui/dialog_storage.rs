@@ -1,7 +1,8 @@
-use adw::traits::ActionRowExt;
-use gtk::prelude::*;
+use adw::prelude::*;
 
+use crate::borg;
 use crate::config;
+use crate::config::history::CheckRunInfo;
 use crate::ui;
 use crate::ui::prelude::*;
 
@@ -13,6 +14,10 @@ pub async fn show() -> Result<()> {
         .set_transient_for(Some(&main_ui().window()));
 
     let backup = BACKUP_CONFIG.load().active()?.clone();
+    storage
+        .encryption()
+        .set_subtitle(&backup.encryption_description());
+
     match &backup.repo {
         config::Repository::Local(repo) => {
             storage
@@ -21,25 +26,527 @@ pub async fn show() -> Result<()> {
             storage
                 .device()
                 .set_subtitle(&repo.drive_name.clone().unwrap_or_default());
+            show_drive_health(&storage, repo).await;
             storage.path().set_subtitle(&repo.path().to_string_lossy());
             storage.disk().set_visible(true);
         }
-        config::Repository::Remote { .. } => {
+        config::Repository::Remote(remote) => {
             storage.uri().set_subtitle(&backup.repo.to_string());
+            init_host_key_policy(&storage, &backup.id, remote.host_key_policy);
+            init_reuse_connection(&storage, &backup.id, remote.reuse_connection);
+            show_host_key(&storage, &backup.repo).await;
 
             storage.remote().set_visible(true);
         }
     }
 
+    storage
+        .host_key_reset()
+        .connect_clicked(clone!(@weak storage, @strong backup => move |_| {
+            Handler::new()
+                .error_transient_for(storage.dialog())
+                .spawn(enclose!((storage, backup) async move {
+                    reset_host_key(&storage, &backup.repo).await
+                }));
+        }));
+
+    storage
+        .connection_verify()
+        .connect_clicked(clone!(@weak storage, @strong backup => move |_| {
+            Handler::new()
+                .error_transient_for(storage.dialog())
+                .spawn(enclose!((storage, backup) async move {
+                    verify_connection(&storage, &backup.repo).await
+                }));
+        }));
+
+    let repo_id = backup.repo_id.clone();
+    storage
+        .cache_delete()
+        .connect_clicked(clone!(@weak storage, @strong repo_id => move |_| {
+            Handler::new()
+                .error_transient_for(storage.dialog())
+                .spawn(enclose!((storage, repo_id) async move {
+                    delete_cache(&storage, &repo_id).await
+                }));
+        }));
+
+    show_last_upgrade(&backup.id, &storage);
+
+    if backup.repo.is_network() {
+        show_network_usage(&backup.id, &storage);
+    }
+
+    storage
+        .format_check()
+        .connect_clicked(clone!(@weak storage, @strong backup => move |_| {
+            Handler::new()
+                .error_transient_for(storage.dialog())
+                .spawn(enclose!((storage, backup) async move {
+                    check_format(&storage, &backup).await.map(|_| ())
+                }));
+        }));
+
+    storage
+        .format_upgrade()
+        .connect_clicked(clone!(@weak storage, @strong backup => move |_| {
+            Handler::new()
+                .error_transient_for(storage.dialog())
+                .spawn(enclose!((storage, backup) async move {
+                    upgrade(&storage, &backup).await
+                }));
+        }));
+
+    storage.dialog().set_visible(true);
+
+    // Looking up the space of remote repositories can take long on bad connections
     if let Some(df) = ui::utils::df::cached_or_lookup(&backup).await {
         show_df(&df, &storage);
     }
 
-    storage.dialog().set_visible(true);
+    show_cache(&repo_id, &storage).await
+}
+
+async fn show_drive_health(ui: &ui::builder::DialogStorage, repo: &config::local::Repository) {
+    let Some(health) = crate::utils::udisks::health(&repo.path()).await else {
+        return;
+    };
+
+    ui.device().set_subtitle(&format!(
+        "{}\n{}",
+        repo.drive_name.clone().unwrap_or_default(),
+        health.summary()
+    ));
+
+    if !health.is_good() {
+        ui.device().add_css_class("warning");
+    }
+}
+
+fn init_host_key_policy(
+    ui: &ui::builder::DialogStorage,
+    config_id: &ConfigId,
+    policy: config::remote::HostKeyPolicy,
+) {
+    let policies = config::remote::HostKeyPolicy::VALUES;
+    let names = policies.map(|x| x.to_string());
+    ui.host_key_policy().set_model(Some(&gtk::StringList::new(
+        &names.iter().map(String::as_str).collect::<Vec<_>>(),
+    )));
+    ui.host_key_policy().set_selected(
+        policies
+            .iter()
+            .position(|x| *x == policy)
+            .unwrap_or_default() as u32,
+    );
+
+    ui.host_key_policy().connect_selected_notify(
+        clone!(@weak ui, @strong config_id => move |row| {
+            let Some(policy) = policies.get(row.selected() as usize).copied() else {
+                return;
+            };
+
+            Handler::new()
+                .error_transient_for(ui.dialog())
+                .spawn(enclose!((ui, config_id) async move {
+                    set_host_key_policy(&ui, &config_id, policy).await
+                }));
+        }),
+    );
+}
+
+async fn set_host_key_policy(
+    ui: &ui::builder::DialogStorage,
+    config_id: &ConfigId,
+    policy: config::remote::HostKeyPolicy,
+) -> Result<()> {
+    let previous = BACKUP_CONFIG
+        .load()
+        .try_get(config_id)?
+        .repo
+        .host_key_policy()
+        .unwrap_or_default();
+
+    if policy == previous {
+        return Ok(());
+    }
+
+    if policy == config::remote::HostKeyPolicy::Off {
+        let confirmed = ui::confirm::confirm(
+            ui::confirm::Category::Critical,
+            &gettext("Turn Off Server Identity Verification?"),
+            &gettext("Any server that poses as the backup server will be able to receive the backup data and to supply manipulated archives. Only turn off the verification if the network is fully trusted."),
+            &gettext("Cancel"),
+            &gettext("Turn Off"),
+        )
+        .await;
+
+        if let Err(err) = confirmed {
+            ui.host_key_policy().set_selected(
+                config::remote::HostKeyPolicy::VALUES
+                    .iter()
+                    .position(|x| *x == previous)
+                    .unwrap_or_default() as u32,
+            );
+            return Err(err);
+        }
+    }
+
+    BACKUP_CONFIG.try_update(|settings| {
+        if let config::Repository::Remote(remote) = &mut settings.try_get_mut(config_id)?.repo {
+            remote.host_key_policy = policy;
+        }
+        Ok(())
+    })
+}
+
+fn init_reuse_connection(ui: &ui::builder::DialogStorage, config_id: &ConfigId, active: bool) {
+    ui.reuse_connection().set_active(active);
+
+    ui.reuse_connection()
+        .connect_active_notify(clone!(@weak ui, @strong config_id => move |row| {
+            let active = row.is_active();
+
+            Handler::new()
+                .error_transient_for(ui.dialog())
+                .spawn(enclose!((config_id) async move {
+                    BACKUP_CONFIG.try_update(|settings| {
+                        if let config::Repository::Remote(remote) =
+                            &mut settings.try_get_mut(&config_id)?.repo
+                        {
+                            remote.reuse_connection = active;
+                        }
+                        Ok(())
+                    })
+                }));
+        }));
+}
+
+async fn show_host_key(ui: &ui::builder::DialogStorage, repo: &config::Repository) {
+    let fingerprints = ui::utils::host_key::fingerprints(repo).await;
+
+    if fingerprints.is_empty() {
+        ui.host_key().set_subtitle(&gettext("Not stored"));
+    } else {
+        ui.host_key().set_subtitle(&fingerprints.join("\n"));
+    }
+
+    ui.host_key_reset().set_sensitive(!fingerprints.is_empty());
+}
+
+async fn reset_host_key(ui: &ui::builder::DialogStorage, repo: &config::Repository) -> Result<()> {
+    ui::confirm::confirm(
+        ui::confirm::Category::Critical,
+        &gettext("Reset Host Key?"),
+        &gettext("Only reset the host key if the server was reinstalled or its key was changed on purpose. The key presented at the next connection will be stored."),
+        &gettext("Cancel"),
+        &gettext("Reset"),
+    )
+    .await?;
+
+    ui::utils::host_key::reset(repo).await?;
+    show_host_key(ui, repo).await;
 
     Ok(())
 }
 
+async fn verify_connection(
+    ui: &ui::builder::DialogStorage,
+    repo: &config::Repository,
+) -> Result<()> {
+    let rows = [
+        (ui.connection_ssh(), ui.connection_ssh_icon()),
+        (ui.connection_borg(), ui.connection_borg_icon()),
+        (ui.connection_lock(), ui.connection_lock_icon()),
+    ];
+
+    for (row, icon) in &rows {
+        row.set_subtitle(&gettext("Checking…"));
+        icon.set_icon_name(Some("content-loading-symbolic"));
+        row.set_visible(true);
+    }
+
+    ui.connection_verify().set_sensitive(false);
+    scopeguard::defer! { ui.connection_verify().set_sensitive(true); }
+
+    let report = ui::utils::connection::verify(repo).await;
+
+    for ((row, icon), check) in rows.iter().zip([report.ssh, report.borg, report.lock]) {
+        let (subtitle, icon_name) = match check {
+            ui::utils::connection::Check::Passed => (gettext("Yes"), "emblem-ok-symbolic"),
+            ui::utils::connection::Check::Failed(message) => (message, "dialog-warning-symbolic"),
+            ui::utils::connection::Check::Skipped => {
+                (gettext("Not checked"), "action-unavailable-symbolic")
+            }
+        };
+
+        row.set_subtitle(&subtitle);
+        icon.set_icon_name(Some(icon_name));
+    }
+
+    Ok(())
+}
+
+fn show_last_upgrade(config_id: &ConfigId, ui: &ui::builder::DialogStorage) {
+    let last_upgrade = BACKUP_HISTORY
+        .load()
+        .try_get(config_id)
+        .ok()
+        .and_then(|history| history.last_upgrade);
+
+    if let Some(date) = last_upgrade {
+        ui.format_status().set_subtitle(&gettextf(
+            "Upgraded on {}",
+            &[&date
+                .naive_local()
+                .to_locale()
+                .unwrap_or_else(|| date.to_string())],
+        ));
+    }
+}
+
+fn show_network_usage(config_id: &ConfigId, ui: &ui::builder::DialogStorage) {
+    let histories = BACKUP_HISTORY.load();
+    let Ok(history) = histories.try_get(config_id) else {
+        return;
+    };
+
+    let now = chrono::Local::now();
+    ui.network_this_month()
+        .set_subtitle(&network_usage_text(history, now));
+    ui.network_last_month().set_subtitle(&network_usage_text(
+        history,
+        chronoutil::delta::shift_months(now, -1),
+    ));
+    ui.network().set_visible(true);
+}
+
+fn network_usage_text(
+    history: &config::history::History,
+    date: chrono::DateTime<chrono::Local>,
+) -> String {
+    let sent = glib::format_size(history.sent_in_month(date));
+
+    match history.received_in_month(date) {
+        Some(received) => gettextf(
+            "{} sent, {} received",
+            &[&sent, &glib::format_size(received)],
+        ),
+        None => gettextf("{} sent", &[&sent]),
+    }
+}
+
+async fn check_format(
+    ui: &ui::builder::DialogStorage,
+    config: &config::Backup,
+) -> Result<borg::RepositoryFormat> {
+    let guard = QuitGuard::default();
+
+    let command = borg::Command::<borg::task::RepositoryFormat>::new(config.clone());
+    let communication = command.communication.clone();
+
+    ui.format_check().set_visible(false);
+    ui.format_cancel().set_sensitive(true);
+    ui.format_cancel().set_visible(true);
+    let handler = ui.format_cancel().connect_clicked(move |button| {
+        info!("Canceling repository format check");
+        button.set_sensitive(false);
+        communication.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+    });
+    scopeguard::defer! {
+        ui.format_cancel().disconnect(handler);
+        ui.format_cancel().set_visible(false);
+        ui.format_check().set_visible(true);
+    }
+
+    let format = ui::utils::borg::exec(command, &guard)
+        .await
+        .into_message(gettext("Failed to Check Repository Format"))?;
+
+    let subtitle = if format.needs_upgrade() {
+        gettext("Outdated, an upgrade is required for newer versions of BorgBackup")
+    } else if format.manifest_authenticated.is_none() {
+        gettext("No upgrade available with the installed version of BorgBackup")
+    } else {
+        gettext("Up to date")
+    };
+
+    ui.format_status().set_subtitle(&subtitle);
+    ui.format_upgrade().set_visible(format.needs_upgrade());
+
+    Ok(format)
+}
+
+async fn upgrade(ui: &ui::builder::DialogStorage, config: &config::Backup) -> Result<()> {
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(&ui.dialog())
+        .modal(true)
+        .heading(gettext("Upgrade Repository Format?"))
+        .body(gettext("The upgrade cannot be undone. Afterwards, the backup repository can no longer be accessed with BorgBackup versions older than 1.2.5 on any computer.\n\nThe integrity of all archives is verified first. The upgrade is only performed if no errors are found."))
+        .build();
+
+    dialog.add_responses(&[
+        ("cancel", &gettext("Cancel")),
+        ("upgrade", &gettext("Verify and Upgrade")),
+    ]);
+    dialog.set_response_appearance("upgrade", adw::ResponseAppearance::Destructive);
+
+    if dialog.choose_future().await != "upgrade" {
+        return Ok(());
+    }
+
+    ui.format_upgrade().set_sensitive(false);
+    scopeguard::defer! { ui.format_upgrade().set_sensitive(true); }
+
+    verify_before_upgrade(config).await?;
+
+    // The format might have changed since it was displayed
+    let format = check_format(ui, config).await?;
+    if !format.needs_upgrade() {
+        return Ok(());
+    }
+
+    let guard = QuitGuard::default();
+    let mut command = borg::Command::<borg::task::Upgrade>::new(config.clone());
+    command.task.set_format(format.clone());
+
+    ui::utils::borg::exec(command, &guard)
+        .await
+        .into_message(gettext("Failed to Upgrade Repository Format"))?;
+
+    info!(
+        "Upgraded repository format, manifest: {}, archives: {}",
+        format.manifest_needs_upgrade(),
+        format.unauthenticated_archives.len()
+    );
+
+    BACKUP_HISTORY.try_update(|history| {
+        history.set_last_upgrade(config.id.clone(), chrono::Local::now());
+        Ok(())
+    })?;
+
+    ui.format_upgrade().set_visible(false);
+    show_last_upgrade(&config.id, ui);
+    ui::utils::show_notice(gettext("Repository format upgraded successfully"));
+
+    Ok(())
+}
+
+/// Mandatory integrity check since an upgrade of a damaged repository cannot be undone
+async fn verify_before_upgrade(config: &config::Backup) -> Result<()> {
+    let guard = QuitGuard::default();
+
+    let command = borg::Command::<borg::task::Check>::new(config.clone());
+    let communication = command.communication.clone();
+    let result = ui::utils::borg::exec(command, &guard)
+        .await
+        .into_message(gettext("Verify Archives Integrity"));
+    let mut message_history = communication
+        .general_info
+        .load()
+        .all_combined_message_history();
+
+    if let Err(err) = result {
+        if matches!(err, Error::UserCanceled) {
+            BACKUP_HISTORY.try_update(|history| {
+                history.set_last_check(config.id.clone(), CheckRunInfo::new_aborted());
+                Ok(())
+            })?;
+
+            return Err(err);
+        }
+
+        if message_history.is_empty() {
+            message_history = vec![borg::log_json::LogEntry::UnparsableErr(err.to_string())];
+        }
+    }
+
+    if message_history.is_empty() {
+        BACKUP_HISTORY.try_update(|history| {
+            history.set_last_check(config.id.clone(), CheckRunInfo::new_success());
+            Ok(())
+        })?;
+
+        Ok(())
+    } else {
+        BACKUP_HISTORY.try_update(|history| {
+            history.set_last_check(
+                config.id.clone(),
+                CheckRunInfo::new_error(message_history.clone()),
+            );
+            Ok(())
+        })?;
+
+        Err(Message::new(
+            gettext("Integrity Check Failed, Repository Not Upgraded"),
+            message_history
+                .iter()
+                .map(|entry| entry.message())
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+        .into())
+    }
+}
+
+async fn show_cache(repo_id: &borg::RepoId, ui: &ui::builder::DialogStorage) -> Result<()> {
+    let size = ui::utils::spawn_thread(
+        "cache_size",
+        enclose!((repo_id) move || borg::cache_size(&repo_id)),
+    )
+    .await?;
+
+    if let Some(size) = size {
+        ui.cache_size().set_subtitle(&glib::format_size(size));
+    }
+    ui.cache().set_visible(size.is_some());
+
+    Ok(())
+}
+
+async fn delete_cache(ui: &ui::builder::DialogStorage, repo_id: &borg::RepoId) -> Result<()> {
+    let repo_in_use = BORG_OPERATION.with(|operations| {
+        operations
+            .load()
+            .values()
+            .any(|operation| operation.repo_id() == repo_id)
+    });
+
+    if repo_in_use || ACTIVE_MOUNTS.load().contains(repo_id) {
+        return Err(Message::new(
+            gettext("Repository already in use"),
+            gettext("The cache can only be deleted while no operation is running and no archives are being browsed."),
+        )
+        .into());
+    }
+
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(&ui.dialog())
+        .modal(true)
+        .heading(gettext("Delete Local Cache?"))
+        .body(gettext("The next backup will take longer since the cache has to be rebuilt by reading information about all archives from the backup repository. For remote repositories this can transfer a considerable amount of data."))
+        .build();
+
+    dialog.add_responses(&[
+        ("cancel", &gettext("Cancel")),
+        ("delete", &gettext("Delete")),
+    ]);
+    dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+
+    if dialog.choose_future().await != "delete" {
+        return Ok(());
+    }
+
+    ui::utils::spawn_thread(
+        "cache_delete",
+        enclose!((repo_id) move || borg::delete_cache(&repo_id)),
+    )
+    .await?
+    .err_to_msg(gettext("Failed to Delete Cache"))?;
+
+    show_cache(repo_id, ui).await
+}
+
 fn show_df(df: &ui::utils::df::Space, ui: &ui::builder::DialogStorage) {
     ui.fs_size().set_subtitle(&glib::format_size(df.size));
     ui.fs_free().set_subtitle(&glib::format_size(df.avail));
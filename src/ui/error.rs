@@ -70,7 +70,7 @@ impl Message {
     }
 
     pub async fn show(&self) {
-        self.show_transient_for(&main_ui().window()).await;
+        self.show_transient_for(&active_window()).await;
     }
 
     pub async fn show_transient_for<W: IsA<gtk::Window> + IsA<gtk::Widget>>(&self, window: &W) {
@@ -187,14 +187,14 @@ pub struct Handler<W: IsA<gtk::Window> + IsA<gtk::Widget>> {
     transient_for: Option<W>,
 }
 
-impl Handler<adw::ApplicationWindow> {
+impl Handler<gtk::Window> {
     pub fn run<F: std::future::Future<Output = Result<()>> + 'static>(f: F) {
-        Self::new().error_transient_for(main_ui().window()).spawn(f);
+        Self::new().error_transient_for(active_window()).spawn(f);
     }
 
     pub fn handle(result: Result<()>) {
         Self::new()
-            .error_transient_for(main_ui().window())
+            .error_transient_for(active_window())
             .spawn(async { result });
     }
 }
@@ -1,4 +1,5 @@
 pub use glib::prelude::*;
+use gtk::prelude::*;
 
 use crate::config;
 use crate::config::ConfigId;
@@ -11,6 +12,7 @@ use arc_swap::ArcSwap;
 use once_cell::sync::Lazy;
 
 use crate::borg;
+use crate::schedule;
 use crate::ui;
 
 pub static BACKUP_CONFIG: Lazy<ArcSwap<config::Writeable<config::Backups>>> =
@@ -18,12 +20,28 @@ pub static BACKUP_CONFIG: Lazy<ArcSwap<config::Writeable<config::Backups>>> =
 pub static BACKUP_HISTORY: Lazy<ArcSwap<config::Writeable<config::Histories>>> =
     Lazy::new(Default::default);
 
+/// Removed backup configurations that can still be restored
+pub static BACKUP_TRASH: Lazy<ArcSwap<config::Writeable<config::Trash>>> =
+    Lazy::new(Default::default);
+
 pub static SCHEDULE_STATUS: Lazy<ArcSwap<config::ScheduleStatus>> = Lazy::new(Default::default);
 
 pub static ACTIVE_BACKUP_ID: Lazy<ArcSwap<Option<ConfigId>>> = Lazy::new(Default::default);
 
 pub static ACTIVE_MOUNTS: Lazy<ArcSwap<HashSet<borg::RepoId>>> = Lazy::new(Default::default);
 
+/// Scheduled backups waiting for the keyring to be unlocked
+pub static KEYRING_QUEUE: Lazy<ArcSwap<BTreeMap<ConfigId, schedule::DueCause>>> =
+    Lazy::new(Default::default);
+
+/// Operations waiting for an operation of another backup on the same repository, with their name
+pub static REPOSITORY_QUEUE: Lazy<ArcSwap<BTreeMap<ConfigId, String>>> =
+    Lazy::new(Default::default);
+
+/// Running maintenance with the current step and the number of steps
+pub static MAINTENANCE_STEP: Lazy<ArcSwap<BTreeMap<ConfigId, (usize, usize)>>> =
+    Lazy::new(Default::default);
+
 /// Is the app currently shutting down
 pub static IS_SHUTDOWN: Lazy<ArcSwap<bool>> = Lazy::new(Default::default);
 
@@ -52,12 +70,18 @@ thread_local!(
         debug!("Setting up application with id '{}'", crate::APP_ID);
         adw::Application::builder()
             .application_id(crate::APP_ID)
+            // Required for the 'query-end' signal
+            .register_session(true)
             .build()
     });
 
     pub static BORG_OPERATION: ArcSwap<BTreeMap<ConfigId, Rc<dyn ui::operation::OperationExt>>> =
         Default::default();
 
+    /// Operations running in other processes
+    pub static REMOTE_OPERATION: ArcSwap<BTreeMap<ConfigId, Rc<ui::backup_status::Display>>> =
+        Default::default();
+
     pub static STATUS_TRACKING: Rc<ui::status::StatusTracking> =
         ui::status::StatusTracking::new_rc();
 );
@@ -66,6 +90,15 @@ pub fn main_ui() -> Rc<ui::builder::AppWindow> {
     MAIN_UI_STORE.with(|x| x.clone())
 }
 
+/// Window the user currently interacts with, as parent for dialogs
+///
+/// Falls back to the main window if no window of the application has the focus.
+pub fn active_window() -> gtk::Window {
+    adw_app()
+        .active_window()
+        .unwrap_or_else(|| main_ui().window().upcast())
+}
+
 pub fn adw_app() -> Rc<adw::Application> {
     ADW_APPLICATION.with(|x| x.clone())
 }
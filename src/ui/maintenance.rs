@@ -0,0 +1,175 @@
+//! Integrity check, cleanup and compaction of a repository in one run
+
+use crate::borg;
+use crate::config;
+use crate::config::history::CheckRunInfo;
+use crate::schedule;
+use crate::ui;
+use crate::ui::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Step {
+    Check,
+    Prune,
+    Compact,
+}
+
+impl Step {
+    fn name(self) -> String {
+        match self {
+            Self::Check => gettext("Verify Archives Integrity"),
+            Self::Prune => gettext("Delete old Archives"),
+            Self::Compact => gettext("Reclaim Free Space"),
+        }
+    }
+}
+
+/// Run the maintenance steps after each other and report the results
+///
+/// The integrity check runs first such that no archives are deleted from a
+/// damaged repository. Returns `false` if the maintenance was aborted.
+pub async fn run(
+    config: config::Backup,
+    from_schedule: Option<schedule::DueCause>,
+    guard: &QuitGuard,
+) -> Result<bool> {
+    let mut steps = vec![Step::Check];
+    if config.prune.enabled {
+        steps.push(Step::Prune);
+    }
+    steps.push(Step::Compact);
+
+    let config_id = config.id.clone();
+    scopeguard::defer!({
+        MAINTENANCE_STEP.update(|running| {
+            running.remove(&config_id);
+        });
+    });
+
+    let mut report = Vec::new();
+
+    for (i, step) in steps.iter().enumerate() {
+        info!("Maintenance step {} of {}: {:?}", i + 1, steps.len(), step);
+        MAINTENANCE_STEP.update(|running| {
+            running.insert(config.id.clone(), (i + 1, steps.len()));
+        });
+        ui::page_backup::refresh_status();
+
+        let result = match step {
+            Step::Check => run_check(&config, from_schedule.clone(), guard).await?,
+            Step::Prune if ui::utils::append_only::is_protected(&config) => {
+                info!("Skipping deletion of old archives since the repository is append-only");
+                report.push(gettextf("{}: Skipped", &[&step.name()]));
+                report.push(ui::utils::append_only::skipped_prune_message(&config));
+                continue;
+            }
+            Step::Prune => {
+                let command = borg::Command::<borg::task::Prune>::new(config.clone())
+                    .set_from_schedule(from_schedule.clone());
+                step_result(
+                    ui::utils::borg::exec(command, guard)
+                        .await
+                        .into_borg_error()?,
+                )
+            }
+            Step::Compact => {
+                let command = borg::Command::<borg::task::Compact>::new(config.clone())
+                    .set_from_schedule(from_schedule.clone());
+                step_result(
+                    ui::utils::borg::exec(command, guard)
+                        .await
+                        .into_borg_error()?,
+                )
+            }
+        };
+
+        match result {
+            Ok(()) => report.push(gettextf("{}: Completed", &[&step.name()])),
+            Err(None) => return Ok(false),
+            Err(Some(err)) => {
+                report.push(gettextf("{}: Failed", &[&step.name()]));
+                report.push(err);
+
+                return Err(Message::new(gettext("Maintenance Failed"), report.join("\n")).into());
+            }
+        }
+    }
+
+    BACKUP_HISTORY.try_update(|histories| {
+        histories.set_last_maintenance(config.id.clone(), chrono::Local::now());
+        Ok(())
+    })?;
+
+    ui::page_archives::refresh_status();
+    ui::utils::show_notice(format!(
+        "{}\n{}",
+        gettext("Maintenance completed"),
+        report.join("\n")
+    ));
+
+    Ok(true)
+}
+
+fn step_result(result: borg::Result<()>) -> std::result::Result<(), Option<String>> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(borg::Error::Aborted(_)) => Err(None),
+        Err(err) => Err(Some(err.to_string())),
+    }
+}
+
+/// Check the repository and store the result as the last integrity check
+///
+/// Returns `Err(None)` if the check was aborted.
+async fn run_check(
+    config: &config::Backup,
+    from_schedule: Option<schedule::DueCause>,
+    guard: &QuitGuard,
+) -> Result<std::result::Result<(), Option<String>>> {
+    let mut command =
+        borg::Command::<borg::task::Check>::new(config.clone()).set_from_schedule(from_schedule);
+    command.task.set_verify_data(config.maintenance.verify_data);
+
+    let communication = command.communication.clone();
+    let result = ui::utils::borg::exec(command, guard)
+        .await
+        .into_borg_error()?;
+    let mut message_history = communication
+        .general_info
+        .load()
+        .all_combined_message_history();
+
+    let run_info = match result {
+        Err(borg::Error::Aborted(_)) => {
+            BACKUP_HISTORY.try_update(|history| {
+                history.set_last_check(config.id.clone(), CheckRunInfo::new_aborted());
+                Ok(())
+            })?;
+
+            return Ok(Err(None));
+        }
+        Err(err) if message_history.is_empty() => {
+            message_history = vec![borg::log_json::LogEntry::UnparsableErr(err.to_string())];
+            CheckRunInfo::new_error(message_history.clone())
+        }
+        _ if !message_history.is_empty() => CheckRunInfo::new_error(message_history.clone()),
+        _ => CheckRunInfo::new_success(),
+    };
+
+    BACKUP_HISTORY.try_update(|history| {
+        history.set_last_check(config.id.clone(), run_info.clone());
+        Ok(())
+    })?;
+
+    if message_history.is_empty() {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(Some(
+            message_history
+                .iter()
+                .map(|h| h.message())
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )))
+    }
+}
@@ -98,6 +98,10 @@ impl<T: borg::Task> Operation<T> {
 
     fn check_output(&self, update: borg::Update) {
         match update {
+            // Files are handled by the task itself, don't refresh the UI for every one
+            borg::Update::Msg(log_json::Output::Progress(log_json::Progress::FileStatus(_))) => {
+                return
+            }
             borg::Update::Msg(output) => {
                 let output = Rc::new(output);
 
@@ -179,11 +183,22 @@ impl<T: borg::Task> Operation<T> {
     fn ui_status_update(&self) {
         debug!("UI status update");
 
+        ui::operation_status::refresh(&self.command.config.id);
+
         if ACTIVE_BACKUP_ID.get() == self.command.config_id() {
             ui::page_backup::refresh_status();
             ui::page_archives::refresh_status();
             ui::dialog_info::refresh_status();
         }
+        ui::window_backup::refresh_status(&self.command.config.id);
+
+        if T::KIND == borg::task::Kind::Create {
+            if self.operation_shutdown.get() {
+                ui::utils::notification::withdraw_progress(&self.command.config.id);
+            } else {
+                ui::utils::notification::update_progress(&self.command.config.id);
+            }
+        }
 
         ui::page_overview::refresh_status();
         ui::page_backup::refresh_disk_status();
@@ -0,0 +1,140 @@
+//! Status of a backup config as GObject properties
+//!
+//! Widgets bind to these properties instead of being set by every function
+//! that refreshes the status. All windows showing the same backup config share
+//! one status object, which is only notified about values that changed.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::borg;
+use crate::ui::backup_status;
+use crate::ui::prelude::*;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+
+    #[derive(Debug, Default, glib::Properties)]
+    #[properties(wrapper_type = super::OperationStatus)]
+    pub struct OperationStatus {
+        /// Title of the current phase, like “Backup Running”
+        #[property(get, set)]
+        phase: RefCell<String>,
+        /// Progress between 0 and 1
+        #[property(get, set)]
+        fraction: Cell<f64>,
+        #[property(get, set)]
+        has_progress: Cell<bool>,
+        /// An operation is running in this instance
+        #[property(get, set)]
+        running: Cell<bool>,
+        #[property(get, set)]
+        paused: Cell<bool>,
+        /// A backup is running that can be paused or resumed
+        #[property(get, set)]
+        pausable: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for OperationStatus {
+        const NAME: &'static str = "PikaOperationStatus";
+        type Type = super::OperationStatus;
+    }
+
+    impl ObjectImpl for OperationStatus {
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct OperationStatus(ObjectSubclass<imp::OperationStatus>);
+}
+
+thread_local!(
+    static STATUS: RefCell<BTreeMap<ConfigId, OperationStatus>> = Default::default();
+);
+
+impl OperationStatus {
+    /// The shared status object of `config_id`
+    pub fn get(config_id: &ConfigId) -> Self {
+        if let Some(status) = STATUS.with(|status| status.borrow().get(config_id).cloned()) {
+            return status;
+        }
+
+        let status: Self = glib::Object::new();
+        status.update(config_id);
+        STATUS.with(|objects| {
+            objects
+                .borrow_mut()
+                .insert(config_id.clone(), status.clone())
+        });
+
+        status
+    }
+
+    fn update(&self, config_id: &ConfigId) {
+        let display = backup_status::Display::new_from_id(config_id);
+        let operation = BORG_OPERATION.with(|operations| {
+            operations.load().get(config_id).map(|op| {
+                (
+                    op.status(),
+                    op.task_kind() == borg::task::Kind::Create && !op.aborting(),
+                )
+            })
+        });
+
+        let running = operation.is_some();
+        let paused = matches!(operation, Some((borg::Run::Paused, _)));
+        let pausable = matches!(
+            operation,
+            Some((
+                borg::Run::Running | borg::Run::Stalled | borg::Run::Paused,
+                true
+            ))
+        );
+
+        if self.phase() != display.title {
+            self.set_phase(display.title);
+        }
+        if let Some(fraction) = display.progress {
+            if self.fraction() != fraction {
+                self.set_fraction(fraction);
+            }
+        }
+        if self.has_progress() != display.progress.is_some() {
+            self.set_has_progress(display.progress.is_some());
+        }
+        if self.running() != running {
+            self.set_running(running);
+        }
+        if self.paused() != paused {
+            self.set_paused(paused);
+        }
+        if self.pausable() != pausable {
+            self.set_pausable(pausable);
+        }
+    }
+}
+
+/// Update the status object of `config_id` if it's in use
+pub fn refresh(config_id: &ConfigId) {
+    if let Some(status) = STATUS.with(|status| status.borrow().get(config_id).cloned()) {
+        status.update(config_id);
+    }
+}
+
+/// Stop updating the status object of a removed backup config
+pub fn remove(config_id: &ConfigId) {
+    STATUS.with(|status| status.borrow_mut().remove(config_id));
+}
@@ -1,22 +1,37 @@
 pub mod cache;
 mod display;
 mod events;
+mod filter;
 mod init;
+mod usage;
 
 pub use display::{refresh_status, update_info};
+pub use events::{mount_archive, open_archive_file, restore_archive_file};
 pub use init::init;
+pub use usage::refresh as refresh_usage;
 
 use adw::prelude::*;
 
 use crate::ui;
 use ui::prelude::*;
 
-fn is_visible() -> bool {
+pub fn view(id: &ConfigId) {
+    ACTIVE_BACKUP_ID.update(|active_id| *active_id = Some(id.clone()));
+
+    main_ui()
+        .navigation_view()
+        .push(&main_ui().navigation_page_detail());
+    main_ui()
+        .detail_stack()
+        .set_visible_child(&main_ui().page_archives());
+}
+
+pub fn is_visible() -> bool {
     main_ui().detail_stack().visible_child()
         == Some(main_ui().page_archives().upcast::<gtk::Widget>())
 }
 
-fn find_first_populated_dir(dir: &std::path::Path) -> std::path::PathBuf {
+pub fn find_first_populated_dir(dir: &std::path::Path) -> std::path::PathBuf {
     if let Ok(mut dir_iter) = dir.read_dir() {
         if let Some(Ok(new_dir)) = dir_iter.next() {
             if new_dir.path().is_dir() && dir_iter.next().is_none() {
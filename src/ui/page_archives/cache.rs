@@ -7,6 +7,9 @@ use crate::schedule;
 use crate::ui;
 use crate::ui::utils::repo_cache::RepoCache;
 
+/// Number of latest archives that are retrieved
+const ARCHIVES_LIMIT: u32 = 100;
+
 pub async fn refresh_archives(
     config: config::Backup,
     from_schedule: Option<schedule::DueCause>,
@@ -29,7 +32,7 @@ pub async fn refresh_archives(
 
     let mut command =
         borg::Command::<borg::task::List>::new(config.clone()).set_from_schedule(from_schedule);
-    command.task.set_limit_first(100);
+    command.task.set_limit_first(ARCHIVES_LIMIT);
     let result = ui::utils::borg::exec(command, &guard)
         .await
         .into_message(gettext("Failed to refresh archives cache."));
@@ -56,7 +59,8 @@ pub async fn refresh_archives(
                 .map(|x| (x.name.clone(), x.clone()))
                 .collect(),
         );
-
+        repo_archives.archives_updated = Some(chrono::Local::now());
+        repo_archives.archives_limited = archives.len() >= ARCHIVES_LIMIT as usize;
     }));
     info!("Archives cache refreshed");
 
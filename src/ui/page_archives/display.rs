@@ -4,13 +4,13 @@ use adw::prelude::*;
 
 use super::cache;
 use super::events;
+use super::filter::{self, HostFilter};
 use crate::ui::utils::repo_cache::RepoCache;
 use crate::{borg, config, ui};
 
 pub async fn show() -> Result<()> {
-    ui::utils::clear(&main_ui().archive_list());
-
     let config = BACKUP_CONFIG.load().active()?.clone();
+    clear_other_repo(&config.repo_id);
 
     // location info
 
@@ -28,15 +28,38 @@ pub async fn show() -> Result<()> {
         trace!("Archives have never been retrieved");
         cache::refresh_archives(config.clone(), None).await
     } else {
+        if repo_archives.archives_outdated() && can_refresh_in_background(&config) {
+            trace!("Refreshing outdated archives in background");
+            glib::MainContext::default().spawn_local(enclose!((config) async move {
+                if let Err(err) = cache::refresh_archives(config, None).await {
+                    debug!("Failed to refresh archives in background: {:?}", err);
+                }
+            }));
+        }
         Ok(())
     };
 
     ui_display_archives(&config.repo_id);
     refresh_status();
+    Handler::run(super::usage::refresh());
 
     result
 }
 
+/// Refreshing must not ask for anything, like connecting a device
+fn can_refresh_in_background(config: &config::Backup) -> bool {
+    let operation_running = BORG_OPERATION.with(|operations| {
+        operations
+            .load()
+            .values()
+            .any(|operation| operation.repo_id() == &config.repo_id)
+    });
+
+    !operation_running
+        && !ACTIVE_MOUNTS.load().contains(&config.repo_id)
+        && config.repo.is_drive_connected() != Some(false)
+}
+
 pub fn refresh_status() {
     if super::is_visible() {
         if let Some(id) = ACTIVE_BACKUP_ID.load().as_ref().as_ref() {
@@ -52,7 +75,9 @@ pub fn refresh_status() {
                     matches!(op, Some(ref op) if op.task_kind() == borg::task::Kind::Check);
 
                 main_ui().archives_check_now().set_visible(!running);
-                main_ui().archives_check_now().set_sensitive(op.is_none());
+                main_ui()
+                    .archives_check_now()
+                    .set_sensitive(op.is_none() && !REPOSITORY_QUEUE.load().contains_key(id));
                 main_ui().archives_check_abort().set_visible(running);
             });
         }
@@ -61,6 +86,8 @@ pub fn refresh_status() {
             let is_mounted = ACTIVE_MOUNTS.load().contains(&config.repo_id);
             main_ui().archives_eject_button().set_visible(is_mounted);
         }
+
+        ui_update_archives_spinner();
     }
 }
 
@@ -112,17 +139,28 @@ pub async fn show_dir(path: &std::path::Path) -> Result<()> {
 
 pub fn ui_update_archives_spinner() {
     if super::is_visible() {
-        if let Ok(repo_id) = BACKUP_CONFIG.load().active().map(|x| &x.repo_id) {
+        if let Ok(config) = BACKUP_CONFIG.load().active() {
             let reloading = REPO_CACHE
                 .load()
-                .get(repo_id)
+                .get(&config.repo_id)
                 .map(|x| x.reloading)
                 .unwrap_or_default();
 
-            if reloading {
+            if let Some(name) = REPOSITORY_QUEUE.load().get(&config.id) {
+                // Operations of this backup wait for another backup on the same repository
+                main_ui()
+                    .archives_queued_label()
+                    .set_tooltip_text(Some(&gettextf(
+                        "“{}” starts when the operation of another backup on this repository is completed",
+                        &[name.as_str()],
+                    )));
                 main_ui()
                     .archives_reloading_stack()
-                    .set_visible_child(&main_ui().archives_reloading_spinner());
+                    .set_visible_child(&main_ui().archives_queued());
+            } else if reloading {
+                main_ui()
+                    .archives_reloading_stack()
+                    .set_visible_child(&main_ui().archives_reloading());
             } else {
                 main_ui()
                     .archives_reloading_stack()
@@ -138,6 +176,38 @@ pub async fn update_eject_button() -> Result<()> {
     Ok(())
 }
 
+thread_local!(
+    static ARCHIVE_STORE: gio::ListStore =
+        ui::utils::list_store::bind(&main_ui().archive_list(), archive_row);
+    /// Repository of the archives in the list
+    static DISPLAYED_REPO: std::cell::RefCell<Option<borg::RepoId>> = Default::default();
+    /// Computer whose archives are shown
+    static HOST_FILTER: std::cell::RefCell<HostFilter> = Default::default();
+    /// Other computers offered by the filter, after the fixed items
+    static FILTER_HOSTS: std::cell::RefCell<Vec<String>> = Default::default();
+    /// The filter items are being replaced
+    static UPDATING_FILTER: std::cell::Cell<bool> = Default::default();
+);
+
+/// Number of filter items before the other computers
+const FILTER_FIXED_ITEMS: u32 = 2;
+
+/// Remove archives of a different repository from the list
+fn clear_other_repo(repo_id: &borg::RepoId) {
+    let other = DISPLAYED_REPO.with(|displayed| displayed.borrow().as_ref() != Some(repo_id));
+
+    if other {
+        HOST_FILTER.with(|filter| *filter.borrow_mut() = HostFilter::All);
+        FILTER_HOSTS.with(|hosts| hosts.borrow_mut().clear());
+        ARCHIVE_STORE.with(|store| {
+            ui::utils::list_store::update::<(borg::ArchiveName, borg::ListArchive)>(
+                store,
+                Vec::new(),
+            )
+        });
+    }
+}
+
 pub fn ui_display_archives(repo_id: &borg::RepoId) {
     if Ok(repo_id) != BACKUP_CONFIG.load().active().map(|x| &x.repo_id) || !super::is_visible() {
         debug!("Not displaying archive list because it's not visible");
@@ -147,99 +217,194 @@ pub fn ui_display_archives(repo_id: &borg::RepoId) {
     debug!("Displaying archive list from cache");
     let repo_cache = RepoCache::get(repo_id);
 
-    ui::utils::clear(&main_ui().archive_list());
     ui_update_archives_spinner();
 
-    for (archive_name, archive) in repo_cache.archives_sorted_by_date() {
-        let row = adw::ExpanderRow::builder()
-            .title(
-                &archive
-                    .start
-                    .to_locale()
-                    .unwrap_or_else(|| archive.start.to_string()),
-            )
-            .subtitle(&format!(
-                "{hostname}, {username}",
-                hostname = archive.hostname,
-                username = archive.username
-            ))
-            .build();
+    let archives = repo_cache.archives_sorted_by_date();
+    update_host_filter(&archives);
 
-        if archive.name.as_str().ends_with(".checkpoint") {
-            let checkpoint_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
-            checkpoint_box.add_css_class("tag-box");
-            checkpoint_box.set_valign(gtk::Align::Center);
+    let host_filter = HOST_FILTER.with(|filter| filter.borrow().clone());
+    let archives: Vec<_> = archives
+        .into_iter()
+        .filter(|(_, archive)| host_filter.matches(archive))
+        .collect();
+    let is_empty = archives.is_empty();
 
-            let info_tag = gtk::Label::new(Some(&gettext("Incomplete Archive")));
-            info_tag.add_css_class("tag");
+    ARCHIVE_STORE.with(|store| ui::utils::list_store::update(store, archives));
+    DISPLAYED_REPO.with(|displayed| *displayed.borrow_mut() = Some(repo_id.clone()));
 
-            checkpoint_box.append(&info_tag);
-            row.add_suffix(&checkpoint_box);
-        }
+    if !is_empty {
+        main_ui()
+            .archives_stack()
+            .set_visible_child(&main_ui().archive_list());
+    } else {
+        main_ui()
+            .archives_stack()
+            .set_visible_child(&main_ui().archive_list_placeholder());
+    }
+}
 
-        let info = |title: String, info: &str| -> adw::ActionRow {
-            let label = gtk::Label::builder()
-                .label(info)
-                .wrap(true)
-                .wrap_mode(gtk::pango::WrapMode::WordChar)
-                .natural_wrap_mode(gtk::NaturalWrapMode::None)
-                .build();
-            label.add_css_class("dim-label");
-
-            let row = adw::ActionRow::builder().title(title).build();
-            row.add_suffix(&label);
-            row
+/// Offer the computers that created archives in the filter
+fn update_host_filter(archives: &[(borg::ArchiveName, borg::ListArchive)]) {
+    let hosts = filter::other_hosts(archives.iter().map(|(_, archive)| archive));
+    let host_filter = HOST_FILTER.with(|filter| filter.borrow().clone());
+
+    if FILTER_HOSTS.with(|current| *current.borrow() != hosts) {
+        let mut items = vec![gettext("All Computers"), gettext("This Computer")];
+        items.extend(hosts.iter().cloned());
+
+        let position = match &host_filter {
+            HostFilter::All => Some(0),
+            HostFilter::ThisComputer => Some(1),
+            HostFilter::Host(hostname) => hosts
+                .iter()
+                .position(|x| x == hostname)
+                .map(|i| i as u32 + FILTER_FIXED_ITEMS),
         };
 
-        row.add_row(&info(gettext("Name"), archive.name.as_str()));
-        row.add_row(&info(
-            gettext("Duration"),
-            &ui::utils::duration::plain(&(archive.end - archive.start)),
-        ));
-        if !archive.comment.is_empty() {
-            row.add_row(&info(gettext("Comment"), &archive.comment));
+        UPDATING_FILTER.with(|updating| updating.set(true));
+        let model = main_ui().archives_host_filter_model();
+        model.splice(
+            0,
+            model.n_items(),
+            &items.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+        main_ui()
+            .archives_host_filter()
+            .set_selected(position.unwrap_or_default());
+        UPDATING_FILTER.with(|updating| updating.set(false));
+
+        if position.is_none() {
+            HOST_FILTER.with(|filter| *filter.borrow_mut() = HostFilter::All);
         }
+        FILTER_HOSTS.with(|current| *current.borrow_mut() = hosts.clone());
+    }
 
-        let browse_row = adw::ActionRow::builder()
-            .title(&gettext("Browse saved files"))
-            .activatable(true)
-            .build();
+    main_ui()
+        .archives_host_filter()
+        .set_visible(!hosts.is_empty() || host_filter != HostFilter::All);
+}
 
-        browse_row.add_prefix(&gtk::Image::from_icon_name("folder-open-symbolic"));
-        browse_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+pub fn on_host_filter_changed() {
+    if UPDATING_FILTER.with(|updating| updating.get()) {
+        return;
+    }
 
-        row.add_row(&browse_row);
+    let host_filter = match main_ui().archives_host_filter().selected() {
+        0 => HostFilter::All,
+        1 => HostFilter::ThisComputer,
+        position => FILTER_HOSTS
+            .with(|hosts| {
+                hosts
+                    .borrow()
+                    .get((position - FILTER_FIXED_ITEMS) as usize)
+                    .cloned()
+            })
+            .map(HostFilter::Host)
+            .unwrap_or_default(),
+    };
 
-        browse_row.connect_activated(
-            enclose!((archive_name) move |_| Handler::run(events::browse_archive(archive_name.clone()))),
-        );
+    HOST_FILTER.with(|filter| *filter.borrow_mut() = host_filter);
 
-        let delete_row = adw::ActionRow::builder()
-            .title(&gettext("Delete archive"))
-            .activatable(true)
-            .build();
+    if let Ok(config) = BACKUP_CONFIG.load().active() {
+        ui_display_archives(&config.repo_id);
+    }
+}
 
-        delete_row.add_prefix(&gtk::Image::from_icon_name("edit-delete-symbolic"));
-        delete_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+fn archive_row(item: &(borg::ArchiveName, borg::ListArchive)) -> gtk::Widget {
+    let (archive_name, archive) = item.clone();
+
+    let row = adw::ExpanderRow::builder()
+        .title(
+            &archive
+                .start
+                .to_locale()
+                .unwrap_or_else(|| archive.start.to_string()),
+        )
+        .subtitle(&format!(
+            "{hostname}, {username}",
+            hostname = archive.hostname,
+            username = archive.username
+        ))
+        .build();
+
+    if archive.name.as_str().ends_with(".checkpoint") {
+        let checkpoint_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        checkpoint_box.add_css_class("tag-box");
+        checkpoint_box.set_valign(gtk::Align::Center);
+
+        let info_tag = gtk::Label::new(Some(&gettext("Incomplete Archive")));
+        info_tag.add_css_class("tag");
+
+        checkpoint_box.append(&info_tag);
+        row.add_suffix(&checkpoint_box);
+    }
 
-        row.add_row(&delete_row);
+    let info = |title: String, info: &str| -> adw::ActionRow {
+        let label = gtk::Label::builder()
+            .label(info)
+            .wrap(true)
+            .wrap_mode(gtk::pango::WrapMode::WordChar)
+            .natural_wrap_mode(gtk::NaturalWrapMode::None)
+            .build();
+        label.add_css_class("dim-label");
 
-        delete_row.connect_activated(
-            enclose!((archive_name) move |_| Handler::run(events::delete_archive(archive_name.clone(), archive.clone()))),
-        );
+        let row = adw::ActionRow::builder().title(title).build();
+        row.add_suffix(&label);
+        row
+    };
 
-        main_ui().archive_list().append(&row);
+    row.add_row(&info(gettext("Name"), archive.name.as_str()));
+    row.add_row(&info(
+        gettext("Duration"),
+        &ui::utils::duration::plain(&(archive.end - archive.start)),
+    ));
+    if !archive.comment.is_empty() {
+        row.add_row(&info(gettext("Comment"), &archive.comment));
     }
 
-    if !repo_cache.archives_sorted_by_date().is_empty() {
-        main_ui()
-            .archives_stack()
-            .set_visible_child(&main_ui().archive_list());
-    } else {
-        main_ui()
-            .archives_stack()
-            .set_visible_child(&main_ui().archive_list_placeholder());
-    }
+    let browse_row = adw::ActionRow::builder()
+        .title(&gettext("Browse saved files"))
+        .activatable(true)
+        .build();
+
+    browse_row.add_prefix(&gtk::Image::from_icon_name("folder-open-symbolic"));
+    browse_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+    row.add_row(&browse_row);
+
+    browse_row.connect_activated(
+        enclose!((archive_name) move |_| Handler::run(events::browse_archive(archive_name.clone()))),
+    );
+
+    let restore_row = adw::ActionRow::builder()
+        .title(&gettext("Restore entire archive"))
+        .activatable(true)
+        .build();
+
+    restore_row.add_prefix(&gtk::Image::from_icon_name("edit-undo-symbolic"));
+    restore_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+    row.add_row(&restore_row);
+
+    restore_row.connect_activated(
+        enclose!((archive_name, archive) move |_| Handler::run(events::restore_archive(archive_name.clone(), archive.clone()))),
+    );
+
+    let delete_row = adw::ActionRow::builder()
+        .title(&gettext("Delete archive"))
+        .activatable(true)
+        .build();
+
+    delete_row.add_prefix(&gtk::Image::from_icon_name("edit-delete-symbolic"));
+    delete_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+    row.add_row(&delete_row);
+
+    delete_row.connect_activated(
+        enclose!((archive_name) move |_| Handler::run(events::delete_archive(archive_name.clone(), archive.clone()))),
+    );
+
+    row.upcast()
 }
 
 pub async fn update_df() -> Result<()> {
@@ -5,6 +5,7 @@ use adw::prelude::*;
 use super::display;
 use crate::borg;
 use crate::ui;
+use ui::utils::restore;
 
 pub async fn check() -> Result<()> {
     let configs = BACKUP_CONFIG.load();
@@ -23,6 +24,35 @@ pub async fn cleanup() -> Result<()> {
     ui::dialog_prune::run(config).await
 }
 
+pub async fn file_history() -> Result<()> {
+    let config = BACKUP_CONFIG.load().active()?.clone();
+
+    ui::dialog_file_history::run(&config).await
+}
+
+/// Abort reloading the archives of the repository
+pub async fn cancel_refresh() -> Result<()> {
+    let repo_id = BACKUP_CONFIG.load().active()?.repo_id.clone();
+
+    BORG_OPERATION.with(|operations| {
+        for operation in operations.load().values() {
+            if operation.repo_id() == &repo_id && operation.task_kind() == borg::task::Kind::List {
+                info!("Canceling archives refresh");
+                operation.set_instruction(borg::Instruction::Abort(borg::Abort::User));
+            }
+        }
+    });
+
+    Ok(())
+}
+
+pub async fn dequeue() -> Result<()> {
+    let config_id = BACKUP_CONFIG.load().active()?.id.clone();
+    ui::utils::borg::dequeue(&config_id);
+
+    Ok(())
+}
+
 pub async fn edit_prefix() -> Result<()> {
     let configs = BACKUP_CONFIG.load();
     let config = configs.active()?;
@@ -43,14 +73,16 @@ pub async fn eject_button_clicked() -> Result<()> {
     display::update_eject_button().await
 }
 
-pub async fn browse_archive(archive_name: borg::ArchiveName) -> Result<()> {
+/// Mount the repository if not already mounted and return the path of the archive
+pub async fn mount_archive(
+    config: &crate::config::Backup,
+    archive_name: &borg::ArchiveName,
+) -> Result<std::path::PathBuf> {
+    ui::utils::authentication::require().await?;
+
     let guard = QuitGuard::default();
-    let configs = BACKUP_CONFIG.load();
-    let config = configs.active()?;
     let repo_id = &config.repo_id;
 
-    debug!("Trying to browse an archive");
-
     // Register mounts from a previous run that quit improperly
     crate::ui::utils::borg::cleanup_mounts().await?;
 
@@ -66,11 +98,10 @@ pub async fn browse_archive(archive_name: borg::ArchiveName) -> Result<()> {
 
         main_ui().pending_menu().set_visible(true);
 
-        let mount = ui::utils::borg::exec(
-            borg::Command::<borg::task::Mount>::new(config.clone()),
-            &guard,
-        )
-        .await;
+        let mut command = borg::Command::<borg::task::Mount>::new(config.clone());
+        ui::utils::permissions::configure_mount(&mut command.task);
+
+        let mount = ui::utils::borg::exec(command, &guard).await;
 
         if mount.is_err() {
             ACTIVE_MOUNTS.update(|mounts| {
@@ -84,6 +115,17 @@ pub async fn browse_archive(archive_name: borg::ArchiveName) -> Result<()> {
 
     display::update_eject_button().await?;
 
+    Ok(path)
+}
+
+pub async fn browse_archive(archive_name: borg::ArchiveName) -> Result<()> {
+    let configs = BACKUP_CONFIG.load();
+    let config = configs.active()?;
+
+    debug!("Trying to browse an archive");
+
+    let path = mount_archive(config, &archive_name).await?;
+
     let first_populated_dir = ui::utils::spawn_thread("open_archive", move || {
         super::find_first_populated_dir(&path)
     })
@@ -101,12 +143,89 @@ pub async fn delete_archive(
 
     debug!("Trying to delete an archive");
 
-    let archive_name = archive_name.as_str();
-    let archive_date = &archive
+    ui::dialog_delete_archive::run(config, &archive_name, &archive).await
+}
+
+pub async fn restore_archive(
+    archive_name: borg::ArchiveName,
+    archive: borg::ListArchive,
+) -> Result<()> {
+    let config = BACKUP_CONFIG.load().active()?.clone();
+
+    debug!("Trying to restore an archive");
+
+    let owners = restore::owners(&config, &archive_name).await;
+    let path = mount_archive(&config, &archive_name).await?;
+    main_ui().pending_menu().set_visible(false);
+
+    let archive_date = archive
         .start
         .to_locale()
-        .unwrap_or_else(|| archive.start.to_string())
-        .clone();
+        .unwrap_or_else(|| archive.start.to_string());
+
+    ui::dialog_restore_archive::run(path, archive_name.as_str(), &archive_date, owners).await
+}
+
+/// Show the folder containing a file from an archive
+pub async fn open_archive_file(
+    config: crate::config::Backup,
+    archive_name: borg::ArchiveName,
+    file: borg::ArchiveFile,
+) -> Result<()> {
+    let path = mount_archive(&config, &archive_name)
+        .await?
+        .join(&file.path);
+
+    let dir = if file.is_dir() {
+        path
+    } else {
+        path.parent().map(|x| x.to_path_buf()).unwrap_or(path)
+    };
+
+    display::show_dir(&dir).await
+}
+
+/// Restore a single file or folder from an archive to its original location
+///
+/// Existing files are never replaced.
+pub async fn restore_archive_file(
+    config: crate::config::Backup,
+    archive_name: borg::ArchiveName,
+    file: borg::ArchiveFile,
+) -> Result<()> {
+    let archive_root = mount_archive(&config, &archive_name).await?;
+    main_ui().pending_menu().set_visible(false);
+
+    let _guard = QuitGuard::default();
+    let path = archive_root.join(&file.path);
+
+    let summary = ui::utils::spawn_thread(
+        "restore_archive_file",
+        enclose!((archive_root, path) move || {
+            restore::restore_path(
+                &archive_root,
+                &path,
+                restore::ConflictPolicy::KeepBoth,
+                &Default::default(),
+            )
+        }),
+    )
+    .await?;
 
-    ui::dialog_delete_archive::run(config, archive_name, archive_date).await
+    if let Some((path, err)) = summary.failed.first() {
+        return Err(Message::new(
+            gettext("Failed to Restore File"),
+            format!("{}: {}", path.display(), err),
+        )
+        .into());
+    }
+
+    ui::utils::show_notice(gettextf(
+        "Restored “{}”",
+        &[&restore::original_path(&archive_root, &path)
+            .display()
+            .to_string()],
+    ));
+
+    Ok(())
 }
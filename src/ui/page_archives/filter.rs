@@ -0,0 +1,66 @@
+//! Show only archives created by a specific computer
+//!
+//! Repositories can be shared by several computers, for example within a
+//! family. Archives record the host that created them.
+
+use crate::borg;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HostFilter {
+    #[default]
+    All,
+    ThisComputer,
+    Host(String),
+}
+
+impl HostFilter {
+    pub fn matches(&self, archive: &borg::ListArchive) -> bool {
+        match self {
+            Self::All => true,
+            Self::ThisComputer => archive.is_from_this_computer(),
+            Self::Host(hostname) => archive.hostname == *hostname,
+        }
+    }
+}
+
+/// Other computers that created archives, sorted by name
+pub fn other_hosts<'a>(archives: impl IntoIterator<Item = &'a borg::ListArchive>) -> Vec<String> {
+    let mut hosts: Vec<String> = archives
+        .into_iter()
+        .filter(|archive| !archive.is_from_this_computer())
+        .map(|archive| archive.hostname.clone())
+        .collect();
+
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
+#[test]
+fn test_host_filter() {
+    let archive = |hostname: &str| borg::ListArchive {
+        id: borg::ArchiveId::new(String::from("id")),
+        name: borg::ArchiveName::new(String::from("name")),
+        comment: String::new(),
+        username: String::from("user"),
+        hostname: hostname.to_string(),
+        start: chrono::NaiveDateTime::default(),
+        end: chrono::NaiveDateTime::default(),
+        command_line: Vec::new(),
+    };
+
+    let this = archive(&glib::host_name());
+    let laptop = archive("laptop-of-someone-else");
+    let desktop = archive("desktop-of-someone-else");
+
+    assert!(HostFilter::All.matches(&laptop));
+    assert!(HostFilter::ThisComputer.matches(&this));
+    assert!(!HostFilter::ThisComputer.matches(&laptop));
+    assert!(HostFilter::Host(laptop.hostname.clone()).matches(&laptop));
+    assert!(!HostFilter::Host(laptop.hostname.clone()).matches(&desktop));
+
+    assert_eq!(
+        other_hosts([&laptop, &this, &desktop, &laptop]),
+        vec![desktop.hostname.clone(), laptop.hostname.clone()]
+    );
+}
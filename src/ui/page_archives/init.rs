@@ -36,6 +36,10 @@ pub fn init() {
         .archives_cleanup()
         .connect_activated(|_| Handler::run(events::cleanup()));
 
+    main_ui()
+        .archives_file_history()
+        .connect_activated(|_| Handler::run(events::file_history()));
+
     main_ui().refresh_archives().connect_clicked(|_| {
         Handler::run(async move {
             let config = BACKUP_CONFIG.load().active()?.clone();
@@ -43,6 +47,18 @@ pub fn init() {
         });
     });
 
+    main_ui()
+        .archives_host_filter()
+        .connect_selected_notify(|_| display::on_host_filter_changed());
+
+    main_ui()
+        .archives_reloading_cancel()
+        .connect_clicked(|_| Handler::run(events::cancel_refresh()));
+
+    main_ui()
+        .archives_queued_cancel()
+        .connect_clicked(|_| Handler::run(events::dequeue()));
+
     main_ui().archives_eject_button().connect_clicked(|_| {
         Handler::run(events::eject_button_clicked());
     });
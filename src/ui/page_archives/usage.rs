@@ -0,0 +1,88 @@
+//! Share of the included folders in the latest indexed archive
+
+use adw::prelude::*;
+
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+use ui::utils::file_index::{self, FileIndex, RootUsage};
+use ui::widget::UsageBar;
+
+pub async fn refresh() -> Result<()> {
+    let config = BACKUP_CONFIG.load().active()?.clone();
+
+    let usage = ui::utils::spawn_thread(
+        "archives_usage",
+        enclose!((config) move || {
+            file_index::usage_by_root(&config, &FileIndex::load(&config.repo_id))
+        }),
+    )
+    .await?;
+
+    // Another backup might have been opened in the meantime
+    if BACKUP_CONFIG.load().active().ok().map(|x| &x.id) == Some(&config.id) {
+        show(usage);
+    }
+
+    Ok(())
+}
+
+fn show(usage: Option<(chrono::NaiveDateTime, Vec<RootUsage>)>) {
+    let list = main_ui().archives_usage_list();
+    while let Some(row) = list.row_at_index(1) {
+        list.remove(&row);
+    }
+
+    let Some((date, roots)) = usage.filter(|(_, roots)| roots.iter().any(|x| x.size > 0)) else {
+        main_ui().archives_usage_group().set_visible(false);
+        return;
+    };
+
+    main_ui().archives_usage_group().set_description(Some(&gettextf(
+        "Size of the included folders in the latest indexed archive from {}, before deduplication and compression",
+        &[&date.to_locale().unwrap_or_else(|| date.to_string())],
+    )));
+
+    main_ui()
+        .archives_usage_bar()
+        .set_values(&roots.iter().map(|x| x.size).collect::<Vec<_>>());
+
+    for (i, root) in roots.iter().enumerate() {
+        list.append(&row(i, root));
+    }
+
+    main_ui().archives_usage_group().set_visible(true);
+}
+
+fn row(index: usize, usage: &RootUsage) -> adw::ActionRow {
+    let path = usage
+        .root
+        .strip_prefix(glib::home_dir())
+        .unwrap_or(&usage.root);
+
+    let row = adw::ActionRow::builder()
+        .use_markup(false)
+        .title(config::display_path(path))
+        .subtitle(ngettextf_("{} file", "{} files", usage.files as u32))
+        .build();
+
+    let legend = gtk::Box::builder()
+        .valign(gtk::Align::Center)
+        .width_request(12)
+        .height_request(12)
+        .build();
+    legend.add_css_class("usage-segment");
+    legend.add_css_class("legend");
+    legend.add_css_class(&UsageBar::color_class(index));
+    row.add_prefix(&legend);
+
+    let size = gtk::Label::builder()
+        .label(glib::format_size(usage.size))
+        .valign(gtk::Align::Center)
+        .build();
+    size.add_css_class("dim-label");
+    size.add_css_class("numeric");
+    row.add_suffix(&size);
+
+    row
+}
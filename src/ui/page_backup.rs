@@ -1,10 +1,11 @@
 mod display;
 mod events;
 mod execution;
+mod include_size;
 pub mod init;
 
-pub use display::{refresh, refresh_disk_status, refresh_status};
-pub use events::on_stop_backup_create;
+pub use display::{refresh, refresh_disk_status, refresh_status, reset_same_drive_cache};
+pub use events::{on_backup_run, on_stop_backup_create, stop_operation};
 
 use crate::schedule;
 use crate::ui::prelude::*;
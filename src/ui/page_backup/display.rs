@@ -1,21 +1,51 @@
 use adw::prelude::*;
 
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use crate::config;
 
 use crate::ui;
 use crate::ui::backup_status;
 use crate::ui::prelude::*;
+use crate::utils::include_check;
 
 use super::events;
 
-pub fn add_list_row(list: &gtk::ListBox, file: &std::path::Path) -> gtk::Button {
+/// Included folder as shown in the include list
+#[derive(Clone, PartialEq)]
+struct IncludeItem {
+    path: PathBuf,
+    problems: Vec<include_check::Problem>,
+}
+
+thread_local!(
+    static INCLUDE_STORE: gio::ListStore =
+        ui::utils::list_store::bind(&main_ui().include(), include_row);
+    static SIZE_LABELS: RefCell<BTreeMap<PathBuf, gtk::Label>> = Default::default();
+    /// Whether a repository path shares a drive with the included folders
+    static SAME_DRIVE: RefCell<BTreeMap<(PathBuf, Vec<PathBuf>), bool>> = Default::default();
+);
+
+/// Returns the row, the remove button and the label for the size of the folder
+fn list_row(
+    file: &std::path::Path,
+    problems: Vec<include_check::Problem>,
+) -> (adw::ActionRow, gtk::Button, gtk::Label) {
     let title = if file == std::path::Path::new("") {
         gettext("Home")
     } else {
         file.display().to_string()
     };
 
-    let subtitle = if file == std::path::Path::new("") {
+    let subtitle = if !problems.is_empty() {
+        problems
+            .iter()
+            .map(|problem| problem.description())
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else if file == std::path::Path::new("") {
         gettext("Usually contains all personal data")
     } else {
         String::new()
@@ -28,13 +58,33 @@ pub fn add_list_row(list: &gtk::ListBox, file: &std::path::Path) -> gtk::Button
 
     row.set_title(&title);
     row.set_subtitle(&subtitle);
-    list.append(&row);
 
     if let Some(image) = crate::utils::file_symbolic_icon(&config::absolute(file)) {
         image.add_css_class("row-icon");
         row.add_prefix(&image);
     }
 
+    let size = gtk::Label::builder().valign(gtk::Align::Center).build();
+    size.add_css_class("dim-label");
+    size.add_css_class("numeric");
+    row.add_suffix(&size);
+
+    if !problems.is_empty() {
+        let warning = gtk::Button::builder()
+            .icon_name("dialog-warning-symbolic")
+            .valign(gtk::Align::Center)
+            .tooltip_text(gettext("Fix Problem"))
+            .build();
+        warning.add_css_class("flat");
+        warning.add_css_class("warning");
+
+        let path = file.to_path_buf();
+        warning.connect_clicked(move |_| {
+            Handler::run(events::on_include_problems(path.clone(), problems.clone()))
+        });
+        row.add_suffix(&warning);
+    }
+
     let button = gtk::Button::builder()
         .icon_name("edit-delete-symbolic")
         .valign(gtk::Align::Center)
@@ -43,7 +93,21 @@ pub fn add_list_row(list: &gtk::ListBox, file: &std::path::Path) -> gtk::Button
     button.add_css_class("flat");
     row.add_suffix(&button);
 
-    button
+    (row, button, size)
+}
+
+fn include_row(item: &IncludeItem) -> gtk::Widget {
+    let (row, button, size) = list_row(&item.path, item.problems.clone());
+
+    SIZE_LABELS.with(|labels| labels.borrow_mut().insert(item.path.clone(), size));
+
+    let path = item.path.clone();
+    button.connect_clicked(move |_| {
+        let path = path.clone();
+        Handler::run(events::on_remove_include(path))
+    });
+
+    row.upcast()
 }
 
 // TODO: Function has too many lines
@@ -52,11 +116,13 @@ pub fn refresh() -> Result<()> {
 
     refresh_status();
     refresh_disk_status();
+    ui::window_backup::refresh_config(&backup.id);
 
     // backup target ui
     if let Ok(icon) = gio::Icon::for_string(&backup.repo.icon()) {
         main_ui().detail_repo_icon().set_from_gicon(&icon);
     }
+    ui::utils::set_accent_color(&main_ui().detail_repo_icon(), backup.accent_color);
 
     main_ui()
         .detail_repo_row()
@@ -65,17 +131,81 @@ pub fn refresh() -> Result<()> {
         .detail_repo_row()
         .set_subtitle(&glib::markup_escape_text(&backup.repo.subtitle()));
 
+    main_ui()
+        .plaintext_banner()
+        .set_revealed(backup.warn_plaintext());
+    Handler::run(refresh_same_drive_warning(backup.clone()));
+
     // include list
-    ui::utils::clear(&main_ui().include());
+    let mounts = include_check::mounts();
+    let exclude = backup.exclude_dirs_internal();
+
+    let includes = backup
+        .include
+        .iter()
+        .map(|file| IncludeItem {
+            path: file.clone(),
+            problems: include_check::check(&config::absolute(file), &exclude, &mounts),
+        })
+        .collect();
+    INCLUDE_STORE.with(|store| ui::utils::list_store::update(store, includes));
+
+    let size_labels = SIZE_LABELS.with(|labels| {
+        let mut labels = labels.borrow_mut();
+        labels.retain(|path, _| backup.include.contains(path));
+        labels
+            .iter()
+            .map(|(path, label)| (path.clone(), label.clone()))
+            .collect()
+    });
+
+    super::include_size::show(&backup, size_labels);
+
+    // include pattern list
+    ui::utils::clear(&main_ui().include_patterns());
+
+    for pattern in &backup.include_patterns {
+        let matches = crate::utils::glob_paths(&config::absolute(pattern));
+
+        let row = adw::ExpanderRow::builder()
+            .use_markup(false)
+            .title(pattern.display().to_string())
+            .subtitle(ngettextf_(
+                "Currently matches one folder",
+                "Currently matches {} folders",
+                matches.len() as u32,
+            ))
+            .enable_expansion(!matches.is_empty())
+            .build();
+
+        for path in matches {
+            let path_row = adw::ActionRow::builder()
+                .use_markup(false)
+                .title(ui::utils::rel_path(&path).display().to_string())
+                .build();
 
-    for file in &backup.include {
-        let button = add_list_row(&main_ui().include(), file);
+            if let Some(image) = crate::utils::file_symbolic_icon(&path) {
+                image.add_css_class("row-icon");
+                path_row.add_prefix(&image);
+            }
+
+            row.add_row(&path_row);
+        }
+
+        let delete_button = gtk::Button::builder()
+            .icon_name("edit-delete-symbolic")
+            .valign(gtk::Align::Center)
+            .tooltip_text(gettext("Remove Pattern"))
+            .build();
+        delete_button.add_css_class("flat");
 
-        let path = file.clone();
-        button.connect_clicked(move |_| {
-            let path = path.clone();
-            Handler::run(events::on_remove_include(path))
+        let pattern = pattern.clone();
+        delete_button.connect_clicked(move |_| {
+            Handler::run(events::on_remove_include_pattern(pattern.clone()))
         });
+        row.add_suffix(&delete_button);
+
+        main_ui().include_patterns().append(&row);
     }
 
     // exclude list
@@ -140,13 +270,77 @@ pub fn refresh() -> Result<()> {
         main_ui().backup_exclude().append(&row);
     }
 
+    for file in backup.patterns_files {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&config::display_path(&file.path)))
+            .subtitle(if file.linked {
+                gettext("Linked Patterns File")
+            } else {
+                ngettextf_(
+                    "Imported Patterns File, one rule",
+                    "Imported Patterns File, {} rules",
+                    file.imported.len() as u32,
+                )
+            })
+            .activatable(false)
+            .build();
+
+        let image = gtk::Image::from_icon_name("text-x-generic-symbolic");
+        image.add_css_class("row-icon");
+        row.add_prefix(&image);
+
+        if !file.linked {
+            let sync_button = gtk::Button::builder()
+                .icon_name("view-refresh-symbolic")
+                .valign(gtk::Align::Center)
+                .tooltip_text(gettext("Import Again"))
+                .build();
+
+            sync_button.add_css_class("flat");
+
+            sync_button.connect_clicked(clone!(@strong file => move |_| {
+                Handler::handle(ui::dialog_exclude::sync_patterns_file(file.clone()));
+            }));
+
+            row.add_suffix(&sync_button);
+        }
+
+        let delete_button = gtk::Button::builder()
+            .icon_name("edit-delete-symbolic")
+            .valign(gtk::Align::Center)
+            .tooltip_text(gettext("Remove File and Its Rules"))
+            .build();
+
+        delete_button.add_css_class("flat");
+
+        delete_button.connect_clicked(move |_| {
+            let path = file.path.clone();
+            Handler::run(async move {
+                BACKUP_CONFIG.try_update(|settings| {
+                    settings.active_mut()?.remove_patterns_file(&path);
+                    Ok(())
+                })?;
+                refresh()?;
+                Ok(())
+            });
+        });
+        row.add_suffix(&delete_button);
+
+        main_ui().backup_exclude().append(&row);
+    }
+
     Ok(())
 }
 
 pub fn refresh_disk_status() {
     if let Ok(backup) = BACKUP_CONFIG.load().active().cloned() {
-        let operation_running =
-            BORG_OPERATION.with(|operations| operations.load().get(&backup.id).is_some());
+        // Other backups on the same repository might be using the disk as well
+        let operation_running = BORG_OPERATION.with(|operations| {
+            operations
+                .load()
+                .values()
+                .any(|operation| operation.repo_id() == &backup.repo_id)
+        });
 
         main_ui()
             .backup_disk_eject_button()
@@ -158,19 +352,75 @@ pub fn refresh_disk_status() {
     }
 }
 
+/// Forget which drives repositories and included folders are on
+///
+/// Called when mounts change since paths can then be on a different drive.
+pub fn reset_same_drive_cache() {
+    SAME_DRIVE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// Show an advisory if the repository shares a physical drive with backed up files
+async fn refresh_same_drive_warning(backup: config::Backup) -> Result<()> {
+    let same_drive = match &backup.repo {
+        config::Repository::Local(repo) if !backup.repo.is_network() => {
+            let key = (
+                repo.path(),
+                backup.include_dirs().into_iter().collect::<Vec<_>>(),
+            );
+
+            if let Some(same_drive) = SAME_DRIVE.with(|cache| cache.borrow().get(&key).copied()) {
+                same_drive
+            } else {
+                let same_drive = ui::utils::spawn_thread(
+                    "shares_drive",
+                    enclose!((key) move || {
+                        async_std::task::block_on(crate::utils::udisks::shares_drive(
+                            &key.0, &key.1,
+                        ))
+                    }),
+                )
+                .await?;
+                SAME_DRIVE.with(|cache| cache.borrow_mut().insert(key, same_drive));
+
+                same_drive
+            }
+        }
+        _ => false,
+    };
+
+    if BACKUP_CONFIG
+        .load()
+        .active()
+        .is_ok_and(|active| active.id == backup.id)
+    {
+        main_ui().same_drive_banner().set_revealed(same_drive);
+    }
+
+    Ok(())
+}
+
 pub fn refresh_status() {
     if super::is_visible() {
         if let Some(id) = ACTIVE_BACKUP_ID.load().as_ref().as_ref() {
-            refresh_status_display(&backup_status::Display::new_from_id(id));
+            let verified = BACKUP_HISTORY
+                .load()
+                .try_get(id)
+                .ok()
+                .and_then(|history| history.run.front())
+                .is_some_and(|run_info| run_info.verified);
+            refresh_status_display(&backup_status::Display::new_from_id(id), verified);
         }
     }
 }
 
-fn refresh_status_display(status: &ui::backup_status::Display) {
+fn refresh_status_display(status: &ui::backup_status::Display, verified: bool) {
     main_ui().detail_status_row().set_from_backup_status(status);
 
     let running = matches!(&status.graphic, ui::backup_status::Graphic::Spinner);
     main_ui().stop_backup_create().set_visible(running);
     main_ui().backup_run().set_sensitive(!running);
     main_ui().detail_hint_icon().set_visible(!running);
+    main_ui()
+        .detail_verified_badge()
+        .set_visible(verified && !running);
 }
@@ -1,12 +1,15 @@
 use std::ffi::OsStr;
 use std::path::PathBuf;
 
-use gtk::prelude::*;
+use adw::prelude::*;
+use gtk::gdk;
 
 use crate::borg;
+use crate::config;
 use crate::ui;
 
 use crate::ui::prelude::*;
+use crate::utils::include_check;
 
 use super::display;
 use super::execution;
@@ -18,13 +21,39 @@ pub fn on_stack_changed() {
 }
 
 pub async fn on_stop_backup_create() -> Result<()> {
-    let operation = BORG_OPERATION.with(|op| Ok::<_, Error>(op.load().active()?.clone()))?;
+    let config_id = BACKUP_CONFIG.load().active()?.id.clone();
+    stop_operation(&config_id).await
+}
+
+/// Stop the operation of `config_id` after asking for confirmation
+pub async fn stop_operation(config_id: &ConfigId) -> Result<()> {
+    if ui::utils::borg::dequeue(config_id) {
+        return Ok(());
+    }
+
+    if ui::remote_operation::is_attached(config_id) {
+        ui::confirm::confirm(
+            ui::confirm::Category::Stop,
+            &gettext("Abort Operation?"),
+            &gettext("The operation has been started by another process. Aborting now will cause any progress made by the operation to be lost."),
+            &gettext("Continue"),
+            &gettext("Abort"),
+        )
+        .await?;
+
+        return ui::remote_operation::abort(config_id).await;
+    }
+
+    let Some(operation) = BORG_OPERATION.with(|op| op.load().get(config_id).cloned()) else {
+        return Ok(());
+    };
 
     // Abort immediately if only reconnecting
     if !operation.aborting() && !matches!(operation.status(), borg::Run::Reconnecting(_)) {
         match operation.task_kind() {
             borg::task::Kind::Create => {
-                ui::utils::confirmation_dialog(
+                ui::confirm::confirm(
+                ui::confirm::Category::Stop,
                 &gettext("Stop Running Backup?"),
                 &gettext("The current backup state will be saved. You can continue your backup later by starting it again."),
                 &gettext("Continue"),
@@ -33,7 +62,8 @@ pub async fn on_stop_backup_create() -> Result<()> {
             .await?;
             }
             borg::task::Kind::Prune | borg::task::Kind::Delete => {
-                ui::utils::confirmation_dialog(
+                ui::confirm::confirm(
+                    ui::confirm::Category::Stop,
                     &gettext("Abort Delete Operation?"),
                     &gettext("Archives are currently being deleted. Free space will not be reclaimed when aborting."),
                     &gettext("Continue"),
@@ -42,7 +72,8 @@ pub async fn on_stop_backup_create() -> Result<()> {
                 .await?;
             }
             _ => {
-                ui::utils::confirmation_dialog(
+                ui::confirm::confirm(
+                ui::confirm::Category::Stop,
                 &gettext("Abort Operation?"),
                 &gettext("An operation is currently being performed. Aborting now will cause any progress made by the operation to be lost."),
                 &gettext("Continue"),
@@ -58,6 +89,75 @@ pub async fn on_stop_backup_create() -> Result<()> {
     Ok(())
 }
 
+/// Path currently read by the running backup
+fn current_path(config_id: &ConfigId) -> Option<PathBuf> {
+    match ui::backup_status::Display::new_from_id(config_id).stats {
+        Some(ui::backup_status::Stats::Progress(archive)) if !archive.path.is_empty() => {
+            Some(PathBuf::from(format!("/{}", archive.path)))
+        }
+        _ => None,
+    }
+}
+
+/// Stop the running backup and restart it without the current file or the include containing it
+pub async fn on_skip_current(include_root: bool) -> Result<()> {
+    let config = BACKUP_CONFIG.load().active()?.clone();
+    let operation = BORG_OPERATION.with(|op| Ok::<_, Error>(op.load().active()?.clone()))?;
+
+    if operation.aborting() {
+        return Ok(());
+    }
+
+    let Some(path) = current_path(&config.id) else {
+        return Ok(());
+    };
+
+    let instruction = if include_root {
+        let Some(root) = config
+            .include_dirs()
+            .into_iter()
+            .filter(|dir| path.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+        else {
+            return Ok(());
+        };
+
+        ui::confirm::confirm(
+            ui::confirm::Category::Stop,
+            &gettextf("Skip “{}”?", &[&root.display().to_string()]),
+            &gettext("The backup will be restarted without this folder. The folder stays included for future backups."),
+            &gettext("Cancel"),
+            &gettext("Skip"),
+        )
+        .await?;
+
+        borg::Instruction::SkipIncludeRoot(root)
+    } else {
+        borg::Instruction::SkipPath(path)
+    };
+
+    operation.set_instruction(instruction);
+
+    Ok(())
+}
+
+/// Suspend the running backup or continue it if it is paused
+pub fn on_pause_toggle() -> Result<()> {
+    let operation = BORG_OPERATION.with(|op| Ok::<_, Error>(op.load().active()?.clone()))?;
+
+    if operation.aborting() {
+        return Ok(());
+    }
+
+    if matches!(operation.status(), borg::Run::Paused) {
+        operation.set_instruction(borg::Instruction::Resume);
+    } else {
+        operation.set_instruction(borg::Instruction::Pause);
+    }
+
+    Ok(())
+}
+
 pub async fn on_backup_run(guard: &QuitGuard) -> Result<()> {
     execution::backup(BACKUP_CONFIG.load().active()?.clone(), None, guard).await
 }
@@ -72,26 +172,31 @@ pub async fn on_backup_disk_eject() -> Result<()> {
     res
 }
 
+pub fn on_dismiss_plaintext_warning() -> Result<()> {
+    BACKUP_CONFIG.try_update(|settings| {
+        settings.active_mut()?.plaintext_warning_dismissed = true;
+        Ok(())
+    })?;
+
+    main_ui().plaintext_banner().set_revealed(false);
+
+    Ok(())
+}
+
 pub async fn add_include() -> Result<()> {
-    let chooser = gtk::FileDialog::builder()
-        .initial_folder(&gio::File::for_path(glib::home_dir()))
-        .title(gettext("Include Folder"))
-        .accept_label(gettext("Select"))
-        .modal(true)
-        .build();
+    let chooser = ui::utils::file_chooser::FileChooser::new(
+        &gettext("Include Folder"),
+        &gettext("Select"),
+        None,
+    );
 
-    let paths = ui::utils::paths_from_model(
-        chooser
-            .select_multiple_folders_future(Some(&main_ui().window()))
-            .await
-            .map_err(|err| match err.kind::<gtk::DialogError>() {
-                Some(gtk::DialogError::Cancelled | gtk::DialogError::Dismissed) => {
-                    Error::UserCanceled
-                }
-                _ => Message::short(err.to_string()).into(),
-            })?,
-    )?;
+    let paths = ui::utils::paths_from_model(chooser.select_folders(&main_ui().window()).await?)?;
 
+    include_paths(paths).await
+}
+
+/// Include folders, for example from the file chooser or the file manager
+pub async fn include_paths(paths: Vec<PathBuf>) -> Result<()> {
     let paths = if *APP_IS_SANDBOXED {
         let runtime_dir = glib::user_runtime_dir();
         let mut sandbox_filtered_paths = Vec::new();
@@ -158,12 +263,85 @@ pub async fn add_include() -> Result<()> {
     Ok(())
 }
 
+pub async fn add_include_pattern(pattern: String) -> Result<()> {
+    let pattern = pattern.trim();
+
+    if pattern.is_empty() {
+        return Ok(());
+    }
+
+    let pattern = ui::utils::rel_path(std::path::Path::new(
+        pattern.strip_prefix("~/").unwrap_or(pattern),
+    ));
+
+    BACKUP_CONFIG.try_update(|settings| {
+        settings
+            .active_mut()?
+            .include_patterns
+            .insert(pattern.clone());
+        Ok(())
+    })?;
+
+    display::refresh()
+}
+
+pub async fn on_remove_include_pattern(pattern: PathBuf) -> Result<()> {
+    BACKUP_CONFIG.try_update(|settings| {
+        settings.active_mut()?.include_patterns.remove(&pattern);
+        Ok(())
+    })?;
+
+    display::refresh()
+}
+
 pub async fn add_exclude() -> Result<()> {
     ui::dialog_exclude::show();
 
     Ok(())
 }
 
+/// Exclude files and folders, for example from the file manager
+pub async fn exclude_paths(paths: Vec<PathBuf>) -> Result<()> {
+    BACKUP_CONFIG.try_update(|settings| {
+        for path in &paths {
+            let pattern = if path.is_dir() {
+                config::Pattern::path_prefix(path)
+            } else {
+                config::Pattern::path_full_match(path)
+            };
+
+            settings
+                .active_mut()?
+                .exclude
+                .insert(config::Exclude::from_pattern(pattern));
+        }
+        Ok(())
+    })?;
+
+    display::refresh()
+}
+
+/// Paths of files copied in the file manager or pasted as text
+pub async fn clipboard_paths(clipboard: &gdk::Clipboard) -> Vec<PathBuf> {
+    if let Ok(value) = clipboard
+        .read_value_future(gdk::FileList::static_type(), glib::Priority::DEFAULT)
+        .await
+    {
+        if let Ok(files) = value.get::<gdk::FileList>() {
+            return files
+                .files()
+                .iter()
+                .filter_map(|file| file.path())
+                .collect();
+        }
+    }
+
+    match clipboard.read_text_future().await {
+        Ok(Some(text)) => ui::utils::paths_from_text(&text),
+        _ => Vec::new(),
+    }
+}
+
 pub async fn on_remove_include(path: std::path::PathBuf) -> Result<()> {
     if confirm_remove_include(&path).await {
         BACKUP_CONFIG.try_update(|settings| {
@@ -176,6 +354,47 @@ pub async fn on_remove_include(path: std::path::PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Offer to exclude the problematic parts of an included folder or to remove it
+pub async fn on_include_problems(
+    path: PathBuf,
+    problems: Vec<include_check::Problem>,
+) -> Result<()> {
+    let mount_points: Vec<PathBuf> = problems
+        .iter()
+        .filter_map(|problem| problem.mount_point())
+        .map(|path| path.to_path_buf())
+        .collect();
+
+    let body = problems
+        .iter()
+        .map(|problem| problem.description())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(&main_ui().window())
+        .modal(true)
+        .heading(gettext("Problematic Folder"))
+        .body(body)
+        .build();
+
+    dialog.add_responses(&[("cancel", &gettext("Cancel"))]);
+
+    if !mount_points.is_empty() {
+        dialog.add_response("exclude", &gettext("Exclude"));
+        dialog.set_response_appearance("exclude", adw::ResponseAppearance::Suggested);
+    }
+
+    dialog.add_response("remove", &gettext("Remove"));
+    dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+
+    match dialog.choose_future().await.as_str() {
+        "exclude" => exclude_paths(mount_points).await,
+        "remove" => on_remove_include(path).await,
+        _ => Ok(()),
+    }
+}
+
 async fn confirm_remove_include(path: &std::path::Path) -> bool {
     let path_string = if path == std::path::Path::new("") {
         gettext("Home")
@@ -183,7 +402,8 @@ async fn confirm_remove_include(path: &std::path::Path) -> bool {
         path.display().to_string()
     };
 
-    ui::utils::confirmation_dialog(
+    ui::confirm::confirm(
+        ui::confirm::Category::Remove,
         &gettextf("No longer include “{}” in backups?", &[&path_string]),
         &gettext("All files contained in this folder will no longer be part of future backups."),
         &gettext("Cancel"),
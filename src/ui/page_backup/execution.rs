@@ -18,8 +18,12 @@ pub async fn backup(
     from_schedule: Option<schedule::DueCause>,
     guard: &QuitGuard,
 ) -> Result<()> {
+    let config_id = config.id.clone();
     let result = run_backup(config, from_schedule, guard).await;
     display::refresh_status();
+    ui::operation_status::refresh(&config_id);
+    ui::dialog_info::refresh_status();
+    ui::window_backup::refresh_status(&config_id);
 
     result
 }
@@ -29,6 +33,13 @@ async fn run_prune(
     from_schedule: Option<schedule::DueCause>,
     guard: &QuitGuard,
 ) -> Result<bool> {
+    // Deleting archives from append-only repositories would not free any space
+    if ui::utils::append_only::is_protected(&config) {
+        info!("Skipping deletion of old archives since the repository is append-only");
+        ui::utils::show_notice(ui::utils::append_only::skipped_prune_message(&config));
+        return Ok(true);
+    }
+
     let prune_command = borg::Command::<borg::task::Prune>::new(config.clone())
         .set_from_schedule(from_schedule.clone());
     let prune_result = ui::utils::borg::exec(prune_command, guard)
@@ -41,7 +52,8 @@ async fn run_prune(
         _ => {}
     };
 
-    let compact_command = borg::Command::<borg::task::Compact>::new(config.clone());
+    let compact_command =
+        borg::Command::<borg::task::Compact>::new(config.clone()).set_from_schedule(from_schedule);
     let compact_result = ui::utils::borg::exec(compact_command, guard)
         .await
         .into_borg_error()?;
@@ -60,6 +72,8 @@ async fn run_backup(
     from_schedule: Option<schedule::DueCause>,
     guard: &QuitGuard,
 ) -> Result<()> {
+    let start = chrono::Local::now();
+
     run_script(UserScriptKind::PreBackup, config.clone(), None, guard).await?;
 
     // Configure additional free space if not already configured
@@ -71,12 +85,20 @@ async fn run_backup(
         );
     }
 
-    let command = borg::Command::<borg::task::Create>::new(config.clone())
+    warn_drive_health(&config).await;
+
+    let mut command = borg::Command::<borg::task::Create>::new(config.clone())
         .set_from_schedule(from_schedule.clone());
-    let communication = command.communication.clone();
+    let mut communication = command.communication.clone();
+
+    // Without a previous backup, the estimate is the only early progress information
+    let first_backup = BACKUP_HISTORY
+        .load()
+        .try_get(&config.id)
+        .map_or(true, |x| x.last_completed.is_none());
 
     // estimate backup size if not running in background
-    if crate::ui::app_window::is_displayed() {
+    if crate::ui::app_window::is_displayed() || first_backup {
         let config = config.clone();
         let communication = communication.clone();
         glib::MainContext::default().spawn_local(async move {
@@ -88,10 +110,98 @@ async fn run_backup(
     adw_app().withdraw_notification(&BackupNote::Warnings(&config.id).to_string());
     adw_app().withdraw_notification(&BackupNote::Failed(&config.id).to_string());
 
+    // Resumed when dropped at the end of the run
+    let _indexing_pause = if config.pause_indexing {
+        crate::utils::tracker::Pause::new(&gettext("Backup running")).await
+    } else {
+        None
+    };
+
+    // Data transferred by borg runs that were restarted
+    let mut network_usage = None;
+
+    // Paths skipped on user request are only excluded for this run
+    let mut run_config = config.clone();
+    let mut skipped = Vec::new();
+
     // execute backup
-    let result = ui::utils::borg::exec(command, guard).await;
+    let result = loop {
+        let result = ui::utils::borg::exec(command, guard)
+            .await
+            .into_borg_error()?;
+
+        let Err(borg::Error::Aborted(borg::Abort::Skip(path))) = result else {
+            break result;
+        };
+
+        // The checkpoint written when stopping makes the restart fast
+        info!("Restarting backup without {:?}", path);
+
+        let pattern = if path.is_dir() {
+            config::Pattern::path_prefix(&path)
+        } else {
+            config::Pattern::path_full_match(&path)
+        };
+        run_config
+            .exclude
+            .insert(config::Exclude::from_pattern(pattern));
+        skipped.push(path);
+
+        network_usage = add_network_usage(
+            network_usage,
+            communication
+                .general_info
+                .load()
+                .all_combined_message_history()
+                .network_usage(),
+        );
+
+        let estimated_size = communication.specific_info.load().estimated_size.clone();
 
-    let result = result.into_borg_error()?;
+        command = borg::Command::<borg::task::Create>::new(run_config.clone())
+            .set_from_schedule(from_schedule.clone());
+        communication = command.communication.clone();
+        communication
+            .specific_info
+            .update(move |status| status.estimated_size = estimated_size.clone());
+    };
+
+    let mut message_history = communication
+        .general_info
+        .load()
+        .all_combined_message_history();
+    network_usage = add_network_usage(network_usage, message_history.network_usage());
+
+    let changed_files = message_history.changed_files();
+    let result = match result {
+        Ok(stats) if config.retry_changed_files && !changed_files.is_empty() => {
+            match retry_changed_files(
+                &run_config,
+                from_schedule.clone(),
+                &stats,
+                changed_files.len(),
+                guard,
+            )
+            .await?
+            {
+                Some((retry_stats, retry_messages)) => {
+                    network_usage =
+                        add_network_usage(network_usage, retry_messages.network_usage());
+                    message_history = retry_messages;
+                    Ok(retry_stats)
+                }
+                None => Ok(stats),
+            }
+        }
+        result => result,
+    };
+
+    for path in &skipped {
+        message_history.push(note(gettextf(
+            "Skipped “{}” on user request.",
+            &[&path.display().to_string()],
+        )));
+    }
 
     // This is because the error cannot be cloned
     let outcome = match &result {
@@ -103,18 +213,28 @@ async fn run_backup(
         },
     };
 
-    let message_history = communication
-        .general_info
-        .load()
-        .all_combined_message_history();
-
-    let run_info = history::RunInfo::new(&config, outcome, message_history);
+    let mut run_info = history::RunInfo::new(&config, outcome, message_history);
+    run_info.start = Some(start);
+    if let Some(usage) = network_usage {
+        run_info.sent = Some(usage.sent);
+        run_info.received = Some(usage.received);
+    } else if config.repo.is_network() {
+        run_info.sent = result.as_ref().ok().map(borg::Stats::estimated_sent);
+    }
 
     BACKUP_HISTORY.try_update(|history| {
         history.insert(config.id.clone(), run_info.clone());
         Ok(())
     })?;
 
+    if let Err(err) = ui::utils::metrics::write() {
+        error!("Failed to update metrics: {}", err);
+    }
+
+    if let Err(err) = ui::utils::report::write(&config, &run_info) {
+        error!("Failed to write report: {}", err);
+    }
+
     run_script(
         UserScriptKind::PostBackup,
         config.clone(),
@@ -123,16 +243,63 @@ async fn run_backup(
     )
     .await?;
 
+    // Writing the backup can reveal new problems
+    warn_drive_health(&config).await;
+
     match result {
+        Err(borg::Error::Aborted(borg::Abort::KeyringLocked)) => {
+            if let Some(due_cause) = from_schedule {
+                ui::utils::password_storage::queue_until_unlocked(config.id.clone(), due_cause);
+            }
+            Ok(())
+        }
         Err(borg::Error::Aborted(_)) => Ok(()),
+        Err(borg::Error::Failed(borg::Failure::RepositoryMismatch(found_id)))
+            if from_schedule.is_none() =>
+        {
+            relink_repository(&config, found_id).await
+        }
+        Err(borg::Error::Failed(borg::Failure::HostKeyChanged)) if from_schedule.is_none() => {
+            reset_host_key(&config).await
+        }
+        Err(borg::Error::Failed(failure))
+            if failure.is_cache_error() && from_schedule.is_none() =>
+        {
+            ui::dialog_cache_recovery::run(&config, &failure).await
+        }
         Err(err) => Err(Message::with_notification_id(
             gettext("Backup Failed"),
             err,
             BackupNote::Failed(&config.id),
         )
         .into()),
-        Ok(_) => {
-            if config.prune.enabled {
+        Ok(stats) => {
+            if !verify_written(&config, &run_info, guard).await? {
+                return Ok(());
+            }
+
+            let last_maintenance = BACKUP_HISTORY
+                .load()
+                .try_get(&config.id)
+                .ok()
+                .and_then(|history| history.last_maintenance);
+
+            if from_schedule.is_some()
+                && config
+                    .maintenance
+                    .is_due(last_maintenance, chrono::Local::now())
+            {
+                // Maintenance includes deleting old archives
+                if let Ok(current_config) = BACKUP_CONFIG.load().try_get(&config.id) {
+                    match ui::maintenance::run(current_config.clone(), from_schedule.clone(), guard)
+                        .await
+                    {
+                        Ok(false) => return Ok(()),
+                        Err(err) => return Err(err),
+                        _ => {}
+                    };
+                }
+            } else if config.prune.enabled {
                 // use current config for pruning archives
                 // assuming it's closer to what users expect
                 if let Ok(current_config) = BACKUP_CONFIG.load().try_get(&config.id) {
@@ -144,6 +311,13 @@ async fn run_backup(
                 }
             }
 
+            if let Some(cache) = &stats.cache {
+                let _ignore = ui::utils::repo_cache::RepoCache::set_deduplicated_size(
+                    &config.repo_id,
+                    cache.stats.unique_csize,
+                );
+            }
+
             let _ignore =
                 ui::page_archives::cache::refresh_archives(config.clone(), from_schedule).await;
             let _ignore = ui::utils::df::lookup_and_cache(&config).await;
@@ -164,6 +338,227 @@ async fn run_backup(
     }
 }
 
+/// Read back the written data after backups to removable drives
+///
+/// Returns `false` if the verification was aborted.
+async fn verify_written(
+    config: &config::Backup,
+    run_info: &RunInfo,
+    guard: &QuitGuard,
+) -> Result<bool> {
+    if !config.repo.is_drive_removable() {
+        return Ok(true);
+    }
+
+    let mut command = borg::Command::<borg::task::Check>::new(config.clone());
+    match config.write_verification {
+        config::WriteVerification::None => return Ok(true),
+        config::WriteVerification::Repository => command.task.set_repository_only(true),
+        config::WriteVerification::LatestArchive => {
+            command.task.set_verify_data(true);
+            command.task.set_latest_archive_only(true);
+        }
+    }
+
+    info!("Verifying written data of {:?}", config.id);
+    let communication = command.communication.clone();
+    let result = ui::utils::borg::exec(command, guard)
+        .await
+        .into_borg_error()?;
+    let message_history = communication
+        .general_info
+        .load()
+        .all_combined_message_history();
+
+    let details = match result {
+        Err(borg::Error::Aborted(_)) => return Ok(false),
+        Err(err) => err.to_string(),
+        Ok(()) if !message_history.is_empty() => message_history
+            .iter()
+            .map(|x| x.message())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Ok(()) => {
+            BACKUP_HISTORY.try_update(|history| {
+                history.set_verified(config.id.clone(), run_info.end);
+                Ok(())
+            })?;
+
+            return Ok(true);
+        }
+    };
+
+    Err(Message::with_notification_id(
+        gettext("Verifying Written Data Failed"),
+        format!(
+            "{}\n{}",
+            gettext("The backup drive might be damaged. Try to back up to another drive."),
+            details
+        ),
+        BackupNote::Failed(&config.id),
+    )
+    .into())
+}
+
+thread_local!(
+    static DRIVE_HEALTH: std::cell::RefCell<
+        std::collections::HashMap<ConfigId, crate::utils::udisks::Health>,
+    > = Default::default();
+);
+
+/// Warn if the drive of a local repository reports problems
+///
+/// Every problem is only reported once per backup configuration.
+async fn warn_drive_health(config: &config::Backup) {
+    let config::Repository::Local(repo) = &config.repo else {
+        return;
+    };
+
+    let Some(health) = crate::utils::udisks::health(&repo.path()).await else {
+        return;
+    };
+
+    let reported = DRIVE_HEALTH.with(|x| x.borrow_mut().insert(config.id.clone(), health.clone()));
+
+    if !health.is_good() && reported.as_ref() != Some(&health) {
+        ui::utils::show_notice(gettextf(
+            "Problems with the backup drive “{}”: {}",
+            &[
+                &repo
+                    .drive_name
+                    .clone()
+                    .unwrap_or_else(|| config.repo.location()),
+                &health.summary(),
+            ],
+        ));
+    }
+}
+
+/// Offer to use the repository that is now at the backup location
+///
+/// The repository can legitimately change if it was recreated on purpose.
+async fn relink_repository(config: &config::Backup, found_id: borg::RepoId) -> Result<()> {
+    ui::confirm::confirm(
+        ui::confirm::Category::Critical,
+        &gettext("Repository Mismatch"),
+        &gettext("The backup location contains a different repository than the one this backup was set up with. This happens if a different backup disk is connected or the repository was recreated. Only use the new repository if it is the intended backup destination."),
+        &gettext("Cancel"),
+        &gettext("Use New Repository"),
+    )
+    .await?;
+
+    info!(
+        "Relinking backup {} from repository {:?} to {:?}",
+        config.id, config.repo_id, found_id
+    );
+
+    BACKUP_CONFIG.try_update(|settings| {
+        settings.try_get_mut(&config.id)?.repo_id = found_id.clone();
+        Ok(())
+    })?;
+
+    ui::utils::show_notice(gettext(
+        "The backup now uses the new repository. Start the backup again to save the data.",
+    ));
+
+    Ok(())
+}
+
+/// Offer to forget the stored host key after the server identity changed
+///
+/// The key legitimately changes if the server was reinstalled.
+async fn reset_host_key(config: &config::Backup) -> Result<()> {
+    ui::confirm::confirm(
+        ui::confirm::Category::Critical,
+        &gettext("Server Identity Changed"),
+        &gettext("The backup server presented a different identity than before. This happens if the server was reinstalled, but could also mean that someone is intercepting the connection. Only reset the stored host key if the server was reinstalled or its key was changed on purpose."),
+        &gettext("Cancel"),
+        &gettext("Reset Host Key"),
+    )
+    .await?;
+
+    ui::utils::host_key::reset(&config.repo).await?;
+
+    ui::utils::show_notice(gettext(
+        "The stored host key was removed. Start the backup again to connect to the server.",
+    ));
+
+    Ok(())
+}
+
+/// Back up again because files changed while they were read
+///
+/// Borg can't add files to an existing archive, therefore a complete second pass is
+/// created. It is usually quick since unchanged files are recognized via the files cache.
+/// The archive of the first pass is deleted afterwards such that a single consistent
+/// archive remains. Returns `None` if the second pass didn't complete.
+async fn retry_changed_files(
+    config: &config::Backup,
+    from_schedule: Option<schedule::DueCause>,
+    first_stats: &borg::Stats,
+    changed_files: usize,
+    guard: &QuitGuard,
+) -> Result<Option<(borg::Stats, borg::log_json::LogCollection)>> {
+    info!("Backing up again since {changed_files} files changed during the backup");
+
+    let command =
+        borg::Command::<borg::task::Create>::new(config.clone()).set_from_schedule(from_schedule);
+    let communication = command.communication.clone();
+
+    let stats = match ui::utils::borg::exec(command, guard)
+        .await
+        .into_borg_error()?
+    {
+        Ok(stats) => stats,
+        Err(err) => {
+            warn!("Second pass for changed files did not complete: {}", err);
+            return Ok(None);
+        }
+    };
+
+    let mut delete = borg::Command::<borg::task::Delete>::new(config.clone());
+    delete
+        .task
+        .set_archive_name(Some(first_stats.archive.name.clone()));
+
+    if let Err(err) = ui::utils::borg::exec(delete, guard)
+        .await
+        .into_borg_error()?
+    {
+        warn!("Failed to delete archive of the first pass: {}", err);
+    }
+
+    let mut messages = communication
+        .general_info
+        .load()
+        .all_combined_message_history();
+
+    messages.push(note(ngettextf_(
+        "One file changed during the backup and has been backed up again.",
+        "{} files changed during the backup and have been backed up again.",
+        changed_files as u32,
+    )));
+
+    Ok(Some((stats, messages)))
+}
+
+/// Informational message added to the log of a backup run
+fn note(message: String) -> borg::log_json::LogEntry {
+    borg::log_json::LogEntry::ParsedErr(borg::log_json::LogMessage {
+        levelname: borg::log_json::LogLevel::Info,
+        name: String::from(env!("CARGO_PKG_NAME")),
+        message,
+        msgid: Default::default(),
+    })
+}
+
+fn add_network_usage(
+    a: Option<borg::log_json::NetworkUsage>,
+    b: Option<borg::log_json::NetworkUsage>,
+) -> Option<borg::log_json::NetworkUsage> {
+    a.into_iter().chain(b).reduce(|a, b| a + b)
+}
+
 async fn run_script(
     kind: UserScriptKind,
     config: crate::config::Backup,
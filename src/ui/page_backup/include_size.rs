@@ -0,0 +1,118 @@
+//! Size of the included folders
+//!
+//! Sizes are calculated one folder at a time in a background thread. A new
+//! calculation cancels the previous one, and results are cached to not walk
+//! through all files every time the page is shown.
+
+use adw::prelude::*;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::borg;
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+/// Time after which a cached size is calculated again
+const CACHE_DURATION: Duration = Duration::from_secs(10 * 60);
+
+struct CachedSize {
+    exclude: BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
+    size: u64,
+    updated: std::time::Instant,
+}
+
+thread_local!(
+    static CACHE: RefCell<BTreeMap<PathBuf, CachedSize>> = Default::default();
+    static CANCEL: RefCell<Arc<AtomicBool>> = Default::default();
+);
+
+fn cached(
+    path: &PathBuf,
+    exclude: &BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
+) -> Option<u64> {
+    CACHE.with(|cache| {
+        cache
+            .borrow()
+            .get(path)
+            .filter(|cached| {
+                &cached.exclude == exclude && cached.updated.elapsed() < CACHE_DURATION
+            })
+            .map(|cached| cached.size)
+    })
+}
+
+fn set_size(label: &gtk::Label, size: u64) {
+    label.set_label(&glib::format_size(size));
+    label.set_tooltip_text(Some(&gettext("Size without excluded files")));
+}
+
+/// Show the size of every include row, calculating it if necessary
+pub fn show(config: &config::Backup, rows: Vec<(PathBuf, gtk::Label)>) {
+    cancel();
+    let cancel = CANCEL.with(|cancel| cancel.borrow().clone());
+
+    let exclude = config.exclude_dirs_internal();
+    let mut missing = Vec::new();
+
+    for (path, label) in rows {
+        let path = config::absolute(&path);
+
+        if let Some(size) = cached(&path, &exclude) {
+            set_size(&label, size);
+        } else {
+            label.set_label(&gettext("Calculating size…"));
+            missing.push((path, label));
+        }
+    }
+
+    if missing.is_empty() {
+        return;
+    }
+
+    glib::MainContext::default().spawn_local(async move {
+        for (path, label) in missing {
+            let size = ui::utils::spawn_thread(
+                "include_size",
+                enclose!((path, exclude, cancel) move || {
+                    borg::size_estimate::include_size(&path, exclude, &cancel)
+                }),
+            )
+            .await;
+
+            match size {
+                Ok(Some(size)) => {
+                    CACHE.with(|cache| {
+                        cache.borrow_mut().insert(
+                            path,
+                            CachedSize {
+                                exclude: exclude.clone(),
+                                size,
+                                updated: std::time::Instant::now(),
+                            },
+                        )
+                    });
+                    set_size(&label, size);
+                }
+                // Cancelled
+                Ok(None) => return,
+                Err(err) => {
+                    warn!("Failed to calculate size of {:?}: {}", path, err);
+                    label.set_label("");
+                }
+            }
+        }
+    });
+}
+
+/// Stop the running calculation
+pub fn cancel() {
+    CANCEL.with(|cancel| {
+        cancel
+            .replace(Default::default())
+            .store(true, Ordering::Relaxed)
+    });
+}
@@ -1,4 +1,6 @@
 use adw::prelude::*;
+use gtk::gdk;
+use std::path::PathBuf;
 
 use crate::ui;
 
@@ -25,6 +27,10 @@ pub fn init() {
         .detail_repo_row()
         .connect_activated(|_| Handler::run(ui::dialog_storage::show()));
 
+    main_ui()
+        .plaintext_banner()
+        .connect_button_clicked(|_| Handler::handle(events::on_dismiss_plaintext_warning()));
+
     main_ui()
         .navigation_view()
         .connect_visible_page_notify(|navigation_view| {
@@ -43,15 +49,81 @@ pub fn init() {
     main_ui()
         .add_include()
         .connect_clicked(|_| Handler::run(events::add_include()));
+
+    main_ui()
+        .add_include_apps()
+        .connect_clicked(|_| Handler::handle(ui::dialog_flatpak_apps::show()));
+    main_ui().include_pattern_entry().connect_apply(|entry| {
+        let pattern = entry.text().to_string();
+        entry.set_text("");
+        Handler::run(events::add_include_pattern(pattern))
+    });
     main_ui()
         .add_exclude()
         .connect_clicked(|_| Handler::run(events::add_exclude()));
+    main_ui()
+        .edit_exclude_text()
+        .connect_clicked(|_| Handler::handle(ui::dialog_exclude_text::show()));
+
+    accept_paths(&main_ui().include(), |paths| {
+        Handler::run(events::include_paths(paths))
+    });
+    accept_paths(&main_ui().backup_exclude(), |paths| {
+        Handler::run(events::exclude_paths(paths))
+    });
 
     main_ui()
         .stop_backup_create()
         .connect_clicked(|_| Handler::run(events::on_stop_backup_create()));
 
+    main_ui()
+        .detail_skip_path()
+        .connect_clicked(|_| Handler::run(events::on_skip_current(false)));
+
+    main_ui()
+        .detail_skip_include()
+        .connect_clicked(|_| Handler::run(events::on_skip_current(true)));
+
+    main_ui()
+        .detail_pause()
+        .connect_clicked(|_| Handler::handle(events::on_pause_toggle()));
+
     main_ui()
         .backup_disk_eject_button()
         .connect_clicked(|_| Handler::run(events::on_backup_disk_eject()));
 }
+
+/// Accept files dropped from the file manager or pasted with <kbd>Ctrl</kbd>+<kbd>V</kbd>
+fn accept_paths(list: &gtk::ListBox, add: fn(Vec<PathBuf>)) {
+    let drop_target = gtk::DropTarget::new(gdk::FileList::static_type(), gdk::DragAction::COPY);
+    drop_target.connect_drop(move |_, value, _, _| match value.get::<gdk::FileList>() {
+        Ok(files) => {
+            add(files
+                .files()
+                .iter()
+                .filter_map(|file| file.path())
+                .collect());
+            true
+        }
+        Err(_) => false,
+    });
+    list.add_controller(drop_target);
+
+    let paste = gtk::Shortcut::new(
+        gtk::ShortcutTrigger::parse_string("<Control>v"),
+        Some(gtk::CallbackAction::new(move |widget, _| {
+            let clipboard = widget.clipboard();
+            glib::MainContext::default().spawn_local(async move {
+                let paths = events::clipboard_paths(&clipboard).await;
+                if !paths.is_empty() {
+                    add(paths);
+                }
+            });
+            true
+        })),
+    );
+
+    let shortcuts = gtk::ShortcutController::new();
+    shortcuts.add_shortcut(paste);
+    list.add_controller(shortcuts);
+}
@@ -1,3 +1,8 @@
+mod search;
+pub mod trash;
+
+pub use search::show as show_search;
+
 use crate::ui::prelude::*;
 use adw::prelude::*;
 
@@ -5,6 +10,7 @@ use std::collections::BTreeMap;
 use std::sync::RwLock;
 
 use crate::ui;
+use crate::ui::utils::repo_cache::RepoCache;
 
 pub fn dbus_show() {
     main_ui()
@@ -31,8 +37,13 @@ pub fn init() {
     main_ui()
         .add_backup_empty()
         .connect_clicked(|_| ui::dialog_setup::show());
+    main_ui()
+        .restore_backup_empty()
+        .connect_clicked(|_| ui::dialog_setup::show_restore());
 
     main_ui().main_backups().connect_map(|_| rebuild_list());
+    search::init();
+    trash::init();
     reload_visible_page();
 }
 
@@ -46,9 +57,14 @@ pub fn remove_backup() {
 }
 
 async fn on_remove_backup() -> Result<()> {
-    ui::utils::confirmation_dialog(
+    ui::confirm::confirm(
+        ui::confirm::Category::Remove,
         &gettext("Remove Backup Setup?"),
-        &gettext("Removing the setup will not delete any archives."),
+        &ngettextf_(
+            "The setup can be restored for one day. Removing the setup will not delete any archives.",
+            "The setup can be restored for {} days. Removing the setup will not delete any archives.",
+            crate::config::trash::RETENTION_DAYS as u32,
+        ),
         &gettext("Cancel"),
         &gettext("Remove Setup"),
     )
@@ -58,17 +74,11 @@ async fn on_remove_backup() -> Result<()> {
 
     let config_id = config.id.clone();
 
-    BACKUP_CONFIG.try_update(|s| {
-        s.remove(&config_id)?;
-        Ok(())
-    })?;
-
-    if let Err(err) = ui::utils::password_storage::remove_password(&config, false).await {
-        // Display the error and continue to leave the UI in a consistent state
-        err.show().await;
-    }
+    trash::remove(&config)?;
 
     ACTIVE_BACKUP_ID.update(|active_id| *active_id = None);
+    ui::window_backup::close(&config_id);
+    ui::operation_status::remove(&config_id);
 
     reload_visible_page();
     main_ui()
@@ -79,7 +89,11 @@ async fn on_remove_backup() -> Result<()> {
 }
 
 pub fn reload_visible_page() {
-    if BACKUP_CONFIG.load().iter().next().is_none() {
+    if search::is_active() {
+        main_ui()
+            .main_stack()
+            .set_visible_child(&main_ui().page_search());
+    } else if BACKUP_CONFIG.load().iter().next().is_none() {
         main_ui()
             .main_stack()
             .set_visible_child(&main_ui().page_overview_empty());
@@ -115,16 +129,48 @@ fn rebuild_list() {
                 ui::page_schedule::view(&config.id);
             }));
 
+        // Ctrl+click and context menu to open a separate window
+
+        let gesture = gtk::GestureClick::builder()
+            .button(0)
+            .propagation_phase(gtk::PropagationPhase::Capture)
+            .build();
+        gesture.connect_pressed(enclose!((config) move |gesture, _, x, y| {
+            let ctrl = gesture
+                .current_event_state()
+                .contains(gtk::gdk::ModifierType::CONTROL_MASK);
+
+            match gesture.current_button() {
+                gtk::gdk::BUTTON_PRIMARY if ctrl => {
+                    gesture.set_state(gtk::EventSequenceState::Claimed);
+                    Handler::handle(ui::window_backup::show(&config.id));
+                }
+                gtk::gdk::BUTTON_SECONDARY => {
+                    gesture.set_state(gtk::EventSequenceState::Claimed);
+                    if let Some(widget) = gesture.widget() {
+                        show_context_menu(&widget, &config.id, x, y);
+                    }
+                }
+                _ => {}
+            }
+        }));
+        row.location().add_controller(gesture);
+
         // Repo Icon
 
         if let Ok(icon) = gio::Icon::for_string(&config.repo.icon()) {
             row.location_icon().set_from_gicon(&icon);
         }
+        ui::utils::set_accent_color(&row.location_icon(), config.accent_color);
 
         // Repo Name
 
         row.location_title().set_label(&config.title());
         row.location_subtitle().set_label(&config.repo.subtitle());
+        row.location().set_tooltip_text(Some(&gettextf(
+            "Encryption: {}",
+            &[&config.encryption_description()],
+        )));
 
         // Include
 
@@ -144,6 +190,63 @@ fn rebuild_list() {
     force_refresh_status();
 }
 
+fn show_context_menu(widget: &gtk::Widget, config_id: &ConfigId, x: f64, y: f64) {
+    let menu = gio::Menu::new();
+    let item = gio::MenuItem::new(Some(&gettext("Open in New Window")), None);
+    item.set_action_and_target_value(Some("app.backup.window"), Some(&config_id.to_variant()));
+    menu.append_item(&item);
+
+    let popover = gtk::PopoverMenu::from_model(Some(&menu));
+    popover.set_parent(widget);
+    popover.set_has_arrow(false);
+    popover.set_pointing_to(Some(&gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+    popover.connect_closed(|popover| {
+        let popover = popover.clone();
+        // Unparent after the menu action has been activated
+        glib::idle_add_local_once(move || popover.unparent());
+    });
+
+    popover.popup();
+}
+
+/// Number of archives, age of the oldest archive and size of the repository
+fn repo_facts(repo_cache: &RepoCache) -> Option<String> {
+    let count = repo_cache.archive_count()?;
+    let mut facts = vec![if repo_cache.archives_limited {
+        ngettextf(
+            "At least {} archive",
+            "At least {} archives",
+            count as u32,
+            &[&count.to_string()],
+        )
+    } else {
+        ngettextf(
+            "One archive",
+            "{} archives",
+            count as u32,
+            &[&count.to_string()],
+        )
+    }];
+
+    if let Some(oldest) = repo_cache.oldest_archive() {
+        // Translators: Argument is 'x days ago'
+        facts.push(gettextf(
+            "oldest {}",
+            &[&ui::utils::duration::ago(
+                &(chrono::Local::now().naive_local() - oldest),
+            )],
+        ));
+    }
+
+    if let Some(size) = repo_cache.deduplicated_size {
+        // Translators: Argument is a size like '5 GB'
+        facts.push(gettextf("{} in total", &[&glib::format_size(size)]));
+    }
+
+    // Translators: Separator between facts like '12 archives, oldest 3 months ago'
+    Some(facts.join(&gettext(", ")))
+}
+
 fn force_refresh_status() {
     glib::MainContext::default().spawn_local(async move {
         for config in BACKUP_CONFIG.load().iter() {
@@ -154,6 +257,25 @@ fn force_refresh_status() {
                         let status = ui::backup_status::Display::new_from_id(&config.id);
 
                         row.status().set_from_backup_status(&status);
+
+                        // cached repository facts
+
+                        let repo_cache = RepoCache::get(&config.repo_id);
+                        let facts = repo_facts(&repo_cache);
+                        row.location_archives().set_visible(facts.is_some());
+                        row.location_archives()
+                            .set_label(&facts.unwrap_or_default());
+                        row.location_archives().set_tooltip_text(
+                            repo_cache
+                                .newest_archive()
+                                .map(|date| {
+                                    gettextf(
+                                        "Newest archive: {}",
+                                        &[&date.to_locale().unwrap_or_else(|| date.to_string())],
+                                    )
+                                })
+                                .as_deref(),
+                        );
                         // schedule status
 
                         row.schedule()
@@ -168,5 +290,7 @@ fn force_refresh_status() {
                 }
             })
         }
+
+        ui::problems::refresh();
     });
 }
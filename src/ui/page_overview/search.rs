@@ -0,0 +1,258 @@
+//! Search for files in the indexed archives of all backups
+
+use adw::prelude::*;
+
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+use ui::utils::file_chooser;
+use ui::utils::file_index::{self, SearchResult};
+
+pub fn init() {
+    main_ui()
+        .overview_search_bar()
+        .connect_entry(&main_ui().overview_search_entry());
+
+    main_ui()
+        .overview_search_bar()
+        .connect_search_mode_enabled_notify(|bar| {
+            if bar.is_search_mode() {
+                Handler::run(open());
+            } else {
+                main_ui().overview_search_entry().set_text("");
+                super::reload_visible_page();
+            }
+        });
+
+    main_ui()
+        .overview_search_entry()
+        .connect_search_changed(|entry| {
+            let query = entry.text().to_string();
+            Handler::run(search(query));
+        });
+
+    main_ui()
+        .search_index()
+        .connect_clicked(|_| Handler::run(index_archives()));
+}
+
+pub fn is_active() -> bool {
+    main_ui().overview_search_bar().is_search_mode()
+}
+
+/// Show the search in the overview
+pub fn show() {
+    main_ui()
+        .navigation_view()
+        .pop_to_page(&main_ui().navigation_page_overview());
+    main_ui().overview_search_bar().set_search_mode(true);
+}
+
+async fn open() -> Result<()> {
+    if let Err(err) = ui::utils::authentication::require().await {
+        main_ui().overview_search_bar().set_search_mode(false);
+        return Err(err);
+    }
+
+    main_ui()
+        .main_stack()
+        .set_visible_child(&main_ui().page_search());
+    main_ui()
+        .page_search()
+        .set_visible_child(&main_ui().search_page_empty());
+    main_ui().overview_search_entry().grab_focus();
+
+    show_empty().await
+}
+
+/// Explain the search and offer to index the archives that are not indexed yet
+async fn show_empty() -> Result<()> {
+    let configs = BACKUP_CONFIG.get();
+
+    let missing = ui::utils::spawn_thread(
+        "file_index_missing",
+        enclose!((configs) move || {
+            let indices = file_index::load_all(&configs);
+            configs
+                .iter()
+                .filter_map(|config| {
+                    indices
+                        .get(&config.repo_id)
+                        .map(|index| file_index::missing(config, index).len())
+                })
+                .sum::<usize>()
+        }),
+    )
+    .await?;
+
+    let page = main_ui().search_page_empty();
+    if missing > 0 {
+        page.set_description(Some(&ngettextf_(
+            "Find files in the archives of all backups. One archive has not been indexed yet. Indexing reads the list of files from the backup repository.",
+            "Find files in the archives of all backups. {} archives have not been indexed yet. Indexing reads the list of files from the backup repository.",
+            missing as u32,
+        )));
+    } else {
+        page.set_description(Some(&gettext("Find files in the archives of all backups.")));
+    }
+    main_ui().search_index().set_visible(missing > 0);
+
+    Ok(())
+}
+
+/// Index the missing archives of all backups
+async fn index_archives() -> Result<()> {
+    let button = main_ui().search_index();
+    button.set_sensitive(false);
+    scopeguard::defer! {
+        main_ui().search_index().set_sensitive(true);
+    }
+
+    let configs = BACKUP_CONFIG.get();
+
+    for config in configs.iter() {
+        file_index::update_missing(config, |current, total| {
+            main_ui()
+                .search_page_empty()
+                .set_description(Some(&gettextf(
+                    "Indexing archive {} of {} from “{}”…",
+                    &[&current.to_string(), &total.to_string(), &config.title()],
+                )));
+        })
+        .await?;
+    }
+
+    if ui::page_archives::is_visible() {
+        Handler::run(ui::page_archives::refresh_usage());
+    }
+
+    show_empty().await?;
+
+    // Show results for a query entered in the meantime
+    search(main_ui().overview_search_entry().text().to_string()).await
+}
+
+async fn search(query: String) -> Result<()> {
+    if !is_active() {
+        return Ok(());
+    }
+
+    let configs = BACKUP_CONFIG.get();
+
+    let results = ui::utils::spawn_thread(
+        "file_index_search",
+        enclose!((configs, query) move || {
+            file_index::search(&configs, &file_index::load_all(&configs), &query)
+        }),
+    )
+    .await?;
+
+    // Ignore results for outdated queries
+    if main_ui().overview_search_entry().text().as_str() != query {
+        return Ok(());
+    }
+
+    let results: Vec<SearchResult> = results
+        .into_iter()
+        .filter_map(|mut result| {
+            result
+                .files
+                .retain(|file| file_chooser::is_listed(std::path::Path::new(&file.path)));
+            (!result.files.is_empty()).then_some(result)
+        })
+        .collect();
+
+    let list = main_ui().search_results();
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let stack = main_ui().page_search();
+    if query.trim().is_empty() {
+        stack.set_visible_child(&main_ui().search_page_empty());
+    } else if results.is_empty() {
+        stack.set_visible_child(&main_ui().search_page_no_results());
+    } else {
+        for result in results {
+            if let Ok(config) = configs.try_get(&result.config_id) {
+                list.append(&result_group(config, result));
+            }
+        }
+        stack.set_visible_child(&main_ui().search_page_results());
+    }
+
+    Ok(())
+}
+
+fn result_group(config: &config::Backup, result: SearchResult) -> adw::PreferencesGroup {
+    let config = config.clone();
+    let archive_name = result.archive_name;
+    let group = adw::PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&config.title()))
+        .description(glib::markup_escape_text(
+            &result
+                .start
+                .to_locale()
+                .unwrap_or_else(|| result.start.to_string()),
+        ))
+        .build();
+
+    for file in result.files {
+        let path = std::path::Path::new("/").join(&file.path);
+
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(
+                path.file_name()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            )
+            .subtitle(
+                path.parent()
+                    .map(|x| x.display().to_string())
+                    .unwrap_or_default(),
+            )
+            .build();
+
+        let icon = if file.is_dir() {
+            "folder-symbolic"
+        } else {
+            "text-x-generic-symbolic"
+        };
+        row.add_prefix(&gtk::Image::from_icon_name(icon));
+
+        let open = gtk::Button::builder()
+            .icon_name("folder-open-symbolic")
+            .tooltip_text(gettext("Show in Archive"))
+            .valign(gtk::Align::Center)
+            .build();
+        open.add_css_class("flat");
+        open.connect_clicked(enclose!((config, archive_name, file) move |_| {
+            Handler::run(ui::page_archives::open_archive_file(
+                config.clone(),
+                archive_name.clone(),
+                file.clone(),
+            ));
+        }));
+        row.add_suffix(&open);
+
+        let restore = gtk::Button::builder()
+            .icon_name("edit-undo-symbolic")
+            .tooltip_text(gettext("Restore to Original Location"))
+            .valign(gtk::Align::Center)
+            .build();
+        restore.add_css_class("flat");
+        restore.connect_clicked(enclose!((config, archive_name, file) move |_| {
+            Handler::run(ui::page_archives::restore_archive_file(
+                config.clone(),
+                archive_name.clone(),
+                file.clone(),
+            ));
+        }));
+        row.add_suffix(&restore);
+
+        group.add(&row);
+    }
+
+    group
+}
@@ -0,0 +1,182 @@
+//! Removed backup setups that can be restored for some time
+
+use adw::prelude::*;
+
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+pub fn init() {
+    Handler::run(delete_expired());
+}
+
+/// Move the configuration to the trash and offer to undo it
+pub fn remove(config: &config::Backup) -> Result<()> {
+    BACKUP_TRASH.try_update(|trash| {
+        trash.insert(config.clone(), chrono::Local::now());
+        Ok(())
+    })?;
+
+    BACKUP_CONFIG.try_update(|s| {
+        s.remove(&config.id)?;
+        Ok(())
+    })?;
+
+    let toast = adw::Toast::builder()
+        .title(gettextf("Removed “{}”", &[&config.title()]))
+        .button_label(gettext("Undo"))
+        .build();
+
+    let config_id = config.id.clone();
+    toast.connect_button_clicked(move |_| Handler::run(restore(config_id.clone())));
+    main_ui().toast().add_toast(toast);
+
+    Ok(())
+}
+
+async fn restore(config_id: ConfigId) -> Result<()> {
+    let config = BACKUP_TRASH.load().try_get(&config_id)?.config.clone();
+
+    BACKUP_CONFIG.try_update(|s| {
+        s.insert(config.clone())?;
+        Ok(())
+    })?;
+
+    BACKUP_TRASH.try_update(|trash| {
+        trash.take(&config_id)?;
+        Ok(())
+    })?;
+
+    super::reload_visible_page();
+    super::rebuild_list();
+
+    Ok(())
+}
+
+/// Permanently delete configurations that have been in the trash for too long
+async fn delete_expired() -> Result<()> {
+    let now = chrono::Local::now();
+    let expired: Vec<_> = BACKUP_TRASH
+        .load()
+        .iter()
+        .filter(|x| x.expires() <= now)
+        .map(|x| x.config.clone())
+        .collect();
+
+    if expired.is_empty() {
+        return Ok(());
+    }
+
+    BACKUP_TRASH.try_update(|trash| {
+        trash.take_expired(now);
+        Ok(())
+    })?;
+
+    for config in expired {
+        info!("Permanently deleting backup setup {:?}", config.id);
+        remove_secrets(&config).await;
+    }
+
+    Ok(())
+}
+
+async fn delete(config_id: ConfigId) -> Result<()> {
+    ui::confirm::confirm(
+        ui::confirm::Category::Critical,
+        &gettext("Delete Backup Setup Permanently?"),
+        &gettext("The setup can not be restored afterwards. Deleting the setup will not delete any archives."),
+        &gettext("Cancel"),
+        &gettext("Delete Setup"),
+    )
+    .await?;
+
+    let config = BACKUP_TRASH.load().try_get(&config_id)?.config.clone();
+
+    BACKUP_TRASH.try_update(|trash| {
+        trash.take(&config_id)?;
+        Ok(())
+    })?;
+
+    remove_secrets(&config).await;
+
+    Ok(())
+}
+
+async fn remove_secrets(config: &config::Backup) {
+    if let Err(err) = ui::utils::password_storage::remove_password(config, false).await {
+        // Display the error and continue to leave the UI in a consistent state
+        err.show().await;
+    }
+}
+
+/// Show the removed configurations in the preferences
+pub fn fill_group(group: &adw::PreferencesGroup, list: &gtk::ListBox) {
+    ui::utils::clear(list);
+
+    let trash = BACKUP_TRASH.load();
+    group.set_visible(!trash.is_empty());
+    group.set_description(Some(&ngettextf_(
+        "Removed backup setups are deleted permanently after one day",
+        "Removed backup setups are deleted permanently after {} days",
+        config::trash::RETENTION_DAYS as u32,
+    )));
+
+    let now = chrono::Local::now();
+    for trashed in trash.iter() {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(trashed.config.title())
+            .subtitle(
+                [
+                    gettextf(
+                        "Removed {}",
+                        &[&ui::utils::duration::ago(&(now - trashed.removed))],
+                    ),
+                    ui::utils::duration::left(&(trashed.expires() - now)),
+                ]
+                // Translators: Separator between facts like 'Removed 3 days ago, 27 days left'
+                .join(&gettext(", ")),
+            )
+            .build();
+
+        if let Ok(icon) = gio::Icon::for_string(&trashed.config.repo.icon()) {
+            let image = gtk::Image::from_gicon(&icon);
+            image.add_css_class("row-icon");
+            row.add_prefix(&image);
+        }
+
+        let config_id = trashed.config.id.clone();
+
+        let restore_button = gtk::Button::builder()
+            .label(gettext("Restore"))
+            .valign(gtk::Align::Center)
+            .build();
+        restore_button.connect_clicked(
+            clone!(@weak group, @weak list, @strong config_id => move |_| {
+                Handler::run(enclose!((group, list, config_id) async move {
+                    restore(config_id).await?;
+                    fill_group(&group, &list);
+                    Ok(())
+                }));
+            }),
+        );
+        row.add_suffix(&restore_button);
+
+        let delete_button = gtk::Button::builder()
+            .icon_name("edit-delete-symbolic")
+            .valign(gtk::Align::Center)
+            .tooltip_text(gettext("Delete Permanently"))
+            .build();
+        delete_button.add_css_class("flat");
+        delete_button.connect_clicked(clone!(@weak group, @weak list => move |_| {
+            Handler::run(enclose!((group, list, config_id) async move {
+                delete(config_id).await?;
+                fill_group(&group, &list);
+                Ok(())
+            }));
+        }));
+        row.add_suffix(&delete_button);
+
+        list.append(&row);
+    }
+}
@@ -4,8 +4,11 @@ use adw::prelude::*;
 mod event;
 pub mod frequency;
 pub mod init;
+mod locale;
 pub mod prune_preset;
+mod prune_preview;
 pub mod status;
+mod upcoming;
 pub mod weekday;
 
 pub fn dbus_show(id: ConfigId) {
@@ -3,7 +3,10 @@ use chrono::prelude::*;
 
 use super::frequency;
 use super::init;
+use super::locale;
 use super::prune_preset;
+use super::prune_preview;
+use super::upcoming;
 use super::weekday;
 use crate::config;
 use crate::ui;
@@ -30,9 +33,7 @@ pub async fn show_page() -> Result<()> {
             config::Frequency::Hourly => main_ui().schedule_frequency().set_selected(0),
             config::Frequency::Daily { preferred_time } => {
                 main_ui().schedule_frequency().set_selected(1);
-                main_ui()
-                    .schedule_preferred_hour()
-                    .set_value(preferred_time.hour() as f64);
+                set_preferred_hour(preferred_time.hour());
                 main_ui()
                     .schedule_preferred_minute()
                     .set_value(preferred_time.minute() as f64);
@@ -41,7 +42,7 @@ pub async fn show_page() -> Result<()> {
                 main_ui().schedule_frequency().set_selected(2);
                 main_ui()
                     .preferred_weekday_row()
-                    .set_selected(preferred_weekday.num_days_from_monday());
+                    .set_selected(weekday_position(preferred_weekday));
             }
             config::Frequency::Monthly { preferred_day } => {
                 main_ui().schedule_frequency().set_selected(3);
@@ -58,11 +59,23 @@ pub async fn show_page() -> Result<()> {
         main_ui().prune_save_revealer().set_reveal_child(false);
 
         main_ui().prune_enabled().set_active(config.prune.enabled);
+        main_ui()
+            .prune_delete_checkpoints()
+            .set_active(config.prune.delete_checkpoints);
         main_ui()
             .prune_preset()
             .set_selected(prune_preset::PrunePreset::matching(&config.prune.keep) as u32);
 
         update_prune_details(config);
+        prune_preview::reset(config);
+
+        // maintenance
+        main_ui()
+            .maintenance_enabled()
+            .set_active(config.maintenance.enabled);
+        main_ui()
+            .maintenance_verify_data()
+            .set_active(config.maintenance.verify_data);
     }
 
     Ok(())
@@ -92,6 +105,9 @@ fn update_prune_details(config: &config::Backup) {
     main_ui()
         .schedule_keep_yearly()
         .set_value(config.prune.keep.yearly as f64);
+    main_ui()
+        .schedule_keep_last()
+        .set_value(config.prune.keep.last as f64);
 }
 
 pub async fn update_status(config: &config::Backup) {
@@ -117,6 +133,15 @@ pub async fn update_status(config: &config::Backup) {
     for problem in status.problems {
         main_ui().schedule_status_list().append(&problem);
     }
+
+    main_ui()
+        .schedule_quick_actions()
+        .set_visible(config.schedule.enabled && status_tracking().daemon_running.get());
+    main_ui()
+        .schedule_skip()
+        .set_sensitive(crate::globals::active_skip(&config.id).is_none());
+
+    upcoming::show(config);
 }
 
 fn frequency() -> Result<config::Frequency> {
@@ -129,7 +154,7 @@ fn frequency() -> Result<config::Frequency> {
             config::Frequency::Hourly => config::Frequency::Hourly,
             config::Frequency::Daily { .. } => config::Frequency::Daily {
                 preferred_time: chrono::NaiveTime::from_hms_opt(
-                    main_ui().schedule_preferred_hour().value() as u32,
+                    preferred_hour(),
                     main_ui().schedule_preferred_minute().value() as u32,
                     0,
                 )
@@ -176,9 +201,7 @@ pub async fn frequency_change() -> Result<()> {
     let backups = BACKUP_CONFIG.load();
     let config = backups.active()?;
     if config.schedule.frequency != frequency {
-        main_ui()
-            .schedule_preferred_hour()
-            .set_value(glib::random_int_range(1, 24) as f64);
+        set_preferred_hour(glib::random_int_range(1, 24) as u32);
         main_ui().schedule_preferred_minute().set_value(0.);
 
         main_ui()
@@ -213,17 +236,60 @@ pub async fn preferred_time_close() -> Result<()> {
 }
 
 pub fn preferred_time_change(button: &gtk::SpinButton) -> glib::Propagation {
+    update_preferred_time_label();
+
+    if locale::uses_12h_clock() && button == &main_ui().schedule_preferred_hour() {
+        button.set_text(&button.value().to_string());
+    } else {
+        button.set_text(&format!("{:02}", button.value()));
+    }
+
+    glib::Propagation::Stop
+}
+
+pub fn update_preferred_time_label() {
     main_ui()
         .schedule_preferred_time_button()
-        .set_label(&format!(
-            "{:02}\u{2009}:\u{2009}{:02}",
-            main_ui().schedule_preferred_hour().value(),
-            main_ui().schedule_preferred_minute().value()
+        .set_label(&locale::format_time(
+            preferred_hour(),
+            main_ui().schedule_preferred_minute().value() as u32,
         ));
+    main_ui()
+        .preferred_time_row()
+        .set_subtitle(&gettextf("Time zone: {}", &[&locale::time_zone()]));
+}
 
-    button.set_text(&format!("{:02}", button.value()));
+/// Hour in 24-hour format, independent of how it is displayed
+fn preferred_hour() -> u32 {
+    let hour = main_ui().schedule_preferred_hour().value() as u32;
 
-    glib::Propagation::Stop
+    if locale::uses_12h_clock() {
+        locale::hour_from_12h(hour, main_ui().schedule_preferred_period().selected() == 1)
+    } else {
+        hour
+    }
+}
+
+fn set_preferred_hour(hour: u32) {
+    if locale::uses_12h_clock() {
+        let (hour_12h, pm) = locale::hour_to_12h(hour);
+        main_ui()
+            .schedule_preferred_period()
+            .set_selected(u32::from(pm));
+        main_ui()
+            .schedule_preferred_hour()
+            .set_value(hour_12h as f64);
+    } else {
+        main_ui().schedule_preferred_hour().set_value(hour as f64);
+    }
+}
+
+/// Position of the weekday in the list that starts with the first day of the week
+fn weekday_position(weekday: chrono::Weekday) -> u32 {
+    locale::weekdays()
+        .iter()
+        .position(|day| *day == weekday)
+        .unwrap_or_default() as u32
 }
 
 pub async fn preferred_weekday_change() -> Result<()> {
@@ -251,7 +317,8 @@ pub async fn active_change() -> Result<()> {
     let active = main_ui().schedule_active().enables_expansion();
 
     if !active
-        && ui::utils::confirmation_dialog(
+        && ui::confirm::confirm(
+            ui::confirm::Category::Disable,
             &gettext("Disable backup schedule?"),
             &gettext("No longer remind of backups based on a schedule."),
             &gettext("Keep Schedule"),
@@ -291,6 +358,7 @@ pub async fn prune_save() -> Result<()> {
 }
 
 pub async fn prune_enabled() -> Result<()> {
+    prune_preview::reset(BACKUP_CONFIG.load().active()?);
     let unsafe_changes = prune_pending_unsafe_changes()?;
 
     main_ui()
@@ -304,6 +372,58 @@ pub async fn prune_enabled() -> Result<()> {
     Ok(())
 }
 
+pub async fn prune_delete_checkpoints() -> Result<()> {
+    let delete_checkpoints = main_ui().prune_delete_checkpoints().is_active();
+
+    BACKUP_CONFIG.try_update(|configs| {
+        configs.active_mut()?.prune.delete_checkpoints = delete_checkpoints;
+        Ok(())
+    })
+}
+
+pub async fn maintenance_change() -> Result<()> {
+    let enabled = main_ui().maintenance_enabled().is_active();
+    let verify_data = main_ui().maintenance_verify_data().is_active();
+
+    BACKUP_CONFIG.try_update(|configs| {
+        let maintenance = &mut configs.active_mut()?.maintenance;
+        maintenance.enabled = enabled;
+        maintenance.verify_data = verify_data;
+        Ok(())
+    })
+}
+
+pub async fn maintenance_run() -> Result<()> {
+    let config = BACKUP_CONFIG.load().active()?.clone();
+
+    // Deleting old archives is confirmed like when started from the archives page
+    if config.prune.enabled
+        && !ui::utils::append_only::is_protected(&config)
+        && !ui::dialog_prune::confirm(&config).await?
+    {
+        return Ok(());
+    }
+
+    let guard = QuitGuard::default();
+
+    ui::maintenance::run(config, None, &guard).await?;
+
+    Ok(())
+}
+
+pub async fn skip_next() -> Result<()> {
+    let config_id = BACKUP_CONFIG.load().active()?.id.clone();
+
+    // The status is refreshed once the background process wrote the skip
+    ui::dbus::activate_daemon_action("skip-scheduled-backup", &config_id)
+}
+
+pub async fn run_now() -> Result<()> {
+    let config_id = BACKUP_CONFIG.load().active()?.id.clone();
+
+    ui::dbus::activate_daemon_action("start-scheduled-backup", &config_id)
+}
+
 pub async fn prune_preset_change() -> Result<()> {
     if let Some(preset) = main_ui()
         .prune_preset()
@@ -325,6 +445,7 @@ pub async fn prune_preset_change() -> Result<()> {
 }
 
 pub async fn keep_change() -> Result<()> {
+    prune_preview::reset(BACKUP_CONFIG.load().active()?);
     main_ui()
         .prune_preset()
         .set_selected(prune_preset::PrunePreset::matching(&keep()) as u32);
@@ -341,6 +462,13 @@ pub async fn keep_change() -> Result<()> {
     Ok(())
 }
 
+pub async fn prune_preview() -> Result<()> {
+    let mut config = BACKUP_CONFIG.load().active()?.clone();
+    config.prune.keep = keep();
+
+    prune_preview::show(config).await
+}
+
 fn prune_pending_unsafe_changes() -> Result<bool> {
     let configs = BACKUP_CONFIG.load();
     let current_config = configs.active()?;
@@ -373,5 +501,6 @@ fn keep() -> config::Keep {
         weekly: main_ui().schedule_keep_weekly().value() as u32,
         monthly: main_ui().schedule_keep_monthly().value() as u32,
         yearly: main_ui().schedule_keep_yearly().value() as u32,
+        last: main_ui().schedule_keep_last().value() as u32,
     }
 }
@@ -2,8 +2,9 @@ use adw::prelude::*;
 
 use super::event;
 use super::frequency::{self, FrequencyObject};
+use super::locale;
 use super::prune_preset::PrunePresetObject;
-use super::weekday::{self, WeekdayObject};
+use super::weekday::WeekdayObject;
 use crate::ui::prelude::*;
 
 use once_cell::sync::Lazy;
@@ -26,8 +27,8 @@ pub fn init() {
 
     let model = gio::ListStore::with_type(WeekdayObject::static_type());
 
-    for weekday in &weekday::LIST {
-        model.append(&WeekdayObject::new(*weekday));
+    for weekday in locale::weekdays() {
+        model.append(&WeekdayObject::new(weekday));
     }
 
     main_ui().preferred_weekday_row().set_model(Some(&model));
@@ -44,6 +45,14 @@ pub fn init() {
 
     Lazy::force(&SCHEDULE_ACTIVE_SIGNAL_HANDLER);
 
+    main_ui()
+        .schedule_skip()
+        .connect_clicked(|_| Handler::run(event::skip_next()));
+
+    main_ui()
+        .schedule_run_now()
+        .connect_clicked(|_| Handler::run(event::run_now()));
+
     main_ui()
         .schedule_frequency()
         .connect_selected_item_notify(|_| Handler::run(event::frequency_change()));
@@ -56,6 +65,23 @@ pub fn init() {
         .schedule_preferred_minute()
         .connect_output(event::preferred_time_change);
 
+    if locale::uses_12h_clock() {
+        main_ui().schedule_preferred_hour().set_range(1., 12.);
+
+        let periods = locale::periods();
+        main_ui()
+            .schedule_preferred_period()
+            .set_model(Some(&gtk::StringList::new(&[
+                periods[0].as_str(),
+                periods[1].as_str(),
+            ])));
+        main_ui().schedule_preferred_period().set_visible(true);
+    }
+
+    main_ui()
+        .schedule_preferred_period()
+        .connect_selected_notify(|_| event::update_preferred_time_label());
+
     main_ui()
         .schedule_preferred_time_popover()
         .connect_closed(|_| Handler::run(event::preferred_time_close()));
@@ -78,6 +104,10 @@ pub fn init() {
         .prune_enabled()
         .connect_active_notify(|_| Handler::run(event::prune_enabled()));
 
+    main_ui()
+        .prune_preview()
+        .connect_expanded_notify(|_| Handler::run(event::prune_preview()));
+
     main_ui()
         .prune_preset()
         .set_model(Some(&PrunePresetObject::list_store()));
@@ -106,6 +136,28 @@ pub fn init() {
         .schedule_keep_yearly()
         .connect_value_notify(|_| Handler::run(event::keep_change()));
 
+    main_ui()
+        .schedule_keep_last()
+        .connect_value_notify(|_| Handler::run(event::keep_change()));
+
+    main_ui()
+        .prune_delete_checkpoints()
+        .connect_active_notify(|_| Handler::run(event::prune_delete_checkpoints()));
+
+    // Maintenance
+
+    main_ui()
+        .maintenance_enabled()
+        .connect_active_notify(|_| Handler::run(event::maintenance_change()));
+
+    main_ui()
+        .maintenance_verify_data()
+        .connect_active_notify(|_| Handler::run(event::maintenance_change()));
+
+    main_ui()
+        .maintenance_run()
+        .connect_clicked(|_| Handler::run(event::maintenance_run()));
+
     // Network
 
     gio::NetworkMonitor::default()
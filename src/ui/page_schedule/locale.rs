@@ -0,0 +1,135 @@
+//! Display times and weekdays following the conventions of the locale
+//!
+//! The configuration always stores times in 24-hour format and weekdays
+//! independent of the first day of the week.
+
+use std::ffi::CStr;
+
+/// The locale formats times with AM and PM
+pub fn uses_12h_clock() -> bool {
+    // SAFETY: `nl_langinfo` returns a pointer to a null terminated string
+    let format = unsafe { CStr::from_ptr(nix::libc::nl_langinfo(nix::libc::T_FMT)) };
+
+    is_12h_format(&format.to_string_lossy())
+}
+
+fn is_12h_format(format: &str) -> bool {
+    ["%r", "%I", "%l", "%p", "%P"]
+        .iter()
+        .any(|x| format.contains(x))
+}
+
+/// Localized names of AM and PM
+pub fn periods() -> [String; 2] {
+    [9, 21].map(|hour| {
+        glib::DateTime::from_local(2021, 3, 1, hour, 0, 0.)
+            .ok()
+            .and_then(|dt| dt.format("%p").ok())
+            .map(|x| x.to_string())
+            .filter(|x| !x.is_empty())
+            .unwrap_or_else(|| if hour < 12 { "AM" } else { "PM" }.to_string())
+    })
+}
+
+/// Hour on a 12-hour clock and whether it is PM
+pub fn hour_to_12h(hour: u32) -> (u32, bool) {
+    let hour_12h = match hour % 12 {
+        0 => 12,
+        hour => hour,
+    };
+
+    (hour_12h, hour >= 12)
+}
+
+pub fn hour_from_12h(hour_12h: u32, pm: bool) -> u32 {
+    hour_12h % 12 + if pm { 12 } else { 0 }
+}
+
+pub fn format_time(hour: u32, minute: u32) -> String {
+    if uses_12h_clock() {
+        let (hour, pm) = hour_to_12h(hour);
+        let [am_name, pm_name] = periods();
+
+        format!(
+            "{hour}\u{2009}:\u{2009}{minute:02} {}",
+            if pm { pm_name } else { am_name }
+        )
+    } else {
+        format!("{hour:02}\u{2009}:\u{2009}{minute:02}")
+    }
+}
+
+/// Active time zone, like `Europe/Berlin (CEST)`
+pub fn time_zone() -> String {
+    let identifier = glib::TimeZone::local().identifier().to_string();
+    let abbreviation = glib::DateTime::now_local()
+        .map(|now| now.timezone_abbreviation().to_string())
+        .unwrap_or_default();
+
+    if abbreviation.is_empty() || abbreviation == identifier {
+        identifier
+    } else {
+        format!("{identifier} ({abbreviation})")
+    }
+}
+
+/// First day of the week in the current locale
+///
+/// Uses the same translation as the GTK calendar.
+pub fn first_weekday() -> chrono::Weekday {
+    parse_week_start(&gettextrs::dgettext("gtk40", "calendar:week_start:0"))
+        .unwrap_or(chrono::Weekday::Mon)
+}
+
+fn parse_week_start(s: &str) -> Option<chrono::Weekday> {
+    let days_from_sunday = s
+        .strip_prefix("calendar:week_start:")?
+        .parse::<usize>()
+        .ok()
+        .filter(|days| *days < 7)?;
+
+    std::iter::successors(Some(chrono::Weekday::Sun), |day| Some(day.succ())).nth(days_from_sunday)
+}
+
+/// All weekdays, starting with the first day of the week
+pub fn weekdays() -> Vec<chrono::Weekday> {
+    std::iter::successors(Some(first_weekday()), |day| Some(day.succ()))
+        .take(7)
+        .collect()
+}
+
+#[test]
+fn test_12h_clock() {
+    assert!(is_12h_format("%r"));
+    assert!(is_12h_format("%I:%M:%S %p"));
+    assert!(!is_12h_format("%T"));
+    assert!(!is_12h_format("%H:%M:%S"));
+
+    for hour in 0..24 {
+        let (hour_12h, pm) = hour_to_12h(hour);
+        assert!((1..=12).contains(&hour_12h));
+        assert_eq!(hour_from_12h(hour_12h, pm), hour);
+    }
+
+    assert_eq!(hour_to_12h(0), (12, false));
+    assert_eq!(hour_to_12h(12), (12, true));
+    assert_eq!(hour_to_12h(13), (1, true));
+}
+
+#[test]
+fn test_parse_week_start() {
+    assert_eq!(
+        parse_week_start("calendar:week_start:0"),
+        Some(chrono::Weekday::Sun)
+    );
+    assert_eq!(
+        parse_week_start("calendar:week_start:1"),
+        Some(chrono::Weekday::Mon)
+    );
+    assert_eq!(
+        parse_week_start("calendar:week_start:6"),
+        Some(chrono::Weekday::Sat)
+    );
+    assert_eq!(parse_week_start("calendar:week_start:7"), None);
+    assert_eq!(parse_week_start("other"), None);
+}
@@ -32,6 +32,7 @@ impl PrunePreset {
                 weekly: 2,
                 monthly: 6,
                 yearly: 0,
+                last: 0,
             }),
             Self::Custom => None,
         }
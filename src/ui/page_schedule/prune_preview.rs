@@ -0,0 +1,113 @@
+//! Archives that would be deleted with the current prune settings
+//!
+//! The preview is calculated via a dry run when it is expanded and collapsed
+//! again whenever the settings change.
+
+use adw::prelude::*;
+
+use std::cell::RefCell;
+
+use crate::borg;
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+thread_local!(
+    static ROWS: RefCell<Vec<adw::ActionRow>> = Default::default();
+);
+
+fn clear() {
+    ROWS.with(|rows| {
+        for row in rows.borrow_mut().drain(..) {
+            main_ui().prune_preview().remove(&row);
+        }
+    });
+}
+
+fn add_row(row: adw::ActionRow) {
+    main_ui().prune_preview().add_row(&row);
+    ROWS.with(|rows| rows.borrow_mut().push(row));
+}
+
+/// Hide the preview since it no longer matches the settings
+pub fn reset(config: &config::Backup) {
+    clear();
+
+    main_ui().prune_preview().set_expanded(false);
+    main_ui().prune_preview().set_subtitle(&gettextf(
+        "Archives with prefix “{}” the current settings would delete",
+        &[&config.archive_prefix.to_string()],
+    ));
+}
+
+pub async fn show(config: config::Backup) -> Result<()> {
+    if !main_ui().prune_preview().is_expanded() {
+        return Ok(());
+    }
+
+    clear();
+    main_ui()
+        .prune_preview()
+        .set_subtitle(&gettext("Identifying old archives…"));
+
+    let result = calculate(&config).await;
+
+    if result.is_err() {
+        reset(&config);
+    }
+
+    result
+}
+
+async fn calculate(config: &config::Backup) -> Result<()> {
+    ui::dialog_device_missing::ensure_device_plugged_in(
+        config,
+        &gettext("Identifying old Archives"),
+    )
+    .await?;
+
+    let prune_info = ui::utils::borg::exec(
+        borg::Command::<borg::task::PruneInfo>::new(config.clone()),
+        &QuitGuard::default(),
+    )
+    .await
+    .into_message(gettext(
+        "Failed to determine how many archives would be deleted",
+    ))?;
+
+    // Settings changed while calculating
+    if !main_ui().prune_preview().is_expanded() {
+        return Ok(());
+    }
+
+    main_ui()
+        .prune_preview()
+        .set_subtitle(&if prune_info.prune_archives.is_empty() {
+            gettext("No archives would be deleted right now")
+        } else {
+            ngettextf_(
+                "One archive would be deleted right now",
+                "{} archives would be deleted right now",
+                prune_info.prune_archives.len() as u32,
+            )
+        });
+
+    for archive in prune_info.prune_archives {
+        let mut facts = Vec::new();
+        // Checkpoints are only listed if they are deleted via the setting
+        if archive.name.as_str().contains(".checkpoint") {
+            facts.push(gettext("Checkpoint of an interrupted backup"));
+        }
+        facts.extend(archive.time.and_then(|time| time.to_locale()));
+
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(archive.name.as_str())
+            .subtitle(facts.join(&gettext(", ")))
+            .build();
+
+        add_row(row);
+    }
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use chrono::Timelike;
+
 use crate::config;
 use crate::schedule::requirements;
 use crate::ui::prelude::*;
@@ -118,6 +120,12 @@ impl Status {
                         "media-playback-start-symbolic",
                         problem_level,
                     )),
+                    requirements::Global::OperationInBackground => problems.push(StatusRow::new(
+                        gettext("Operations running in the background have to be completed"),
+                        "",
+                        "media-playback-start-symbolic",
+                        problem_level,
+                    )),
                     requirements::Global::ThisBackupRunning => (),
                     requirements::Global::OnBattery => problems.push(StatusRow::new(
                         gettext("Device must be connected to power"),
@@ -125,6 +133,52 @@ impl Status {
                         "battery-good-symbolic",
                         problem_level,
                     )),
+                    requirements::Global::PowerSaver => problems.push(StatusRow::new(
+                        gettext("Deferred due to power saving"),
+                        gettext("Power saver mode has to be turned off"),
+                        "power-profile-power-saver-symbolic",
+                        problem_level,
+                    )),
+                    requirements::Global::StartupHoldoff { until } => {
+                        problems.push(StatusRow::new(
+                            gettext("Waiting after login"),
+                            gettextf(
+                                "Scheduled backups start at {} at the earliest",
+                                &[&super::locale::format_time(until.hour(), until.minute())],
+                            ),
+                            "system-reboot-symbolic",
+                            problem_level,
+                        ))
+                    }
+                    requirements::Global::Vetoed { reason, until } => {
+                        problems.push(StatusRow::new(
+                            gettext("Postponed by veto command"),
+                            gettextf(
+                                "{} – Next attempt at {}",
+                                &[
+                                    &reason,
+                                    &super::locale::format_time(until.hour(), until.minute()),
+                                ],
+                            ),
+                            "action-unavailable-symbolic",
+                            problem_level,
+                        ))
+                    }
+                    requirements::Global::Skipped { until } => problems.push(StatusRow::new(
+                        gettext("Next backup skipped"),
+                        gettextf(
+                            "Scheduled backups resume on {} at {}",
+                            &[
+                                &glib::DateTime::from_unix_local(until.timestamp())
+                                    .and_then(|x| x.format("%x"))
+                                    .map(|x| x.to_string())
+                                    .unwrap_or_else(|_| until.date_naive().to_string()),
+                                &super::locale::format_time(until.hour(), until.minute()),
+                            ],
+                        ),
+                        "media-skip-forward-symbolic",
+                        StatusLevel::Neutral,
+                    )),
                 }
             }
 
@@ -0,0 +1,127 @@
+//! Timeline of the backups the schedule would start during the next days
+
+use adw::prelude::*;
+use chrono::prelude::*;
+
+use std::cell::RefCell;
+
+use super::locale;
+use crate::config;
+use crate::schedule::local_time;
+use crate::schedule::simulation;
+use crate::schedule::DueCause;
+use crate::ui::prelude::*;
+
+/// Number of days shown in the timeline
+const DAYS: i64 = 7;
+
+/// Days with more backups only show the number of backups
+const MAX_LISTED_TIMES: usize = 4;
+
+thread_local!(
+    static ROWS: RefCell<Vec<adw::ActionRow>> = Default::default();
+);
+
+fn clear() {
+    ROWS.with(|rows| {
+        for row in rows.borrow_mut().drain(..) {
+            main_ui().schedule_upcoming().remove(&row);
+        }
+    });
+}
+
+fn add_row(row: adw::ActionRow) {
+    main_ui().schedule_upcoming().add_row(&row);
+    ROWS.with(|rows| rows.borrow_mut().push(row));
+}
+
+fn day_name(date: NaiveDate, today: NaiveDate) -> String {
+    if date == today {
+        gettext("Today")
+    } else if Some(date) == today.succ_opt() {
+        gettext("Tomorrow")
+    } else {
+        date.and_hms_opt(12, 0, 0)
+            .and_then(|datetime| Local.from_local_datetime(&datetime).earliest())
+            .and_then(|datetime| glib::DateTime::from_unix_local(datetime.timestamp()).ok())
+            .and_then(|datetime| datetime.format("%A, %x").ok())
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| date.to_string())
+    }
+}
+
+fn describe(run: &simulation::Run) -> String {
+    let time = locale::format_time(run.time.hour(), run.time.minute());
+
+    match run.cause {
+        DueCause::Regular => time,
+        // Translators: Time of a backup that is repeated because the previous one failed
+        DueCause::Retry => gettextf("{} (retry)", &[&time]),
+    }
+}
+
+pub fn show(config: &config::Backup) {
+    clear();
+
+    main_ui()
+        .schedule_upcoming()
+        .set_visible(config.schedule.enabled);
+
+    let now = Local::now();
+    let histories = BACKUP_HISTORY.load();
+    let mut runs = simulation::simulate(
+        config,
+        histories.try_get(&config.id).ok(),
+        now,
+        chrono::Duration::days(DAYS),
+    );
+
+    if let Some(until) = crate::globals::active_skip(&config.id) {
+        runs.retain(|run| run.time >= until);
+    }
+
+    main_ui().schedule_upcoming().set_subtitle(&format!(
+        "{}\n{}",
+        ngettextf_(
+            "One backup during the next week, if the computer is in use",
+            "{} backups during the next week, if the computer is in use",
+            runs.len() as u32,
+        ),
+        // Translators: Argument is a time zone like 'Europe/Berlin (CEST)'
+        gettextf("Times in {}", &[&locale::time_zone()]),
+    ));
+
+    let today = now.date_naive();
+
+    for date in today.iter_days().take(DAYS as usize) {
+        let times = runs
+            .iter()
+            .filter(|run| run.time.date_naive() == date)
+            .map(describe)
+            .collect::<Vec<_>>();
+
+        let mut subtitle = if times.is_empty() {
+            gettext("No backup")
+        } else if times.len() > MAX_LISTED_TIMES {
+            gettextf(
+                "{} backups, starting at {}",
+                &[&times.len().to_string(), &times[0]],
+            )
+        } else {
+            times.join(", ")
+        };
+
+        if local_time::clock_change_on(&Local, date) {
+            // Translators: Argument is the list of backup times on a day with a daylight saving time change
+            subtitle = gettextf("{} (clocks change)", &[&subtitle]);
+        }
+
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(day_name(date, today))
+            .subtitle(subtitle)
+            .build();
+
+        add_row(row);
+    }
+}
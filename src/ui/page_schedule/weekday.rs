@@ -5,16 +5,6 @@ use std::cell::RefCell;
 use glib::{ParamSpec, ParamSpecString};
 use once_cell::sync::Lazy;
 
-pub static LIST: [chrono::Weekday; 7] = [
-    chrono::Weekday::Mon,
-    chrono::Weekday::Tue,
-    chrono::Weekday::Wed,
-    chrono::Weekday::Thu,
-    chrono::Weekday::Fri,
-    chrono::Weekday::Sat,
-    chrono::Weekday::Sun,
-];
-
 glib::wrapper! {
     pub struct WeekdayObject(ObjectSubclass<imp::WeekdayObject>);
 }
@@ -0,0 +1,299 @@
+//! Actionable problems of all backup configurations
+//!
+//! The problems are collected from the backup history, the cached disk space
+//! and the schedule, and are shown in a list on the overview page. Every
+//! problem offers an action that helps to fix it.
+
+use adw::prelude::*;
+use chrono::prelude::*;
+
+use crate::config;
+use crate::config::history::{CheckOutcome, History};
+use crate::schedule::requirements;
+use crate::ui;
+use crate::ui::backup_status;
+use crate::ui::prelude::*;
+use crate::ui::utils::df::Space;
+use crate::ui::utils::repo_cache::RepoCache;
+
+/// Days after which the last completed backup is considered old
+const BACKUP_OUTDATED_DAYS: i64 = 14;
+
+/// Free space below this fraction of the disk size is considered low
+const SPACE_LOW_FRACTION: f64 = 0.05;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    BackupFailed,
+    BackupOutdated { days: i64 },
+    DeviceMissing,
+    ScheduleUnavailable,
+    SpaceLow { avail: u64 },
+    CheckErrors,
+}
+
+/// Information about a configuration that problems are derived from
+pub struct State<'a> {
+    pub history: Option<&'a History>,
+    pub space: Option<&'a Space>,
+    /// A scheduled backup is due
+    pub due: bool,
+    pub device_missing: bool,
+    pub daemon_running: bool,
+    pub now: DateTime<Local>,
+}
+
+impl Kind {
+    pub fn check(config: &config::Backup, state: &State) -> Vec<Self> {
+        let mut problems = Vec::new();
+
+        if let Some(history) = state.history {
+            if let Some(run_info) = history.run.front() {
+                if matches!(
+                    backup_status::Status::from_run_info(run_info, state.now),
+                    backup_status::Status::LastRun {
+                        result: backup_status::RunResult::Failed,
+                        ..
+                    }
+                ) {
+                    problems.push(Self::BackupFailed);
+                }
+            }
+
+            if let Some(last_completed) = &history.last_completed {
+                let days = (state.now - last_completed.end).num_days();
+                if days >= BACKUP_OUTDATED_DAYS && !history.is_running() {
+                    problems.push(Self::BackupOutdated { days });
+                }
+            }
+
+            if matches!(
+                history.last_check.as_ref().map(|check| &check.outcome),
+                Some(CheckOutcome::Error(_))
+            ) {
+                problems.push(Self::CheckErrors);
+            }
+        }
+
+        if config.schedule.enabled {
+            if !state.daemon_running {
+                problems.push(Self::ScheduleUnavailable);
+            } else if state.due && state.device_missing {
+                problems.push(Self::DeviceMissing);
+            }
+        }
+
+        if let Some(space) = state.space {
+            if space.size > 0 && (space.avail as f64) < space.size as f64 * SPACE_LOW_FRACTION {
+                problems.push(Self::SpaceLow { avail: space.avail });
+            }
+        }
+
+        problems
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            Self::BackupFailed => gettext("Last Backup Failed"),
+            Self::BackupOutdated { days } => ngettextf_(
+                "Last Backup One Day Ago",
+                "Last Backup {} Days Ago",
+                *days as u32,
+            ),
+            Self::DeviceMissing => gettext("Backup Device Not Connected"),
+            Self::ScheduleUnavailable => gettext("Scheduled Backups Unavailable"),
+            Self::SpaceLow { avail } => gettextf(
+                "Only {} Free on Backup Location",
+                &[&glib::format_size(*avail)],
+            ),
+            Self::CheckErrors => gettext("Integrity Check Found Errors"),
+        }
+    }
+
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            Self::BackupFailed | Self::CheckErrors => "dialog-error-symbolic",
+            Self::BackupOutdated { .. } => "document-open-recent-symbolic",
+            Self::DeviceMissing => "drive-removable-media-symbolic",
+            Self::ScheduleUnavailable => "schedule-symbolic",
+            Self::SpaceLow { .. } => "drive-harddisk-symbolic",
+        }
+    }
+
+    fn fix_label(&self) -> String {
+        match self {
+            Self::BackupOutdated { .. } => gettext("Back Up Now"),
+            Self::SpaceLow { .. } => gettext("Show Archives"),
+            Self::ScheduleUnavailable => gettext("Show Schedule"),
+            Self::BackupFailed | Self::DeviceMissing | Self::CheckErrors => gettext("Show Details"),
+        }
+    }
+
+    fn fix(&self, config_id: &ConfigId) {
+        match self {
+            Self::BackupOutdated { .. } => {
+                ui::page_backup::start_backup(config_id.clone(), None, QuitGuard::default())
+            }
+            Self::SpaceLow { .. } => ui::page_archives::view(config_id),
+            Self::ScheduleUnavailable => ui::page_schedule::view(config_id),
+            Self::BackupFailed | Self::DeviceMissing | Self::CheckErrors => {
+                ui::page_backup::view_backup_conf(config_id)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub config_id: ConfigId,
+    pub kind: Kind,
+}
+
+/// Current problems of all configurations
+pub fn collect() -> Vec<Problem> {
+    let histories = BACKUP_HISTORY.load();
+    let daemon_running = status_tracking().daemon_running.get();
+    let now = Local::now();
+
+    let mut problems = Vec::new();
+
+    for config in BACKUP_CONFIG.load().iter() {
+        let space = RepoCache::get(&config.repo_id).space;
+        let state = State {
+            history: histories.try_get(&config.id).ok(),
+            space: space.as_ref(),
+            due: requirements::Due::check(config).is_ok(),
+            device_missing: config.repo.is_drive_connected() == Some(false),
+            daemon_running,
+            now,
+        };
+
+        problems.extend(Kind::check(config, &state).into_iter().map(|kind| Problem {
+            config_id: config.id.clone(),
+            kind,
+        }));
+    }
+
+    problems
+}
+
+/// Update the problems button and list on the overview page
+pub fn refresh() {
+    let problems = collect();
+    let list = main_ui().problems_list();
+
+    ui::utils::clear(&list);
+
+    main_ui()
+        .problems_button()
+        .set_visible(!problems.is_empty());
+    main_ui()
+        .problems_count()
+        .set_label(&problems.len().to_string());
+
+    for problem in problems {
+        let config_title = BACKUP_CONFIG
+            .load()
+            .try_get(&problem.config_id)
+            .map(|config| config.title())
+            .unwrap_or_default();
+
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(problem.kind.title())
+            .subtitle(config_title)
+            .activatable(false)
+            .build();
+
+        row.add_prefix(&gtk::Image::from_icon_name(problem.kind.icon_name()));
+
+        let fix = gtk::Button::builder()
+            .label(problem.kind.fix_label())
+            .valign(gtk::Align::Center)
+            .build();
+
+        fix.connect_clicked(move |_| {
+            main_ui().problems_button().popdown();
+            problem.kind.fix(&problem.config_id);
+        });
+
+        row.add_suffix(&fix);
+        list.append(&row);
+    }
+}
+
+#[cfg(test)]
+fn test_state(history: Option<&History>) -> State {
+    State {
+        history,
+        space: None,
+        due: false,
+        device_missing: false,
+        daemon_running: true,
+        now: Local::now(),
+    }
+}
+
+#[test]
+fn test_check_history() {
+    let config = config::Backup::test_new_mock();
+
+    assert!(Kind::check(&config, &test_state(None)).is_empty());
+
+    let mut history = History::default();
+    history.insert(config::history::RunInfo::test_new_mock(
+        chrono::Duration::days(1),
+    ));
+    assert!(Kind::check(&config, &test_state(Some(&history))).is_empty());
+
+    history.insert(config::history::RunInfo::new(
+        &config,
+        crate::borg::Outcome::Failed(crate::borg::error::Failure::Undefined),
+        vec![],
+    ));
+    history.last_check = Some(config::history::CheckRunInfo::new_error(vec![]));
+    assert_eq!(
+        Kind::check(&config, &test_state(Some(&history))),
+        vec![Kind::BackupFailed, Kind::CheckErrors]
+    );
+
+    let mut history = History::default();
+    history.insert(config::history::RunInfo::test_new_mock(
+        chrono::Duration::days(BACKUP_OUTDATED_DAYS),
+    ));
+    assert_eq!(
+        Kind::check(&config, &test_state(Some(&history))),
+        vec![Kind::BackupOutdated {
+            days: BACKUP_OUTDATED_DAYS
+        }]
+    );
+}
+
+#[test]
+fn test_check_schedule_and_space() {
+    let mut config = config::Backup::test_new_mock();
+    config.schedule.enabled = true;
+
+    let mut state = test_state(None);
+    state.due = true;
+    state.device_missing = true;
+    assert_eq!(Kind::check(&config, &state), vec![Kind::DeviceMissing]);
+
+    state.daemon_running = false;
+    assert_eq!(
+        Kind::check(&config, &state),
+        vec![Kind::ScheduleUnavailable]
+    );
+
+    config.schedule.enabled = false;
+    let space = Space {
+        size: 1000,
+        used: 980,
+        avail: 20,
+    };
+    state.space = Some(&space);
+    assert_eq!(
+        Kind::check(&config, &state),
+        vec![Kind::SpaceLow { avail: 20 }]
+    );
+}
@@ -0,0 +1,173 @@
+//! Operations running in another process
+//!
+//! The status of these operations is polled over D-Bus from the process that
+//! holds the operation lock, such that they are displayed like local
+//! operations and can be aborted.
+
+use crate::ui::prelude::*;
+
+use crate::borg;
+use crate::ui;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use ui::backup_status;
+
+/// How often the status of operations in other processes is updated
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+thread_local!(
+    /// Unique D-Bus names of the processes running the followed operations
+    static BUS_NAMES: RefCell<BTreeMap<ConfigId, String>> = Default::default();
+);
+
+/// Last known status of an operation that runs in another process
+pub fn status(config_id: &ConfigId) -> Option<Rc<backup_status::Display>> {
+    REMOTE_OPERATION.with(|operations| operations.load().get(config_id).cloned())
+}
+
+pub fn is_attached(config_id: &ConfigId) -> bool {
+    status(config_id).is_some()
+}
+
+/// Follow operations that other processes run on the repositories of all configs
+pub fn attach_running() {
+    for config in BACKUP_CONFIG.load().iter() {
+        if let Some(holder) = borg::operation_lock::holder(&config.repo_id) {
+            attach(holder);
+        }
+    }
+}
+
+/// Display the progress of the operation that holds the lock
+pub fn attach(holder: borg::operation_lock::Holder) {
+    if holder.pid == std::process::id() || is_attached(&holder.config_id) {
+        return;
+    }
+
+    debug!(
+        "Following operation '{}' of process {}",
+        holder.operation, holder.pid
+    );
+
+    let title = if holder.operation.is_empty() {
+        gettext("Operation Running in Background")
+    } else {
+        holder.operation.clone()
+    };
+
+    update(
+        &holder.config_id,
+        Some(backup_status::Display {
+            title,
+            subtitle: Some(gettext("Started by another process")),
+            graphic: backup_status::Graphic::Spinner,
+            progress: None,
+            stats: None,
+        }),
+    );
+
+    if let Some(bus_name) = &holder.bus_name {
+        BUS_NAMES.with(|names| {
+            names
+                .borrow_mut()
+                .insert(holder.config_id.clone(), bus_name.clone())
+        });
+    }
+
+    glib::MainContext::default().spawn_local(follow(holder.config_id));
+}
+
+fn bus_name(config_id: &ConfigId) -> Option<String> {
+    BUS_NAMES.with(|names| names.borrow().get(config_id).cloned())
+}
+
+pub async fn abort(config_id: &ConfigId) -> Result<()> {
+    let bus_name = bus_name(config_id).ok_or_else(|| {
+        Message::new(
+            gettext("Failed to abort operation"),
+            gettext("The process running the operation can not be reached."),
+        )
+    })?;
+
+    ui::dbus::remote_proxy(&bus_name)
+        .await
+        .err_to_msg(gettext("Failed to abort operation"))?
+        .abort_operation(config_id)
+        .await
+        .err_to_msg(gettext("Failed to abort operation"))
+}
+
+async fn follow(config_id: ConfigId) {
+    loop {
+        async_std::task::sleep(POLL_INTERVAL).await;
+
+        let status = match fetch_status(&config_id).await {
+            Ok(status) => status,
+            Err(err) => {
+                debug!("Lost track of operation in other process: {}", err);
+                None
+            }
+        };
+
+        let finished = status.is_none();
+        update(&config_id, status);
+
+        if finished {
+            BUS_NAMES.with(|names| names.borrow_mut().remove(&config_id));
+            break;
+        }
+    }
+}
+
+async fn fetch_status(config_id: &ConfigId) -> zbus::Result<Option<backup_status::Display>> {
+    let Some(bus_name) = bus_name(config_id) else {
+        // Without D-Bus name only the end of the operation can be detected
+        let running = BACKUP_CONFIG
+            .load()
+            .try_get(config_id)
+            .ok()
+            .and_then(|config| borg::operation_lock::holder(&config.repo_id))
+            .is_some();
+
+        return Ok(if running {
+            status(config_id).map(|x| (*x).clone())
+        } else {
+            None
+        });
+    };
+
+    let status = ui::dbus::remote_proxy(&bus_name)
+        .await?
+        .operation_status(config_id)
+        .await?;
+
+    if status.is_empty() {
+        Ok(None)
+    } else {
+        Ok(serde_json::from_str(&status).ok())
+    }
+}
+
+fn update(config_id: &ConfigId, status: Option<backup_status::Display>) {
+    let status = status.map(Rc::new);
+
+    REMOTE_OPERATION.with(|operations| {
+        operations.update(|operations| {
+            if let Some(status) = &status {
+                operations.insert(config_id.clone(), status.clone());
+            } else {
+                operations.remove(config_id);
+            }
+        })
+    });
+
+    ui::operation_status::refresh(config_id);
+
+    if ACTIVE_BACKUP_ID.load().as_ref().as_ref() == Some(config_id) {
+        ui::page_backup::refresh_status();
+        ui::dialog_info::refresh_status();
+    }
+    ui::window_backup::refresh_status(config_id);
+
+    ui::page_overview::refresh_status();
+}
@@ -114,10 +114,12 @@ impl StatusTracking {
         }));
 
         volume_monitor.connect_mount_added(move |_, _| {
+            ui::page_backup::reset_same_drive_cache();
             ui::page_backup::refresh_disk_status();
         });
 
         volume_monitor.connect_mount_removed(move |_, _| {
+            ui::page_backup::reset_same_drive_cache();
             ui::page_backup::refresh_disk_status();
         });
 
@@ -155,10 +157,10 @@ impl StatusTracking {
     fn ui_status_update(&self) {
         debug!("UI status update");
 
+        // Operation status objects and views bound to them are updated by the operations
         ui::page_backup::refresh_status();
         ui::page_archives::refresh_status();
         ui::page_overview::refresh_status();
-        ui::dialog_info::refresh_status();
     }
 
     fn ui_schedule_update(&self) {
@@ -3,6 +3,8 @@ use crate::config;
 use crate::ui;
 use crate::ui::prelude::*;
 
+use std::collections::BTreeSet;
+
 pub async fn check(
     config: &config::Backup,
     communication: borg::Communication<borg::task::Create>,
@@ -24,9 +26,12 @@ pub async fn check(
                 status.estimated_size = estimated_size.clone();
             }));
 
-        let history_save_result = BACKUP_HISTORY.try_update(clone!(@strong config.id as config_id, @strong estimate.unreadable_paths as paths => move |history| {
+        let previous_other_backups = other_backup_suggestions(&config.id);
+
+        let history_save_result = BACKUP_HISTORY.try_update(clone!(@strong config.id as config_id, @strong estimate.unreadable_paths as paths, @strong estimate.other_backup_paths as other_backup_paths => move |history| {
             if let Ok(history) = history.try_get_mut(&config_id) {
                 history.set_suggested_excludes_from_absolute(config::history::SuggestedExcludeReason::PermissionDenied, paths.clone());
+                history.set_suggested_excludes_from_absolute(config::history::SuggestedExcludeReason::OtherBackupTool, other_backup_paths.clone());
             }
 
             Ok(())
@@ -36,6 +41,18 @@ pub async fn check(
             err.show().await;
         }
 
+        let new_other_backups = other_backup_suggestions(&config.id);
+
+        if new_other_backups
+            .iter()
+            .any(|x| !previous_other_backups.contains(x) && !config.exclude.contains(x))
+        {
+            ui::utils::show_notice(gettextf(
+                "Backup “{}” includes folders of other backup tools. They can be excluded from the backup via “Exclude from Backup”.",
+                &[&config.title()],
+            ));
+        }
+
         let space_avail = ui::utils::df::cached_or_lookup(config)
             .await
             .map(|x| x.avail);
@@ -55,3 +72,18 @@ pub async fn check(
         }
     }
 }
+
+fn other_backup_suggestions(
+    config_id: &ConfigId,
+) -> BTreeSet<config::Exclude<{ config::RELATIVE }>> {
+    BACKUP_HISTORY
+        .load()
+        .try_get(config_id)
+        .ok()
+        .and_then(|history| {
+            history
+                .suggested_excludes(&config::history::SuggestedExcludeReason::OtherBackupTool)
+                .cloned()
+        })
+        .unwrap_or_default()
+}
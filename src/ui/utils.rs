@@ -1,12 +1,23 @@
+pub mod append_only;
+pub mod authentication;
 pub mod borg;
 pub mod config_io;
+pub mod connection;
 pub mod df;
 pub mod duration;
 pub mod ext;
+pub mod file_chooser;
+pub mod file_index;
 pub mod flatpak_info;
+pub mod host_key;
+pub mod list_store;
+pub mod metrics;
 pub mod notification;
 pub mod password_storage;
+pub mod permissions;
 pub mod repo_cache;
+pub mod report;
+pub mod restore;
 
 use crate::ui::prelude::*;
 use adw::prelude::*;
@@ -96,6 +107,16 @@ pub async fn is_backup_repo(path: &std::path::Path) -> bool {
     false
 }
 
+/// Repository id as stored in the `config` file of a local repository
+pub fn local_repo_id(path: &std::path::Path) -> Option<crate::borg::RepoId> {
+    let content = std::fs::read_to_string(path.join("config")).ok()?;
+
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "id").then(|| crate::borg::RepoId::new(value.trim().to_string()))
+    })
+}
+
 pub fn cache_dir() -> std::path::PathBuf {
     [glib::user_cache_dir(), env!("CARGO_PKG_NAME").into()]
         .iter()
@@ -288,23 +309,9 @@ pub async fn folder_chooser_dialog(
     title: &str,
     initial_folder: Option<&gio::File>,
 ) -> Result<gio::File> {
-    let dialog = gtk::FileDialog::builder()
-        .title(title)
-        .accept_label(gettext("Select"))
-        .modal(true)
-        .build();
-
-    dialog.set_initial_folder(Some(
-        initial_folder.unwrap_or(&gio::File::for_path(glib::home_dir())),
-    ));
-
-    dialog
-        .select_folder_future(Some(&main_ui().window()))
+    file_chooser::FileChooser::new(title, &gettext("Select"), initial_folder)
+        .select_folder(&main_ui().window())
         .await
-        .map_err(|err| match err.kind::<gtk::DialogError>() {
-            Some(gtk::DialogError::Cancelled | gtk::DialogError::Dismissed) => Error::UserCanceled,
-            _ => Message::short(err.to_string()).into(),
-        })
 }
 
 pub fn paths_from_model(model: Option<gio::ListModel>) -> Result<Vec<std::path::PathBuf>> {
@@ -325,6 +332,22 @@ pub fn paths_from_model(model: Option<gio::ListModel>) -> Result<Vec<std::path::
     }
 }
 
+/// Absolute paths or `file://` URIs, one per line
+pub fn paths_from_text(text: &str) -> Vec<std::path::PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            if line.starts_with("file://") {
+                gio::File::for_uri(line).path()
+            } else if line.starts_with('/') {
+                Some(std::path::PathBuf::from(line))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 fn ellipsize_multiline<S: std::fmt::Display>(x: S) -> String {
     let s = x.to_string();
     let vec = s.chars().collect::<Vec<_>>();
@@ -438,17 +461,6 @@ pub async fn show_error_transient_for(
     }
 }
 
-pub async fn confirmation_dialog(
-    title: &str,
-    message: &str,
-    cancel: &str,
-    accept: &str,
-) -> Result<()> {
-    ConfirmationDialog::new(title, message, cancel, accept)
-        .ask()
-        .await
-}
-
 pub struct ConfirmationDialog {
     title: String,
     message: String,
@@ -475,7 +487,7 @@ impl ConfirmationDialog {
 
     pub async fn ask(&self) -> Result<()> {
         let dialog = adw::MessageDialog::builder()
-            .transient_for(&main_ui().window())
+            .transient_for(&active_window())
             .modal(true)
             .heading(&self.title)
             .body(&self.message)
@@ -519,6 +531,20 @@ pub fn new_action_row_with_gicon(icon: Option<&gio::Icon>) -> adw::ActionRow {
     row
 }
 
+/// Show `widget` in the accent color of a backup config
+pub fn set_accent_color(widget: &impl IsA<gtk::Widget>, color: config::AccentColor) {
+    for class in config::AccentColor::VALUES
+        .iter()
+        .filter_map(config::AccentColor::css_class)
+    {
+        widget.remove_css_class(class);
+    }
+
+    if let Some(class) = color.css_class() {
+        widget.add_css_class(class);
+    }
+}
+
 pub trait Logable {
     fn handle<D: Display>(&self, msg: D);
 }
@@ -0,0 +1,191 @@
+//! Protect archives by keeping repositories append-only
+//!
+//! Archives deleted while the repository is append-only can still be
+//! recovered. The protection is only lifted while the user deletes archives
+//! manually, after confirming with the encryption password.
+//!
+//! A marker file is kept while the protection is lifted. If the app terminates
+//! before the protection is restored, it is restored on the next start.
+
+use std::future::Future;
+
+use crate::borg;
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+/// BorgBackup can only change the configuration of local repositories
+pub fn is_available(config: &config::Backup) -> bool {
+    config.repo.is_filesystem()
+}
+
+/// Deleting archives doesn't free any space since the repository is append-only
+pub fn is_protected(config: &config::Backup) -> bool {
+    config.append_only && is_available(config)
+}
+
+/// Explanation for scheduled deletions of old archives that have been skipped
+pub fn skipped_prune_message(config: &config::Backup) -> String {
+    gettextf(
+        "Old archives of “{}” have not been deleted since they are protected from deletion. Delete old archives manually to free space.",
+        &[&config.title()],
+    )
+}
+
+fn lifted_markers_dir() -> std::path::PathBuf {
+    ui::utils::cache_dir().join("append-only-lifted")
+}
+
+fn lifted_marker(config_id: &ConfigId) -> std::path::PathBuf {
+    lifted_markers_dir().join(config_id.as_str())
+}
+
+fn set_lifted_marker(config_id: &ConfigId, lifted: bool) -> std::io::Result<()> {
+    let marker = lifted_marker(config_id);
+
+    if lifted {
+        if let Some(dir) = marker.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(marker, "")
+    } else {
+        match std::fs::remove_file(marker) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+/// Restore the protection if the app terminated while it was lifted
+pub async fn restore_after_termination() {
+    let Ok(entries) = std::fs::read_dir(lifted_markers_dir()) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let config_id = ConfigId::new(entry.file_name().to_string_lossy().to_string());
+        let config = BACKUP_CONFIG.load().try_get(&config_id).ok().cloned();
+
+        if let Some(config) = config.filter(is_protected) {
+            warn!(
+                "Restoring append-only mode of {:?} after termination",
+                config_id
+            );
+            if let Err(err) = set(&config, true).await {
+                // The repository might be unavailable, try again on the next start
+                warn!("Failed to restore append-only mode: {}", err);
+                continue;
+            }
+        }
+
+        if let Err(err) = set_lifted_marker(&config_id, false) {
+            warn!("Failed to remove append-only marker: {}", err);
+        }
+    }
+}
+
+async fn set(config: &config::Backup, append_only: bool) -> Result<()> {
+    debug!("Setting append-only mode to {}", append_only);
+
+    borg::CommandOnlyRepo::new(config.repo.clone())
+        .set_append_only(append_only)
+        .await
+        .err_to_msg(gettext("Failed to Change Archive Protection"))
+}
+
+/// Ask for the encryption password, or for confirmation if not encrypted
+async fn confirm(config: &config::Backup) -> Result<()> {
+    if !config.encrypted {
+        return ui::confirm::confirm(
+            ui::confirm::Category::Critical,
+            &gettext("Lift Archive Protection?"),
+            &gettext("Archives deleted while the protection is lifted cannot be recovered."),
+            &gettext("Cancel"),
+            &gettext("Lift Protection"),
+        )
+        .await;
+    }
+
+    let mut failed_attempts = 0;
+
+    loop {
+        let password = ui::utils::password_storage::password_dialog(
+            config.repo.clone(),
+            gettext("Lifting Archive Protection"),
+            None,
+            failed_attempts,
+            None,
+        )
+        .await
+        .ok_or(Error::UserCanceled)?;
+
+        let mut command = borg::CommandOnlyRepo::new(config.repo.clone());
+        command.password = Some(password);
+
+        match command.peek().await {
+            Ok(_) => return Ok(()),
+            Err(borg::Error::Failed(borg::Failure::PassphraseWrong)) => failed_attempts += 1,
+            Err(err) => {
+                return Err(
+                    Message::new(gettext("Failed to Verify Encryption Password"), err).into(),
+                )
+            }
+        }
+    }
+}
+
+/// Apply the protection setting of the backup to the repository
+///
+/// Turning the protection off has to be confirmed. If the confirmation fails,
+/// the setting is switched back on.
+pub async fn configure(config: &config::Backup) -> Result<()> {
+    if !is_available(config) {
+        return Ok(());
+    }
+
+    if !config.append_only {
+        if let Err(err) = confirm(config).await {
+            BACKUP_CONFIG.try_update(|settings| {
+                settings.try_get_mut(&config.id)?.append_only = true;
+                Ok(())
+            })?;
+
+            return Err(err);
+        }
+    }
+
+    set(config, config.append_only).await
+}
+
+/// Run `operation` with the protection lifted if it's enabled
+///
+/// The protection is restored afterwards, even if the operation failed.
+pub async fn lifted<T>(
+    config: &config::Backup,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    if !config.append_only || !is_available(config) {
+        return operation.await;
+    }
+
+    confirm(config).await?;
+    set_lifted_marker(&config.id, true)
+        .err_to_msg(gettext("Failed to Change Archive Protection"))?;
+    set(config, false).await?;
+
+    let result = operation.await;
+
+    match set(config, true).await {
+        Ok(()) => {
+            if let Err(err) = set_lifted_marker(&config.id, false) {
+                warn!("Failed to remove append-only marker: {}", err);
+            }
+            result
+        }
+        Err(err) if result.is_ok() => Err(err),
+        Err(err) => {
+            error!("Failed to restore append-only mode: {}", err);
+            result
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Optional authentication before backed up files are browsed or restored
+
+use crate::ui::prelude::*;
+use crate::utils::polkit;
+
+/// Ask for the password of the user if enabled in the preferences
+///
+/// Polkit keeps the authorization for a few minutes, such that consecutive
+/// operations don't ask again.
+pub async fn require() -> Result<()> {
+    let required = crate::utils::app_settings()
+        .is_some_and(|settings| settings.boolean("require-authentication"));
+
+    if !required {
+        return Ok(());
+    }
+
+    match polkit::check_authorization(&polkit::authenticate_action_id()).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::UserCanceled),
+        Err(err) => Err(Message::new(
+            gettext("Authentication Not Available"),
+            format!(
+                "{}\n\n{}",
+                gettext("The password can not be requested. Backed up files can be accessed again once the authentication service is available."),
+                err
+            ),
+        )
+        .into()),
+    }
+}
@@ -5,11 +5,18 @@ use crate::ui::prelude::*;
 use crate::borg;
 use crate::ui;
 use borg::task::Task;
+use futures::channel::oneshot;
 use gio::traits::DriveExt;
 use gio::traits::VolumeExt;
+use std::cell::RefCell;
 use std::future::Future;
 use ui::error::Combined;
 
+thread_local!(
+    /// Queued operations waiting for an operation or a queue entry to be removed
+    static REPOSITORY_WAITERS: RefCell<Vec<oneshot::Sender<()>>> = Default::default();
+);
+
 /// Is a borg operation registered with a [QuitGuard]]?
 pub fn is_borg_operation_running() -> bool {
     STATUS_TRACKING.with(|status| status.quit_inhibit_count() > 0)
@@ -40,27 +47,61 @@ where
 {
     let config_id = command.config.id.clone();
 
+    if T::KIND.deletes_data() && command.from_schedule.is_some() {
+        cleanup_mounts().await?;
+
+        // Don't pull mounted archives away from under the user without asking
+        if ACTIVE_MOUNTS.load().contains(&command.config.repo_id) {
+            info!(
+                "Postponing scheduled {:?} since the repository is mounted",
+                T::KIND
+            );
+            return Err(borg::Error::Aborted(borg::Abort::RepositoryMounted).into());
+        }
+    }
+
     if T::KIND != borg::task::Kind::Mount {
         // If a repository is mounted we ask to unmount it before we continue
         ask_unmount(T::KIND, &command.config.repo_id).await?;
     }
 
     BORG_OPERATION.with(enclose!((command) move |operations| {
-        if let Some(operation) = operations
-            .load()
-            .values()
-            .find(|x| x.repo_id() == &command.config.repo_id)
-        {
+        if let Some(operation) = operations.load().get(&command.config.id) {
             return Err(Combined::Ui(
-                Message::new(gettext("Repository already in use"), operation.name()).into(),
+                Message::new(gettext("Backup already in use"), operation.name()).into(),
             ));
         }
 
-        ui::operation::Operation::register(command);
+        if let Some(name) = REPOSITORY_QUEUE.load().get(&command.config.id) {
+            return Err(Combined::Ui(
+                Message::new(gettext("Backup already in use"), name).into(),
+            ));
+        }
 
         Ok(())
     }))?;
 
+    wait_for_repository(&command.config, &T::name()).await?;
+
+    // Operations started by other processes, like another instance of the app
+    let _lock = match borg::operation_lock::OperationLock::try_acquire(
+        &command.config.repo_id,
+        &config_id,
+        &T::name(),
+    ) {
+        Ok(lock) => lock,
+        Err(holder) => {
+            let text = operation_in_background_text(&holder);
+            ui::remote_operation::attach(holder);
+
+            return Err(Combined::Ui(
+                Message::new(gettext("Operation Already Running in Background"), text).into(),
+            ));
+        }
+    };
+
+    ui::operation::Operation::register(command.clone());
+
     BACKUP_HISTORY.try_update(enclose!((config_id) move |history| {
         history.set_running(config_id.clone());
         Ok(())
@@ -72,11 +113,14 @@ where
                 op.remove(&config_id);
             });
         }));
+        wake_queued();
 
-        Handler::handle(BACKUP_HISTORY.try_update(move |history| {
+        Handler::handle(BACKUP_HISTORY.try_update(enclose!((config_id) move |history| {
             history.remove_running(config_id.clone());
             Ok(())
-        }));
+        })));
+
+        ui::operation_status::refresh(&config_id);
     };
 
     let mounted_result =
@@ -92,7 +136,88 @@ where
         }
     }
 
-    spawn_borg_thread_ask_password(command).await
+    let repo_id = command.config.repo_id.clone();
+    let result = spawn_borg_thread_ask_password(command).await;
+
+    if T::KIND.modifies_repository() {
+        ui::utils::repo_cache::RepoCache::invalidate(&repo_id);
+    }
+
+    result
+}
+
+/// Wait until operations of other backups on the same repository are completed
+///
+/// Aborting the operation of the backup removes it from the queue.
+async fn wait_for_repository(config: &crate::config::Backup, name: &str) -> CombinedResult<()> {
+    let in_use = || {
+        BORG_OPERATION.with(|operations| {
+            operations
+                .load()
+                .values()
+                .any(|operation| operation.repo_id() == &config.repo_id)
+        })
+    };
+
+    if !in_use() {
+        return Ok(());
+    }
+
+    info!("Queuing {name:?} until the repository is no longer in use");
+    REPOSITORY_QUEUE.update(|queue| {
+        queue.insert(config.id.clone(), name.to_string());
+    });
+    refresh_queue_status(&config.id);
+
+    let result = loop {
+        let (sender, receiver) = oneshot::channel();
+        REPOSITORY_WAITERS.with(|waiters| waiters.borrow_mut().push(sender));
+        // Only fails if the waiters are dropped, checking the state again is fine then
+        let _ = receiver.await;
+
+        if !REPOSITORY_QUEUE.load().contains_key(&config.id) {
+            break Err(borg::Error::Aborted(borg::Abort::User).into());
+        } else if !in_use() {
+            break Ok(());
+        }
+    };
+
+    REPOSITORY_QUEUE.update(|queue| {
+        queue.remove(&config.id);
+    });
+    refresh_queue_status(&config.id);
+
+    result
+}
+
+/// Remove a queued operation of the backup, returns whether one was queued
+pub fn dequeue(config_id: &ConfigId) -> bool {
+    let queued = REPOSITORY_QUEUE.load().contains_key(config_id);
+
+    if queued {
+        info!("Removing queued operation of {config_id:?}");
+        REPOSITORY_QUEUE.update(|queue| {
+            queue.remove(config_id);
+        });
+        refresh_queue_status(config_id);
+        wake_queued();
+    }
+
+    queued
+}
+
+/// Let queued operations check again whether they can start or were removed
+fn wake_queued() {
+    for waiter in REPOSITORY_WAITERS.with(|waiters| waiters.take()) {
+        let _ = waiter.send(());
+    }
+}
+
+fn refresh_queue_status(config_id: &ConfigId) {
+    ui::operation_status::refresh(config_id);
+    ui::page_backup::refresh_status();
+    ui::page_archives::refresh_status();
+    ui::page_overview::refresh_status();
 }
 
 pub async fn exec_repo_only<P: core::fmt::Display, F, R, V>(
@@ -109,6 +234,71 @@ where
     spawn_borg_thread(name, borg, task).await
 }
 
+/// Executes a borg command for a configuration that is not stored
+///
+/// The command is not registered as an operation of a backup and passwords are
+/// neither asked for nor stored. It still respects operations on the same
+/// repository, for example if the repository is also set up as a backup.
+pub async fn exec_unstored<T: Task>(command: borg::Command<T>) -> CombinedResult<T::Return>
+where
+    borg::Command<T>: borg::CommandRun<T>,
+{
+    let repo_id = command.config.repo_id.clone();
+
+    let repo_in_use = BORG_OPERATION.with(|operations| {
+        operations
+            .load()
+            .values()
+            .any(|operation| operation.repo_id() == &repo_id)
+    });
+
+    if repo_in_use {
+        return Err(Combined::Ui(
+            Message::new(
+                gettext("Repository already in use"),
+                gettext("Another operation is running on this repository. Try again once it is completed."),
+            )
+            .into(),
+        ));
+    }
+
+    let _lock = match borg::operation_lock::OperationLock::try_acquire(
+        &repo_id,
+        &command.config.id,
+        &T::name(),
+    ) {
+        Ok(lock) => lock,
+        Err(holder) => {
+            return Err(Combined::Ui(
+                Message::new(
+                    gettext("Operation Already Running in Background"),
+                    operation_in_background_text(&holder),
+                )
+                .into(),
+            ));
+        }
+    };
+
+    spawn_borg_thread(T::name(), command, |x| x.run()).await
+}
+
+fn operation_in_background_text(holder: &borg::operation_lock::Holder) -> String {
+    let title = BACKUP_CONFIG
+        .load()
+        .try_get(&holder.config_id)
+        .map(|config| config.title())
+        .unwrap_or_default();
+
+    if holder.operation.is_empty() || title.is_empty() {
+        gettext("Another process is already running an operation on this repository.")
+    } else {
+        gettextf(
+            "“{}” of “{}” has been started by another process. Its progress is shown in the backup details.",
+            &[&holder.operation, &title],
+        )
+    }
+}
+
 async fn ask_unmount(kind: task::Kind, repo_id: &RepoId) -> Result<()> {
     crate::ui::utils::borg::cleanup_mounts().await?;
 
@@ -117,7 +307,8 @@ async fn ask_unmount(kind: task::Kind, repo_id: &RepoId) -> Result<()> {
 
         match kind {
             task::Kind::Create => {
-                ui::utils::confirmation_dialog(
+                ui::confirm::confirm(
+                    ui::confirm::Category::Stop,
                     &gettext("Stop browsing files and start backup?"),
                     &gettext(
                         "Browsing through archived files is not possible while running a backup.",
@@ -127,8 +318,22 @@ async fn ask_unmount(kind: task::Kind, repo_id: &RepoId) -> Result<()> {
                 )
                 .await?;
             }
+            kind if kind.deletes_data() => {
+                // Deleting archives can't be undone, even if stop confirmations are disabled
+                ui::confirm::confirm(
+                    ui::confirm::Category::Critical,
+                    &gettext("Stop browsing files and delete archives?"),
+                    &gettext(
+                        "Browsed archives might be deleted by the operation. Open files and folders from archives will become unavailable.",
+                    ),
+                    &gettext("Keep Browsing"),
+                    &gettext("Continue"),
+                )
+                .await?;
+            }
             _ => {
-                ui::utils::confirmation_dialog(
+                ui::confirm::confirm(
+                    ui::confirm::Category::Stop,
                     &gettext("Stop browsing files and start operation?"),
                     &gettext(
                         "Browsing through archived files is not possible while running an operation on the repository.",
@@ -153,13 +358,40 @@ async fn spawn_borg_thread_ask_password<C: 'static + borg::CommandRun<T>, T: Tas
     mut command: C,
 ) -> CombinedResult<T::Return> {
     let mut password_changed = false;
+    let mut unlock_requested = false;
+    let mut failed_attempts = 0;
 
     loop {
         let result = spawn_borg_thread(T::name(), command.clone(), |x| x.run()).await;
 
         return match result {
+            Err(Combined::Borg(borg::Error::PasswordKeyringLocked))
+                if !ui::app_window::is_displayed() =>
+            {
+                // Nobody is there to unlock the keyring or enter the password
+                Err(borg::Error::Aborted(borg::Abort::KeyringLocked).into())
+            }
+            Err(Combined::Borg(borg::Error::PasswordKeyringLocked)) if !unlock_requested => {
+                unlock_requested = true;
+
+                if let Err(err) = ui::utils::password_storage::unlock_keyring().await {
+                    warn!("Failed to unlock keyring: {err:?}");
+                }
+
+                continue;
+            }
             Err(Combined::Borg(borg::Error::PasswordMissing { .. }))
+            | Err(Combined::Borg(borg::Error::PasswordKeyringLocked))
             | Err(Combined::Borg(borg::Error::Failed(borg::Failure::PassphraseWrong))) => {
+                if matches!(
+                    result,
+                    Err(Combined::Borg(borg::Error::Failed(
+                        borg::Failure::PassphraseWrong
+                    )))
+                ) {
+                    failed_attempts += 1;
+                }
+
                 let keyring_error =
                     if let Err(Combined::Borg(borg::Error::PasswordMissing { keyring_error })) =
                         result
@@ -173,6 +405,8 @@ async fn spawn_borg_thread_ask_password<C: 'static + borg::CommandRun<T>, T: Tas
                     command.repo(),
                     T::name(),
                     keyring_error,
+                    failed_attempts,
+                    command.try_config(),
                 )
                 .await
                 {
@@ -189,7 +423,14 @@ async fn spawn_borg_thread_ask_password<C: 'static + borg::CommandRun<T>, T: Tas
                     if let (Some(password), Some(config)) =
                         (&command.password(), &command.try_config())
                     {
-                        if let Err(Error::Message(err)) =
+                        let uses_command = BACKUP_CONFIG
+                            .load()
+                            .try_get(&config.id)
+                            .is_ok_and(|x| x.password_command.is_some());
+
+                        if uses_command {
+                            debug!("Not storing password, it is obtained from a command");
+                        } else if let Err(Error::Message(err)) =
                             crate::ui::utils::password_storage::store_password(config, password)
                                 .await
                         {
@@ -1,49 +1,45 @@
+use adw::prelude::*;
+
 use crate::config;
 use crate::config::Loadable;
 use crate::ui::prelude::*;
 use config::ArcSwapWriteable;
 
-fn load_config_e() -> std::io::Result<()> {
-    if glib::user_config_dir()
-        .join(env!("CARGO_PKG_NAME"))
-        .join("config.json")
-        .is_file()
-        && !glib::user_config_dir()
-            .join(env!("CARGO_PKG_NAME"))
-            .join("backup.json")
-            .is_file()
-    {
-        std::fs::rename(
-            glib::user_config_dir()
-                .join(env!("CARGO_PKG_NAME"))
-                .join("config.json"),
-            glib::user_config_dir()
-                .join(env!("CARGO_PKG_NAME"))
-                .join("backup.json"),
-        )?;
-    }
+fn load_config_e() -> std::io::Result<Vec<String>> {
+    let changes = config::migration::run()?;
 
     BACKUP_CONFIG.swap(Arc::new(config::Writeable::from_file()?));
-    BACKUP_CONFIG.update_no_commit(|backups| {
-        for config in backups.iter_mut() {
-            if config.config_version.0 < config::VERSION {
-                config.config_version = Default::default();
-            }
-        }
-    });
-    // potentially write generated default value
-    BACKUP_CONFIG.write_file()?;
+    BACKUP_TRASH.swap(Arc::new(config::Writeable::from_file()?));
 
+    config::history::journal::compact()?;
     BACKUP_HISTORY.swap(Arc::new(config::Histories::from_file_ui()?));
     // potentially write internal error status
     BACKUP_HISTORY.write_file()?;
 
-    Ok(())
+    Ok(changes)
 }
 
 pub fn load_config() {
-    let res = load_config_e().err_to_msg(gettext("Could not load configuration file."));
-    if let Err(err) = res {
-        glib::MainContext::default().spawn_local(async move { err.show().await });
+    match load_config_e().err_to_msg(gettext("Could not load configuration file.")) {
+        Ok(changes) if !changes.is_empty() => {
+            glib::MainContext::default().spawn_local(async move { show_changes(&changes).await });
+        }
+        Ok(_) => {}
+        Err(err) => {
+            glib::MainContext::default().spawn_local(async move { err.show().await });
+        }
     }
 }
+
+/// Inform about the changes made by the migrations
+async fn show_changes(changes: &[String]) {
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(&main_ui().window())
+        .modal(true)
+        .heading(gettext("Backup Configuration Updated"))
+        .body(changes.join("\n"))
+        .build();
+
+    dialog.add_responses(&[("close", &gettext("Close"))]);
+    dialog.choose_future().await;
+}
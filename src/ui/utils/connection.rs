@@ -0,0 +1,149 @@
+//! Diagnose the connection to remote repositories
+//!
+//! Every step only gives a meaningful result if the previous one passed.
+
+use async_std::process;
+
+use crate::borg;
+use crate::config;
+use crate::ui::prelude::*;
+
+/// Seconds after which an unresponsive server is reported as unreachable
+const CONNECT_TIMEOUT: u32 = 15;
+
+/// Exit status of `ssh` if the connection itself failed
+const SSH_ERROR_STATUS: i32 = 255;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Passed,
+    Failed(String),
+    /// Not verified because an earlier step failed or the step is not applicable
+    Skipped,
+}
+
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// The SSH server accepts the connection without asking for a password
+    pub ssh: Check,
+    /// BorgBackup on the server can open the repository
+    pub borg: Check,
+    /// No other operation is holding the repository lock
+    pub lock: Check,
+}
+
+/// Arguments for `ssh` to connect to the server of an `ssh://` URL
+fn ssh_args(uri: &str, host_key_policy: config::remote::HostKeyPolicy) -> Option<Vec<String>> {
+    let uri = glib::Uri::parse(uri, glib::UriFlags::NONE).ok()?;
+    if uri.scheme() != "ssh" {
+        return None;
+    }
+
+    let mut args = vec!["-o".to_string(), "BatchMode=yes".to_string()];
+    args.extend(host_key_policy.ssh_options().into_iter().map(String::from));
+    args.push("-o".to_string());
+    args.push(format!("ConnectTimeout={CONNECT_TIMEOUT}"));
+
+    if uri.port() > 0 {
+        args.push("-p".to_string());
+        args.push(uri.port().to_string());
+    }
+
+    if let Some(user) = uri.userinfo() {
+        args.push("-l".to_string());
+        args.push(user.to_string());
+    }
+
+    args.push(uri.host()?.to_string());
+
+    Some(args)
+}
+
+async fn check_ssh(args: &[String]) -> Check {
+    // Servers that restrict the key to `borg serve` will run that instead of `true`
+    let output = process::Command::new("ssh")
+        .args(args)
+        .arg("true")
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::piped())
+        .output()
+        .await;
+
+    match output {
+        Err(err) => Check::Failed(err.to_string()),
+        Ok(output) if output.status.code() != Some(SSH_ERROR_STATUS) => Check::Passed,
+        Ok(output) => Check::Failed(
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .last()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| gettext("Connection failed.")),
+        ),
+    }
+}
+
+/// Verify that the remote repository is reachable and not locked
+pub async fn verify(repo: &config::Repository) -> Report {
+    let ssh = match ssh_args(
+        &repo.to_string(),
+        repo.host_key_policy().unwrap_or_default(),
+    ) {
+        Some(args) => check_ssh(&args).await,
+        None => Check::Skipped,
+    };
+
+    if matches!(ssh, Check::Failed(_)) {
+        return Report {
+            ssh,
+            borg: Check::Skipped,
+            lock: Check::Skipped,
+        };
+    }
+
+    let (borg, lock) = match borg::CommandOnlyRepo::new(repo.clone()).try_lock().await {
+        Ok(()) => (Check::Passed, Check::Passed),
+        Err(borg::Error::Failed(borg::Failure::LockTimeout)) => (
+            Check::Passed,
+            Check::Failed(gettext(
+                "The repository is locked by another operation. If no operation is running, the lock might be left over from an aborted backup.",
+            )),
+        ),
+        Err(err) => (Check::Failed(err.to_string()), Check::Skipped),
+    };
+
+    Report { ssh, borg, lock }
+}
+
+#[test]
+fn test_ssh_args() {
+    let policy = config::remote::HostKeyPolicy::AcceptNew;
+
+    assert_eq!(
+        ssh_args("ssh://borg@nas.local:2222/~/backup", policy).unwrap()[6..],
+        ["-p", "2222", "-l", "borg", "nas.local"]
+    );
+    assert_eq!(
+        ssh_args("ssh://example.org/./repo", policy).unwrap()[6..],
+        ["example.org"]
+    );
+    assert_eq!(ssh_args("sftp://example.org/repo", policy), None);
+    assert_eq!(ssh_args("/media/backup", policy), None);
+    assert_eq!(
+        ssh_args(
+            "ssh://example.org/./repo",
+            config::remote::HostKeyPolicy::Off
+        )
+        .unwrap()[..6],
+        [
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "StrictHostKeyChecking=no",
+            "-o",
+            "UserKnownHostsFile=/dev/null"
+        ]
+    );
+}
@@ -24,10 +24,10 @@ pub async fn cached_or_lookup(config: &config::Backup) -> Option<Space> {
             }
         }
         config::Repository::Remote(_) => {
-            if cached.is_some() {
+            if cached.is_some() && !RepoCache::get(&config.repo_id).space_outdated() {
                 cached
             } else {
-                lookup_and_cache(config).await.ok()
+                lookup_and_cache(config).await.ok().or(cached)
             }
         }
     }
@@ -40,10 +40,12 @@ pub async fn lookup_and_cache(config: &config::Backup) -> Result<Space> {
     }?;
 
     REPO_CACHE.update(enclose!((config, space) move |cache| {
-        cache
+        let repo_cache = cache
             .entry(config.repo_id.clone())
-            .or_insert_with_key(RepoCache::new)
-            .space = Some(space.clone());
+            .or_insert_with_key(RepoCache::new);
+
+        repo_cache.space = Some(space.clone());
+        repo_cache.space_updated = Some(chrono::Local::now());
     }));
     let _ignore = RepoCache::write(&config.repo_id);
 
@@ -61,3 +61,34 @@ pub fn ago(d: &chrono::Duration) -> String {
         ngettextf_("One year ago", "{} years ago", (d.num_weeks() / 52) as u32)
     }
 }
+
+/// Block characters from lowest to highest bar
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Bar chart of `durations` in a single line of text, relative to the longest duration
+pub fn sparkline(durations: &[chrono::Duration]) -> String {
+    let max = durations
+        .iter()
+        .map(chrono::Duration::num_seconds)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    durations
+        .iter()
+        .map(|d| {
+            let level = d.num_seconds().clamp(0, max) * (SPARKLINE_BARS.len() as i64 - 1) / max;
+            SPARKLINE_BARS[level as usize]
+        })
+        .collect()
+}
+
+#[test]
+fn test_sparkline() {
+    assert_eq!(sparkline(&[]), "");
+    assert_eq!(
+        sparkline(&[0, 7, 14, 70].map(chrono::Duration::seconds)),
+        "▁▁▂█"
+    );
+    assert_eq!(sparkline(&[chrono::Duration::zero()]), "▁");
+}
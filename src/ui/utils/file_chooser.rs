@@ -0,0 +1,178 @@
+//! File choosers and file listings with a shared setting for hidden files
+//!
+//! `gtk::FileDialog` has no property for hidden files, therefore the choosers
+//! are built from `gtk::FileChooserDialog`. Its chooser widget loads the
+//! toggle (<kbd>Ctrl</kbd>+<kbd>H</kbd>) from the GTK settings when it is
+//! shown and stores it there when it is hidden. The app setting is applied to
+//! the widget after it has been shown and the toggle is read back before it is
+//! hidden, such that toggling hidden files in one chooser applies to all
+//! choosers and listings without changing the setting of other apps.
+#![allow(deprecated)]
+
+use std::cell::Cell;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::ui::prelude::*;
+use adw::prelude::*;
+
+const SHOW_HIDDEN_KEY: &str = "show-hidden-files";
+const WIDGET_SHOW_HIDDEN_PROPERTY: &str = "show-hidden";
+
+/// Whether hidden files and folders are shown
+pub fn show_hidden() -> bool {
+    crate::utils::app_settings().is_some_and(|settings| settings.boolean(SHOW_HIDDEN_KEY))
+}
+
+/// Whether the file or folder is hidden by its name
+pub fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.as_bytes().first() == Some(&b'.'))
+}
+
+/// Whether a file or folder should be shown in listings
+pub fn is_listed(path: &Path) -> bool {
+    show_hidden() || !is_hidden(path)
+}
+
+fn set_show_hidden(show_hidden: bool) {
+    if let Some(settings) = crate::utils::app_settings() {
+        if let Err(err) = settings.set_boolean(SHOW_HIDDEN_KEY, show_hidden) {
+            debug!("Failed to remember hidden files setting: {}", err);
+        }
+    }
+}
+
+/// The chooser widget inside of the dialog, if it knows about hidden files
+fn chooser_widget(widget: &gtk::Widget) -> Option<gtk::FileChooserWidget> {
+    if let Some(chooser) = widget.downcast_ref::<gtk::FileChooserWidget>() {
+        return chooser
+            .find_property(WIDGET_SHOW_HIDDEN_PROPERTY)
+            .map(|_| chooser.clone());
+    }
+
+    let mut child = widget.first_child();
+    while let Some(widget) = child {
+        if let Some(chooser) = chooser_widget(&widget) {
+            return Some(chooser);
+        }
+        child = widget.next_sibling();
+    }
+
+    None
+}
+
+/// A file chooser that applies the hidden files setting
+pub struct FileChooser {
+    title: String,
+    accept_label: String,
+    initial_folder: gio::File,
+}
+
+impl FileChooser {
+    pub fn new(title: &str, accept_label: &str, initial_folder: Option<&gio::File>) -> Self {
+        Self {
+            title: title.to_string(),
+            accept_label: accept_label.to_string(),
+            initial_folder: initial_folder
+                .cloned()
+                .unwrap_or_else(|| gio::File::for_path(glib::home_dir())),
+        }
+    }
+
+    async fn run(
+        &self,
+        parent: &impl IsA<gtk::Window>,
+        action: gtk::FileChooserAction,
+        select_multiple: bool,
+    ) -> Result<gio::ListModel> {
+        let dialog = gtk::FileChooserDialog::new(
+            Some(&self.title),
+            Some(parent),
+            action,
+            &[
+                (&gettext("Cancel"), gtk::ResponseType::Cancel),
+                (&self.accept_label, gtk::ResponseType::Accept),
+            ],
+        );
+        dialog.set_modal(true);
+        dialog.set_select_multiple(select_multiple);
+        dialog.set_default_response(gtk::ResponseType::Accept);
+        if let Err(err) = dialog.set_current_folder(Some(&self.initial_folder)) {
+            debug!("Failed to set initial folder: {}", err);
+        }
+
+        let chooser = chooser_widget(dialog.upcast_ref());
+        // Toggle from the GTK settings, written back when the chooser is hidden
+        let gtk_show_hidden = Rc::new(Cell::new(None));
+
+        dialog.connect_map(enclose!((chooser, gtk_show_hidden) move |_| {
+            if let Some(chooser) = &chooser {
+                gtk_show_hidden.set(Some(chooser.property::<bool>(WIDGET_SHOW_HIDDEN_PROPERTY)));
+                chooser.set_property(WIDGET_SHOW_HIDDEN_PROPERTY, show_hidden());
+            }
+        }));
+
+        let response = dialog.run_future().await;
+
+        if let Some(chooser) = &chooser {
+            set_show_hidden(chooser.property(WIDGET_SHOW_HIDDEN_PROPERTY));
+            if let Some(gtk_show_hidden) = gtk_show_hidden.get() {
+                chooser.set_property(WIDGET_SHOW_HIDDEN_PROPERTY, gtk_show_hidden);
+            }
+        }
+
+        let files = dialog.files();
+        dialog.destroy();
+
+        if response == gtk::ResponseType::Accept {
+            Ok(files)
+        } else {
+            Err(Error::UserCanceled)
+        }
+    }
+
+    async fn run_single(
+        &self,
+        parent: &impl IsA<gtk::Window>,
+        action: gtk::FileChooserAction,
+    ) -> Result<gio::File> {
+        self.run(parent, action, false)
+            .await?
+            .item(0)
+            .and_downcast::<gio::File>()
+            .ok_or(Error::UserCanceled)
+    }
+
+    pub async fn select_folder(&self, parent: &impl IsA<gtk::Window>) -> Result<gio::File> {
+        self.run_single(parent, gtk::FileChooserAction::SelectFolder)
+            .await
+    }
+
+    pub async fn select_folders(
+        &self,
+        parent: &impl IsA<gtk::Window>,
+    ) -> Result<Option<gio::ListModel>> {
+        self.run(parent, gtk::FileChooserAction::SelectFolder, true)
+            .await
+            .map(Some)
+    }
+
+    pub async fn open(&self, parent: &impl IsA<gtk::Window>) -> Result<gio::File> {
+        self.run_single(parent, gtk::FileChooserAction::Open).await
+    }
+
+    pub async fn open_multiple(&self, parent: &impl IsA<gtk::Window>) -> Result<gio::ListModel> {
+        self.run(parent, gtk::FileChooserAction::Open, true).await
+    }
+}
+
+#[test]
+fn test_is_hidden() {
+    assert!(is_hidden(Path::new("/home/user/.cache")));
+    assert!(is_hidden(Path::new(".config")));
+    assert!(!is_hidden(Path::new("/home/user/.cache/data")));
+    assert!(!is_hidden(Path::new("/home/user")));
+    assert!(!is_hidden(Path::new("/")));
+}
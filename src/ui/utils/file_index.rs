@@ -0,0 +1,702 @@
+//! Index of the files contained in archives
+//!
+//! Archives are indexed on request, which allows searching for files without
+//! mounting the archives. The files of every archive are stored in a separate
+//! JSON lines file and are only read one by one.
+
+use crate::ui::prelude::*;
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::borg;
+use crate::config;
+use crate::ui;
+use crate::ui::utils::repo_cache::RepoCache;
+
+/// Maximum number of matching files returned by a search
+pub const MAX_RESULTS: usize = 200;
+
+const ARCHIVES_FILE: &str = "archives.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexedArchive {
+    pub id: borg::ArchiveId,
+    pub start: chrono::naive::NaiveDateTime,
+    /// Number of files and folders
+    pub files: u64,
+}
+
+/// Indexed archives of one repository
+///
+/// Only the list of archives is kept in memory. The files are read from disk
+/// one by one via [`FileIndex::files`].
+#[derive(Debug, Clone, Default)]
+pub struct FileIndex {
+    dir: PathBuf,
+    pub archives: BTreeMap<borg::ArchiveName, IndexedArchive>,
+}
+
+/// Content of an archive that has not been added to the index yet
+struct PendingArchive {
+    file: tempfile::NamedTempFile,
+    files: u64,
+}
+
+/// Files from one archive that match a search
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub config_id: ConfigId,
+    pub archive_name: borg::ArchiveName,
+    pub start: chrono::naive::NaiveDateTime,
+    pub files: Vec<borg::ArchiveFile>,
+}
+
+/// State of a path in one archive
+#[derive(Debug, Clone)]
+pub struct FileVersion {
+    pub archive_name: borg::ArchiveName,
+    pub start: chrono::naive::NaiveDateTime,
+    /// `None` if the path does not exist in the archive
+    pub file: Option<borg::ArchiveFile>,
+}
+
+impl FileVersion {
+    /// Whether the path differs from the `previous` version
+    pub fn is_changed(&self, previous: &Self) -> bool {
+        match (&self.file, &previous.file) {
+            (Some(file), Some(previous)) => {
+                file.type_ != previous.type_
+                    || file.size != previous.size
+                    || file.mtime != previous.mtime
+            }
+            (None, None) => false,
+            _ => true,
+        }
+    }
+}
+
+/// File or folder directly below a folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// Files below one include root in an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootUsage {
+    pub root: PathBuf,
+    pub files: u64,
+    /// Original size, before deduplication and compression
+    pub size: u64,
+}
+
+impl FileIndex {
+    pub fn dir(repo_id: &borg::RepoId) -> std::path::PathBuf {
+        [
+            super::cache_dir(),
+            "file-index".into(),
+            repo_id.as_str().into(),
+        ]
+        .iter()
+        .collect()
+    }
+
+    pub fn load(repo_id: &borg::RepoId) -> Self {
+        Self::load_from(Self::dir(repo_id))
+    }
+
+    fn load_from(dir: PathBuf) -> Self {
+        let archives = std::fs::File::open(dir.join(ARCHIVES_FILE))
+            .ok()
+            .and_then(|f| serde_json::from_reader(std::io::BufReader::new(f)).ok())
+            .unwrap_or_default();
+
+        Self { dir, archives }
+    }
+
+    fn write(&self) -> Result<()> {
+        let mut file = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| tempfile::NamedTempFile::new_in(&self.dir))
+            .err_to_msg(gettext("Failed to Save Cache"))?;
+
+        serde_json::ser::to_writer(std::io::BufWriter::new(&mut file), &self.archives)
+            .err_to_msg(gettext("Failed to Save Cache"))?;
+        file.persist(self.dir.join(ARCHIVES_FILE))
+            .err_to_msg(gettext("Failed to Save Cache"))?;
+
+        Ok(())
+    }
+
+    fn files_path(&self, archive: &IndexedArchive) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", archive.id.as_str()))
+    }
+
+    /// Write the content of an archive to a temporary file
+    ///
+    /// The files are written one by one, they are never all kept in memory.
+    fn write_files(
+        &self,
+        files: impl IntoIterator<Item = borg::ArchiveFile>,
+    ) -> Result<PendingArchive> {
+        let file = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| tempfile::NamedTempFile::new_in(&self.dir))
+            .err_to_msg(gettext("Failed to Save Cache"))?;
+
+        let mut writer = std::io::BufWriter::new(file.as_file());
+        let mut count = 0;
+
+        for entry in files {
+            serde_json::ser::to_writer(&mut writer, &entry)
+                .err_to_msg(gettext("Failed to Save Cache"))?;
+            writer
+                .write_all(b"\n")
+                .err_to_msg(gettext("Failed to Save Cache"))?;
+            count += 1;
+        }
+
+        writer.flush().err_to_msg(gettext("Failed to Save Cache"))?;
+        drop(writer);
+
+        Ok(PendingArchive { file, files: count })
+    }
+
+    /// Add an archive whose content has been written completely
+    fn insert(&mut self, archive: &borg::ListArchive, pending: PendingArchive) -> Result<()> {
+        let indexed = IndexedArchive {
+            id: archive.id.clone(),
+            start: archive.start,
+            files: pending.files,
+        };
+
+        pending
+            .file
+            .persist(self.files_path(&indexed))
+            .err_to_msg(gettext("Failed to Save Cache"))?;
+        self.archives.insert(archive.name.clone(), indexed);
+
+        self.write()
+    }
+
+    /// Read the files of an indexed archive one by one
+    pub fn files(&self, archive: &IndexedArchive) -> impl Iterator<Item = borg::ArchiveFile> {
+        std::fs::File::open(self.files_path(archive))
+            .map(std::io::BufReader::new)
+            .into_iter()
+            .flat_map(|reader| reader.lines().map_while(std::io::Result::ok))
+            .filter_map(|line| serde_json::from_str(&line).ok())
+    }
+
+    /// Indexed archives created by `config`
+    fn archives_of<'a>(
+        &'a self,
+        config: &'a config::Backup,
+    ) -> impl Iterator<Item = (&'a borg::ArchiveName, &'a IndexedArchive)> {
+        self.archives
+            .iter()
+            .filter(|(archive_name, _)| archive_name.as_str().starts_with(&config.archive_prefix.0))
+    }
+
+    /// Forget archives that no longer exist in the repository
+    ///
+    /// Archives older than the oldest cached archive are kept since the archive
+    /// cache only contains the most recent archives.
+    fn retain_existing(&mut self, repo_cache: &RepoCache) -> Result<()> {
+        let Some(archives) = &repo_cache.archives else {
+            return Ok(());
+        };
+
+        let oldest = archives.values().map(|x| x.start).min();
+
+        let removed: Vec<_> = self
+            .archives
+            .iter()
+            .filter(|(name, archive)| {
+                !archives.contains_key(*name)
+                    && !oldest.is_some_and(|oldest| archive.start < oldest)
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in removed {
+            if let Some(archive) = self.archives.remove(&name) {
+                let _ignore = std::fs::remove_file(self.files_path(&archive));
+            }
+        }
+
+        self.write()
+    }
+}
+
+/// Archives of `config` from the archive list that have not been indexed yet
+///
+/// The newest archives come first.
+pub fn missing(config: &config::Backup, index: &FileIndex) -> Vec<borg::ListArchive> {
+    let mut missing: Vec<_> = RepoCache::get(&config.repo_id)
+        .archives
+        .unwrap_or_default()
+        .into_values()
+        .filter(|archive| {
+            archive.name.as_str().starts_with(&config.archive_prefix.0)
+                && !index.archives.contains_key(&archive.name)
+        })
+        .collect();
+
+    missing.sort_by(|x, y| y.start.cmp(&x.start));
+    missing
+}
+
+/// Add the content of an archive to the index
+pub async fn update(
+    config: &config::Backup,
+    archive: &borg::ListArchive,
+    guard: &QuitGuard,
+) -> Result<()> {
+    let mut command = borg::Command::<borg::task::ListFiles>::new(config.clone());
+    command.task.set_archive_name(Some(archive.name.clone()));
+    let entries = command.task.entries();
+
+    // Entries are written while they are listed
+    let repo_id = config.repo_id.clone();
+    let write = ui::utils::spawn_thread("file_index_write", move || {
+        FileIndex::load(&repo_id).write_files(std::iter::from_fn(|| {
+            async_std::task::block_on(entries.recv()).ok()
+        }))
+    });
+
+    let (result, pending) = futures::join!(ui::utils::borg::exec(command, guard), write);
+    result.into_message(gettext("Failed to index archive content."))?;
+    let pending = pending??;
+
+    let repo_id = config.repo_id.clone();
+    let repo_cache = RepoCache::get(&repo_id);
+    let archive = archive.clone();
+
+    ui::utils::spawn_thread("file_index_update", move || {
+        let mut index = FileIndex::load(&repo_id);
+        index.insert(&archive, pending)?;
+        index.retain_existing(&repo_cache)
+    })
+    .await?
+}
+
+/// Index all archives of `config` that have not been indexed yet
+///
+/// `progress` is called with the number of the archive that is indexed next
+/// and the total number of archives to index.
+pub async fn update_missing(
+    config: &config::Backup,
+    progress: impl Fn(usize, usize),
+) -> Result<()> {
+    let guard = QuitGuard::default();
+
+    if RepoCache::get(&config.repo_id).archives.is_none() {
+        ui::page_archives::cache::refresh_archives(config.clone(), None).await?;
+    }
+
+    let index = FileIndex::load(&config.repo_id);
+    let missing = missing(config, &index);
+
+    for (i, archive) in missing.iter().enumerate() {
+        progress(i + 1, missing.len());
+        update(config, archive, &guard).await?;
+    }
+
+    Ok(())
+}
+
+/// Load the indices of all repositories used by `configs`
+///
+/// Only the lists of indexed archives are loaded.
+pub fn load_all(configs: &config::Backups) -> BTreeMap<borg::RepoId, FileIndex> {
+    configs
+        .iter()
+        .map(|config| (config.repo_id.clone(), FileIndex::load(&config.repo_id)))
+        .collect()
+}
+
+/// Find files whose path contains `query`, ignoring case
+///
+/// The results are grouped by archive, with the newest archives first. The
+/// search stops after [`MAX_RESULTS`] matching files.
+pub fn search(
+    configs: &config::Backups,
+    indices: &BTreeMap<borg::RepoId, FileIndex>,
+    query: &str,
+) -> Vec<SearchResult> {
+    let query = query.trim().to_lowercase();
+    let mut results = Vec::new();
+
+    if query.is_empty() {
+        return results;
+    }
+
+    let mut archives: Vec<_> = configs
+        .iter()
+        .filter_map(|config| Some((config, indices.get(&config.repo_id)?)))
+        .flat_map(|(config, index)| {
+            index
+                .archives_of(config)
+                .map(move |(archive_name, archive)| (config, index, archive_name, archive))
+        })
+        .collect();
+    archives.sort_by(|x, y| y.3.start.cmp(&x.3.start));
+
+    let mut remaining = MAX_RESULTS;
+
+    for (config, index, archive_name, archive) in archives {
+        if remaining == 0 {
+            break;
+        }
+
+        let files: Vec<_> = index
+            .files(archive)
+            .filter(|file| file.path.to_lowercase().contains(&query))
+            .take(remaining)
+            .collect();
+
+        if !files.is_empty() {
+            remaining -= files.len();
+            results.push(SearchResult {
+                config_id: config.id.clone(),
+                archive_name: archive_name.clone(),
+                start: archive.start,
+                files,
+            });
+        }
+    }
+
+    results
+}
+
+/// The state of `path` in all indexed archives of `config`, oldest first
+///
+/// `path` is absolute, like the paths that are backed up.
+pub fn versions(config: &config::Backup, index: &FileIndex, path: &Path) -> Vec<FileVersion> {
+    let path = path.strip_prefix("/").unwrap_or(path).to_string_lossy();
+
+    let mut versions: Vec<_> = index
+        .archives_of(config)
+        .map(|(archive_name, archive)| FileVersion {
+            archive_name: archive_name.clone(),
+            start: archive.start,
+            file: index.files(archive).find(|file| file.path == path),
+        })
+        .collect();
+
+    versions.sort_by_key(|version| version.start);
+    versions
+}
+
+/// Files and folders directly below `dir` in any indexed archive of `config`
+///
+/// Files and folders that only exist in older archives are included. Folders
+/// that lead to backed up locations are included even if they are not backed
+/// up themselves. Folders are listed first.
+pub fn children(config: &config::Backup, index: &FileIndex, dir: &Path) -> Vec<IndexedEntry> {
+    let mut children: BTreeMap<PathBuf, bool> = BTreeMap::new();
+
+    for (_, archive) in index.archives_of(config) {
+        for file in index.files(archive) {
+            let path = Path::new("/").join(&file.path);
+            let Some(first) = path
+                .strip_prefix(dir)
+                .ok()
+                .and_then(|relative| relative.components().next())
+            else {
+                continue;
+            };
+
+            let child = dir.join(first);
+            let is_dir = child != path || file.is_dir();
+            *children.entry(child).or_default() |= is_dir;
+        }
+    }
+
+    let mut children: Vec<_> = children
+        .into_iter()
+        .map(|(path, is_dir)| IndexedEntry { path, is_dir })
+        .collect();
+    children.sort_by_key(|entry| !entry.is_dir);
+
+    children
+}
+
+/// Files and size below every include root in the latest indexed archive of `config`
+///
+/// Files below nested include roots count for the innermost root. The roots are
+/// sorted by size, largest first.
+pub fn usage_by_root(
+    config: &config::Backup,
+    index: &FileIndex,
+) -> Option<(chrono::naive::NaiveDateTime, Vec<RootUsage>)> {
+    let archive = index
+        .archives_of(config)
+        .map(|(_, archive)| archive)
+        .max_by_key(|archive| archive.start)?;
+
+    let mut usage: Vec<_> = config
+        .include_dirs()
+        .into_iter()
+        .map(|root| RootUsage {
+            root,
+            files: 0,
+            size: 0,
+        })
+        .collect();
+
+    // Innermost roots first
+    usage.sort_by_key(|x| std::cmp::Reverse(x.root.components().count()));
+
+    for file in index.files(archive).filter(|file| !file.is_dir()) {
+        let path = Path::new("/").join(&file.path);
+        if let Some(root) = usage.iter_mut().find(|x| path.starts_with(&x.root)) {
+            root.files += 1;
+            root.size += file.size;
+        }
+    }
+
+    usage.sort_by_key(|x| std::cmp::Reverse(x.size));
+
+    Some((archive.start, usage))
+}
+
+#[cfg(test)]
+fn test_insert(
+    index: &mut FileIndex,
+    name: &str,
+    start: chrono::NaiveDateTime,
+    files: Vec<borg::ArchiveFile>,
+) {
+    let archive = borg::ListArchive {
+        id: borg::ArchiveId::new(format!("id-{name}")),
+        name: borg::ArchiveName::new(name.into()),
+        comment: String::new(),
+        username: String::new(),
+        hostname: String::new(),
+        start,
+        end: start,
+        command_line: Vec::new(),
+    };
+
+    let pending = index.write_files(files).unwrap();
+    index.insert(&archive, pending).unwrap();
+}
+
+#[test]
+fn test_search() {
+    let file = |path: &str| borg::ArchiveFile {
+        path: path.to_string(),
+        type_: String::from("-"),
+        size: 0,
+        user: String::new(),
+        group: String::new(),
+        mtime: None,
+    };
+
+    let mut config = config::Backup::test_new_mock();
+    config.archive_prefix = config::ArchivePrefix::new("mine");
+
+    let dir = tempfile::tempdir().unwrap();
+    let start = chrono::NaiveDateTime::default();
+    let mut index = FileIndex::load_from(dir.path().to_path_buf());
+    test_insert(
+        &mut index,
+        "mine-old",
+        start,
+        vec![file("home/user/Report.odt"), file("home/user/notes")],
+    );
+    test_insert(
+        &mut index,
+        "mine-new",
+        start + chrono::Duration::days(1),
+        vec![file("home/user/report.odt")],
+    );
+    test_insert(
+        &mut index,
+        "other-new",
+        start,
+        vec![file("home/user/report.odt")],
+    );
+
+    // The list of archives is stored
+    let index = FileIndex::load_from(dir.path().to_path_buf());
+    assert_eq!(index.archives.len(), 3);
+
+    let mut configs = config::Backups::default();
+    configs.insert(config.clone()).unwrap();
+    let indices = BTreeMap::from([(config.repo_id.clone(), index)]);
+
+    let results = search(&configs, &indices, " REPORT");
+    let names: Vec<_> = results.iter().map(|x| x.archive_name.as_str()).collect();
+    assert_eq!(names, ["mine-new", "mine-old"]);
+    assert_eq!(results[1].files.len(), 1);
+
+    assert!(search(&configs, &indices, "").is_empty());
+}
+
+#[test]
+fn test_versions() {
+    let file = |size: u64| borg::ArchiveFile {
+        path: String::from("home/user/report.odt"),
+        type_: String::from("-"),
+        size,
+        user: String::new(),
+        group: String::new(),
+        mtime: None,
+    };
+
+    let mut config = config::Backup::test_new_mock();
+    config.archive_prefix = config::ArchivePrefix::new("mine");
+
+    let dir = tempfile::tempdir().unwrap();
+    let start = chrono::NaiveDateTime::default();
+    let mut index = FileIndex::load_from(dir.path().to_path_buf());
+    for (name, days, files) in [
+        ("mine-3", 3, vec![file(20)]),
+        ("mine-1", 1, vec![]),
+        ("mine-2", 2, vec![file(10)]),
+        ("mine-4", 4, vec![file(20)]),
+        ("other", 5, vec![file(30)]),
+    ] {
+        test_insert(
+            &mut index,
+            name,
+            start + chrono::Duration::days(days),
+            files,
+        );
+    }
+
+    let versions = versions(&config, &index, Path::new("/home/user/report.odt"));
+    let names: Vec<_> = versions.iter().map(|x| x.archive_name.as_str()).collect();
+    assert_eq!(names, ["mine-1", "mine-2", "mine-3", "mine-4"]);
+
+    assert!(versions[0].file.is_none());
+    assert!(versions[1].is_changed(&versions[0]));
+    assert!(versions[2].is_changed(&versions[1]));
+    assert!(!versions[3].is_changed(&versions[2]));
+}
+
+#[test]
+fn test_usage_by_root() {
+    let file = |path: &str, size: u64| borg::ArchiveFile {
+        path: path.to_string(),
+        type_: String::from("-"),
+        size,
+        user: String::new(),
+        group: String::new(),
+        mtime: None,
+    };
+
+    let mut config = config::Backup::test_new_mock();
+    config.archive_prefix = config::ArchivePrefix::new("mine");
+    config.include = ["/data", "/data/photos", "/etc"].map(PathBuf::from).into();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut index = FileIndex::load_from(dir.path().to_path_buf());
+    assert_eq!(usage_by_root(&config, &index), None);
+
+    let start = chrono::NaiveDateTime::default();
+    test_insert(
+        &mut index,
+        "mine-old",
+        start,
+        vec![file("etc/hosts", 100_000)],
+    );
+    test_insert(
+        &mut index,
+        "mine-new",
+        start + chrono::Duration::days(1),
+        vec![
+            file("data/notes.txt", 10),
+            file("data/photos/a.jpg", 300),
+            file("data/photos/b.jpg", 200),
+            file("etc/hosts", 1),
+            file("var/log", 1000),
+        ],
+    );
+
+    let (date, usage) = usage_by_root(&config, &index).unwrap();
+    assert_eq!(date, start + chrono::Duration::days(1));
+    assert_eq!(
+        usage,
+        vec![
+            RootUsage {
+                root: "/data/photos".into(),
+                files: 2,
+                size: 500
+            },
+            RootUsage {
+                root: "/data".into(),
+                files: 1,
+                size: 10
+            },
+            RootUsage {
+                root: "/etc".into(),
+                files: 1,
+                size: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_children() {
+    let file = |path: &str, type_: &str| borg::ArchiveFile {
+        path: path.to_string(),
+        type_: type_.to_string(),
+        size: 0,
+        user: String::new(),
+        group: String::new(),
+        mtime: None,
+    };
+
+    let mut config = config::Backup::test_new_mock();
+    config.archive_prefix = config::ArchivePrefix::new("mine");
+
+    let dir = tempfile::tempdir().unwrap();
+    let start = chrono::NaiveDateTime::default();
+    let mut index = FileIndex::load_from(dir.path().to_path_buf());
+    test_insert(
+        &mut index,
+        "mine-old",
+        start,
+        vec![
+            file("home/user/deleted.txt", "-"),
+            file("home/user/empty", "d"),
+        ],
+    );
+    test_insert(
+        &mut index,
+        "mine-new",
+        start + chrono::Duration::days(1),
+        vec![file("home/user/docs/report.odt", "-")],
+    );
+    test_insert(
+        &mut index,
+        "other",
+        start,
+        vec![file("home/user/other.txt", "-")],
+    );
+
+    let entry = |path: &str, is_dir: bool| IndexedEntry {
+        path: PathBuf::from(path),
+        is_dir,
+    };
+
+    assert_eq!(
+        children(&config, &index, Path::new("/")),
+        vec![entry("/home", true)]
+    );
+    assert_eq!(
+        children(&config, &index, Path::new("/home/user")),
+        vec![
+            entry("/home/user/docs", true),
+            entry("/home/user/empty", true),
+            entry("/home/user/deleted.txt", false),
+        ]
+    );
+    assert!(children(&config, &index, Path::new("/home/user/empty")).is_empty());
+}
@@ -0,0 +1,112 @@
+//! Host keys of SSH servers stored in `~/.ssh/known_hosts`
+
+use async_std::process;
+
+use crate::config;
+use crate::ui::prelude::*;
+
+/// Name of the server in `known_hosts`, like `nas.local` or `[nas.local]:2222`
+fn known_hosts_name(uri: &str) -> Option<String> {
+    let uri = glib::Uri::parse(uri, glib::UriFlags::NONE).ok()?;
+    if uri.scheme() != "ssh" {
+        return None;
+    }
+
+    let host = uri.host()?;
+
+    if uri.port() > 0 && uri.port() != 22 {
+        Some(format!("[{host}]:{}", uri.port()))
+    } else {
+        Some(host.to_string())
+    }
+}
+
+async fn ssh_keygen(args: &[&str]) -> std::io::Result<process::Output> {
+    process::Command::new("ssh-keygen")
+        .args(args)
+        .stdin(process::Stdio::null())
+        .output()
+        .await
+}
+
+/// Fingerprints of the stored host keys, like `ED25519 SHA256:…`
+pub async fn fingerprints(repo: &config::Repository) -> Vec<String> {
+    let Some(name) = known_hosts_name(&repo.to_string()) else {
+        return Vec::new();
+    };
+
+    match ssh_keygen(&["-l", "-F", &name]).await {
+        // Fails if the host is not known, which leaves the output empty
+        Ok(output) => parse_fingerprints(&String::from_utf8_lossy(&output.stdout)),
+        Err(err) => {
+            warn!("Failed to look up host key of {}: {}", name, err);
+            Vec::new()
+        }
+    }
+}
+
+/// Lines look like `nas.local ED25519 SHA256:…` after a `# Host … found` comment
+fn parse_fingerprints(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(' '))
+        .map(|(_, fingerprint)| fingerprint.trim().to_string())
+        .filter(|fingerprint| !fingerprint.is_empty())
+        .collect()
+}
+
+/// Remove the stored host keys of the server
+///
+/// With [`config::remote::HostKeyPolicy::AcceptNew`], the new key is stored on
+/// the next connection.
+pub async fn reset(repo: &config::Repository) -> Result<()> {
+    let Some(name) = known_hosts_name(&repo.to_string()) else {
+        return Ok(());
+    };
+
+    // Nothing to reset without any known hosts
+    if !glib::home_dir().join(".ssh/known_hosts").exists() {
+        return Ok(());
+    }
+
+    info!("Removing host key of {} from known hosts", name);
+
+    let output = ssh_keygen(&["-R", &name])
+        .await
+        .err_to_msg(gettext("Failed to Reset Host Key"))?;
+
+    if !output.status.success() {
+        return Err(Message::new(
+            gettext("Failed to Reset Host Key"),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_known_hosts_name() {
+    assert_eq!(
+        known_hosts_name("ssh://borg@nas.local:2222/~/backup").as_deref(),
+        Some("[nas.local]:2222")
+    );
+    assert_eq!(
+        known_hosts_name("ssh://example.org:22/./repo").as_deref(),
+        Some("example.org")
+    );
+    assert_eq!(known_hosts_name("/media/backup"), None);
+}
+
+#[test]
+fn test_parse_fingerprints() {
+    assert_eq!(
+        parse_fingerprints(
+            "# Host nas.local found: line 3\nnas.local ED25519 SHA256:abc (ED25519)\n"
+        ),
+        vec!["ED25519 SHA256:abc (ED25519)"]
+    );
+    assert!(parse_fingerprints("").is_empty());
+}
@@ -0,0 +1,79 @@
+//! List boxes that are updated incrementally
+//!
+//! Rows are only created for items that were added or changed. Rows of
+//! unchanged items are kept, such that the scroll position, keyboard focus
+//! and expanded rows survive a refresh.
+
+use adw::prelude::*;
+
+use crate::ui::prelude::*;
+
+/// Bind `list` to a new store whose items are shown via `create_row`
+pub fn bind<T: 'static>(
+    list: &gtk::ListBox,
+    create_row: impl Fn(&T) -> gtk::Widget + 'static,
+) -> gio::ListStore {
+    let store = gio::ListStore::with_type(glib::BoxedAnyObject::static_type());
+
+    list.bind_model(Some(&store), move |object| {
+        let item = object
+            .downcast_ref::<glib::BoxedAnyObject>()
+            .expect("List store only contains boxed items");
+        create_row(&item.borrow::<T>())
+    });
+
+    store
+}
+
+/// Replace the items of `store` with `items`
+///
+/// Only the range between the unchanged items at the start and the end is
+/// replaced.
+pub fn update<T: PartialEq + 'static>(store: &gio::ListStore, items: Vec<T>) {
+    let current: Vec<_> = (0..store.n_items())
+        .filter_map(|i| store.item(i))
+        .filter_map(|object| object.downcast::<glib::BoxedAnyObject>().ok())
+        .collect();
+
+    let (start, end) = unchanged_range(&current, &items, |object, item| {
+        *object.borrow::<T>() == *item
+    });
+
+    let removed = current.len() - start - end;
+    let added_len = items.len() - start - end;
+    let added: Vec<_> = items
+        .into_iter()
+        .skip(start)
+        .take(added_len)
+        .map(glib::BoxedAnyObject::new)
+        .collect();
+
+    if removed > 0 || !added.is_empty() {
+        store.splice(start as u32, removed as u32, &added);
+    }
+}
+
+/// Number of equal items at the start and at the end of both lists
+fn unchanged_range<A, B>(a: &[A], b: &[B], eq: impl Fn(&A, &B) -> bool) -> (usize, usize) {
+    let start = a.iter().zip(b).take_while(|(a, b)| eq(a, b)).count();
+    let end = a[start..]
+        .iter()
+        .rev()
+        .zip(b[start..].iter().rev())
+        .take_while(|(a, b)| eq(a, b))
+        .count();
+
+    (start, end)
+}
+
+#[test]
+fn test_unchanged_range() {
+    let eq = |a: &u32, b: &u32| a == b;
+
+    assert_eq!(unchanged_range(&[1, 2, 3], &[1, 2, 3], eq), (3, 0));
+    assert_eq!(unchanged_range(&[1, 2, 3], &[1, 4, 3], eq), (1, 1));
+    assert_eq!(unchanged_range(&[1, 2, 3], &[1, 3], eq), (1, 1));
+    assert_eq!(unchanged_range(&[1, 3], &[1, 2, 3], eq), (1, 1));
+    assert_eq!(unchanged_range(&[], &[1], eq), (0, 0));
+    assert_eq!(unchanged_range(&[2, 2], &[2], eq), (1, 0));
+}
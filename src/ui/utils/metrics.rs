@@ -0,0 +1,228 @@
+//! Backup metrics in the Prometheus text exposition format
+//!
+//! The file is rewritten after every backup run and can be picked up by the
+//! textfile collector of the Prometheus node exporter.
+
+use crate::ui::prelude::*;
+use std::fmt::Write;
+
+use crate::borg;
+use crate::config;
+use crate::config::history::History;
+use crate::ui::utils::df::Space;
+use crate::ui::utils::repo_cache::RepoCache;
+
+type Sample = fn(&config::Backup, Option<&History>, Option<&Space>) -> Option<f64>;
+
+const METRICS: &[(&str, &str, Sample)] = &[
+    (
+        "pika_backup_last_run_timestamp_seconds",
+        "End of the last backup run",
+        |_, history, _| Some(history?.run.front()?.end.timestamp() as f64),
+    ),
+    (
+        "pika_backup_last_success_timestamp_seconds",
+        "End of the last successful backup run",
+        |_, history, _| Some(history?.last_completed.as_ref()?.end.timestamp() as f64),
+    ),
+    (
+        "pika_backup_last_success_duration_seconds",
+        "Duration of the last successful backup run",
+        |_, history, _| last_stats(history?).map(|stats| stats.archive.duration),
+    ),
+    (
+        "pika_backup_last_success_added_bytes",
+        "Deduplicated size of the data added by the last successful backup run",
+        |_, history, _| {
+            last_stats(history?).map(|stats| stats.archive.stats.deduplicated_size as f64)
+        },
+    ),
+    (
+        "pika_backup_last_success_original_bytes",
+        "Original size of the data saved by the last successful backup run",
+        |_, history, _| last_stats(history?).map(|stats| stats.archive.stats.original_size as f64),
+    ),
+    (
+        "pika_backup_last_success_files",
+        "Number of files saved by the last successful backup run",
+        |_, history, _| last_stats(history?).map(|stats| stats.archive.stats.nfiles as f64),
+    ),
+    (
+        "pika_backup_running",
+        "Whether a backup is currently running",
+        |_, history, _| {
+            Some(
+                if history.is_some_and(|history| history.running.is_some()) {
+                    1.
+                } else {
+                    0.
+                },
+            )
+        },
+    ),
+    (
+        "pika_backup_recent_failures",
+        "Number of failed runs among the recorded backup history",
+        |_, history, _| {
+            Some(history.map_or(0, |history| {
+                history
+                    .run
+                    .iter()
+                    .filter(|run| matches!(run.outcome, borg::Outcome::Failed(_)))
+                    .count()
+            }) as f64)
+        },
+    ),
+    (
+        "pika_backup_consecutive_failures",
+        "Number of failed runs since the last successful backup run",
+        |_, history, _| {
+            Some(history.map_or(0, |history| {
+                history
+                    .run
+                    .iter()
+                    .take_while(|run| !run.outcome.is_completed())
+                    .filter(|run| matches!(run.outcome, borg::Outcome::Failed(_)))
+                    .count()
+            }) as f64)
+        },
+    ),
+    (
+        "pika_backup_repository_filesystem_size_bytes",
+        "Size of the filesystem the repository is stored on",
+        |_, _, space| Some(space?.size as f64),
+    ),
+    (
+        "pika_backup_repository_filesystem_used_bytes",
+        "Used space on the filesystem the repository is stored on",
+        |_, _, space| Some(space?.used as f64),
+    ),
+    (
+        "pika_backup_repository_filesystem_avail_bytes",
+        "Available space on the filesystem the repository is stored on",
+        |_, _, space| Some(space?.avail as f64),
+    ),
+];
+
+fn last_stats(history: &History) -> Option<&borg::Stats> {
+    match &history.last_completed.as_ref()?.outcome {
+        borg::Outcome::Completed { stats } => Some(stats),
+        _ => None,
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub fn path() -> std::path::PathBuf {
+    [
+        glib::user_data_dir(),
+        env!("CARGO_PKG_NAME").into(),
+        "metrics.prom".into(),
+    ]
+    .iter()
+    .collect()
+}
+
+/// Render metrics for all backups that have metrics export enabled
+pub fn render(
+    configs: &config::Backups,
+    histories: &config::Histories,
+    space: impl Fn(&borg::RepoId) -> Option<Space>,
+) -> String {
+    let configs: Vec<_> = configs
+        .iter()
+        .filter(|config| config.export_metrics)
+        .map(|config| {
+            (
+                config,
+                histories.try_get(&config.id).ok(),
+                space(&config.repo_id),
+            )
+        })
+        .collect();
+
+    let mut output = String::new();
+
+    for (name, help, sample) in METRICS {
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} gauge");
+
+        for (config, history, space) in &configs {
+            if let Some(value) = sample(config, *history, space.as_ref()) {
+                let _ = writeln!(
+                    output,
+                    "{name}{{config_id=\"{}\",title=\"{}\"}} {value}",
+                    escape_label(config.id.as_str()),
+                    escape_label(&config.title()),
+                );
+            }
+        }
+    }
+
+    output
+}
+
+/// Rewrite the metrics file or remove it if no backup exports metrics
+pub fn write() -> Result<()> {
+    let path = path();
+    let configs = BACKUP_CONFIG.load();
+
+    if !configs.iter().any(|config| config.export_metrics) {
+        if path.exists() {
+            std::fs::remove_file(&path).err_to_msg(gettext("Failed to remove metrics file."))?;
+        }
+        return Ok(());
+    }
+
+    let output = render(&configs, &BACKUP_HISTORY.load(), |repo_id| {
+        RepoCache::get(repo_id).space
+    });
+
+    let dir = path.parent().map(|x| x.to_path_buf()).unwrap_or_default();
+
+    // Write atomically to not expose partial files to the collector
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| tempfile::NamedTempFile::new_in(&dir))
+        .and_then(|mut file| {
+            std::io::Write::write_all(&mut file, output.as_bytes())?;
+            file.persist(&path)?;
+            Ok(())
+        })
+        .err_to_msg(gettext("Failed to write metrics file."))
+}
+
+#[test]
+fn test_render() {
+    let mut config = config::Backup::test_new_mock();
+    config.export_metrics = true;
+    config.title = String::from("Title \"quoted\"");
+
+    let mut histories = config::Histories::default();
+    histories.insert(
+        config.id.clone(),
+        config::history::RunInfo::test_new_mock(chrono::Duration::zero()),
+    );
+
+    let mut configs = config::Backups::default();
+    configs.insert(config.clone()).unwrap();
+    let mut hidden = config::Backup::test_new_mock();
+    hidden.title = String::from("Hidden");
+    configs.insert(hidden).unwrap();
+
+    let output = render(&configs, &histories, |_| None);
+
+    let labels = format!(
+        "{{config_id=\"{}\",title=\"Title \\\"quoted\\\"\"}}",
+        config.id.as_str()
+    );
+    assert!(output.contains("# TYPE pika_backup_last_success_timestamp_seconds gauge"));
+    assert!(output.contains(&format!("pika_backup_last_success_added_bytes{labels} 0")));
+    assert!(output.contains(&format!("pika_backup_consecutive_failures{labels} 0")));
+    assert!(!output.contains("Hidden"));
+    assert!(!output.contains("pika_backup_repository_filesystem_size_bytes{"));
+}
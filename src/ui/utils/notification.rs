@@ -1,8 +1,20 @@
 use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// Backups that finish faster don't show a progress notification
+const PROGRESS_DELAY: Duration = Duration::from_secs(60);
+/// Minimum time between two updates of a progress notification
+const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_secs(15);
 
 pub enum BackupNote<'a> {
     Warnings(&'a config::ConfigId),
     Failed(&'a config::ConfigId),
+    Progress(&'a config::ConfigId),
 }
 
 impl<'a> std::fmt::Display for BackupNote<'a> {
@@ -10,6 +22,90 @@ impl<'a> std::fmt::Display for BackupNote<'a> {
         match self {
             Self::Warnings(id) => write!(f, "backup-warnings-{id}"),
             Self::Failed(id) => write!(f, "backup-failed-{id}"),
+            Self::Progress(id) => write!(f, "backup-progress-{id}"),
+        }
+    }
+}
+
+struct ProgressNote {
+    started: Instant,
+    last_update: Option<Instant>,
+    last_body: String,
+}
+
+thread_local!(
+    static PROGRESS_NOTES: RefCell<BTreeMap<ConfigId, ProgressNote>> = Default::default();
+);
+
+/// Update the notification that shows the progress of a running backup
+///
+/// A single notification is replaced for every update. It is only shown for
+/// long running backups while the window is not displayed.
+pub fn update_progress(config_id: &ConfigId) {
+    let status = ui::backup_status::Display::new_from_id(config_id);
+
+    let body = match (BACKUP_CONFIG.load().try_get(config_id), &status.subtitle) {
+        (Ok(config), Some(subtitle)) => format!("{} – {}", config.title(), subtitle),
+        (Ok(config), None) => config.title(),
+        (Err(_), subtitle) => subtitle.clone().unwrap_or_default(),
+    };
+
+    let send = PROGRESS_NOTES.with(|notes| {
+        let mut notes = notes.borrow_mut();
+        let note = notes
+            .entry(config_id.clone())
+            .or_insert_with(|| ProgressNote {
+                started: Instant::now(),
+                last_update: None,
+                last_body: String::new(),
+            });
+
+        if ui::app_window::is_displayed() {
+            if note.last_update.take().is_some() {
+                adw_app().withdraw_notification(&BackupNote::Progress(config_id).to_string());
+            }
+            return false;
+        }
+
+        let due = note.started.elapsed() >= PROGRESS_DELAY
+            && note.last_body != body
+            && note
+                .last_update
+                .map_or(true, |x| x.elapsed() >= PROGRESS_UPDATE_INTERVAL);
+
+        if due {
+            note.last_update = Some(Instant::now());
+            note.last_body = body.clone();
         }
+
+        due
+    });
+
+    if send {
+        let notification = gio::Notification::new(&status.title);
+        notification.set_body(Some(&body));
+        notification.set_priority(gio::NotificationPriority::Low);
+        notification
+            .set_default_action_and_target_value("app.backup.show", Some(&config_id.to_variant()));
+        notification.add_button_with_target_value(
+            &gettext("Show Details"),
+            "app.backup.show",
+            Some(&config_id.to_variant()),
+        );
+        notification.add_button_with_target_value(
+            &gettext("Abort"),
+            "app.backup.abort",
+            Some(&config_id.to_variant()),
+        );
+
+        adw_app().send_notification(
+            Some(&BackupNote::Progress(config_id).to_string()),
+            &notification,
+        );
     }
 }
+
+pub fn withdraw_progress(config_id: &ConfigId) {
+    PROGRESS_NOTES.with(|notes| notes.borrow_mut().remove(config_id));
+    adw_app().withdraw_notification(&BackupNote::Progress(config_id).to_string());
+}
@@ -1,13 +1,23 @@
+use crate::borg;
 use crate::config::{self, Password};
+use crate::schedule;
+use crate::ui;
 use crate::ui::prelude::*;
 use std::collections::HashMap;
 
+/// How often queued backups check whether the keyring has been unlocked
+const KEYRING_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
 pub async fn password_dialog(
     repo: config::Repository,
     purpose: String,
     keyring_error: Option<String>,
+    failed_attempts: u32,
+    config: Option<config::Backup>,
 ) -> Option<config::Password> {
     crate::ui::dialog_encryption_password::Ask::new(repo, purpose, keyring_error)
+        .failed_attempts(failed_attempts)
+        .config(config)
         .run()
         .await
 }
@@ -24,10 +34,11 @@ pub async fn store_password(config: &config::Backup, password: &Password) -> Res
 pub async fn remove_password(config: &config::Backup, remove_all: bool) -> Result<()> {
     // check if other configs using this repo exist
     if !remove_all
-        && BACKUP_CONFIG
+        && (BACKUP_CONFIG
             .load()
             .iter()
             .any(|x| x.id != config.id && x.repo_id == config.repo_id)
+            || BACKUP_TRASH.load().uses_repo(&config.repo_id))
     {
         debug!("Not removing password because other configs need it");
     } else {
@@ -76,3 +87,74 @@ async fn delete_passwords(config: &config::Backup) -> std::result::Result<(), oo
     debug!("Clearing password returned");
     Ok(())
 }
+
+/// Ask the secret service to unlock the keyring
+///
+/// The secret service shows its own prompt for this.
+pub async fn unlock_keyring() -> std::result::Result<(), oo7::Error> {
+    debug!("Requesting keyring unlock");
+    oo7::Keyring::new().await?.unlock().await
+}
+
+async fn is_password_locked(repo_id: &borg::RepoId) -> std::result::Result<bool, oo7::Error> {
+    let keyring = oo7::Keyring::new().await?;
+    let items = keyring
+        .search_items(HashMap::from([("repo-id", repo_id.as_str())]))
+        .await?;
+
+    match items.first() {
+        Some(item) => item.is_locked().await,
+        None => Ok(false),
+    }
+}
+
+/// Start the scheduled backup again once the keyring has been unlocked
+pub fn queue_until_unlocked(config_id: ConfigId, due_cause: schedule::DueCause) {
+    info!("Queuing backup {config_id:?} until keyring is unlocked");
+
+    let watching = !KEYRING_QUEUE.load().is_empty();
+    KEYRING_QUEUE.update(|queue| {
+        queue.insert(config_id.clone(), due_cause.clone());
+    });
+
+    if !watching {
+        glib::MainContext::default().spawn_local(retry_when_unlocked());
+    }
+}
+
+async fn retry_when_unlocked() {
+    // Keep running in the background until the queued backups are started
+    let _guard = QuitGuard::default();
+
+    while !KEYRING_QUEUE.load().is_empty() {
+        async_std::task::sleep(KEYRING_POLL_INTERVAL).await;
+
+        for (config_id, due_cause) in KEYRING_QUEUE.get() {
+            let repo_id = BACKUP_CONFIG
+                .load()
+                .try_get(&config_id)
+                .map(|config| config.repo_id.clone());
+
+            if let Ok(repo_id) = repo_id {
+                match is_password_locked(&repo_id).await {
+                    Ok(true) => continue,
+                    Ok(false) => {
+                        info!("Keyring unlocked, starting queued backup {config_id:?}");
+                        ui::page_backup::start_backup(
+                            config_id.clone(),
+                            Some(due_cause),
+                            QuitGuard::default(),
+                        );
+                    }
+                    Err(err) => {
+                        warn!("Keyring unavailable, dropping queued backup: {err:?}");
+                    }
+                }
+            }
+
+            KEYRING_QUEUE.update(|queue| {
+                queue.remove(&config_id);
+            });
+        }
+    }
+}
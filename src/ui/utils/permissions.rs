@@ -0,0 +1,71 @@
+//! Permissions of restored files and of files in browsed archives
+
+use crate::borg;
+use crate::ui::prelude::*;
+
+const UMASK_KEY: &str = "restore-umask";
+
+/// Permissions that can be chosen in the preferences
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Preset {
+    #[default]
+    Archived,
+    OwnerAndGroup,
+    Owner,
+}
+
+impl Preset {
+    pub fn list() -> [Self; 3] {
+        [Self::Archived, Self::OwnerAndGroup, Self::Owner]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Archived => gettext("Keep Archived Permissions"),
+            Self::OwnerAndGroup => gettext("Owner and Group Only"),
+            Self::Owner => gettext("Owner Only"),
+        }
+    }
+
+    /// Permission bits that are removed, like a umask
+    pub const fn umask(self) -> u32 {
+        match self {
+            Self::Archived => 0o000,
+            Self::OwnerAndGroup => 0o007,
+            Self::Owner => 0o077,
+        }
+    }
+
+    pub fn from_umask(umask: u32) -> Option<Self> {
+        Self::list().into_iter().find(|x| x.umask() == umask)
+    }
+}
+
+/// Permission bits removed from restored files and browsed archives
+pub fn umask() -> u32 {
+    crate::utils::app_settings()
+        .map(|settings| settings.uint(UMASK_KEY) & 0o777)
+        .unwrap_or_default()
+}
+
+pub fn set_umask(umask: u32) {
+    if let Some(settings) = crate::utils::app_settings() {
+        if let Err(err) = settings.set_uint(UMASK_KEY, umask & 0o777) {
+            warn!("Failed to store file permissions: {}", err);
+        }
+    }
+}
+
+/// Apply the configured permissions to mounting archives
+pub fn configure_mount(task: &mut borg::task::Mount) {
+    task.set_umask(umask());
+}
+
+#[test]
+fn test_preset() {
+    for preset in Preset::list() {
+        assert_eq!(Preset::from_umask(preset.umask()), Some(preset));
+    }
+
+    assert_eq!(Preset::from_umask(0o022), None);
+}
@@ -1,4 +1,12 @@
+//! Cached information about repositories
+//!
+//! Listing archives and looking up the free space can take a long time for
+//! remote repositories. The cached values are shown immediately and are
+//! refreshed in the background once they are older than [`MAX_AGE_MINUTES`].
+//! Operations that change the repository invalidate the cache.
+
 use crate::ui::prelude::*;
+use chrono::prelude::*;
 
 use std::collections::BTreeMap;
 use std::iter::FromIterator;
@@ -6,6 +14,14 @@ use std::iter::FromIterator;
 use crate::borg;
 use crate::ui;
 
+/// Age after which cached values are refreshed
+const MAX_AGE_MINUTES: i64 = 60;
+
+fn is_fresh(updated: Option<DateTime<Local>>) -> bool {
+    updated
+        .is_some_and(|updated| Local::now() - updated < chrono::Duration::minutes(MAX_AGE_MINUTES))
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RepoCache {
     pub repo_id: borg::RepoId,
@@ -13,6 +29,18 @@ pub struct RepoCache {
     #[serde(skip)]
     pub reloading: bool,
     pub space: Option<ui::utils::df::Space>,
+    /// Time the archives have last been retrieved
+    #[serde(default)]
+    pub archives_updated: Option<DateTime<Local>>,
+    /// Time the free space has last been looked up
+    #[serde(default)]
+    pub space_updated: Option<DateTime<Local>>,
+    /// Only the latest archives have been retrieved
+    #[serde(default)]
+    pub archives_limited: bool,
+    /// Size of all archives after deduplication, as of the last backup
+    #[serde(default)]
+    pub deduplicated_size: Option<u64>,
 }
 
 impl RepoCache {
@@ -22,9 +50,52 @@ impl RepoCache {
             archives: None,
             reloading: false,
             space: None,
+            archives_updated: None,
+            space_updated: None,
+            archives_limited: false,
+            deduplicated_size: None,
         }
     }
 
+    pub fn archives_outdated(&self) -> bool {
+        !is_fresh(self.archives_updated)
+    }
+
+    pub fn space_outdated(&self) -> bool {
+        !is_fresh(self.space_updated)
+    }
+
+    /// Mark the cached values as outdated after the repository has been changed
+    pub fn invalidate(repo_id: &borg::RepoId) {
+        // Load the cache from file first to not lose it
+        Self::get(repo_id);
+
+        REPO_CACHE.update(|repos| {
+            let cache = repos
+                .entry(repo_id.clone())
+                .or_insert_with_key(RepoCache::new);
+
+            cache.archives_updated = None;
+            cache.space_updated = None;
+        });
+
+        let _ignore = Self::write(repo_id);
+    }
+
+    /// Remember the repository size reported at the end of a backup
+    pub fn set_deduplicated_size(repo_id: &borg::RepoId, size: u64) -> Result<()> {
+        Self::get(repo_id);
+
+        REPO_CACHE.update(|repos| {
+            repos
+                .entry(repo_id.clone())
+                .or_insert_with_key(RepoCache::new)
+                .deduplicated_size = Some(size);
+        });
+
+        Self::write(repo_id)
+    }
+
     pub fn get(repo_id: &borg::RepoId) -> Self {
         if let Some(repo_archives) = REPO_CACHE.load().get(repo_id) {
             debug!("Repo cache already loaded from file");
@@ -67,6 +138,23 @@ impl RepoCache {
             .collect()
     }
 
+    pub fn archive_count(&self) -> Option<usize> {
+        self.archives.as_ref().map(BTreeMap::len)
+    }
+
+    /// Start of the oldest archive, unknown if only the latest archives have been retrieved
+    pub fn oldest_archive(&self) -> Option<NaiveDateTime> {
+        if self.archives_limited {
+            return None;
+        }
+
+        self.archives.as_ref()?.values().map(|x| x.start).min()
+    }
+
+    pub fn newest_archive(&self) -> Option<NaiveDateTime> {
+        self.archives.as_ref()?.values().map(|x| x.start).max()
+    }
+
     pub fn archives_sorted_by_date(&self) -> Vec<(borg::ArchiveName, borg::ListArchive)> {
         if let Some(archives) = self.archives.clone() {
             let mut vec = Vec::from_iter(archives);
@@ -0,0 +1,131 @@
+//! Machine-readable reports of backup runs
+//!
+//! Reports provide an audit trail that is independent of the internal history
+//! format. Every report is accompanied by a checksum file in the format of
+//! `sha256sum`, such that it can be verified with `sha256sum --check`.
+
+use crate::ui::prelude::*;
+
+use std::path::{Path, PathBuf};
+
+use crate::borg;
+use crate::config;
+use crate::config::history::RunInfo;
+
+/// Increased on incompatible changes of the report format
+const REPORT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Report<'a> {
+    report_version: u32,
+    config: &'a config::Backup,
+    archive_name: Option<&'a str>,
+    start: Option<chrono::DateTime<chrono::Local>>,
+    end: chrono::DateTime<chrono::Local>,
+    outcome: &'a borg::Outcome,
+    /// Software that performed the run
+    versions: Option<&'a config::history::SoftwareVersions>,
+    /// Estimated data sent over the network
+    sent: Option<u64>,
+    /// Number of log messages of the run
+    message_count: usize,
+    /// SHA-256 of the log messages of the run in JSON
+    messages_digest: String,
+}
+
+fn sha256(data: &str) -> String {
+    glib::compute_checksum_for_string(glib::ChecksumType::Sha256, data)
+        .map(|x| x.to_string())
+        .unwrap_or_default()
+}
+
+pub fn file_name(config: &config::Backup, run_info: &RunInfo) -> String {
+    format!(
+        "{}-{}.json",
+        config.id.as_str(),
+        run_info.end.format("%Y%m%dT%H%M%S")
+    )
+}
+
+pub fn render(config: &config::Backup, run_info: &RunInfo) -> serde_json::Result<String> {
+    let archive_name = match &run_info.outcome {
+        borg::Outcome::Completed { stats } => Some(stats.archive.name.as_str()),
+        _ => None,
+    };
+
+    let report = Report {
+        report_version: REPORT_VERSION,
+        config,
+        archive_name,
+        start: run_info.start,
+        end: run_info.end,
+        outcome: &run_info.outcome,
+        versions: run_info.versions.as_ref(),
+        sent: run_info.sent,
+        message_count: run_info.messages.len(),
+        messages_digest: sha256(&serde_json::to_string(&run_info.messages)?),
+    };
+
+    serde_json::to_string_pretty(&report)
+}
+
+/// Line of a checksum file as written by `sha256sum`
+pub fn checksum_line(content: &str, file_name: &str) -> String {
+    format!("{}  {file_name}\n", sha256(content))
+}
+
+fn write_to(dir: &Path, config: &config::Backup, run_info: &RunInfo) -> std::io::Result<PathBuf> {
+    let content = render(config, run_info)?;
+    let file_name = file_name(config, run_info);
+    let path = dir.join(&file_name);
+
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&path, &content)?;
+    std::fs::write(
+        dir.join(format!("{file_name}.sha256")),
+        checksum_line(&content, &file_name),
+    )?;
+
+    Ok(path)
+}
+
+/// Write the report of a run if reports are enabled for the backup
+pub fn write(config: &config::Backup, run_info: &RunInfo) -> Result<()> {
+    let Some(dir) = &config.report_dir else {
+        return Ok(());
+    };
+
+    let path = write_to(dir, config, run_info).err_to_msg(gettextf(
+        "Failed to write report to “{}”.",
+        &[&dir.display().to_string()],
+    ))?;
+    debug!("Report written to {:?}", path);
+
+    Ok(())
+}
+
+#[test]
+fn test_write_to() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config::Backup::test_new_mock();
+    let run_info = RunInfo::test_new_mock(chrono::Duration::zero());
+
+    let path = write_to(dir.path(), &config, &run_info).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+    assert_eq!(report["report_version"], REPORT_VERSION);
+    assert_eq!(report["config"]["id"], config.id.as_str());
+    assert_eq!(report["message_count"], 0);
+    assert!(report["versions"].is_null());
+
+    let file_name = file_name(&config, &run_info);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join(format!("{file_name}.sha256"))).unwrap(),
+        checksum_line(&content, &file_name)
+    );
+    assert_eq!(
+        checksum_line(&content, &file_name).len(),
+        64 + 2 + file_name.len() + 1
+    );
+}
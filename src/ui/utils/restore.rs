@@ -0,0 +1,643 @@
+//! Restore the content of a mounted archive to its original location
+//!
+//! The archive is mounted such that all files belong to the current user.
+//! The original owners are looked up from the archive content and mapped to
+//! the users of this system by name, like borg does without `--numeric-ids`.
+
+use std::collections::BTreeMap;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::borg;
+use crate::config;
+use crate::ui;
+use crate::ui::prelude::*;
+
+const ORIGINAL_OWNERS_KEY: &str = "restore-original-owners";
+
+/// What to do with files that already exist at their original location
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+impl ConflictPolicy {
+    pub fn list() -> [Self; 3] {
+        [Self::Skip, Self::Overwrite, Self::KeepBoth]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Skip => gettext("Skip Existing Files"),
+            Self::Overwrite => gettext("Overwrite Existing Files"),
+            Self::KeepBoth => gettext("Keep Both Versions"),
+        }
+    }
+}
+
+/// Who owns the restored files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OwnershipPolicy {
+    #[default]
+    CurrentUser,
+    /// Users with the same name on this system, where permitted
+    Original,
+}
+
+impl OwnershipPolicy {
+    pub fn list() -> [Self; 2] {
+        [Self::CurrentUser, Self::Original]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::CurrentUser => gettext("Current User"),
+            Self::Original => gettext("Original Owners"),
+        }
+    }
+
+    /// Policy chosen in the preferences
+    pub fn configured() -> Self {
+        let original = crate::utils::app_settings()
+            .is_some_and(|settings| settings.boolean(ORIGINAL_OWNERS_KEY));
+
+        if original {
+            Self::Original
+        } else {
+            Self::CurrentUser
+        }
+    }
+
+    pub fn set_configured(self) {
+        if let Some(settings) = crate::utils::app_settings() {
+            if let Err(err) = settings.set_boolean(ORIGINAL_OWNERS_KEY, self == Self::Original) {
+                warn!("Failed to store ownership policy: {}", err);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Owner {
+    pub user: String,
+    pub group: String,
+}
+
+/// Owners to apply to the restored files
+#[derive(Clone, Debug, Default)]
+pub struct Ownership {
+    pub policy: OwnershipPolicy,
+    /// Archived owners by original location, empty if unknown
+    pub owners: BTreeMap<PathBuf, Owner>,
+    current_user: Option<String>,
+}
+
+impl Ownership {
+    pub fn new(policy: OwnershipPolicy, owners: BTreeMap<PathBuf, Owner>) -> Self {
+        Self {
+            policy,
+            owners,
+            current_user: nix::unistd::User::from_uid(nix::unistd::getuid())
+                .ok()
+                .flatten()
+                .map(|user| user.name),
+        }
+    }
+
+    fn is_current_user(&self, owner: &Owner) -> bool {
+        self.current_user.as_ref() == Some(&owner.user)
+    }
+
+    /// Number of archived files that belong to other users
+    pub fn foreign_files(&self) -> usize {
+        self.owners
+            .values()
+            .filter(|owner| !self.is_current_user(owner))
+            .count()
+    }
+
+    /// Set the owner of the file restored from `original` to `path`
+    ///
+    /// Returns whether the owner differs from the archived owner.
+    fn apply(&self, original: &Path, path: &Path) -> bool {
+        let Some(owner) = self.owners.get(original) else {
+            return false;
+        };
+
+        match self.policy {
+            OwnershipPolicy::CurrentUser => !self.is_current_user(owner),
+            OwnershipPolicy::Original => !set_owner(owner, path),
+        }
+    }
+}
+
+/// Returns whether the user and group have been set
+fn set_owner(owner: &Owner, path: &Path) -> bool {
+    let uid = nix::unistd::User::from_name(&owner.user)
+        .ok()
+        .flatten()
+        .map(|user| user.uid);
+    let gid = nix::unistd::Group::from_name(&owner.group)
+        .ok()
+        .flatten()
+        .map(|group| group.gid);
+
+    if uid.is_none() && gid.is_none() {
+        return false;
+    }
+
+    match nix::unistd::fchownat(
+        None,
+        path,
+        uid,
+        gid,
+        nix::unistd::FchownatFlags::NoFollowSymlink,
+    ) {
+        Ok(()) => uid.is_some() && gid.is_some(),
+        Err(err) => {
+            debug!("Failed to set owner of {:?}: {}", path, err);
+            false
+        }
+    }
+}
+
+/// Archived owners of all files by original location
+///
+/// Listing the archive content takes a while for large archives, so the owners
+/// are only looked up if the original owners should be kept. They are also
+/// unknown if the repository is already mounted since borg can't list the
+/// archive content at the same time.
+pub async fn owners(
+    config: &config::Backup,
+    archive_name: &borg::ArchiveName,
+) -> BTreeMap<PathBuf, Owner> {
+    if OwnershipPolicy::configured() != OwnershipPolicy::Original
+        || ACTIVE_MOUNTS.load().contains(&config.repo_id)
+    {
+        return Default::default();
+    }
+
+    let guard = QuitGuard::default();
+    let mut command = borg::Command::<borg::task::ListFiles>::new(config.clone());
+    command.task.set_archive_name(Some(archive_name.clone()));
+    let entries = command.task.entries();
+
+    // Only the owners are kept from the listed entries
+    let collect = async move {
+        let mut owners = BTreeMap::new();
+
+        while let Ok(file) = entries.recv().await {
+            if !file.user.is_empty() {
+                owners.insert(
+                    Path::new("/").join(&file.path),
+                    Owner {
+                        user: file.user,
+                        group: file.group,
+                    },
+                );
+            }
+        }
+
+        owners
+    };
+
+    let (result, owners) = futures::join!(ui::utils::borg::exec(command, &guard), collect);
+
+    match result {
+        Ok(_) => owners,
+        Err(err) => {
+            warn!("Failed to look up owners of archived files: {:?}", err);
+            Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Preview {
+    /// Locations that will be written to
+    pub roots: Vec<PathBuf>,
+    pub files: u64,
+    pub bytes: u64,
+    /// Files that already exist at their original location
+    pub conflicts: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Progress {
+    pub files_done: AtomicU64,
+    pub cancel: AtomicBool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    pub restored: u64,
+    pub skipped: u64,
+    pub failed: Vec<(PathBuf, String)>,
+    pub cancelled: bool,
+    /// Restored files that don't belong to their archived owner
+    pub ownership_adjusted: u64,
+}
+
+/// The original location of a path inside the mounted archive
+pub fn original_path(archive_root: &Path, path: &Path) -> PathBuf {
+    Path::new("/").join(path.strip_prefix(archive_root).unwrap_or(path))
+}
+
+/// Path for a restored file that should not replace the existing one
+pub fn keep_both_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|x| format!(".{}", x.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut n = 1;
+    loop {
+        let name = if n == 1 {
+            format!("{stem}.restored{extension}")
+        } else {
+            format!("{stem}.restored-{n}{extension}")
+        };
+
+        let candidate = path.with_file_name(name);
+        if candidate.symlink_metadata().is_err() {
+            return candidate;
+        }
+
+        n += 1;
+    }
+}
+
+fn roots(archive_root: &Path) -> Vec<PathBuf> {
+    let populated = crate::ui::page_archives::find_first_populated_dir(archive_root);
+
+    let mut roots: Vec<PathBuf> = std::fs::read_dir(&populated)
+        .map(|dir| {
+            dir.filter_map(|entry| entry.ok())
+                .map(|entry| original_path(archive_root, &entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if roots.is_empty() {
+        roots.push(original_path(archive_root, &populated));
+    }
+
+    roots.sort();
+    roots
+}
+
+pub fn preview(archive_root: &Path) -> Preview {
+    let mut preview = Preview {
+        roots: roots(archive_root),
+        ..Default::default()
+    };
+
+    for entry in walkdir::WalkDir::new(archive_root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        if entry.file_type().is_dir() {
+            continue;
+        }
+
+        preview.files += 1;
+        preview.bytes += entry.metadata().map(|x| x.len()).unwrap_or_default();
+
+        if original_path(archive_root, entry.path())
+            .symlink_metadata()
+            .is_ok()
+        {
+            preview.conflicts += 1;
+        }
+    }
+
+    preview
+}
+
+fn set_times(metadata: &std::fs::Metadata, path: &Path) {
+    let atime = nix::sys::time::TimeSpec::new(metadata.atime(), metadata.atime_nsec());
+    let mtime = nix::sys::time::TimeSpec::new(metadata.mtime(), metadata.mtime_nsec());
+
+    if let Err(err) = nix::sys::stat::utimensat(
+        None,
+        path,
+        &atime,
+        &mtime,
+        nix::sys::stat::UtimensatFlags::NoFollowSymlink,
+    ) {
+        debug!("Failed to restore times of {:?}: {}", path, err);
+    }
+}
+
+/// Returns the path the entry has been written to, if any
+///
+/// The permission bits in `umask` are removed from restored files. Directories
+/// are handled by [`copy`].
+fn restore_entry(
+    entry: &walkdir::DirEntry,
+    destination: &Path,
+    policy: ConflictPolicy,
+    umask: u32,
+) -> std::io::Result<Option<PathBuf>> {
+    let file_type = entry.file_type();
+    let metadata = entry.metadata()?;
+    let existing = destination.symlink_metadata().ok();
+
+    let destination = match (existing, policy) {
+        (None, _) => destination.to_path_buf(),
+        (Some(_), ConflictPolicy::Skip) => return Ok(None),
+        (Some(existing), ConflictPolicy::Overwrite) => {
+            if existing.is_dir() {
+                // Never remove whole directories on the system
+                return Ok(None);
+            }
+            std::fs::remove_file(destination)?;
+            destination.to_path_buf()
+        }
+        (Some(_), ConflictPolicy::KeepBoth) => keep_both_path(destination),
+    };
+
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if file_type.is_symlink() {
+        std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &destination)?;
+    } else {
+        std::fs::copy(entry.path(), &destination)?;
+        if umask != 0 {
+            std::fs::set_permissions(
+                &destination,
+                std::fs::Permissions::from_mode(metadata.mode() & 0o7777 & !umask),
+            )?;
+        }
+        set_times(&metadata, &destination);
+    }
+
+    Ok(Some(destination))
+}
+
+/// Copy all files from the mounted archive to their original location
+pub fn restore(
+    archive_root: &Path,
+    policy: ConflictPolicy,
+    ownership: &Ownership,
+    progress: &Progress,
+) -> Summary {
+    copy(
+        archive_root,
+        1,
+        |source| original_path(archive_root, source),
+        policy,
+        ownership,
+        progress,
+    )
+}
+
+/// Copy `path` from the mounted archive, including its content, to its original location
+pub fn restore_path(
+    archive_root: &Path,
+    path: &Path,
+    policy: ConflictPolicy,
+    progress: &Progress,
+) -> Summary {
+    // The archive root itself corresponds to '/'
+    let min_depth = usize::from(path == archive_root);
+
+    copy(
+        path,
+        min_depth,
+        |source| original_path(archive_root, source),
+        policy,
+        &Default::default(),
+        progress,
+    )
+}
+
+/// Copy `path` from the mounted archive, including its content, to `destination`
+///
+/// Used when the original location doesn't match the current system, for
+/// example after the user name changed.
+pub fn restore_path_to(
+    path: &Path,
+    destination: &Path,
+    policy: ConflictPolicy,
+    progress: &Progress,
+) -> Summary {
+    copy(
+        path,
+        0,
+        |source| destination.join(source.strip_prefix(path).unwrap_or(source)),
+        policy,
+        &Default::default(),
+        progress,
+    )
+}
+
+fn copy(
+    path: &Path,
+    min_depth: usize,
+    destination_of: impl Fn(&Path) -> PathBuf,
+    policy: ConflictPolicy,
+    ownership: &Ownership,
+    progress: &Progress,
+) -> Summary {
+    let mut summary = Summary::default();
+    let umask = super::permissions::umask();
+    // Set times of directories after their content has been written
+    let mut directories = Vec::new();
+
+    for entry in walkdir::WalkDir::new(path).min_depth(min_depth) {
+        if progress.cancel.load(Ordering::Relaxed) {
+            summary.cancelled = true;
+            break;
+        }
+
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                let path = err.path().map(Path::to_path_buf).unwrap_or_default();
+                summary.failed.push((path, err.to_string()));
+                continue;
+            }
+        };
+
+        let destination = destination_of(entry.path());
+
+        if entry.file_type().is_dir() {
+            match destination.symlink_metadata() {
+                Ok(existing) if existing.is_dir() => {}
+                Ok(_) => {
+                    // A file is in the way of the directory
+                    summary
+                        .failed
+                        .push((destination, gettext("Not a directory")));
+                }
+                // Not accessible for others while the content is restored
+                Err(_) => match std::fs::DirBuilder::new()
+                    .recursive(true)
+                    .mode(0o777 & !umask)
+                    .create(&destination)
+                {
+                    Ok(()) => {
+                        // Only files are counted
+                        let _ignore = ownership.apply(&destination, &destination);
+                        if let Ok(metadata) = entry.metadata() {
+                            directories.push((metadata, destination));
+                        }
+                    }
+                    Err(err) => summary.failed.push((destination, err.to_string())),
+                },
+            }
+            continue;
+        }
+
+        match restore_entry(&entry, &destination, policy, umask) {
+            Ok(Some(path)) => {
+                summary.restored += 1;
+                if ownership.apply(&destination, &path) {
+                    summary.ownership_adjusted += 1;
+                }
+            }
+            Ok(None) => summary.skipped += 1,
+            Err(err) => summary.failed.push((destination, err.to_string())),
+        }
+
+        progress.files_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    for (metadata, path) in directories.iter().rev() {
+        // Applied last since the archived permissions might not allow writing
+        if umask != 0 {
+            if let Err(err) = std::fs::set_permissions(
+                path,
+                std::fs::Permissions::from_mode(metadata.mode() & 0o7777 & !umask),
+            ) {
+                debug!("Failed to set permissions of {:?}: {}", path, err);
+            }
+        }
+        set_times(metadata, path);
+    }
+
+    summary
+}
+
+#[test]
+fn test_restore_conflicts() {
+    let archive = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+
+    let relative = target.path().strip_prefix("/").unwrap();
+    std::fs::create_dir_all(archive.path().join(relative).join("dir")).unwrap();
+    std::fs::write(archive.path().join(relative).join("dir/new"), "archive").unwrap();
+    std::fs::write(archive.path().join(relative).join("existing"), "archive").unwrap();
+    std::fs::write(target.path().join("existing"), "current").unwrap();
+
+    let preview = preview(archive.path());
+    assert_eq!(preview.files, 2);
+    assert_eq!(preview.conflicts, 1);
+
+    let summary = restore(
+        archive.path(),
+        ConflictPolicy::Skip,
+        &Default::default(),
+        &Default::default(),
+    );
+    assert_eq!((summary.restored, summary.skipped), (1, 1));
+    assert_eq!(
+        std::fs::read_to_string(target.path().join("existing")).unwrap(),
+        "current"
+    );
+
+    assert_eq!(
+        keep_both_path(&target.path().join("notes.txt")),
+        target.path().join("notes.restored.txt")
+    );
+
+    restore(
+        archive.path(),
+        ConflictPolicy::KeepBoth,
+        &Default::default(),
+        &Default::default(),
+    );
+    assert_eq!(
+        std::fs::read_to_string(target.path().join("existing.restored")).unwrap(),
+        "archive"
+    );
+    assert_eq!(
+        std::fs::read_to_string(target.path().join("dir/new.restored")).unwrap(),
+        "archive"
+    );
+
+    restore(
+        archive.path(),
+        ConflictPolicy::Overwrite,
+        &Default::default(),
+        &Default::default(),
+    );
+    assert_eq!(
+        std::fs::read_to_string(target.path().join("existing")).unwrap(),
+        "archive"
+    );
+}
+
+#[test]
+fn test_restore_path_to() {
+    let archive = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+
+    let app = archive.path().join("home/old/.var/app/org.example.App");
+    std::fs::create_dir_all(app.join("config")).unwrap();
+    std::fs::write(app.join("config/settings"), "archive").unwrap();
+
+    let destination = target.path().join(".var/app/org.example.App");
+    let summary = restore_path_to(
+        &app,
+        &destination,
+        ConflictPolicy::Skip,
+        &Default::default(),
+    );
+
+    assert_eq!(summary.restored, 1);
+    assert_eq!(
+        std::fs::read_to_string(destination.join("config/settings")).unwrap(),
+        "archive"
+    );
+}
+
+#[test]
+fn test_ownership() {
+    // Independent of the users known to the system running the test
+    let current_user = "pika-test-current-user";
+    let owner = |user: &str| Owner {
+        user: user.to_string(),
+        group: "pika-test-missing-group".to_string(),
+    };
+
+    let owners = BTreeMap::from([
+        (PathBuf::from("/own"), owner(current_user)),
+        (PathBuf::from("/other"), owner("pika-test-missing-user")),
+    ]);
+
+    let ownership = Ownership {
+        policy: OwnershipPolicy::CurrentUser,
+        owners: owners.clone(),
+        current_user: Some(current_user.to_string()),
+    };
+    assert_eq!(ownership.foreign_files(), 1);
+    assert!(!ownership.apply(Path::new("/own"), Path::new("/own")));
+    assert!(ownership.apply(Path::new("/other"), Path::new("/other")));
+    assert!(!ownership.apply(Path::new("/unknown"), Path::new("/unknown")));
+
+    // Users that don't exist on this system can't be mapped
+    let ownership = Ownership {
+        policy: OwnershipPolicy::Original,
+        owners,
+        current_user: Some(current_user.to_string()),
+    };
+    assert!(ownership.apply(Path::new("/other"), Path::new("/other")));
+}
@@ -2,12 +2,14 @@ mod encryption_preferences_group;
 mod location_tag;
 mod status_icon;
 mod status_row;
+mod usage_bar;
 mod wrap_box;
 
 pub use encryption_preferences_group::EncryptionPreferencesGroup;
 pub use location_tag::LocationTag;
 pub use status_icon::StatusIcon;
 pub use status_row::StatusRow;
+pub use usage_bar::UsageBar;
 pub use wrap_box::WrapBox;
 
 use crate::ui;
@@ -23,5 +25,6 @@ pub fn init() {
     EncryptionPreferencesGroup::static_type();
     StatusIcon::static_type();
     StatusRow::static_type();
+    UsageBar::static_type();
     WrapBox::static_type();
 }
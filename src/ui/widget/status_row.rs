@@ -81,9 +81,26 @@ impl StatusRow {
 
     pub fn set_from_backup_status(&self, status: &backup_status::Display) {
         self.set_title(&glib::markup_escape_text(&status.title));
+        self.set_details_from_backup_status(status);
+    }
+
+    /// Everything but the title, for rows with the title bound to the phase of an operation
+    pub fn set_details_from_backup_status(&self, status: &backup_status::Display) {
         self.set_subtitle(&glib::markup_escape_text(
             status.subtitle.as_deref().unwrap_or(""),
         ));
         self.imp().status_icon.set_from_graphic(&status.graphic);
     }
+
+    /// Show the phase of the status as title
+    pub fn bind_phase(
+        &self,
+        status: &crate::ui::operation_status::OperationStatus,
+    ) -> glib::Binding {
+        status
+            .bind_property("phase", self, "title")
+            .transform_to(|_, phase: String| Some(glib::markup_escape_text(&phase).to_string()))
+            .sync_create()
+            .build()
+    }
 }
@@ -0,0 +1,131 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+/// Number of colors the segments cycle through
+const COLORS: usize = 6;
+
+glib::wrapper! {
+    pub struct UsageBar(ObjectSubclass<imp::UsageBar>)
+        @extends gtk::Widget;
+}
+
+impl UsageBar {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Show one segment per value, with a width proportional to the value
+    ///
+    /// Segment `i` has the CSS class `usage-color-{i % COLORS}`.
+    pub fn set_values(&self, values: &[u64]) {
+        let imp = self.imp();
+
+        for (child, _) in imp.segments.take() {
+            child.unparent();
+        }
+
+        let segments = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let child = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+                child.add_css_class("usage-segment");
+                child.add_css_class(&Self::color_class(i));
+                child.set_parent(self);
+                (child.upcast(), *value)
+            })
+            .collect();
+
+        imp.segments.replace(segments);
+        self.queue_resize();
+    }
+
+    /// CSS class of the segment at `index`
+    pub fn color_class(index: usize) -> String {
+        format!("usage-color-{}", index % COLORS)
+    }
+}
+
+impl Default for UsageBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+mod imp {
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+
+    use std::cell::RefCell;
+
+    #[derive(Debug, Default)]
+    pub struct UsageBar {
+        pub segments: RefCell<Vec<(gtk::Widget, u64)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for UsageBar {
+        const NAME: &'static str = "PikaUsageBar";
+        type Type = super::UsageBar;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("usagebar");
+        }
+    }
+
+    impl ObjectImpl for UsageBar {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_hexpand(true);
+            self.obj().set_overflow(gtk::Overflow::Hidden);
+        }
+
+        fn dispose(&self) {
+            for (child, _) in self.segments.take() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for UsageBar {
+        fn measure(&self, orientation: gtk::Orientation, for_size: i32) -> (i32, i32, i32, i32) {
+            let (minimum, natural) = self
+                .segments
+                .borrow()
+                .iter()
+                .map(|(child, _)| {
+                    let (minimum, natural, _, _) = child.measure(orientation, for_size);
+                    (minimum, natural)
+                })
+                .fold((0, 0), |(min_max, nat_max), (minimum, natural)| {
+                    (min_max.max(minimum), nat_max.max(natural))
+                });
+
+            match orientation {
+                // Segments are shrunk to their share of the width
+                gtk::Orientation::Horizontal => (0, natural, -1, -1),
+                _ => (minimum, natural, -1, -1),
+            }
+        }
+
+        fn size_allocate(&self, width: i32, height: i32, _baseline: i32) {
+            let segments = self.segments.borrow();
+            let total: u64 = segments.iter().map(|(_, value)| value).sum();
+            let mut sum = 0;
+            let mut x = 0;
+
+            for (child, value) in segments.iter() {
+                sum += value;
+                let end = if total == 0 {
+                    0
+                } else {
+                    (width as f64 * sum as f64 / total as f64).round() as i32
+                };
+
+                child.size_allocate(&gtk::Allocation::new(x, 0, end - x, height), -1);
+                x = end;
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Separate window showing the status and setup of one backup
+//!
+//! Unlike the detail pages of the main window, every window keeps its own
+//! widgets and only refers to its backup by ID, never to the active backup of
+//! the main window. This allows to watch a running backup while the main
+//! window is used to configure another one. Dialogs opened from the window use
+//! it as parent via [`active_window`].
+
+use adw::prelude::*;
+
+use num_format::ToFormattedString;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::borg;
+use crate::config;
+use crate::config::history::RunInfo;
+use crate::ui;
+use crate::ui::backup_status;
+use crate::ui::builder::WindowBackup;
+use crate::ui::operation_status::OperationStatus;
+use crate::ui::prelude::*;
+
+thread_local!(
+    static WINDOWS: RefCell<BTreeMap<ConfigId, WindowBackup>> = Default::default();
+);
+
+/// Open the window for `config_id` or bring it to the front if it's already open
+pub fn show(config_id: &ConfigId) -> Result<()> {
+    if let Some(ui) = WINDOWS.with(|windows| windows.borrow().get(config_id).cloned()) {
+        ui.window().present();
+        return Ok(());
+    }
+
+    let config = BACKUP_CONFIG.load().try_get(config_id)?.clone();
+
+    let ui = WindowBackup::new();
+    ui.window().set_application(Some(&adw_app()));
+
+    let target = config.id.to_variant();
+    ui.start().set_action_target_value(Some(&target));
+    ui.abort().set_action_target_value(Some(&target));
+
+    let status = OperationStatus::get(config_id);
+    status
+        .bind_property("running", &ui.start(), "visible")
+        .invert_boolean()
+        .sync_create()
+        .build();
+    status
+        .bind_property("running", &ui.abort(), "visible")
+        .sync_create()
+        .build();
+    status
+        .bind_property("fraction", &ui.progress(), "fraction")
+        .sync_create()
+        .build();
+    status
+        .bind_property("has-progress", &ui.progress(), "visible")
+        .sync_create()
+        .build();
+    ui.status().bind_phase(&status);
+
+    let id = config.id.clone();
+    ui.edit().connect_clicked(move |_| {
+        ui::page_backup::view_backup_conf(&id);
+        main_ui().window().present();
+    });
+
+    // Changes from the main window are applied when returning to this window
+    let id = config.id.clone();
+    ui.window().connect_is_active_notify(move |window| {
+        if window.is_active() {
+            refresh_config(&id);
+        }
+    });
+
+    let id = config.id.clone();
+    ui.window().connect_close_request(move |_| {
+        WINDOWS.with(|windows| windows.borrow_mut().remove(&id));
+        glib::Propagation::Proceed
+    });
+
+    WINDOWS.with(|windows| windows.borrow_mut().insert(config_id.clone(), ui.clone()));
+
+    refresh_status(config_id);
+    refresh_config(config_id);
+    ui.window().present();
+
+    Ok(())
+}
+
+/// Close the window, for example because the backup has been removed
+pub fn close(config_id: &ConfigId) {
+    if let Some(ui) = WINDOWS.with(|windows| windows.borrow_mut().remove(config_id)) {
+        ui.window().destroy();
+    }
+}
+
+/// Show the current setup of the backup
+pub fn refresh_config(config_id: &ConfigId) {
+    let Some(ui) = WINDOWS.with(|windows| windows.borrow().get(config_id).cloned()) else {
+        return;
+    };
+
+    let Ok(config) = BACKUP_CONFIG.load().try_get(config_id).cloned() else {
+        return;
+    };
+
+    ui.window().set_title(Some(&config.title()));
+    ui.title().set_title(&config.title());
+    ui.title().set_subtitle(&config.repo.subtitle());
+
+    if let Ok(icon) = gio::Icon::for_string(&config.repo.icon()) {
+        ui.repo_icon().set_from_gicon(&icon);
+    }
+    ui::utils::set_accent_color(&ui.repo_icon(), config.accent_color);
+    ui.repo_row().set_subtitle(&config.repo.subtitle());
+
+    ui::utils::clear(&ui.include_list());
+    for path in &config.include {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(if path.as_os_str().is_empty() {
+                gettext("Home")
+            } else {
+                path.display().to_string()
+            })
+            .build();
+
+        if let Some(image) = crate::utils::file_symbolic_icon(&config::absolute(path)) {
+            image.add_css_class("row-icon");
+            row.add_prefix(&image);
+        }
+
+        ui.include_list().append(&row);
+    }
+
+    for pattern in &config.include_patterns {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(pattern.display().to_string())
+            .subtitle(gettext("Pattern"))
+            .build();
+        ui.include_list().append(&row);
+    }
+
+    ui::utils::clear(&ui.exclude_list());
+    for exclude in &config.exclude {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(exclude.description())
+            .subtitle(exclude.kind())
+            .build();
+
+        if let Some(image) = exclude.symbolic_icon() {
+            image.add_css_class("row-icon");
+            row.add_prefix(&image);
+        }
+
+        ui.exclude_list().append(&row);
+    }
+    ui.exclude_group().set_visible(!config.exclude.is_empty());
+
+    glib::MainContext::default().spawn_local(async move {
+        let schedule = ui::page_schedule::status::Status::new(&config).await;
+        ui::utils::clear(&ui.schedule_list());
+        ui.schedule_list().append(&schedule.main);
+    });
+}
+
+pub fn refresh_status(config_id: &ConfigId) {
+    let Some(ui) = WINDOWS.with(|windows| windows.borrow().get(config_id).cloned()) else {
+        return;
+    };
+
+    let status = backup_status::Display::new_from_id(config_id);
+    ui.status().set_details_from_backup_status(&status);
+
+    match &status.stats {
+        Some(backup_status::Stats::Final(RunInfo {
+            outcome: borg::Outcome::Completed { stats },
+            ..
+        })) => {
+            set_stats(
+                &ui,
+                stats.archive.stats.original_size,
+                stats.archive.stats.deduplicated_size,
+                stats.archive.stats.nfiles,
+            );
+            ui.path_row().set_visible(false);
+        }
+        Some(backup_status::Stats::Progress(progress)) => {
+            set_stats(
+                &ui,
+                progress.original_size,
+                progress.deduplicated_size,
+                progress.nfiles,
+            );
+            ui.path_row().set_visible(true);
+            ui.current_path()
+                .set_text(&format!("/{}", borg::escape_control_chars(&progress.path)));
+        }
+        _ => ui.stats().set_visible(false),
+    }
+
+    if let Some(backup_status::Stats::Final(run_info)) = &status.stats {
+        let mut message = String::new();
+
+        if !matches!(run_info.outcome, borg::Outcome::Completed { .. }) {
+            message.push_str(&run_info.outcome.to_string());
+            message.push_str("\n\n");
+        }
+
+        message.push_str(&run_info.messages.clone().filter_hidden().to_string());
+
+        ui.error().set_text(&message);
+        ui.error().set_visible(true);
+    } else {
+        ui.error().set_visible(false);
+    }
+}
+
+fn set_stats(ui: &WindowBackup, original_size: u64, deduplicated_size: u64, nfiles: u64) {
+    ui.stats().set_visible(true);
+    ui.original_size()
+        .set_text(&glib::format_size(original_size));
+    ui.deduplicated_size()
+        .set_text(&glib::format_size(deduplicated_size));
+    ui.nfiles()
+        .set_text(&nfiles.to_formatted_string(&*LC_LOCALE));
+}
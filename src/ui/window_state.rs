@@ -0,0 +1,119 @@
+//! Restore window size and the last viewed backup configuration
+//!
+//! The state is stored in GSettings. If the schema is not installed, for
+//! example when running from the build directory, nothing is remembered.
+
+use adw::prelude::*;
+
+use crate::ui;
+use crate::ui::prelude::*;
+
+thread_local!(
+    static SETTINGS: Option<gio::Settings> = crate::utils::app_settings();
+);
+
+fn with_settings(f: impl FnOnce(&gio::Settings) -> std::result::Result<(), glib::BoolError>) {
+    SETTINGS.with(|settings| {
+        if let Some(settings) = settings {
+            if let Err(err) = f(settings) {
+                warn!("Failed to store window state: {}", err);
+            }
+        }
+    });
+}
+
+fn detail_page_name() -> Option<&'static str> {
+    let page = main_ui().detail_stack().visible_child()?;
+
+    [
+        (main_ui().page_backup(), "backup"),
+        (main_ui().page_archives(), "archives"),
+        (main_ui().page_schedule(), "schedule"),
+    ]
+    .into_iter()
+    .find(|(widget, _)| widget.clone().upcast::<gtk::Widget>() == page)
+    .map(|(_, name)| name)
+}
+
+pub fn init() {
+    main_ui()
+        .navigation_view()
+        .connect_visible_page_notify(|_| save_page());
+
+    main_ui()
+        .detail_stack()
+        .connect_visible_child_notify(|_| save_page());
+}
+
+/// Restore the window size and the last viewed page
+///
+/// Returns `false` if no backup configuration has been restored.
+pub fn restore() -> bool {
+    let mut restored = false;
+
+    SETTINGS.with(|settings| {
+        let Some(settings) = settings else {
+            return;
+        };
+
+        main_ui()
+            .window()
+            .set_default_size(settings.int("window-width"), settings.int("window-height"));
+
+        if settings.boolean("window-maximized") {
+            main_ui().window().maximize();
+        }
+
+        let config_id = ConfigId::new(settings.string("last-config-id").to_string());
+
+        if BACKUP_CONFIG.load().try_get(&config_id).is_ok() {
+            match settings.string("last-detail-page").as_str() {
+                "archives" => ui::page_archives::view(&config_id),
+                "schedule" => ui::page_schedule::view(&config_id),
+                _ => ui::page_backup::view_backup_conf(&config_id),
+            }
+            restored = true;
+        }
+    });
+
+    restored
+}
+
+/// Remember the window size, called when the window is closed
+pub fn save_window_size() {
+    let window = main_ui().window();
+    let (width, height) = window.default_size();
+
+    with_settings(|settings| {
+        settings.set_boolean("window-maximized", window.is_maximized())?;
+
+        if !window.is_maximized() {
+            settings.set_int("window-width", width)?;
+            settings.set_int("window-height", height)?;
+        }
+
+        Ok(())
+    });
+}
+
+fn save_page() {
+    let config_id = if ui::page_detail::is_navigation_page_visible() {
+        ACTIVE_BACKUP_ID.load().as_ref().as_ref().cloned()
+    } else {
+        None
+    };
+
+    with_settings(|settings| {
+        if let Some(config_id) = &config_id {
+            settings.set_string("last-config-id", config_id.as_str())?;
+
+            if let Some(page) = detail_page_name() {
+                settings.set_string("last-detail-page", page)?;
+            }
+        } else {
+            settings.set_string("last-config-id", "")?;
+        }
+
+        Ok(())
+    });
+}
@@ -1,6 +1,14 @@
+pub mod backup_tools;
 pub mod dbus;
+pub mod flatpak_apps;
 pub mod host;
+pub mod http;
+pub mod include_check;
 pub mod password;
+pub mod polkit;
+pub mod shared_status;
+pub mod tracker;
+pub mod udisks;
 pub mod upower;
 
 use crate::config;
@@ -9,8 +17,9 @@ use async_std::prelude::*;
 
 use gio::prelude::*;
 use std::convert::TryInto;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
+use std::os::unix::ffi::OsStrExt;
 
 pub trait LookupConfigId {
     type Item;
@@ -30,6 +39,56 @@ pub fn posix_fnmatch(pattern: &CStr, string: &CStr) -> bool {
     unsafe { fnmatch(pattern.as_ptr(), string.as_ptr(), 0) == 0 }
 }
 
+/// Expand a glob pattern into the existing paths it matches
+///
+/// Wildcards only match within a single path component. Hidden files are only matched if the
+/// pattern component starts with a dot.
+pub fn glob_paths(pattern: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let mut paths = vec![std::path::PathBuf::new()];
+
+    for component in pattern.components() {
+        let component = component.as_os_str().as_bytes();
+
+        if !component.iter().any(|c| matches!(c, b'*' | b'?' | b'[')) {
+            for path in &mut paths {
+                path.push(std::ffi::OsStr::from_bytes(component));
+            }
+            continue;
+        }
+
+        let Ok(c_pattern) = CString::new(component) else {
+            return Vec::new();
+        };
+        let match_hidden = component.first() == Some(&b'.');
+
+        let mut matches = Vec::new();
+        for path in &paths {
+            let Ok(dir) = std::fs::read_dir(path) else {
+                continue;
+            };
+
+            for entry in dir.flatten() {
+                let name = entry.file_name();
+                if !match_hidden && name.as_bytes().first() == Some(&b'.') {
+                    continue;
+                }
+
+                if let Ok(c_name) = CString::new(name.as_bytes()) {
+                    if posix_fnmatch(&c_pattern, &c_name) {
+                        matches.push(path.join(&name));
+                    }
+                }
+            }
+        }
+
+        matches.sort();
+        paths = matches;
+    }
+
+    paths.retain(|path| path.exists());
+    paths
+}
+
 pub fn init_gettext() {
     gettextrs::setlocale(gettextrs::LocaleCategory::LcAll, "");
     debug!(
@@ -82,3 +141,80 @@ pub fn file_symbolic_icon(path: &std::path::Path) -> Option<gtk::Image> {
         Err(_) => None,
     }
 }
+
+/// Seconds since boot from the content of `/proc/uptime`
+fn parse_uptime(content: &str) -> Option<f64> {
+    content.split_whitespace().next()?.parse().ok()
+}
+
+/// Application settings stored in GSettings
+///
+/// Returns `None` if the schema is not installed, for example when running
+/// from the build directory.
+pub fn app_settings() -> Option<gio::Settings> {
+    let schema = gio::SettingsSchemaSource::default()?.lookup(crate::APP_ID, true);
+
+    if schema.is_none() {
+        debug!("GSettings schema not installed, using default settings");
+    }
+
+    Some(gio::Settings::new_full(
+        &schema?,
+        None::<&gio::SettingsBackend>,
+        None,
+    ))
+}
+
+/// Whether the power saver profile is active
+///
+/// Uses the power profile portal inside of Flatpak.
+pub fn power_saver_enabled() -> bool {
+    gio::PowerProfileMonitor::dup_default().is_power_saver_enabled()
+}
+
+/// Time the system was booted, including time spent suspended
+pub fn boot_time() -> Option<chrono::DateTime<chrono::Local>> {
+    let uptime = parse_uptime(&std::fs::read_to_string("/proc/uptime").ok()?)?;
+
+    Some(chrono::Local::now() - chrono::Duration::milliseconds((uptime * 1000.) as i64))
+}
+
+/// Flatpak runtime like `org.gnome.Platform/x86_64/46`, if running sandboxed
+pub fn flatpak_runtime() -> Option<String> {
+    let key_file = glib::KeyFile::new();
+    key_file
+        .load_from_file("/.flatpak-info", glib::KeyFileFlags::NONE)
+        .ok()?;
+    let runtime = key_file.string("Application", "runtime").ok()?;
+
+    Some(
+        runtime
+            .strip_prefix("runtime/")
+            .unwrap_or(&runtime)
+            .to_string(),
+    )
+}
+
+#[test]
+fn test_parse_uptime() {
+    assert_eq!(parse_uptime("3522.14 13673.89\n"), Some(3522.14));
+    assert_eq!(parse_uptime(""), None);
+}
+
+#[test]
+fn test_glob_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    for path in ["a/src", "b/src", "b/doc", ".hidden/src"] {
+        std::fs::create_dir_all(dir.path().join(path)).unwrap();
+    }
+
+    assert_eq!(
+        glob_paths(&dir.path().join("*/src")),
+        vec![dir.path().join("a/src"), dir.path().join("b/src")]
+    );
+    assert_eq!(
+        glob_paths(&dir.path().join(".*/src")),
+        vec![dir.path().join(".hidden/src")]
+    );
+    assert!(glob_paths(&dir.path().join("*/missing")).is_empty());
+}
@@ -0,0 +1,72 @@
+//! Folders of other backup tools
+//!
+//! Backing up the snapshots or staging folders of other backup tools lets the
+//! backups grow with every run of either tool.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupTool {
+    DejaDup,
+    Timeshift,
+    Syncthing,
+}
+
+/// The backup tool that uses the folder `path`
+///
+/// Only looks at the folder names, except for Timeshift which is confirmed by
+/// its snapshot folder.
+pub fn folder_of(path: &Path) -> Option<BackupTool> {
+    let name = path.file_name()?.to_str()?;
+    let parent = path
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|x| x.to_str());
+
+    match (name, parent) {
+        ("deja-dup", Some(".cache")) => Some(BackupTool::DejaDup),
+        (".stversions", _) => Some(BackupTool::Syncthing),
+        ("timeshift", _) if path.join("snapshots").is_dir() => Some(BackupTool::Timeshift),
+        ("timeshift-btrfs", _) => Some(BackupTool::Timeshift),
+        _ => None,
+    }
+}
+
+/// Whether the file is part of a backup created by Déjà Dup
+///
+/// Backups are stored by duplicity, the folder containing the file is the
+/// backup location.
+pub fn is_deja_dup_backup_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|x| x.to_str())
+        .is_some_and(|name| name.starts_with("duplicity-full.") && name.contains(".manifest"))
+}
+
+#[test]
+fn test_folder_of() {
+    assert_eq!(
+        folder_of(Path::new("/home/user/.cache/deja-dup")),
+        Some(BackupTool::DejaDup)
+    );
+    assert_eq!(folder_of(Path::new("/home/user/deja-dup")), None);
+    assert_eq!(
+        folder_of(Path::new("/home/user/Sync/.stversions")),
+        Some(BackupTool::Syncthing)
+    );
+    assert_eq!(
+        folder_of(Path::new("/run/timeshift-btrfs")),
+        Some(BackupTool::Timeshift)
+    );
+    assert_eq!(folder_of(Path::new("/nonexistent/timeshift")), None);
+    assert_eq!(folder_of(Path::new("/")), None);
+}
+
+#[test]
+fn test_is_deja_dup_backup_file() {
+    assert!(is_deja_dup_backup_file(Path::new(
+        "/media/disk/backup/duplicity-full.20240101T120000Z.manifest.gpg"
+    )));
+    assert!(!is_deja_dup_backup_file(Path::new(
+        "/media/disk/backup/duplicity-full.20240101T120000Z.vol1.difftar.gz"
+    )));
+}
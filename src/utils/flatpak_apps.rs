@@ -0,0 +1,124 @@
+//! Data of Flatpak applications
+//!
+//! Flatpak stores the data of every application in `~/.var/app/<app-id>`.
+
+use std::path::{Path, PathBuf};
+
+/// Location of app data relative to the home directory
+pub const DATA_DIR: &str = ".var/app";
+
+/// Directory depth below the archive root that is searched for app data
+const ARCHIVE_SEARCH_DEPTH: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct App {
+    /// Application ID like `org.gnome.World.PikaBackup`
+    pub id: String,
+    /// Folder containing the data of the app
+    pub path: PathBuf,
+}
+
+impl App {
+    /// Location of the app data in the home directory `home`
+    pub fn data_dir(&self, home: &Path) -> PathBuf {
+        home.join(DATA_DIR).join(&self.id)
+    }
+}
+
+/// Application IDs consist of at least three elements separated by dots
+fn is_app_id(name: &str) -> bool {
+    name.split('.').count() >= 3
+        && name.split('.').all(|element| {
+            !element.is_empty() && !element.starts_with(|c: char| c.is_ascii_digit())
+        })
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn apps_in(dir: &Path) -> Vec<App> {
+    let mut apps: Vec<App> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_ok_and(|x| x.is_dir()))
+                .filter_map(|entry| {
+                    let id = entry.file_name().to_str()?.to_string();
+                    is_app_id(&id).then(|| App {
+                        id,
+                        path: entry.path(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    apps.sort();
+    apps
+}
+
+/// Apps that have stored data in the home directory `home`
+pub fn installed(home: &Path) -> Vec<App> {
+    apps_in(&home.join(DATA_DIR))
+}
+
+/// Apps with data in a mounted archive
+///
+/// The archive can contain the home directories of other users or of a
+/// previous installation. If an app appears more than once, the first
+/// occurrence is used.
+pub fn in_archive(archive_root: &Path) -> Vec<App> {
+    let mut apps = Vec::<App>::new();
+
+    for entry in walkdir::WalkDir::new(archive_root)
+        .max_depth(ARCHIVE_SEARCH_DEPTH)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        if entry.file_type().is_dir() && entry.path().ends_with(DATA_DIR) {
+            for app in apps_in(entry.path()) {
+                if !apps.iter().any(|x| x.id == app.id) {
+                    apps.push(app);
+                }
+            }
+        }
+    }
+
+    apps.sort();
+    apps
+}
+
+#[test]
+fn test_is_app_id() {
+    assert!(is_app_id("org.gnome.World.PikaBackup"));
+    assert!(is_app_id("com.example.App_Name-2"));
+    assert!(!is_app_id("org.gnome"));
+    assert!(!is_app_id("org..example"));
+    assert!(!is_app_id("org.example.1app"));
+    assert!(!is_app_id("org.example/app"));
+}
+
+#[test]
+fn test_in_archive() {
+    let archive = tempfile::tempdir().unwrap();
+    let data = archive.path().join("home/user").join(DATA_DIR);
+
+    std::fs::create_dir_all(data.join("org.gnome.Builder/config")).unwrap();
+    std::fs::create_dir_all(data.join("org.mozilla.firefox")).unwrap();
+    std::fs::create_dir_all(data.join("no-app-id")).unwrap();
+    std::fs::write(data.join("org.example.File"), "").unwrap();
+
+    let apps = in_archive(archive.path());
+    assert_eq!(
+        apps.iter().map(|x| x.id.as_str()).collect::<Vec<_>>(),
+        vec!["org.gnome.Builder", "org.mozilla.firefox"]
+    );
+    assert_eq!(apps[0].path, data.join("org.gnome.Builder"));
+    assert_eq!(
+        apps[0].data_dir(Path::new("/home/other")),
+        PathBuf::from("/home/other/.var/app/org.gnome.Builder")
+    );
+
+    assert_eq!(installed(&archive.path().join("home/user")), apps);
+}
@@ -0,0 +1,116 @@
+//! Client for JSON web APIs
+//!
+//! Uses libsoup, which handles redirects, proxies and keep-alive connections.
+
+use crate::prelude::*;
+use gio::prelude::*;
+use glib::translate::IntoGlib;
+use soup::prelude::*;
+
+/// Responses of the used APIs are small, larger responses are not accepted
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+const READ_SIZE: usize = 16 * 1024;
+const TIMEOUT_SECONDS: u32 = 30;
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        GLib(err: glib::Error) {
+            from()
+            display("{}", err.message())
+        }
+        Json(err: serde_json::Error) {
+            from()
+            display("{}", err)
+        }
+        InvalidResponse {
+            display("{}", gettext("The server sent an invalid response."))
+        }
+        TooLarge {
+            display("{}", gettext("The response of the server is too large."))
+        }
+        Status(code: u16) {
+            display("{}", gettextf("The server responded with status {}.", &[&code.to_string()]))
+        }
+    }
+}
+
+fn session() -> soup::Session {
+    let session = soup::Session::new();
+    session.set_user_agent(concat!(
+        env!("CARGO_PKG_NAME"),
+        "/",
+        env!("CARGO_PKG_VERSION")
+    ));
+    session.set_timeout(TIMEOUT_SECONDS);
+
+    session
+}
+
+/// Send `body` to `url` and parse the response as JSON
+pub async fn post_json(
+    url: &str,
+    bearer_token: Option<&str>,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, Error> {
+    let uri = glib::Uri::parse(url, glib::UriFlags::NONE)?;
+    let message = soup::Message::from_uri("POST", &uri);
+
+    message.set_request_body_from_bytes(
+        Some("application/json"),
+        Some(&glib::Bytes::from_owned(body.to_string().into_bytes())),
+    );
+    if let Some(headers) = message.request_headers() {
+        headers.append("Accept", "application/json");
+        if let Some(token) = bearer_token {
+            headers.append("Authorization", &format!("Bearer {token}"));
+        }
+    }
+
+    let input = session()
+        .send_future(&message, glib::Priority::DEFAULT)
+        .await?;
+
+    let status = message.status().into_glib() as u16;
+    if !(200..300).contains(&status) {
+        return Err(Error::Status(status));
+    }
+
+    let response = read_bounded(&input, MAX_RESPONSE_SIZE).await;
+    let _ignore = input.close_future(glib::Priority::DEFAULT).await;
+
+    Ok(serde_json::from_slice(&response?)?)
+}
+
+/// Read the stream until it ends, failing if it exceeds `limit`
+async fn read_bounded(input: &gio::InputStream, limit: usize) -> Result<Vec<u8>, Error> {
+    let mut response = Vec::new();
+
+    loop {
+        let bytes = input
+            .read_bytes_future(READ_SIZE, glib::Priority::DEFAULT)
+            .await?;
+        if bytes.is_empty() {
+            return Ok(response);
+        }
+        if response.len() + bytes.len() > limit {
+            return Err(Error::TooLarge);
+        }
+        response.extend_from_slice(&bytes);
+    }
+}
+
+#[test]
+fn test_read_bounded() {
+    let input = gio::MemoryInputStream::from_bytes(&glib::Bytes::from_static(b"{\"data\": {}}"));
+    let response = glib::MainContext::default()
+        .block_on(read_bounded(input.upcast_ref(), 12))
+        .unwrap();
+    assert_eq!(response, b"{\"data\": {}}");
+
+    let input = gio::MemoryInputStream::from_bytes(&glib::Bytes::from_static(b"{\"data\": {}}"));
+    assert!(matches!(
+        glib::MainContext::default().block_on(read_bounded(input.upcast_ref(), 11)),
+        Err(Error::TooLarge)
+    ));
+}
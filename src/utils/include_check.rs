@@ -0,0 +1,219 @@
+//! Detection of included folders that can't be backed up reliably
+//!
+//! Inside the Flatpak sandbox only the mounts visible in the sandbox are
+//! considered.
+
+use crate::prelude::*;
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// File systems that don't contain actual files
+const VIRTUAL_FS_TYPES: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "proc",
+    "pstore",
+    "securityfs",
+    "sysfs",
+    "tracefs",
+];
+
+const NETWORK_FS_TYPES: &[&str] = &["9p", "afs", "ceph", "cifs", "davfs", "nfs", "nfs4", "smb3"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// A virtual file system like `/proc` is mounted within the folder
+    VirtualFilesystem(PathBuf),
+    /// A network or FUSE file system is mounted within the folder
+    NetworkFilesystem(PathBuf),
+    /// The folder can't be read, for example the home folder of other users
+    Unreadable,
+}
+
+impl Problem {
+    /// Mount point that can be excluded to fix the problem
+    pub fn mount_point(&self) -> Option<&Path> {
+        match self {
+            Self::VirtualFilesystem(path) | Self::NetworkFilesystem(path) => Some(path),
+            Self::Unreadable => None,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::VirtualFilesystem(path) => gettextf(
+                "Contains the system folder “{}” that can't be backed up",
+                &[&path.display().to_string()],
+            ),
+            Self::NetworkFilesystem(path) => gettextf(
+                "Contains the network or external file system “{}”",
+                &[&path.display().to_string()],
+            ),
+            Self::Unreadable => gettext("The folder can't be read with the current permissions"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub path: PathBuf,
+    pub fs_type: String,
+}
+
+impl Mount {
+    fn problem(&self) -> Option<Problem> {
+        if VIRTUAL_FS_TYPES.contains(&self.fs_type.as_str()) {
+            Some(Problem::VirtualFilesystem(self.path.clone()))
+        } else if NETWORK_FS_TYPES.contains(&self.fs_type.as_str())
+            || self.fs_type == "fuse"
+            || self.fs_type.starts_with("fuse.")
+        {
+            Some(Problem::NetworkFilesystem(self.path.clone()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Currently mounted file systems
+pub fn mounts() -> Vec<Mount> {
+    match std::fs::read_to_string("/proc/self/mountinfo") {
+        Ok(content) => parse_mountinfo(&content),
+        Err(err) => {
+            warn!("Failed to read mounts: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// Replace octal escapes like `\040` for spaces
+fn unescape(s: &str) -> String {
+    let mut result = String::new();
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('\\') {
+        result.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 4);
+
+        match code.and_then(|code| u8::from_str_radix(code, 8).ok()) {
+            Some(byte) => {
+                result.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn parse_mountinfo(content: &str) -> Vec<Mount> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (fields, fs_fields) = line.split_once(" - ")?;
+
+            Some(Mount {
+                path: PathBuf::from(unescape(fields.split(' ').nth(4)?)),
+                fs_type: fs_fields.split(' ').next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Problems of an included folder that are not handled by excludes
+pub fn check(
+    include: &Path,
+    exclude: &BTreeSet<config::Exclude<{ config::ABSOLUTE }>>,
+    mounts: &[Mount],
+) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = mounts
+        .iter()
+        .filter(|mount| mount.path.starts_with(include))
+        .filter(|mount| !exclude.iter().any(|exclude| exclude.is_match(&mount.path)))
+        .filter_map(Mount::problem)
+        .collect();
+
+    if matches!(
+        std::fs::read_dir(include),
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied
+    ) {
+        problems.push(Problem::Unreadable);
+    }
+
+    problems
+}
+
+#[test]
+fn test_parse_mountinfo() {
+    let content = "\
+22 28 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+28 1 253:0 / / rw,relatime shared:1 - ext4 /dev/mapper/root rw
+61 28 0:52 / /home/user/My\\040Server rw,nosuid,nodev,relatime shared:30 - fuse.sshfs user@server: rw,user_id=1000";
+
+    assert_eq!(
+        parse_mountinfo(content),
+        vec![
+            Mount {
+                path: PathBuf::from("/proc"),
+                fs_type: String::from("proc"),
+            },
+            Mount {
+                path: PathBuf::from("/"),
+                fs_type: String::from("ext4"),
+            },
+            Mount {
+                path: PathBuf::from("/home/user/My Server"),
+                fs_type: String::from("fuse.sshfs"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let mount = |path: &str, fs_type: &str| Mount {
+        path: dir.path().join(path),
+        fs_type: fs_type.to_string(),
+    };
+
+    let mounts = vec![
+        mount("", "ext4"),
+        mount("proc", "proc"),
+        mount("server", "fuse.sshfs"),
+        mount("nfs", "nfs4"),
+    ];
+
+    let exclude = BTreeSet::from([config::Exclude::from_pattern(config::Pattern::path_prefix(
+        dir.path().join("nfs"),
+    ))]);
+
+    assert_eq!(
+        check(dir.path(), &exclude, &mounts),
+        vec![
+            Problem::VirtualFilesystem(dir.path().join("proc")),
+            Problem::NetworkFilesystem(dir.path().join("server")),
+        ]
+    );
+
+    assert!(check(&dir.path().join("other"), &exclude, &mounts).is_empty());
+}
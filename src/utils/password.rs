@@ -1,8 +1,13 @@
 use std::{collections::HashMap, sync::RwLock};
 
+use zeroize::Zeroizing;
+
 use crate::config::Password;
 use crate::prelude::*;
 
+/// Maximum time for the password command, it might wait for a PIN entry
+pub const COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
 #[derive(Default)]
 pub struct MemoryPasswordStore {
     passwords: Arc<RwLock<HashMap<ConfigId, Password>>>,
@@ -21,6 +26,56 @@ impl MemoryPasswordStore {
     }
 }
 
+/// Obtain the encryption password from the command configured for the backup
+///
+/// The output of the command is the password, only trailing line breaks are
+/// removed. Works with password managers like `pass show <name>`.
+pub async fn from_command(
+    config: &crate::config::Backup,
+    command: &str,
+) -> std::io::Result<Password> {
+    let env = crate::borg::scripts::script_env_pre(config, false);
+    let home = glib::home_dir().to_string_lossy().to_string();
+    let mut envs: HashMap<&str, &str> = env.iter().map(|(k, v)| (k.name(), v.as_str())).collect();
+    envs.insert("HOME", &home);
+
+    debug!("Running password command:\nbash -c \"{}\"", command);
+
+    let mut cmd = crate::borg::scripts::host_command_kill_on_drop(command, &envs);
+    cmd.stdin(async_std::process::Stdio::null())
+        .stderr(async_std::process::Stdio::null());
+
+    let output = async_std::future::timeout(COMMAND_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                gettext("The password command did not finish in time."),
+            )
+        })??;
+    let stdout = Zeroizing::new(output.stdout);
+
+    if !output.status.success() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            gettextf(
+                "The password command failed with “{}”.",
+                &[&output.status.to_string()],
+            ),
+        ));
+    }
+
+    Ok(password_from_output(stdout))
+}
+
+fn password_from_output(mut output: Zeroizing<Vec<u8>>) -> Password {
+    while matches!(output.last(), Some(b'\n' | b'\r')) {
+        output.pop();
+    }
+
+    Password::from(output)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -41,4 +96,13 @@ mod test {
             Some(password_str.as_bytes().to_vec()),
         );
     }
+
+    #[test]
+    fn test_password_from_output() {
+        let password = password_from_output(Zeroizing::new(b"secret \n".to_vec()));
+        assert_eq!(password.as_bytes(), b"secret ");
+
+        let password = password_from_output(Zeroizing::new(b"secret\r\n\n".to_vec()));
+        assert_eq!(password.as_bytes(), b"secret");
+    }
 }
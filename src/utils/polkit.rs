@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use zbus::zvariant::Value;
+use zbus::Result;
+
+#[zbus::dbus_proxy(
+    default_service = "org.freedesktop.PolicyKit1",
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_path = "/org/freedesktop/PolicyKit1/Authority",
+    assume_defaults = false
+)]
+trait Authority {
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// Let the authentication agent ask the user for the password
+const ALLOW_USER_INTERACTION: u32 = 1;
+
+/// Polkit action that asks for the password of the current user
+pub fn authenticate_action_id() -> String {
+    format!("{}.authenticate", crate::APP_ID)
+}
+
+/// Authorize this process for `action_id`, showing an authentication prompt if required
+pub async fn check_authorization(action_id: &str) -> Result<bool> {
+    query_authorization(action_id, ALLOW_USER_INTERACTION).await
+}
+
+/// Whether polkit is reachable and knows `action_id`
+///
+/// The action is not installed for Flatpak builds since only the host can provide it.
+pub async fn is_available(action_id: &str) -> bool {
+    match query_authorization(action_id, 0).await {
+        Ok(_) => true,
+        Err(err) => {
+            debug!("Polkit action {action_id} not available: {err}");
+            false
+        }
+    }
+}
+
+async fn query_authorization(action_id: &str, flags: u32) -> Result<bool> {
+    let proxy = AuthorityProxy::new(&crate::utils::dbus::system_connection().await?).await?;
+
+    // A start time of zero is looked up by polkit
+    let subject = (
+        "unix-process",
+        HashMap::from([
+            ("pid", Value::from(std::process::id())),
+            ("start-time", Value::from(0_u64)),
+        ]),
+    );
+
+    let (is_authorized, _is_challenge, _details) = proxy
+        .check_authorization(&subject, action_id, HashMap::new(), flags, "")
+        .await?;
+
+    Ok(is_authorized)
+}
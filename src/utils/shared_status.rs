@@ -0,0 +1,207 @@
+/*!
+# Backup status shared with other users
+
+Users can opt in to publish a summary of their backups on the system bus. An
+administrator can then check that the backups of everyone using the computer
+are current. Only titles and the time and outcome of the last backup are
+shared, no paths, archives or passwords.
+
+Every daemon owns the name `<app id>.SharedStatus.u<uid>`. Since the D-Bus
+policy allows everyone to own names with this prefix, the owner of a name is
+verified against the user ID in the name.
+*/
+
+use crate::borg;
+use crate::config;
+use crate::prelude::*;
+
+use chrono::prelude::*;
+
+pub const NAME_PREFIX: &str = const_str::concat!(crate::APP_ID, ".SharedStatus");
+pub const PATH: &str = const_str::concat!(crate::DBUS_API_PATH, "/SharedStatus");
+
+/// Summary of one backup configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, zbus::zvariant::Type)]
+pub struct Summary {
+    pub title: String,
+    /// Unix time of the last completed backup, zero if there is none
+    pub last_completed: i64,
+    /// The last backup run did not fail
+    pub last_run_ok: bool,
+    /// The last completed backup is not older than the schedule allows
+    pub up_to_date: bool,
+}
+
+impl Summary {
+    pub fn new(
+        config: &config::Backup,
+        history: Option<&config::history::History>,
+        now: DateTime<Local>,
+    ) -> Self {
+        let last_completed = history.and_then(|history| history.last_completed.as_ref());
+
+        Self {
+            title: config.title(),
+            last_completed: last_completed
+                .map(|run| run.end.timestamp())
+                .unwrap_or_default(),
+            last_run_ok: history
+                .and_then(|history| history.run.front())
+                .map_or(true, |run| !matches!(run.outcome, borg::Outcome::Failed(_))),
+            up_to_date: last_completed.is_some_and(|run| now - run.end <= max_age(config)),
+        }
+    }
+
+    pub fn last_completed(&self) -> Option<DateTime<Local>> {
+        (self.last_completed != 0)
+            .then(|| Local.timestamp_opt(self.last_completed, 0).single())
+            .flatten()
+    }
+}
+
+/// Age after which the last backup is no longer considered current
+fn max_age(config: &config::Backup) -> chrono::Duration {
+    if !config.schedule.enabled {
+        return chrono::Duration::days(7);
+    }
+
+    // One additional day in case the computer was not in use
+    match config.schedule.frequency {
+        config::Frequency::Hourly => chrono::Duration::days(1),
+        config::Frequency::Daily { .. } => chrono::Duration::days(2),
+        config::Frequency::Weekly { .. } => chrono::Duration::days(8),
+        config::Frequency::Monthly { .. } => chrono::Duration::days(32),
+    }
+}
+
+#[zbus::dbus_proxy(
+    interface = "org.gnome.World.PikaBackup.SharedStatus1",
+    assume_defaults = false,
+    gen_blocking = false
+)]
+trait SharedStatus {
+    fn backups(&self) -> zbus::Result<Vec<Summary>>;
+}
+
+/// Name on the system bus owned by the daemon of user `uid`
+pub fn bus_name(uid: u32) -> String {
+    format!("{NAME_PREFIX}.u{uid}")
+}
+
+fn uid_from_bus_name(name: &str) -> Option<u32> {
+    name.strip_prefix(NAME_PREFIX)?
+        .strip_prefix(".u")?
+        .parse()
+        .ok()
+}
+
+/// Backups of one other user
+#[derive(Debug, Clone)]
+pub struct UserStatus {
+    pub uid: u32,
+    pub user_name: String,
+    pub backups: Vec<Summary>,
+}
+
+/// Status of all other users that share their backup status
+pub async fn discover() -> zbus::Result<Vec<UserStatus>> {
+    let connection = super::dbus::system_connection().await?;
+    let dbus = zbus::fdo::DBusProxy::new(&connection).await?;
+    let own_uid = nix::unistd::getuid().as_raw();
+
+    let mut users = Vec::new();
+
+    for name in dbus.list_names().await? {
+        let Some(uid) = uid_from_bus_name(name.as_str()) else {
+            continue;
+        };
+
+        if uid == own_uid {
+            continue;
+        }
+
+        match dbus.get_connection_unix_user(name.clone().into()).await {
+            Ok(owner) if owner == uid => {}
+            Ok(owner) => {
+                warn!("Ignoring {name}, owned by user {owner}");
+                continue;
+            }
+            Err(err) => {
+                warn!("Cannot determine owner of {name}: {err}");
+                continue;
+            }
+        }
+
+        let proxy = SharedStatusProxy::builder(&connection)
+            .destination(name.clone())?
+            .path(PATH)?
+            .build()
+            .await?;
+
+        let backups = match proxy.backups().await {
+            Ok(backups) => backups,
+            Err(err) => {
+                warn!("Cannot query shared status of {name}: {err}");
+                continue;
+            }
+        };
+
+        let user_name = nix::unistd::User::from_uid(uid.into())
+            .ok()
+            .flatten()
+            .map(|user| {
+                let full_name = user.gecos.to_string_lossy();
+                let full_name = full_name.split(',').next().unwrap_or_default();
+                if full_name.is_empty() {
+                    user.name
+                } else {
+                    full_name.to_string()
+                }
+            })
+            .unwrap_or_else(|| uid.to_string());
+
+        users.push(UserStatus {
+            uid,
+            user_name,
+            backups,
+        });
+    }
+
+    users.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+
+    Ok(users)
+}
+
+#[test]
+fn test_bus_name() {
+    assert_eq!(uid_from_bus_name(&bus_name(1000)), Some(1000));
+    assert_eq!(uid_from_bus_name(&format!("{NAME_PREFIX}.1000")), None);
+    assert_eq!(uid_from_bus_name("org.example.u1000"), None);
+}
+
+#[test]
+fn test_summary_up_to_date() {
+    let mut config = config::Backup::test_new_mock();
+    config.schedule.enabled = true;
+    config.schedule.frequency = config::Frequency::Weekly {
+        preferred_weekday: Weekday::Mon,
+    };
+
+    let now = Local::now();
+    let mut history = config::history::History::default();
+
+    let summary = Summary::new(&config, Some(&history), now);
+    assert!(!summary.up_to_date);
+    assert!(summary.last_run_ok);
+    assert_eq!(summary.last_completed(), None);
+
+    history.insert(config::history::RunInfo::test_new_mock(
+        chrono::Duration::days(3),
+    ));
+    let summary = Summary::new(&config, Some(&history), now);
+    assert!(summary.up_to_date);
+    assert!(summary.last_completed().is_some());
+
+    config.schedule.frequency = config::Frequency::Hourly;
+    assert!(!Summary::new(&config, Some(&history), now).up_to_date);
+}
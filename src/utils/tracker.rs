@@ -0,0 +1,74 @@
+//! Pause the file indexing of tracker-miners while backups are running
+
+use zbus::Result;
+
+#[zbus::dbus_proxy(
+    default_service = "org.freedesktop.Tracker3.Miner.Files",
+    interface = "org.freedesktop.Tracker3.Miner",
+    default_path = "/org/freedesktop/Tracker3/Miner/Files",
+    assume_defaults = false
+)]
+trait Miner {
+    fn pause(&self, application: &str, reason: &str) -> Result<i32>;
+
+    fn resume(&self, cookie: i32) -> Result<()>;
+}
+
+async fn proxy() -> Result<MinerProxy<'static>> {
+    static PROXY: async_lock::Mutex<Option<MinerProxy<'static>>> = async_lock::Mutex::new(None);
+
+    let mut proxy = PROXY.lock().await;
+
+    if let Some(proxy) = &*proxy {
+        Ok(proxy.clone())
+    } else {
+        let new_proxy = MinerProxy::new(&zbus::Connection::session().await?).await?;
+        *proxy = Some(new_proxy.clone());
+        Ok(new_proxy)
+    }
+}
+
+/// Indexing is paused until this is dropped
+///
+/// Tracker also resumes indexing by itself if the process exits.
+pub struct Pause {
+    cookie: i32,
+}
+
+impl Pause {
+    /// Pause indexing, returns `None` if tracker is not available
+    pub async fn new(reason: &str) -> Option<Self> {
+        let result = match proxy().await {
+            Ok(proxy) => proxy.pause(crate::APP_ID, reason).await,
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(cookie) => {
+                debug!("Paused file indexing");
+                Some(Self { cookie })
+            }
+            Err(err) => {
+                warn!("Failed to pause file indexing: {}", err);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for Pause {
+    fn drop(&mut self) {
+        let cookie = self.cookie;
+        glib::MainContext::default().spawn(async move {
+            let result = match proxy().await {
+                Ok(proxy) => proxy.resume(cookie).await,
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(()) => debug!("Resumed file indexing"),
+                Err(err) => warn!("Failed to resume file indexing: {}", err),
+            }
+        });
+    }
+}
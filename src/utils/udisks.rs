@@ -0,0 +1,317 @@
+/*!
+# Health of the drive containing a local backup repository
+
+The SMART status is queried from UDisks2. Drives connected via USB often do
+not pass on SMART data, in which case the health is unknown.
+
+UDisks2 also tells which filesystems are on the same physical drive, such
+that backups to another partition of the drive with the backed up files can
+be detected.
+*/
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::prelude::*;
+use zbus::zvariant::{OwnedObjectPath, Value};
+use zbus::Result;
+
+const SERVICE: &str = "org.freedesktop.UDisks2";
+
+#[zbus::dbus_proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Manager",
+    default_path = "/org/freedesktop/UDisks2/Manager",
+    assume_defaults = false
+)]
+trait Manager {
+    fn get_block_devices(&self, options: HashMap<&str, Value<'_>>) -> Result<Vec<OwnedObjectPath>>;
+}
+
+#[zbus::dbus_proxy(interface = "org.freedesktop.UDisks2.Block", assume_defaults = false)]
+trait Block {
+    #[dbus_proxy(property)]
+    fn device_number(&self) -> Result<u64>;
+    #[dbus_proxy(property)]
+    fn drive(&self) -> Result<OwnedObjectPath>;
+    #[dbus_proxy(property)]
+    fn crypto_backing_device(&self) -> Result<OwnedObjectPath>;
+}
+
+#[zbus::dbus_proxy(
+    interface = "org.freedesktop.UDisks2.Drive.Ata",
+    assume_defaults = false
+)]
+trait Ata {
+    #[dbus_proxy(property)]
+    fn smart_supported(&self) -> Result<bool>;
+    #[dbus_proxy(property)]
+    fn smart_enabled(&self) -> Result<bool>;
+    #[dbus_proxy(property)]
+    fn smart_failing(&self) -> Result<bool>;
+    #[dbus_proxy(property)]
+    fn smart_num_attributes_failing(&self) -> Result<i32>;
+    #[dbus_proxy(property)]
+    fn smart_num_bad_sectors(&self) -> Result<i64>;
+}
+
+#[zbus::dbus_proxy(
+    interface = "org.freedesktop.UDisks2.NVMe.Controller",
+    assume_defaults = false
+)]
+trait NvmeController {
+    #[dbus_proxy(property)]
+    fn smart_critical_warning(&self) -> Result<Vec<String>>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Health {
+    /// No problems reported
+    Good,
+    /// Problems that could lead to data loss
+    Degraded(Vec<String>),
+    /// The drive predicts its own failure
+    Failing,
+}
+
+impl Health {
+    pub fn is_good(&self) -> bool {
+        matches!(self, Self::Good)
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Good => gettext("No problems reported"),
+            Self::Degraded(problems) => problems.join(" "),
+            Self::Failing => gettext(
+                "The drive reports that it is about to fail. Copy the backups to a different drive soon.",
+            ),
+        }
+    }
+
+    fn with_problem(self, problem: String) -> Self {
+        match self {
+            Self::Good => Self::Degraded(vec![problem]),
+            Self::Degraded(mut problems) => {
+                problems.push(problem);
+                Self::Degraded(problems)
+            }
+            Self::Failing => Self::Failing,
+        }
+    }
+}
+
+/// Health of the drive and filesystem containing `path`
+///
+/// Returns `None` if neither the filesystem nor the drive report any status.
+pub async fn health(path: &Path) -> Option<Health> {
+    let read_only = nix::sys::statvfs::statvfs(path)
+        .map(|x| x.flags().contains(nix::sys::statvfs::FsFlags::ST_RDONLY))
+        .unwrap_or_default();
+
+    let smart = match nix::sys::stat::stat(path) {
+        Ok(stat) => smart_health(stat.st_dev).await.unwrap_or_else(|err| {
+            debug!("Failed to query drive health of {:?}: {}", path, err);
+            None
+        }),
+        Err(err) => {
+            debug!("Failed to determine device of {:?}: {}", path, err);
+            None
+        }
+    };
+
+    if read_only {
+        Some(smart.unwrap_or(Health::Good).with_problem(gettext(
+            "The filesystem is read-only, which can be caused by errors on the drive.",
+        )))
+    } else {
+        smart
+    }
+}
+
+async fn block_proxy<'a>(
+    connection: &zbus::Connection,
+    path: OwnedObjectPath,
+) -> Result<BlockProxy<'a>> {
+    BlockProxy::builder(connection)
+        .destination(SERVICE)?
+        .path(path)?
+        .build()
+        .await
+}
+
+fn is_none(path: &OwnedObjectPath) -> bool {
+    path.as_str() == "/"
+}
+
+/// Drive object of the block device `device_number`
+async fn drive(
+    connection: &zbus::Connection,
+    device_number: u64,
+) -> Result<Option<OwnedObjectPath>> {
+    let manager = ManagerProxy::new(connection).await?;
+
+    let mut block = None;
+    for path in manager.get_block_devices(HashMap::new()).await? {
+        let proxy = block_proxy(connection, path).await?;
+        if proxy.device_number().await? == device_number {
+            block = Some(proxy);
+            break;
+        }
+    }
+
+    let Some(mut block) = block else {
+        return Ok(None);
+    };
+
+    // Unlocked encrypted partitions are not directly on a drive
+    let mut drive = block.drive().await?;
+    if is_none(&drive) {
+        let backing_device = block.crypto_backing_device().await?;
+        if is_none(&backing_device) {
+            return Ok(None);
+        }
+        block = block_proxy(connection, backing_device).await?;
+        drive = block.drive().await?;
+    }
+
+    if is_none(&drive) {
+        Ok(None)
+    } else {
+        Ok(Some(drive))
+    }
+}
+
+/// Whether `target` is on the same physical drive as any of the `sources`
+///
+/// Returns `false` if the drives cannot be determined.
+pub async fn shares_drive(target: &Path, sources: &[PathBuf]) -> bool {
+    let Ok(target_device) = nix::sys::stat::stat(target).map(|x| x.st_dev) else {
+        return false;
+    };
+
+    let source_devices = sources
+        .iter()
+        .filter_map(|path| nix::sys::stat::stat(path).ok())
+        .map(|x| x.st_dev)
+        .collect::<BTreeSet<_>>();
+
+    // Same filesystem
+    if source_devices.contains(&target_device) {
+        return true;
+    }
+
+    let result = async {
+        let connection = super::dbus::system_connection().await?;
+        let Some(target_drive) = drive(&connection, target_device).await? else {
+            return Ok(false);
+        };
+
+        for device in source_devices {
+            if drive(&connection, device).await?.as_ref() == Some(&target_drive) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    };
+
+    result.await.unwrap_or_else(|err: zbus::Error| {
+        debug!("Failed to compare drives of {:?}: {}", target, err);
+        false
+    })
+}
+
+/// SMART status of the drive with the block device `device_number`
+async fn smart_health(device_number: u64) -> Result<Option<Health>> {
+    let connection = super::dbus::system_connection().await?;
+
+    let Some(drive) = drive(&connection, device_number).await? else {
+        return Ok(None);
+    };
+
+    let ata = AtaProxy::builder(&connection)
+        .destination(SERVICE)?
+        .path(drive.clone())?
+        .build()
+        .await?;
+
+    if let Ok(true) = ata.smart_supported().await {
+        if !ata.smart_enabled().await? {
+            return Ok(None);
+        }
+
+        let mut health = if ata.smart_failing().await? {
+            Health::Failing
+        } else {
+            Health::Good
+        };
+
+        let failing_attributes = ata.smart_num_attributes_failing().await?;
+        if failing_attributes > 0 {
+            health = health.with_problem(ngettextf(
+                "One health attribute of the drive is below its threshold.",
+                "{} health attributes of the drive are below their threshold.",
+                failing_attributes as u32,
+                &[&failing_attributes.to_string()],
+            ));
+        }
+
+        let bad_sectors = ata.smart_num_bad_sectors().await?;
+        if bad_sectors > 0 {
+            health = health.with_problem(ngettextf(
+                "The drive has one bad sector.",
+                "The drive has {} bad sectors.",
+                bad_sectors.min(u32::MAX.into()) as u32,
+                &[&bad_sectors.to_string()],
+            ));
+        }
+
+        return Ok(Some(health));
+    }
+
+    let nvme = NvmeControllerProxy::builder(&connection)
+        .destination(SERVICE)?
+        .path(drive)?
+        .build()
+        .await?;
+
+    match nvme.smart_critical_warning().await {
+        Ok(warnings) if warnings.is_empty() => Ok(Some(Health::Good)),
+        Ok(warnings) => Ok(Some(nvme_health(&warnings))),
+        // Not an NVMe drive
+        Err(_) => Ok(None),
+    }
+}
+
+/// Health from the critical warnings of an NVMe controller
+fn nvme_health(warnings: &[String]) -> Health {
+    if warnings
+        .iter()
+        .any(|x| x == "reliability" || x == "readonly")
+    {
+        Health::Failing
+    } else {
+        Health::Degraded(vec![gettextf(
+            "The drive reports critical warnings: {}",
+            &[&warnings.join(", ")],
+        )])
+    }
+}
+
+#[test]
+fn test_health() {
+    let health = Health::Good.with_problem("A".to_string());
+    assert_eq!(health, Health::Degraded(vec!["A".to_string()]));
+    assert_eq!(
+        health.with_problem("B".to_string()).summary(),
+        "A B".to_string()
+    );
+    assert_eq!(
+        Health::Failing.with_problem("A".to_string()),
+        Health::Failing
+    );
+
+    assert_eq!(nvme_health(&["reliability".to_string()]), Health::Failing);
+    assert!(!nvme_health(&["temperature".to_string()]).is_good());
+}
@@ -17,14 +17,30 @@ pub fn config(path: &std::path::Path) -> config::Backup {
         repo_id: borg::RepoId::new("repo id".into()),
         archive_prefix: config::ArchivePrefix::generate(),
         encryption_mode: "none".into(),
+        password_command: Default::default(),
         repo: config::local::Repository::from_path(path.to_path_buf()).into_config(),
         encrypted: false,
         include: Default::default(),
+        include_patterns: Default::default(),
         exclude: Default::default(),
         schedule: Default::default(),
         prune: Default::default(),
+        maintenance: Default::default(),
         title: Default::default(),
+        accent_color: Default::default(),
         user_scripts: Default::default(),
+        export_metrics: Default::default(),
+        checkpoints: Default::default(),
+        retry_changed_files: Default::default(),
+        skip_metadata: Default::default(),
+        append_only: Default::default(),
+        patterns_files: Default::default(),
+        report_dir: Default::default(),
+        performance: Default::default(),
+        low_priority_in_power_saver: Default::default(),
+        pause_indexing: Default::default(),
+        write_verification: Default::default(),
+        plaintext_warning_dismissed: Default::default(),
     }
 }
 
@@ -0,0 +1,52 @@
+mod common;
+use common::*;
+
+use pika_backup::borg::size_estimate::{calculate, DIRECTORY_SIZE};
+use pika_backup::config::{Exclude, Pattern};
+use std::os::unix::ffi::OsStrExt;
+
+fn path(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[test]
+fn config_roundtrip() {
+    let mut config = config(&path(b"/tmp/repo-\xff"));
+    config.include.insert("Documents".into());
+    config.include.insert(path(b"caf\xe9"));
+    config.include_patterns.insert(path(b"Music/*\xff"));
+    config
+        .exclude
+        .insert(Exclude::from_pattern(Pattern::PathPrefix(path(
+            b"caf\xe9/cache",
+        ))));
+
+    let json = serde_json::to_string(&config).unwrap();
+    // Valid paths keep being stored as strings
+    assert!(json.contains("\"Documents\""));
+
+    let decoded: config::Backup = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, config);
+    assert_eq!(decoded.include_dirs(), config.include_dirs());
+}
+
+#[test]
+fn size_estimate_exclude() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join(path(b"caf\xe9")), [0; 1000]).unwrap();
+    std::fs::create_dir(dir.path().join(path(b"skip\xff"))).unwrap();
+    std::fs::write(dir.path().join(path(b"skip\xff/file")), [0; 1000]).unwrap();
+
+    let mut config = config(Path::new("/tmp/INVALID"));
+    config.include.insert(dir.path().to_path_buf());
+    config
+        .exclude
+        .insert(Exclude::from_pattern(Pattern::PathPrefix(
+            dir.path().join(path(b"skip\xff")),
+        )));
+
+    let estimate = calculate(&config, &config::Histories::default(), &Default::default()).unwrap();
+
+    assert!(estimate.unreadable_paths.is_empty());
+    assert_eq!(estimate.total, DIRECTORY_SIZE + 1000);
+}